@@ -0,0 +1,60 @@
+/// Range of the filter envelope's depth, in octaves above the cutoff.
+pub const ENV_MOD_MAX: f32 = 6.0;
+
+/// Range of the filter envelope's decay, in ms.
+pub const DECAY_MIN_MS: f32 = 30.0;
+pub const DECAY_MAX_MS: f32 = 2000.0;
+
+/// Time constant of the accent level's move between notes, so a change
+/// of level doesn't click.
+const GAIN_SMOOTH_S: f32 = 0.004;
+
+/// TB-303-style extras on synth 1, driven by sequencer 1: a decaying filter
+/// envelope on `Synth::filter1` and per-step accents.  Slides and the mono
+/// voice are the sequencer's and synth's own; `App::acid_preset()` sets
+/// them all up together.
+#[derive(Clone)]
+pub struct Acid {
+    pub enabled:  bool,
+    /// How far the envelope opens the cutoff at its peak, in octaves.
+    pub env_mod:  f32,
+    /// Time for the envelope to fall to 1/e.
+    pub decay_ms: f32,
+    /// 0–1: how much an accented step adds to the level (up to double)
+    /// and to the envelope's peak (up to twice the depth).
+    pub accent:   f32,
+    env:          f32,
+    gain:         f32,
+    gain_target:  f32,
+}
+
+impl Acid {
+    pub fn new() -> Self {
+        Self {
+            enabled: false, env_mod: 3.0, decay_ms: 250.0, accent: 0.5,
+            env: 0.0, gain: 1.0, gain_target: 1.0,
+        }
+    }
+
+    /// A sequencer note starts.  A slid-into note keeps the envelope where
+    /// it is, as on the 303; its accent still sets the level.
+    pub fn note(&mut self, accent: bool, slide: bool) {
+        let boost = if accent { self.accent } else { 0.0 };
+        if !slide { self.env = 1.0 + boost; }
+        self.gain_target = 1.0 + boost;
+    }
+
+    /// Advance by one sample.  Returns the factor on the filter cutoff and
+    /// the synth's level.
+    pub fn tick(&mut self, sample_rate: f32) -> (f32, f32) {
+        let cutoff = (self.env * self.env_mod).exp2();
+        self.env *= (-1000.0 / (self.decay_ms * sample_rate)).exp();
+        self.gain += (self.gain_target - self.gain) * (1.0 / (GAIN_SMOOTH_S * sample_rate)).min(1.0);
+        (cutoff, self.gain)
+    }
+
+    /// Current envelope level, 0–1 (above 1 just after an accent).
+    pub fn env(&self) -> f32 {
+        self.env
+    }
+}
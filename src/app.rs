@@ -1,39 +1,52 @@
 use std::collections::{HashMap, HashSet};
+use std::sync::mpsc::{self, Receiver, TryRecvError};
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 
-use crate::drums::DrumKind;
-use crate::effects::FilterMode;
-use crate::save::{DelaySave, DistSave, DrumsSave, FilterSave, ReverbSave, RoutingSave,
-                  SaveFile, SeqSave, SidechainSave, TrackSave};
-use crate::scale::{Scale, ScaleQuantizer};
-use crate::synth::{Synth, WaveType, note_name};
-
-const FALLBACK_RELEASE_THRESHOLD: Duration = Duration::from_millis(600);
-
-// ── Key → MIDI note mapping ───────────────────────────────────────────────────
-
-pub fn key_to_note(key: char, base_octave: i32) -> Option<u8> {
-    let (st, oct): (i32, i32) = match key {
-        // Lower row – white keys
-        'z' => (0,0), 'x' => (2,0), 'c' => (4,0), 'v' => (5,0),
-        'b' => (7,0), 'n' => (9,0), 'm' => (11,0),
-        ',' => (12,0), '.' => (14,0), '/' => (16,0),
-        // Lower row – black keys
-        's' => (1,0), 'd' => (3,0), 'g' => (6,0),
-        'h' => (8,0), 'j' => (10,0), 'l' => (13,0), ';' => (15,0),
-        // Upper row – white keys
-        'q' => (0,1), 'w' => (2,1), 'e' => (4,1), 'r' => (5,1),
-        't' => (7,1), 'y' => (9,1), 'u' => (11,1),
-        'i' => (12,1), 'o' => (14,1), 'p' => (16,1),
-        // Upper row – black keys
-        '2' => (1,1), '3' => (3,1), '5' => (6,1),
-        '6' => (8,1), '7' => (10,1), '9' => (13,1), '0' => (15,1),
-        _ => return None,
-    };
-    let note = (base_octave + oct) * 12 + 12 + st;
-    if (0..=127).contains(&note) { Some(note as u8) } else { None }
-}
+use anyhow::{bail, Context, Result};
+
+use crate::acid::{Acid, DECAY_MAX_MS, DECAY_MIN_MS, ENV_MOD_MAX};
+use crate::arp::{ArpMode, ArpRate};
+use crate::audio::AudioEngine;
+use crate::arrange::{Arrangement, Marker, MAX_MARKERS, STEPS_PER_BAR};
+use crate::automation::{Automation, Lane};
+use crate::config::DEFAULT_RELEASE_MS;
+use crate::drums::{DrumKind, DrumTrack, TrackPattern, TrigCondition, MAX_PITCH, MAX_RETRIG_HITS, MAX_TRACKS};
+use crate::effects::{parse_taps, AudioEffect, DistMode, EffectChain, FilterMode, FormantFilter, InsertKind, NoteValue,
+                     FILTER_MAX_GAIN_DB, SYNC_DIVISIONS,
+                     Tap, TapPreset, CHORUS_RATE_MAX, CHORUS_RATE_MIN, COMB_MAX_HZ, COMB_MIN_HZ, CRUSH_MAX_BITS,
+                     CRUSH_MAX_DOWNSAMPLE, CRUSH_MIN_BITS, DEESS_MAX_HZ, DEESS_MIN_HZ, FREEZE_MAX_FADE_MS,
+                     FREEZE_MIN_FADE_MS, MAX_INSERTS, MAX_TAPS};
+use crate::freeze::{Bus, Render};
+use crate::granular::{Granular, DEFAULT_ROOT, DENSITY_MAX, DENSITY_MIN, GRAIN_MAX_MS, GRAIN_MIN_MS};
+use crate::keymap::{Action, Keymap};
+use crate::lfo::{Lfo, LfoRate, LfoShape, LfoTarget};
+use crate::metronome::MAX_COUNT_IN;
+use crate::midi::{CcMap, CcParam, NoteRouting};
+use crate::sample::Sample;
+use crate::save::{AcidSave, ArpSave, AutomationSave, ChorusSave, CombSave, CompSave, CrushSave, DeEsserSave, DelaySave, DistSave, DrumsSave, EnvSave,
+                  FilterSave, FormantSave, GranularSave, InsertSave, LaneSave, LfoSave, LockSave, MarkerSave, ReverbSave, RoutingSave, SaveFile,
+                  SeqSave, SidechainSave, TapSave, TrackSave, export_midi};
+use crate::scale::{ChordShape, Scale, ScaleQuantizer};
+use crate::sequencer::{nudge_by, nudge_label, stack_notes, Direction, Groove, GrooveTemplate, PLock, SeqPattern,
+                       MAX_CHORD_SPAN, MAX_NUDGE, MAX_OCTAVE, MAX_STEPS, VEL_HUMANIZE_MAX};
+use crate::spectrogram::Spectrogram;
+use crate::synth::{SidechainShape, Synth, WaveType, DEFAULT_ADSR, ENV_TIME_MAX, ENV_TIME_MIN, SCOPE_LEN, STEREO_LEN,
+                   bpm_label, crossfade_gains, env_time_label, note_name, notes_label, pan_label};
+use crate::wav::{write_wav, WavFormat, WavSpec};
+use crate::wavetable::{Wavetable, ADDITIVE_TABLE, DEFAULT_HARMONICS, HARMONICS};
+
+/// How long a step audition sounds before its note-off.
+const AUDITION_LEN: Duration = Duration::from_millis(180);
+
+/// How long the master peak readout holds a level before starting over.
+const PEAK_HOLD: Duration = Duration::from_secs(3);
+
+/// Time between spectrogram rows.
+const SPECTROGRAM_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Longest WAV export, in bars: the render holds the UI until it is done.
+const MAX_EXPORT_BARS: usize = 64;
 
 // ── App mode ──────────────────────────────────────────────────────────────────
 
@@ -49,6 +62,18 @@ pub enum AppMode {
     Drums,
     /// Adjust master output effects.
     Effects,
+    /// Edit the additive oscillator's harmonics.
+    Harmonics,
+    /// Load a sample and set its granular and sampler playback.
+    Sample,
+    /// Synth 1 / sequencer 1 as a TB-303: accents, slides, filter envelope.
+    Acid,
+    /// Edit the amp envelopes (ADSR) of both synths.
+    Envelope,
+    /// Edit the two LFOs: shape, rate, depth and target.
+    Modulation,
+    /// Add, remove and reorder the effects in each synth bus's insert chain.
+    Inserts,
 }
 
 // ── Input mode (file path prompt) ─────────────────────────────────────────────
@@ -58,16 +83,373 @@ pub enum InputMode {
     None,
     Save,
     Load,
+    /// Path of a single-cycle WAV to load as a wavetable.
+    LoadWavetable,
+    /// Path of a WAV to load as the granular / sampler sample.
+    LoadSample,
+    /// Path of a WAV to render the project to, and optionally a bar count.
+    Export,
+    /// Path of a MIDI file to write both sequencers to.
+    ExportMidi,
+    /// New name for the selected drum track (empty = back to the kind name).
+    RenameTrack,
+    /// Path of a WAV one-shot for this drum track (empty = synthesise again).
+    LoadDrumSample(usize),
+    /// Note value (`1/8`, `1/8.`, `1/8T` …) to set the delay time from.
+    DelayNote,
+    /// The delay's extra taps as `ms level pan` triples (empty = none).
+    DelayTaps,
+    /// Name of the section marker at `App::marker_bar`.
+    Marker,
+    /// Custom scale editor: toggles `App::scale_draft`, not a text prompt.
+    ScaleEdit,
+    /// Yes/no before clearing patterns, not a text prompt.
+    ConfirmClear(ClearTarget),
+}
+
+/// What `App::clear_focused_pattern()` or `App::clear_all()` empties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ClearTarget { Seq1, Seq2, Drums, All }
+
+impl ClearTarget {
+    pub fn name(self) -> &'static str {
+        match self {
+            ClearTarget::Seq1  => "sequencer 1 pattern",
+            ClearTarget::Seq2  => "sequencer 2 pattern",
+            ClearTarget::Drums => "drum pattern",
+            ClearTarget::All   => "all patterns",
+        }
+    }
+}
+
+/// Patterns as they were before the last clear, for `App::undo_clear()`.
+struct Cleared {
+    target: ClearTarget,
+    seq1:   Option<SeqPattern>,
+    seq2:   Option<SeqPattern>,
+    drums:  Option<Vec<TrackPattern>>,
+}
+
+/// The files a save refers to, read from disk before the target synth is
+/// locked so loading never holds up the audio thread on I/O.
+struct SaveAssets {
+    sample:       Option<Arc<Sample>>,
+    drum_samples: Vec<Option<Arc<Sample>>>,
+    /// Tables named by waves and locks; ones the synth already has are
+    /// kept over these.
+    tables:       Vec<Wavetable>,
+}
+
+impl SaveAssets {
+    fn read(sf: &SaveFile) -> Self {
+        let sample = sf.granular.as_ref().and_then(|g| g.sample.as_deref())
+            .and_then(|p| Sample::load(p).ok()).map(Arc::new);
+        let drum_samples = sf.drums.tracks.iter().take(MAX_TRACKS)
+            .map(|t| t.sample.as_deref().and_then(|p| Sample::load(p).ok()).map(Arc::new))
+            .collect();
+        let locks = sf.seq1.locks.iter().chain(&sf.seq2.locks).map(|l| (l.wave, l.wave_table.as_deref()));
+        let mut tables: Vec<Wavetable> = Vec::new();
+        for (wave, key) in [(Some(sf.wave1), sf.wave1_table.as_deref()), (Some(sf.wave2), sf.wave2_table.as_deref())].into_iter().chain(locks) {
+            let Some(key) = key.filter(|_| wave == Some(4)) else { continue };
+            if tables.iter().any(|t| t.save_key() == key) { continue; }
+            if let Ok(t) = Wavetable::load_wav(key) { tables.push(t); }
+        }
+        Self { sample, drum_samples, tables }
+    }
+}
+
+/// What the WAV export thread sends back to `App::tick_export()`.
+enum ExportUpdate {
+    /// Share of the render done, 0–1.
+    Progress(f32),
+    /// The file was written (the status message to show), or not.
+    Done(Result<String>),
+}
+
+// ── Remote commands ───────────────────────────────────────────────────────────
+
+/// A state change requested from outside the keyboard (OSC).  Applied by
+/// `App::apply()` on the UI thread, through the same methods the keys use.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Command {
+    /// Set a parameter to a value in its own units (clamped to its range).
+    Param(CcParam, f32),
+    /// Live note on/off, like a piano key (not scale-quantised).
+    Note { note: u8, on: bool },
+    SeqPlay { seq2: bool, on: bool },
+    DrumPlay(bool),
+    /// `None` clears the step.
+    SeqStep { seq2: bool, step: usize, note: Option<u8> },
+    /// Trigger probability 0–100 (0 = off).
+    DrumStep { track: usize, step: usize, prob: u8 },
+    DrumMute { track: usize, on: bool },
+    DrumVolume { track: usize, volume: f32 },
+}
+
+// ── UI snapshot ───────────────────────────────────────────────────────────────
+
+/// Everything the UI reads from `Synth` for one frame, copied under a single
+/// lock so the audio thread is blocked once per frame and no panel can show
+/// a different state from its neighbours.
+pub struct Snapshot {
+    pub bpm:     f32,
+    pub volume:  f32,
+    pub volume2: f32,
+    pub wave:    String,
+    pub wave2:   String,
+    pub band_limited: bool,
+    /// Synth 1 and synth 2 in mono mode.
+    pub mono:    [bool; 2],
+    /// Each synth's envelope as `Synth::adsr` gives it.
+    pub adsr:    [[f32; 4]; 2],
+    pub lfos:    [Lfo; 2],
+    /// Names of the effects in synth 1's and synth 2's insert chains, in order.
+    pub inserts: [Vec<&'static str>; 2],
+    /// Synth 1 and synth 2 pan positions.
+    pub pan:     [f32; 2],
+    pub seq:     SeqView,
+    pub seq2:    SeqView,
+    pub drums:   DrumsView,
+    pub fx:      FxView,
+    /// Peak levels of the synth 1, synth 2 and drum buses (pre-effects).
+    pub bus_levels: [f32; 3],
+    pub drum_volume: f32,
+    pub master_gain: f32,
+    pub mono_sum:    bool,
+    pub cue:         Option<Bus>,
+    pub metronome:   bool,
+    /// Beats left of a record count-in.
+    pub count_in:    Option<u32>,
+    pub crossfade:  f32,
+    /// What the MIDI output is connected to, if one is open.
+    pub midi_out:   Option<String>,
+    pub midi_notes: NoteRouting,
+    /// Under an external MIDI clock: whether its transport is running.
+    pub ext_sync:   Option<bool>,
+    /// Bar the playhead is in (`step_pos / STEPS_PER_BAR`).
+    pub bar:     usize,
+    /// Per bus (`Bus::index`), the tempo a frozen render was made at.
+    pub frozen:  [Option<f32>; 3],
+    /// Arpeggiator rate and mode, when it is on.
+    pub arp:     Option<(ArpRate, ArpMode)>,
+    /// Amplitudes of the additive harmonics.
+    pub harmonics: [f32; HARMONICS],
+    /// The loaded sample (shared, not copied), its root and grain settings.
+    pub granular:  Granular,
+    /// Synth 1's acid settings and envelope.
+    pub acid:      Acid,
+    /// Newest output samples for the oscilloscope, oldest first.
+    pub scope:   Vec<f32>,
+    /// Newest output frames for the goniometer, oldest first.
+    pub stereo:  Vec<(f32, f32)>,
+    /// Phase correlation of the output (`None` while silent).
+    pub correlation: Option<f32>,
+}
+
+pub struct SeqView {
+    pub steps:        Vec<Option<u8>>,
+    /// Chord notes above each step's root, in semitones.
+    pub chord:        Vec<Vec<u8>>,
+    pub muted:        Vec<bool>,
+    pub slide:        Vec<bool>,
+    pub accent:       Vec<bool>,
+    pub tie_repeats:  bool,
+    pub direction:    Direction,
+    pub vel_humanize: f32,
+    pub octave:       Vec<i8>,
+    pub nudge:        Vec<i8>,
+    /// Steps with at least one p-lock.
+    pub locked:       Vec<bool>,
+    /// The cursor step's locks, described, if it has any.
+    pub cursor_locks: Option<String>,
+    pub num_steps:    usize,
+    pub current_step: usize,
+    pub playing:      bool,
+}
+
+pub struct DrumTrackView {
+    pub kind:   DrumKind,
+    pub steps:  Vec<u8>,
+    pub conds:  Vec<TrigCondition>,
+    pub nudge:  Vec<i8>,
+    pub muted:  bool,
+    pub volume: f32,
+    pub pan:    f32,
+    /// Tuning in semitones.
+    pub pitch:  f32,
+    /// The track's name, or its kind's name when it has none.
+    pub label:  String,
+    /// Name of the track's sample, if it plays one.
+    pub sample: Option<String>,
+    pub retrig: u8,
+    pub retrig_hits: u8,
+}
+
+pub struct DrumsView {
+    pub tracks:       Vec<DrumTrackView>,
+    pub num_steps:    usize,
+    pub current_step: usize,
+    pub playing:      bool,
+    pub fill:         bool,
+    pub loop_count:   u64,
+    pub groove:       Groove,
+}
+
+/// One row of the effects panel: on/off, three knobs, S1/S2/DR sends, and
+/// the wet output level (sends only; `None` for the sidechain).
+#[derive(Clone, Copy)]
+pub struct FxRow {
+    pub enabled: bool,
+    pub params:  [f32; 3],
+    pub sends:   [f32; 3],
+    pub wet:     Option<f32>,
+}
+
+#[derive(Clone, Copy)]
+pub struct FilterRow {
+    pub enabled: bool,
+    pub mode:    FilterMode,
+    pub cutoff:  f32,
+    pub q:       f32,
+    pub gain_db: f32,
+}
+
+pub struct FxView {
+    pub reverb:    FxRow,
+    /// Reverb early-reflection amount (outside the row's three knobs).
+    pub reverb_er: f32,
+    pub reverb_shimmer: f32,
+    pub delay:     FxRow,
+    pub dist:      FxRow,
+    pub dist_mode: DistMode,
+    pub dist_asym: f32,
+    /// Extra delay taps in use.
+    pub delay_taps: usize,
+    pub delay_reverse: bool,
+    /// The division a synced delay follows; `None` = free time.
+    pub delay_sync: Option<NoteValue>,
+    /// Delay ducking amount (0–1) and release (ms).
+    pub delay_duck: (f32, f32),
+    /// Sends hold the S1/S2 duck flags as 0.0 / 1.0.
+    pub sidechain: FxRow,
+    pub filter1:   FilterRow,
+    pub filter2:   FilterRow,
+    pub comb:      FxRow,
+    /// Sends hold the S1/S2 target as 0.0 / 1.0.
+    pub formant:   FxRow,
+    /// Sends show the drum bus it is on.
+    pub deesser:   FxRow,
+    /// De-esser cut now, in dB.
+    pub deesser_gr: f32,
+    pub spectral:  FreezeRow,
+    pub chorus:    FxRow,
+    pub crusher:   FxRow,
+    pub compressor: CompRow,
+}
+
+/// The master compressor: threshold (dB), ratio, attack and release (ms),
+/// makeup (dB), and the gain reduction now.
+#[derive(Clone, Copy)]
+pub struct CompRow {
+    pub enabled:      bool,
+    pub params:       [f32; 5],
+    pub reduction_db: f32,
+}
+
+/// The spectral freeze: held or not, and its two knobs.
+#[derive(Clone, Copy)]
+pub struct FreezeRow {
+    pub held:    bool,
+    pub mix:     f32,
+    pub fade_ms: f32,
+}
+
+impl Snapshot {
+    /// Title-bar markers for every enabled effect.
+    pub fn fx_indicators(&self) -> String {
+        let fx = &self.fx;
+        let mut ind = String::new();
+        if fx.reverb.enabled    { ind.push_str("  ▶RVB"); }
+        if fx.delay.enabled     { ind.push_str("  ▶DLY"); }
+        if fx.dist.enabled      { ind.push_str("  ▶DST"); }
+        if fx.sidechain.enabled { ind.push_str("  ▶SC"); }
+        if fx.filter1.enabled   { ind.push_str("  ▶F1"); }
+        if fx.filter2.enabled   { ind.push_str("  ▶F2"); }
+        if fx.comb.enabled      { ind.push_str("  ▶CMB"); }
+        if fx.formant.enabled   { ind.push_str("  ▶FMT"); }
+        if fx.deesser.enabled   { ind.push_str("  ▶DES"); }
+        if fx.spectral.held     { ind.push_str("  ▶FRZ"); }
+        if fx.chorus.enabled    { ind.push_str("  ▶CHO"); }
+        if fx.crusher.enabled   { ind.push_str("  ▶BIT"); }
+        if fx.compressor.enabled { ind.push_str("  ▶CMP"); }
+        ind
+    }
 }
 
 // ── App state ─────────────────────────────────────────────────────────────────
 
 pub struct App {
     pub synth:        Arc<Mutex<Synth>>,
+    /// Output stream (or the silent null sink when no device is available).
+    pub audio:        AudioEngine,
     pub base_octave:  i32,
     pub pressed_keys: HashSet<char>,
     key_last_seen:    HashMap<char, Instant>,
+    /// Without key-release events, a held key is released once no repeat has
+    /// arrived for this long.  Must exceed the terminal's initial repeat delay.
+    pub fallback_release: Duration,
+    /// Key → action bindings and the piano / drum-pad key layout.
+    pub keymap:       Keymap,
+    /// CC numbers used to mirror control changes to the MIDI output.
+    pub cc_map:       CcMap,
+    /// Recorded parameter lanes and the record-arm switch.
+    pub automation:   Automation,
+    /// Section markers on the bar timeline.
+    pub arrangement:  Arrangement,
+    /// Bar the open marker prompt names (the playhead's when it opened).
+    marker_bar:       usize,
+    /// The last clear, until it is undone or another replaces it.
+    cleared:          Option<Cleared>,
     pub active_notes: Vec<u8>,
+    /// Latch: notes keep sounding after their key is released, until the
+    /// key is pressed again.
+    pub latch:        bool,
+    /// Notes currently held by the latch.  Independent of the octave and of
+    /// focus changes, so a drone survives both.
+    pub latched:      HashSet<u8>,
+    /// Real-time recording: keyboard notes are written into sequencer 1
+    /// at the playhead once the count-in is over.
+    pub seq_rec:      bool,
+    /// Bars of metronome count-in before recording starts (0–2).
+    pub count_in_bars: u8,
+    /// How far `Q` pulls notes onto the grid (0.25–1).
+    pub quantize_strength: f32,
+    /// Chord mode: each key plays this chord instead of one note.
+    pub chord:        Option<ChordShape>,
+    /// Played note → the chord notes its key started, so the key releases
+    /// what it played even if the shape or scale has changed since.
+    chord_held:       HashMap<u8, Vec<u8>>,
+    /// Play the stored note when the sequencer cursor moves onto a step.
+    pub audition:     bool,
+    /// Notes currently being auditioned: (notes, on synth 2, started).
+    audition_note:    Option<(Vec<u8>, bool, Instant)>,
+    /// A WAV export rendering in the background, and its status label.
+    export:           Option<(Receiver<ExportUpdate>, String)>,
+    /// Hold-to-paint: while a note key is held in Seq focus, → stamps its
+    /// note on each step it leaves.
+    pub paint:        bool,
+    /// Note key held for painting: (key, note, last seen).
+    paint_key:        Option<(char, u8, Instant)>,
+    /// Step held for p-lock editing: (on sequencer 2, step).
+    pub held_step:    Option<(bool, usize)>,
+    /// Master output peak shown in the status bar (linear), and when it
+    /// last rose.
+    pub peak_hold:    f32,
+    peak_since:       Instant,
+    /// The scope slot shows the spectrogram instead of the oscilloscope.
+    pub spectrogram_on: bool,
+    pub spectrogram:  Spectrogram,
+    spectrogram_at:   Instant,
     pub should_quit:  bool,
     pub status_msg:   String,
 
@@ -84,11 +466,38 @@ pub struct App {
     pub drum_step:  usize,  // selected step (column)
 
     // Effects panel cursors
-    pub effects_sel:   usize,  // 0=Reverb 1=Delay 2=Distortion
-    pub effects_param: usize,  // 0-2 = effect param; 3-5 = S1/S2/DR send level
+    pub effects_sel:   usize,  // 0=Reverb 1=Delay 2=Distortion 3=Sidechain 4-5=Filters 6=Comb 7=Formant 8=De-esser 9=Freeze 10=Chorus 11=Bitcrusher 12=Compressor
+    pub effects_param: usize,  // 0-2 = effect param; 3-5 = S1/S2/DR send level (compressor: 0-4 its knobs)
+    /// Last delay tap preset applied: where the preset key cycles on from.
+    pub tap_preset:    TapPreset,
+    /// Harmonic selected in the additive editor (0 = fundamental).
+    pub harm_cursor:   usize,
+    /// Setting selected in Sample focus: 0=Size 1=Density 2=Position 3=Root.
+    pub grain_sel:     usize,
+    /// Setting selected in Acid focus: 0=Cutoff 1=Reso 2=Env 3=Decay 4=Accent.
+    pub acid_sel:      usize,
+    /// Envelope focus edits synth 2's envelope (else synth 1's).
+    pub env_synth2:    bool,
+    /// Stage selected in Envelope focus: 0=Attack 1=Decay 2=Sustain 3=Release.
+    pub env_sel:       usize,
+    /// LFO edited in Modulation focus (0 or 1).
+    pub lfo_sel:       usize,
+    /// Setting selected in Modulation focus: 0=Shape 1=Rate 2=Depth 3=Target.
+    pub lfo_param:     usize,
+    /// Inserts focus edits synth 2's chain (else synth 1's).
+    pub insert_synth2: bool,
+    /// Entry selected in the chain being edited.
+    pub insert_sel:    usize,
+    /// Effect the add key puts in.
+    pub insert_kind:   InsertKind,
 
     // Scale quantizer (input layer — no audio thread involvement)
     pub scale_q: ScaleQuantizer,
+    /// Sequencers play through `scale_q` too (`Synth::live_scale`).
+    pub live_quantize: bool,
+    /// Custom scale being edited: which semitones above the root are in it.
+    pub scale_draft:  [bool; 12],
+    pub scale_cursor: usize,
 
     // File path prompt state
     pub input_mode: InputMode,
@@ -96,13 +505,39 @@ pub struct App {
 }
 
 impl App {
-    pub fn new(synth: Arc<Mutex<Synth>>) -> Self {
+    pub fn new(synth: Arc<Mutex<Synth>>, audio: AudioEngine) -> Self {
         Self {
             synth,
+            audio,
             base_octave:  4,
             pressed_keys: HashSet::new(),
             key_last_seen: HashMap::new(),
+            fallback_release: Duration::from_millis(DEFAULT_RELEASE_MS),
+            keymap:       Keymap::defaults(),
+            cc_map:       CcMap::defaults(),
+            automation:   Automation::new(),
+            arrangement:  Arrangement::new(),
+            marker_bar:   0,
+            cleared:      None,
             active_notes: Vec::new(),
+            latch:        false,
+            latched:      HashSet::new(),
+            seq_rec:      false,
+            count_in_bars: 1,
+            quantize_strength: 1.0,
+            chord:        None,
+            chord_held:   HashMap::new(),
+            audition:     false,
+            audition_note: None,
+            export:       None,
+            paint:        false,
+            paint_key:    None,
+            held_step:    None,
+            peak_hold:    0.0,
+            peak_since:   Instant::now(),
+            spectrogram_on: false,
+            spectrogram:  Spectrogram::new(),
+            spectrogram_at: Instant::now(),
             should_quit:  false,
             status_msg:   String::new(),
             mode:         AppMode::Play,
@@ -112,26 +547,308 @@ impl App {
             drum_step:    0,
             effects_sel:   0,
             effects_param: 0,
+            tap_preset:    TapPreset::Off,
+            harm_cursor:   0,
+            grain_sel:     0,
+            acid_sel:      0,
+            env_synth2:    false,
+            env_sel:       0,
+            lfo_sel:       0,
+            lfo_param:     0,
+            insert_synth2: false,
+            insert_sel:    0,
+            insert_kind:   InsertKind::Reverb,
             scale_q:       ScaleQuantizer::new(),
+            live_quantize: false,
+            scale_draft:   [false; 12],
+            scale_cursor:  1,
             input_mode:    InputMode::None,
             input_buf:     String::new(),
         }
     }
 
+    // ── Key bindings ──────────────────────────────────────────────────────
+
+    /// Run a bound action (see `keymap.rs`).
+    pub fn perform(&mut self, action: Action) {
+        match action {
+            Action::Quit              => self.should_quit = true,
+            Action::SavePrompt        => self.open_prompt(InputMode::Save, "rusttuisynth.json"),
+            Action::LoadPrompt        => self.open_prompt(InputMode::Load, "rusttuisynth.json"),
+            Action::WavetablePrompt   => self.open_prompt(InputMode::LoadWavetable, ""),
+            Action::ExportPrompt      => self.open_prompt(InputMode::Export, "rusttuisynth.wav"),
+            Action::ExportMidiPrompt  => self.open_prompt(InputMode::ExportMidi, "rusttuisynth.mid"),
+            Action::SamplePrompt      => self.sample_prompt(),
+            Action::ScaleEdit         => self.scale_edit_open(),
+            Action::LiveQuantize      => self.toggle_live_quantize(),
+            Action::ToggleMode        => self.toggle_mode(),
+            Action::CycleWave         => self.cycle_wave(),
+            Action::CycleWave2        => self.cycle_wave2(),
+            Action::ToggleBandLimited => self.toggle_band_limited(),
+            Action::MonoSumToggle     => self.toggle_mono_sum(),
+            Action::CueCycle          => self.cycle_cue(),
+            Action::MetronomeToggle   => self.toggle_metronome(),
+            Action::SpectralFreezeToggle => self.toggle_spectral_freeze(),
+            Action::SeqRecToggle      => self.toggle_seq_rec(),
+            Action::CountInCycle      => self.cycle_count_in(),
+            Action::CycleScale        => self.cycle_scale(),
+            Action::CycleScaleRoot    => self.cycle_scale_root(),
+            Action::AudioNextDevice   => self.audio_next_device(),
+            Action::ResetXruns        => self.reset_xruns(),
+            Action::PeakHoldReset     => self.reset_peak_hold(),
+            Action::SpectrogramToggle => self.toggle_spectrogram(),
+            Action::ClearPatternPrompt => self.clear_pattern_prompt(),
+            Action::ClearAllPrompt    => self.clear_all_prompt(),
+            Action::UndoClear         => self.undo_clear(),
+            Action::MasterGainUp      => self.level_adjust(CcParam::MasterGain, 0.05, "Master"),
+            Action::MasterGainDown    => self.level_adjust(CcParam::MasterGain, -0.05, "Master"),
+            Action::DrumBusVolUp      => self.level_adjust(CcParam::DrumVolume, 0.05, "Drum bus"),
+            Action::DrumBusVolDown    => self.level_adjust(CcParam::DrumVolume, -0.05, "Drum bus"),
+            Action::CrossfadeLeft     => self.crossfade_adjust(-0.05),
+            Action::CrossfadeRight    => self.crossfade_adjust(0.05),
+            Action::BpmUp             => self.bpm_up(),
+            Action::BpmDown           => self.bpm_down(),
+            Action::BpmFineUp         => self.bpm_fine_up(),
+            Action::BpmFineDown       => self.bpm_fine_down(),
+            Action::OctaveUp          => self.octave_up(),
+            Action::OctaveDown        => self.octave_down(),
+            Action::VolumeUp          => self.volume_up(),
+            Action::VolumeDown        => self.volume_down(),
+            Action::ToggleLatch       => self.toggle_latch(),
+            Action::ClearLatched      => self.clear_latched(),
+            Action::CycleChord        => self.cycle_chord(),
+            Action::ArpToggle         => self.arp_toggle(),
+            Action::ArpCycleRate      => self.arp_cycle_rate(),
+            Action::ArpCycleMode      => self.arp_cycle_mode(),
+            Action::MonoToggle        => self.mono_toggle(false),
+            Action::Mono2Toggle       => self.mono_toggle(true),
+            Action::SynthPanLeft      => self.synth_pan(false, -0.1),
+            Action::SynthPanRight     => self.synth_pan(false, 0.1),
+            Action::Synth2PanLeft     => self.synth_pan(true, -0.1),
+            Action::Synth2PanRight    => self.synth_pan(true, 0.1),
+            Action::Synth2VolUp       => self.synth2_vol_up(),
+            Action::Synth2VolDown     => self.synth2_vol_down(),
+            Action::SeqCursorLeft     => self.seq_cursor_left(),
+            Action::SeqCursorRight    => self.seq_cursor_right(),
+            Action::SeqTogglePlay     => self.seq_toggle_play(),
+            Action::SeqClearStep      => self.seq_clear_step(),
+            Action::SeqToggleMute     => self.seq_toggle_mute(),
+            Action::SeqToggleSlide    => self.seq_toggle_slide(),
+            Action::SeqToggleTie      => self.seq_toggle_tie(false),
+            Action::SeqVelHumanizeCycle => self.seq_cycle_vel_humanize(false),
+            Action::SeqDirectionCycle => self.seq_cycle_direction(false),
+            Action::SeqCycleSteps     => self.seq_cycle_steps(),
+            Action::SeqDegreeUp       => self.seq_degree_up(),
+            Action::SeqDegreeDown     => self.seq_degree_down(),
+            Action::SeqStepOctaveUp   => self.seq_octave_up(),
+            Action::SeqStepOctaveDown => self.seq_octave_down(),
+            Action::SeqNudgeLeft      => self.seq_nudge(false, -1),
+            Action::SeqNudgeRight     => self.seq_nudge(false, 1),
+            Action::Seq2CursorLeft    => self.seq2_cursor_left(),
+            Action::Seq2CursorRight   => self.seq2_cursor_right(),
+            Action::Seq2TogglePlay    => self.seq2_toggle_play(),
+            Action::Seq2ClearStep     => self.seq2_clear_step(),
+            Action::Seq2ToggleMute    => self.seq2_toggle_mute(),
+            Action::Seq2ToggleSlide   => self.seq2_toggle_slide(),
+            Action::Seq2ToggleTie     => self.seq_toggle_tie(true),
+            Action::Seq2VelHumanizeCycle => self.seq_cycle_vel_humanize(true),
+            Action::Seq2DirectionCycle => self.seq_cycle_direction(true),
+            Action::Seq2CycleSteps    => self.seq2_cycle_steps(),
+            Action::Seq2DegreeUp      => self.seq2_degree_up(),
+            Action::Seq2DegreeDown    => self.seq2_degree_down(),
+            Action::Seq2StepOctaveUp  => self.seq2_octave_up(),
+            Action::Seq2StepOctaveDown => self.seq2_octave_down(),
+            Action::Seq2NudgeLeft     => self.seq_nudge(true, -1),
+            Action::SeqQuantize       => self.seq_quantize(false),
+            Action::Seq2Quantize      => self.seq_quantize(true),
+            Action::QuantizeStrengthCycle => self.cycle_quantize_strength(),
+            Action::Seq2NudgeRight    => self.seq_nudge(true, 1),
+            Action::ToggleAudition    => self.toggle_audition(),
+            Action::TogglePaint       => self.toggle_paint(),
+            Action::DrumTogglePlay    => self.drum_toggle_play(),
+            Action::DrumTrackUp       => self.drum_track_up(),
+            Action::DrumTrackDown     => self.drum_track_down(),
+            Action::DrumStepLeft      => self.drum_step_left(),
+            Action::DrumStepRight     => self.drum_step_right(),
+            Action::DrumToggleStep    => self.drum_toggle_step(),
+            Action::DrumClearStep     => self.drum_clear_step(),
+            Action::DrumCycleSteps    => self.drum_cycle_steps(),
+            Action::DrumToggleMute    => self.drum_toggle_mute(),
+            Action::DrumAddTrack      => self.drum_add_track(),
+            Action::DrumRemoveTrack   => self.drum_remove_track(),
+            Action::DrumMoveTrackUp   => self.drum_move_track_up(),
+            Action::DrumMoveTrackDown => self.drum_move_track_down(),
+            Action::DrumCycleKind     => self.drum_cycle_kind(),
+            Action::DrumRenamePrompt  => self.drum_rename_prompt(),
+            Action::DrumSamplePrompt  => self.drum_sample_prompt(),
+            Action::DelayNotePrompt   => self.delay_note_prompt(),
+            Action::DelayTapPreset    => self.delay_tap_preset(),
+            Action::DelayTapsPrompt   => self.delay_taps_prompt(),
+            Action::DelayReverseToggle => self.delay_reverse_toggle(),
+            Action::DelaySyncCycle     => self.delay_sync_cycle(),
+            Action::DelayDuckUp       => self.delay_duck_adjust(0.05),
+            Action::DelayDuckDown     => self.delay_duck_adjust(-0.05),
+            Action::DelayDuckReleaseUp   => self.delay_duck_release_adjust(50.0),
+            Action::DelayDuckReleaseDown => self.delay_duck_release_adjust(-50.0),
+            Action::ReverbErUp        => self.reverb_er_up(),
+            Action::ReverbErDown      => self.reverb_er_down(),
+            Action::ReverbShimmerUp   => self.reverb_shimmer_adjust(0.05),
+            Action::ReverbShimmerDown => self.reverb_shimmer_adjust(-0.05),
+            Action::DistCycleMode     => self.dist_cycle_mode(),
+            Action::DistAsymUp        => self.dist_asym_adjust(0.05),
+            Action::HarmSelLeft       => self.harm_cursor = (self.harm_cursor + HARMONICS - 1) % HARMONICS,
+            Action::HarmSelRight      => self.harm_cursor = (self.harm_cursor + 1) % HARMONICS,
+            Action::HarmUp            => self.harm_adjust(0.05),
+            Action::HarmDown          => self.harm_adjust(-0.05),
+            Action::HarmClear         => self.harm_adjust(-1.0),
+            Action::HarmReset         => self.harm_reset(),
+            Action::HarmUse1          => self.harm_use(false),
+            Action::HarmUse2          => self.harm_use(true),
+            Action::GrainSelLeft      => self.grain_sel = (self.grain_sel + 3) % 4,
+            Action::GrainSelRight     => self.grain_sel = (self.grain_sel + 1) % 4,
+            Action::GrainUp           => self.grain_adjust(1.0),
+            Action::GrainDown         => self.grain_adjust(-1.0),
+            Action::GrainUse1         => self.sample_use(false, WaveType::Granular),
+            Action::GrainUse2         => self.sample_use(true, WaveType::Granular),
+            Action::SamplerUse1       => self.sample_use(false, WaveType::Sampler),
+            Action::SamplerUse2       => self.sample_use(true, WaveType::Sampler),
+            Action::AcidPreset        => self.acid_preset(),
+            Action::AcidToggle        => self.acid_toggle(),
+            Action::AcidAccentToggle  => self.acid_toggle_accent(),
+            Action::AcidParamLeft     => self.acid_sel = (self.acid_sel + 4) % 5,
+            Action::AcidParamRight    => self.acid_sel = (self.acid_sel + 1) % 5,
+            Action::AcidParamUp       => self.acid_adjust(1.0),
+            Action::AcidParamDown     => self.acid_adjust(-1.0),
+            Action::EnvSynthToggle    => self.env_synth2 = !self.env_synth2,
+            Action::EnvParamLeft      => self.env_sel = (self.env_sel + 3) % 4,
+            Action::EnvParamRight     => self.env_sel = (self.env_sel + 1) % 4,
+            Action::EnvParamInc       => self.env_adjust(1.0),
+            Action::EnvParamDec       => self.env_adjust(-1.0),
+            Action::LfoSelToggle      => self.lfo_sel = 1 - self.lfo_sel,
+            Action::InsertSynthToggle => self.insert_synth_toggle(),
+            Action::InsertSelLeft     => self.insert_sel_move(false),
+            Action::InsertSelRight    => self.insert_sel_move(true),
+            Action::InsertCycleKind   => {
+                self.insert_kind = self.insert_kind.next();
+                self.status_msg = format!("Insert to add: {}", self.insert_kind.name());
+            }
+            Action::InsertAdd         => self.insert_add(),
+            Action::InsertRemove      => self.insert_remove(self.insert_synth2, self.insert_sel),
+            Action::InsertMoveLeft    => self.insert_move(self.insert_synth2, self.insert_sel, true),
+            Action::InsertMoveRight   => self.insert_move(self.insert_synth2, self.insert_sel, false),
+            Action::LfoParamLeft      => self.lfo_param = (self.lfo_param + 3) % 4,
+            Action::LfoParamRight     => self.lfo_param = (self.lfo_param + 1) % 4,
+            Action::LfoParamInc       => self.lfo_adjust(1),
+            Action::LfoParamDec       => self.lfo_adjust(-1),
+            Action::DistAsymDown      => self.dist_asym_adjust(-0.05),
+            Action::DrumVolUp         => self.drum_vol_up(),
+            Action::DrumVolDown       => self.drum_vol_down(),
+            Action::DrumPanLeft       => self.drum_pan(-0.1),
+            Action::DrumPanRight      => self.drum_pan(0.1),
+            Action::DrumPitchUp       => self.drum_pitch_up(),
+            Action::DrumPitchDown     => self.drum_pitch_down(),
+            Action::DrumProbUp        => self.drum_prob_up(),
+            Action::DrumProbDown      => self.drum_prob_down(),
+            Action::DrumEuclidean     => self.drum_euclidean(),
+            Action::DrumCycleCond     => self.drum_cycle_cond(),
+            Action::DrumFillToggle    => self.drum_fill_toggle(),
+            Action::DrumCycleRetrig   => self.drum_cycle_retrig(),
+            Action::DrumCycleRetrigHits => self.drum_cycle_retrig_hits(),
+            Action::DrumNudgeLeft     => self.drum_nudge(-1),
+            Action::DrumNudgeRight    => self.drum_nudge(1),
+            Action::DrumSwingUp       => self.drum_swing_up(),
+            Action::DrumSwingDown     => self.drum_swing_down(),
+            Action::CycleGroove       => self.cycle_groove(),
+            Action::ToggleGrooveMelodic => self.toggle_groove_melodic(),
+            Action::EffectsSelUp      => self.effects_sel_up(),
+            Action::EffectsSelDown    => self.effects_sel_down(),
+            Action::EffectsParamLeft  => self.effects_param_left(),
+            Action::EffectsParamRight => self.effects_param_right(),
+            Action::EffectsParamInc   => self.effects_param_inc(),
+            Action::EffectsParamDec   => self.effects_param_dec(),
+            Action::EffectsOnOff      => self.effects_on_off(),
+            Action::EffectsRouteToggle => self.effects_route_toggle(),
+            Action::MidiToggleKeys    => self.midi_toggle_keys(),
+            Action::MidiToggleSeq1    => self.midi_toggle_seq1(),
+            Action::MidiToggleSeq2    => self.midi_toggle_seq2(),
+            Action::MidiToggleLocal   => self.midi_toggle_local(),
+            Action::AutoRecToggle     => self.auto_rec_toggle(),
+            Action::AutoBypassToggle  => self.auto_bypass_toggle(),
+            Action::MarkerPrompt      => self.marker_prompt(),
+            Action::MarkerRemove      => self.marker_remove(),
+            Action::MarkerNext        => self.marker_next(),
+            Action::MarkerPrev        => self.marker_prev(),
+            Action::SeqHoldStep       => self.seq_hold_step(),
+            Action::Seq2HoldStep      => self.seq2_hold_step(),
+            Action::SeqClearLocks     => self.seq_clear_locks(),
+            Action::Seq2ClearLocks    => self.seq2_clear_locks(),
+            Action::SeqCutoffUp       => self.seq_cutoff_up(),
+            Action::SeqCutoffDown     => self.seq_cutoff_down(),
+            Action::SeqFreeze         => self.freeze_toggle(Bus::Synth1),
+            Action::Seq2Freeze        => self.freeze_toggle(Bus::Synth2),
+            Action::DrumFreeze        => self.freeze_toggle(Bus::Drums),
+            Action::Seq2CutoffUp      => self.seq2_cutoff_up(),
+            Action::Seq2CutoffDown    => self.seq2_cutoff_down(),
+        }
+    }
+
+    fn open_prompt(&mut self, mode: InputMode, initial: &str) {
+        self.input_mode = mode;
+        self.input_buf  = initial.to_string();
+    }
+
     // ── Keyboard / note playback ──────────────────────────────────────────
 
     pub fn key_press(&mut self, key: char) {
         if self.pressed_keys.contains(&key) { return; }
         self.pressed_keys.insert(key);
-        if let Some(note) = key_to_note(key, self.base_octave) {
-            self.synth.lock().unwrap().note_on(self.scale_q.quantize(note));
+        if let Some(note) = self.keymap.note_for(key, self.base_octave) {
+            self.note_press(self.scale_q.quantize(note));
+        }
+    }
+
+    /// Start a note (or its chord, in chord mode) — or, with the latch on,
+    /// toggle it in the latched set.
+    fn note_press(&mut self, note: u8) {
+        if self.latch && self.latched.remove(&note) {
+            self.note_end(note);
+            return;
+        }
+        if self.latch { self.latched.insert(note); }
+        let notes = self.chord_notes(note);
+        let mut s = self.synth.lock().unwrap();
+        if self.seq_rec && s.sequencer.playing && s.metronome.count_in_until.is_none() {
+            let pos = s.step_pos;
+            s.sequencer.record(pos, &notes);
+        }
+        for &n in &notes { s.key_on(n); }
+        // Retriggered while its chord was still held: end any members the
+        // new chord doesn't replay.
+        if let Some(old) = self.chord_held.insert(note, notes.clone()) {
+            for n in old.into_iter().filter(|n| !notes.contains(n)) { s.key_off(n); }
+        }
+    }
+
+    /// `note` and, in chord mode, the rest of its chord.
+    fn chord_notes(&self, note: u8) -> Vec<u8> {
+        match self.chord {
+            Some(shape) => self.scale_q.chord(note, shape),
+            None        => vec![note],
         }
     }
 
+    /// Release `note` and the chord members its key started.
+    fn note_end(&mut self, note: u8) {
+        let notes = self.chord_held.remove(&note).unwrap_or_else(|| vec![note]);
+        let mut s = self.synth.lock().unwrap();
+        for n in notes { s.key_off(n); }
+    }
+
     pub fn key_release(&mut self, key: char) {
         if !self.pressed_keys.remove(&key) { return; }
-        if let Some(note) = key_to_note(key, self.base_octave) {
-            self.synth.lock().unwrap().note_off(self.scale_q.quantize(note));
+        if self.latch { return; } // latched notes end on the next press
+        if let Some(note) = self.keymap.note_for(key, self.base_octave) {
+            self.note_end(self.scale_q.quantize(note));
         }
     }
 
@@ -139,8 +856,8 @@ impl App {
         self.key_last_seen.insert(key, Instant::now());
         if self.pressed_keys.contains(&key) { return; }
         self.pressed_keys.insert(key);
-        if let Some(note) = key_to_note(key, self.base_octave) {
-            self.synth.lock().unwrap().note_on(self.scale_q.quantize(note));
+        if let Some(note) = self.keymap.note_for(key, self.base_octave) {
+            self.note_press(self.scale_q.quantize(note));
         }
     }
 
@@ -148,19 +865,160 @@ impl App {
         let now = Instant::now();
         let stale: Vec<char> = self.pressed_keys.iter().copied()
             .filter(|k| {
-                key_to_note(*k, self.base_octave).is_some()
+                self.keymap.note_for(*k, self.base_octave).is_some()
                     && self.key_last_seen.get(k)
-                        .map(|t| now.duration_since(*t) >= FALLBACK_RELEASE_THRESHOLD)
+                        .map(|t| now.duration_since(*t) >= self.fallback_release)
                         .unwrap_or(true)
             })
             .collect();
         for k in stale { self.key_last_seen.remove(&k); self.key_release(k); }
+        if self.paint_key.is_some_and(|(_, _, t)| now.duration_since(t) >= self.fallback_release) {
+            self.paint_key = None;
+        }
     }
 
     pub fn release_all(&mut self) {
         let keys: Vec<char> = self.pressed_keys.iter().copied().collect();
         for k in keys { self.key_release(k); }
         self.key_last_seen.clear();
+        self.paint_key = None;
+    }
+
+    /// Turning the latch on releases held keys normally first; turning it
+    /// off releases everything latched.
+    pub fn toggle_latch(&mut self) {
+        self.release_all();
+        if self.latch { self.clear_latched(); }
+        self.latch = !self.latch;
+        self.status_msg = format!("Latch: {}", if self.latch { "on" } else { "off" });
+    }
+
+    pub fn clear_latched(&mut self) {
+        let notes: Vec<u8> = self.latched.drain().collect();
+        for note in notes { self.note_end(note); }
+        self.status_msg = "Latched notes released".to_string();
+    }
+
+    /// Poly ↔ mono (legato) for synth 1, or synth 2 when `synth2`.
+    pub fn mono_toggle(&mut self, synth2: bool) {
+        let mut s = self.synth.lock().unwrap();
+        let mono = !if synth2 { s.mono2 } else { s.mono };
+        s.set_mono(synth2, mono);
+        self.status_msg = format!("Synth {}: {}", if synth2 { 2 } else { 1 },
+            if mono { "mono (legato)" } else { "poly" });
+    }
+
+    /// Move synth 1's pan, or synth 2's when `synth2`, by `delta`
+    /// (negative = left), snapped to 5% like the drum pans.
+    pub fn synth_pan(&mut self, synth2: bool, delta: f32) {
+        let mut s = self.synth.lock().unwrap();
+        let pan = if synth2 { &mut s.pan2 } else { &mut s.pan };
+        *pan = ((*pan + delta) * 20.0).round().clamp(-20.0, 20.0) / 20.0;
+        self.status_msg = format!("Synth {} pan: {}", if synth2 { 2 } else { 1 }, pan_label(*pan));
+    }
+
+    /// Held and latched notes are released first: they were started as
+    /// plain voices, or belong to the arpeggiator being turned off.
+    pub fn arp_toggle(&mut self) {
+        self.release_all();
+        if self.latch { self.clear_latched(); }
+        let mut s = self.synth.lock().unwrap();
+        let on = !s.arp.enabled;
+        s.set_arp(on);
+        self.status_msg = if on {
+            format!("Arpeggiator: on ({} {})", s.arp.rate.name(), s.arp.mode.name())
+        } else {
+            "Arpeggiator: off".to_string()
+        };
+    }
+
+    pub fn arp_cycle_rate(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.arp.rate = s.arp.rate.next();
+        self.status_msg = format!("Arp rate: {}", s.arp.rate.name());
+    }
+
+    pub fn arp_cycle_mode(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.arp.mode = s.arp.mode.next();
+        self.status_msg = format!("Arp mode: {}", s.arp.mode.name());
+    }
+
+    /// Chord mode: Off → Triad → Major → Minor → 7th → Sus4 → Power → Off.  Held chords
+    /// keep the notes they started with.
+    pub fn cycle_chord(&mut self) {
+        self.chord = match self.chord {
+            None    => Some(ChordShape::ALL[0]),
+            Some(c) => ChordShape::ALL.iter().position(|&x| x == c)
+                .and_then(|i| ChordShape::ALL.get(i + 1)).copied(),
+        };
+        self.status_msg = match self.chord {
+            None => "Chord mode: off".to_string(),
+            Some(c) if self.scale_q.active() && c.follows_scale() => format!(
+                "Chord mode: {} (in {} {})", c.name(), self.scale_q.root_name(), self.scale_q.scale.name()),
+            Some(c) => format!("Chord mode: {}", c.name()),
+        };
+    }
+
+    // ── Step audition ─────────────────────────────────────────────────────
+
+    pub fn toggle_audition(&mut self) {
+        self.audition = !self.audition;
+        if !self.audition { self.end_audition(); }
+        self.status_msg = format!("Step audition: {}", if self.audition { "on" } else { "off" });
+    }
+
+    /// Briefly play the note (or chord) stored at the cursor (synth 1 for
+    /// Seq, synth 2 for Seq2).  Muted steps are auditioned too; empty steps are silent.
+    fn audition_step(&mut self, synth2: bool) {
+        if !self.audition { return; }
+        self.end_audition();
+        let mut s = self.synth.lock().unwrap();
+        let (q, cursor) = if synth2 { (&s.sequencer2, self.seq2_cursor) } else { (&s.sequencer, self.seq_cursor) };
+        let Some(root) = q.steps.get(cursor).copied().flatten() else { return };
+        let notes = stack_notes(root, &q.chord[cursor]);
+        for &n in &notes { if synth2 { s.note_on2(n) } else { s.note_on(n) } }
+        self.audition_note = Some((notes, synth2, Instant::now()));
+    }
+
+    fn end_audition(&mut self) {
+        let Some((notes, synth2, _)) = self.audition_note.take() else { return };
+        let mut s = self.synth.lock().unwrap();
+        for n in notes { if synth2 { s.note_off2(n) } else { s.note_off(n) } }
+    }
+
+    /// Release the auditioned note once it has sounded for `AUDITION_LEN`.
+    pub fn tick_audition(&mut self) {
+        if self.audition_note.as_ref().is_some_and(|(_, _, t)| t.elapsed() >= AUDITION_LEN) {
+            self.end_audition();
+        }
+    }
+
+    /// What one transpose step moves by under the current scale.
+    fn degree_unit(&self) -> String {
+        if self.scale_q.active() {
+            format!("degree ({} {})", self.scale_q.root_name(), self.scale_q.scale.name())
+        } else {
+            "semitone".to_string()
+        }
+    }
+
+    // ── Hold-to-paint ─────────────────────────────────────────────────────
+
+    pub fn toggle_paint(&mut self) {
+        self.paint = !self.paint;
+        self.paint_key = None;
+        self.status_msg = format!("Hold-to-paint: {}", if self.paint { "on" } else { "off" });
+    }
+
+    /// Note key released (enhanced terminals only).
+    pub fn paint_release(&mut self, key: char) {
+        if self.paint_key.is_some_and(|(k, _, _)| k == key) { self.paint_key = None; }
+    }
+
+    /// Remember the note key just entered so → can keep stamping it.
+    fn paint_hold(&mut self, key: char, note: u8) {
+        if self.paint { self.paint_key = Some((key, note, Instant::now())); }
     }
 
     // ── Global controls ───────────────────────────────────────────────────
@@ -182,57 +1040,404 @@ impl App {
     }
 
     pub fn cycle_wave(&mut self) {
+        if self.lock_wave(false) { return; }
         let mut s = self.synth.lock().unwrap();
-        s.wave_type = s.wave_type.next();
-        self.status_msg = format!("Wave: {}", s.wave_type.name());
+        s.wave_type = s.wave_type.next(s.wavetables.len());
+        self.status_msg = format!("Wave: {}", s.wave_name(s.wave_type));
     }
 
     pub fn cycle_wave2(&mut self) {
+        if self.lock_wave(true) { return; }
+        let mut s = self.synth.lock().unwrap();
+        s.wave_type2 = s.wave_type2.next(s.wavetables.len());
+        self.status_msg = format!("Synth2 Wave: {}", s.wave_name(s.wave_type2));
+    }
+
+    pub fn toggle_band_limited(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.band_limited = !s.band_limited;
+        self.status_msg = if s.band_limited {
+            "Oscillators: band-limited (PolyBLEP)".to_string()
+        } else {
+            "Oscillators: naive (cheaper, aliases on high notes)".to_string()
+        };
+    }
+
+    /// Monitor the output summed to mono, to check the mix translates.
+    pub fn toggle_mono_sum(&mut self) {
         let mut s = self.synth.lock().unwrap();
-        s.wave_type2 = s.wave_type2.next();
-        self.status_msg = format!("Synth2 Wave: {}", s.wave_type2.name());
+        s.mono_sum = !s.mono_sum;
+        self.status_msg = if s.mono_sum {
+            "Monitoring: mono sum (L+R)/2".to_string()
+        } else {
+            "Monitoring: stereo".to_string()
+        };
+    }
+
+    pub fn toggle_metronome(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.metronome.enabled = !s.metronome.enabled;
+        self.status_msg = format!("Metronome: {}", if s.metronome.enabled { "on" } else { "off" });
+    }
+
+    /// Capture the master mix into the spectral freeze, or let it go.
+    pub fn toggle_spectral_freeze(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        if s.spectral.held() { s.spectral.release(); } else { s.spectral.capture(); }
+        self.status_msg = format!("Spectral freeze: {}", if s.spectral.held() { "held" } else { "released" });
+    }
+
+    /// Arm or disarm real-time recording into sequencer 1.  Arming starts
+    /// the sequencer if it is stopped, and the count-in if one is set.
+    pub fn toggle_seq_rec(&mut self) {
+        self.seq_rec = !self.seq_rec;
+        let mut s = self.synth.lock().unwrap();
+        if !self.seq_rec {
+            s.metronome.count_in_until = None;
+            self.status_msg = "Record: off".to_string();
+            return;
+        }
+        if !s.sequencer.playing {
+            for n in s.sequencer.toggle_play() { s.seq_note_off(false, n); }
+        }
+        let pos = s.step_pos;
+        s.metronome.count_in(pos, self.count_in_bars);
+        self.status_msg = match self.count_in_bars {
+            0 => "Record: Seq 1".to_string(),
+            n => format!("Record: Seq 1 after {} bar{} count-in", n, if n == 1 { "" } else { "s" }),
+        };
+    }
+
+    /// Step the count-in through 0, 1 and 2 bars.
+    pub fn cycle_count_in(&mut self) {
+        self.count_in_bars = (self.count_in_bars + 1) % (MAX_COUNT_IN + 1);
+        self.status_msg = match self.count_in_bars {
+            0 => "Count-in: off".to_string(),
+            n => format!("Count-in: {} bar{}", n, if n == 1 { "" } else { "s" }),
+        };
+    }
+
+    /// Step the cue output through off, each bus in turn, and off again.
+    pub fn cycle_cue(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.cue = match s.cue {
+            None    => Some(Bus::ALL[0]),
+            Some(b) => Bus::ALL.get(b.index() + 1).copied(),
+        };
+        self.status_msg = match s.cue {
+            None    => "Cue: off".to_string(),
+            Some(b) if self.audio.has_cue_output() => format!("Cue: {} on outputs 3–4", b.name()),
+            Some(b) => format!("Cue: {} (the output has {} channels; the cue needs 4)",
+                               b.name(), self.audio.channels()),
+        };
+    }
+
+    /// Load a single-cycle WAV as a wavetable and select it on the focused
+    /// synth (synth 2 in Synth Seq 2 focus, otherwise synth 1).
+    pub fn load_wavetable(&mut self, path: &str) {
+        // Read and resample outside the lock; only the push happens under it.
+        match Wavetable::load_wav(path) {
+            Ok(table) => {
+                let name = table.name.clone();
+                let mut s = self.synth.lock().unwrap();
+                let wave = WaveType::Wavetable(s.add_wavetable(table));
+                if self.mode == AppMode::SynthSeq2 {
+                    s.wave_type2 = wave;
+                    self.status_msg = format!("Synth2 Wave: {} (loaded)", name);
+                } else {
+                    s.wave_type = wave;
+                    self.status_msg = format!("Wave: {} (loaded)", name);
+                }
+            }
+            Err(e) => self.status_msg = format!("Wavetable error: {:#}", e),
+        }
+    }
+
+    /// Map a saved wave index (+ table key for wavetables) back to a `WaveType`,
+    /// taking a table `s` doesn't have from the ones read for the load.
+    /// Falls back to Sine with a warning if the table cannot be found.
+    fn resolve_wave(s: &mut Synth, read: &[Wavetable], idx: u8, table: Option<&str>) -> (WaveType, Option<String>) {
+        let wave = match idx {
+            1 => WaveType::Square, 2 => WaveType::Sawtooth, 3 => WaveType::Triangle,
+            4 => {
+                let Some(key) = table else {
+                    return (WaveType::Sine, Some("wavetable name missing".to_string()));
+                };
+                match s.wavetables.iter().position(|t| t.save_key() == key) {
+                    Some(i) => WaveType::Wavetable(i),
+                    None => match read.iter().find(|t| t.save_key() == key) {
+                        Some(t) => WaveType::Wavetable(s.add_wavetable(t.clone())),
+                        None    => return (WaveType::Sine, Some(format!("wavetable {} not found", key))),
+                    },
+                }
+            }
+            5 | 6 if s.granular.sample.is_none() => {
+                return (WaveType::Sine, Some("sample missing".to_string()));
+            }
+            5 => WaveType::Granular,
+            6 => WaveType::Sampler,
+            _ => WaveType::Sine,
+        };
+        (wave, None)
+    }
+
+    /// Saved p-locks as `(step, lock)`, values clamped to their ranges;
+    /// plus the first wavetable warning, if any.
+    fn resolve_locks(s: &mut Synth, read: &[Wavetable], saved: &[LockSave]) -> (Vec<(usize, PLock)>, Option<String>) {
+        let mut warn = None;
+        let locks = saved.iter().map(|l| {
+            let wave = l.wave.map(|idx| {
+                let (w, msg) = Self::resolve_wave(s, read, idx, l.wave_table.as_deref());
+                warn = warn.take().or(msg);
+                w
+            });
+            (l.step, PLock {
+                cutoff: l.cutoff.map(|c| c.clamp(80.0, 18000.0)),
+                wave,
+                volume: l.volume.map(|v| v.clamp(0.0, 1.0)),
+            })
+        }).collect();
+        (locks, warn)
+    }
+
+    /// Send a control change to the MIDI output, if one is open.
+    fn mirror_cc(&self, s: &Synth, param: CcParam, value: f32) {
+        if let Some(out) = &s.midi_out {
+            out.cc(self.cc_map.cc(param), param.to_cc(value));
+        }
+    }
+
+    // ── MIDI note routing ─────────────────────────────────────────────────
+
+    /// Flip one `NoteRouting` switch.  Notes already sent keep sounding on
+    /// the far end after a source is unrouted, so that sends all-notes-off.
+    fn midi_toggle(&mut self, label: &str, flag: fn(&mut NoteRouting) -> &mut bool) {
+        let mut s = self.synth.lock().unwrap();
+        if s.midi_out.is_none() {
+            self.status_msg = "No MIDI output (start with --midi-out)".to_string();
+            return;
+        }
+        let on = {
+            let f = flag(&mut s.midi_notes);
+            *f = !*f;
+            *f
+        };
+        if !on { if let Some(out) = &s.midi_out { out.all_notes_off(); } }
+        self.status_msg = format!("MIDI {}: {}", label, if on { "on" } else { "off" });
+    }
+
+    pub fn midi_toggle_keys(&mut self) { self.midi_toggle("keys", |r| &mut r.keys); }
+    pub fn midi_toggle_seq1(&mut self) { self.midi_toggle("seq 1", |r| &mut r.seq1); }
+    pub fn midi_toggle_seq2(&mut self) { self.midi_toggle("seq 2", |r| &mut r.seq2); }
+
+    /// Whether routed sources also play the internal synths.
+    pub fn midi_toggle_local(&mut self) { self.midi_toggle("local sound", |r| &mut r.local); }
+
+    // ── Automation ────────────────────────────────────────────────────────
+
+    /// Arm or disarm recording of param changes into automation lanes.
+    pub fn auto_rec_toggle(&mut self) {
+        let armed = !self.automation.armed;
+        self.automation.set_armed(armed);
+        self.automation.publish(&mut self.synth.lock().unwrap());
+        let lanes = self.automation.lanes.len();
+        self.status_msg = if armed {
+            "Automation: recording — param changes are written while the transport runs".to_string()
+        } else {
+            format!("Automation: recording off ({} lane{})", lanes, if lanes == 1 { "" } else { "s" })
+        };
+    }
+
+    /// Bypass or restore the lane of the focused control: the selected
+    /// knob in Effects focus, else the focused synth's volume.
+    pub fn auto_bypass_toggle(&mut self) {
+        let param = match self.mode {
+            AppMode::Effects   => self.selected_effect_param(),
+            AppMode::SynthSeq2 => Some(CcParam::Volume2),
+            _                  => Some(CcParam::Volume),
+        };
+        let Some(param) = param else {
+            self.status_msg = "Automation: no automatable control selected".to_string();
+            return;
+        };
+        let Some(lane) = self.automation.lanes.iter_mut().find(|l| l.param == param) else {
+            self.status_msg = format!("Automation: no {} lane", param.name());
+            return;
+        };
+        lane.bypass = !lane.bypass;
+        self.status_msg = format!("Automation: {} lane {}", param.name(),
+                                  if lane.bypass { "bypassed" } else { "playing" });
+        self.automation.publish(&mut self.synth.lock().unwrap());
+    }
+
+    // ── Arrangement ───────────────────────────────────────────────────────
+
+    /// Bar the playhead is in.
+    fn current_bar(&self) -> usize {
+        let pos = self.synth.lock().unwrap().step_pos;
+        (pos.max(0.0) as usize) / STEPS_PER_BAR
+    }
+
+    /// Name (or rename) a marker at the playhead's bar.
+    pub fn marker_prompt(&mut self) {
+        self.marker_bar = self.current_bar();
+        let name = self.arrangement.markers.iter().find(|m| m.bar == self.marker_bar)
+            .map_or_else(|| format!("Section {}", self.arrangement.markers.len() + 1), |m| m.name.clone());
+        self.open_prompt(InputMode::Marker, &name);
+    }
+
+    fn marker_set(&mut self, name: &str) {
+        let bar = self.marker_bar;
+        self.status_msg = if name.is_empty() {
+            "Marker: no name given".to_string()
+        } else if self.arrangement.set(bar, name) {
+            format!("Marker \"{}\" at bar {}", name, bar + 1)
+        } else {
+            format!("Marker: the arrangement is full ({} markers)", MAX_MARKERS)
+        };
+    }
+
+    /// Remove the marker of the section the playhead is in.
+    pub fn marker_remove(&mut self) {
+        let bar = self.current_bar();
+        self.status_msg = match self.arrangement.remove_section(bar) {
+            Some(m) => format!("Marker \"{}\" (bar {}) removed", m.name, m.bar + 1),
+            None    => "Marker: none at or before the playhead".to_string(),
+        };
+    }
+
+    pub fn marker_next(&mut self) {
+        let m = self.arrangement.next_after(self.current_bar()).cloned();
+        self.marker_jump(m);
+    }
+
+    pub fn marker_prev(&mut self) {
+        let m = self.arrangement.prev_before(self.current_bar()).cloned();
+        self.marker_jump(m);
+    }
+
+    /// Move the transport to the start of `marker`'s bar.
+    fn marker_jump(&mut self, marker: Option<Marker>) {
+        let Some(m) = marker else {
+            self.status_msg = "Marker: none set ([F11] adds one)".to_string();
+            return;
+        };
+        let moved = self.synth.lock().unwrap().locate((m.bar * STEPS_PER_BAR) as f64);
+        self.status_msg = if moved {
+            format!("→ {} (bar {})", m.name, m.bar + 1)
+        } else {
+            "Marker: the external clock sets the position".to_string()
+        };
+    }
+
+    // ── Freeze ────────────────────────────────────────────────────────────
+
+    /// Freeze `bus`, or put a frozen one back to live synthesis.
+    pub fn freeze_toggle(&mut self, bus: Bus) {
+        if self.synth.lock().unwrap().unfreeze(bus) {
+            self.status_msg = format!("{}: live again", bus.name());
+        } else {
+            self.freeze_track(bus);
+        }
+    }
+
+    /// Render one loop of `bus` offline and play it back in place of the
+    /// pattern.  The pattern's volume, filter and effects stay live.
+    pub fn freeze_track(&mut self, bus: Bus) {
+        let render = Render::new(&self.synth.lock().unwrap(), bus);
+        let frozen = render.run();
+        let (steps, bpm) = (frozen.steps, frozen.bpm);
+        self.synth.lock().unwrap().freeze(bus, frozen);
+        self.status_msg = format!("{}: frozen ({} steps at {} BPM)", bus.name(), steps, bpm_label(bpm));
+    }
+
+    /// Move the synth 1 ↔ synth 2 crossfader (positive = towards synth 2).
+    pub fn crossfade_adjust(&mut self, delta: f32) {
+        let mut s = self.synth.lock().unwrap();
+        s.crossfade = (s.crossfade + delta).clamp(0.0, 1.0);
+        self.mirror_cc(&s, CcParam::Crossfade, s.crossfade);
+        self.automation.record(&mut s, CcParam::Crossfade);
+        let (g1, g2) = crossfade_gains(s.crossfade);
+        self.status_msg = format!("Crossfade: S1 {:.0}% / S2 {:.0}%", g1 * 100.0, g2 * 100.0);
     }
 
     pub fn volume_up(&mut self) {
+        if self.lock_volume(false, 0.05) { return; }
         let mut s = self.synth.lock().unwrap();
         s.volume = (s.volume + 0.05).min(1.0);
+        self.mirror_cc(&s, CcParam::Volume, s.volume);
+        self.automation.record(&mut s, CcParam::Volume);
         self.status_msg = format!("Vol: {:.0}%", s.volume * 100.0);
     }
 
     pub fn volume_down(&mut self) {
+        if self.lock_volume(false, -0.05) { return; }
         let mut s = self.synth.lock().unwrap();
         s.volume = (s.volume - 0.05).max(0.0);
+        self.mirror_cc(&s, CcParam::Volume, s.volume);
+        self.automation.record(&mut s, CcParam::Volume);
         self.status_msg = format!("Vol: {:.0}%", s.volume * 100.0);
     }
 
+    /// Step a 0–1 level (drum bus, master) by `delta`.
+    fn level_adjust(&mut self, param: CcParam, delta: f32, label: &str) {
+        let mut s = self.synth.lock().unwrap();
+        let v = s.param_mut(param);
+        *v = (*v + delta).clamp(0.0, 1.0);
+        let v = *v;
+        self.mirror_cc(&s, param, v);
+        self.automation.record(&mut s, param);
+        self.status_msg = format!("{}: {:.0}%", label, v * 100.0);
+    }
+
     pub fn synth2_vol_up(&mut self) {
+        if self.lock_volume(true, 0.05) { return; }
         let mut s = self.synth.lock().unwrap();
         s.volume2 = (s.volume2 + 0.05).min(1.0);
+        self.mirror_cc(&s, CcParam::Volume2, s.volume2);
+        self.automation.record(&mut s, CcParam::Volume2);
         self.status_msg = format!("Synth2 Vol: {:.0}%", s.volume2 * 100.0);
     }
 
     pub fn synth2_vol_down(&mut self) {
+        if self.lock_volume(true, -0.05) { return; }
         let mut s = self.synth.lock().unwrap();
         s.volume2 = (s.volume2 - 0.05).max(0.0);
+        self.mirror_cc(&s, CcParam::Volume2, s.volume2);
+        self.automation.record(&mut s, CcParam::Volume2);
         self.status_msg = format!("Synth2 Vol: {:.0}%", s.volume2 * 100.0);
     }
 
     /// Shared master BPM — affects both the melodic and drum sequencers.
-    pub fn bpm_up(&mut self) {
-        let mut s = self.synth.lock().unwrap();
-        s.bpm = (s.bpm + 5.0).clamp(30.0, 300.0);
-        self.status_msg = format!("BPM: {:.0}", s.bpm);
+    pub fn bpm_up(&mut self)   { self.bpm_set(|b| b + 5.0); }
+    pub fn bpm_down(&mut self) { self.bpm_set(|b| b - 5.0); }
+
+    /// Nudge the BPM by `delta`, kept on the 0.1 grid so repeated nudges
+    /// don't accumulate float error.
+    fn bpm_nudge(&mut self, delta: f32) {
+        self.bpm_set(|b| ((b + delta) * 10.0).round() / 10.0);
     }
 
-    pub fn bpm_down(&mut self) {
+    /// Apply `f` to the BPM.  Under an external clock the tempo is the
+    /// clock's, so changes are refused.
+    fn bpm_set(&mut self, f: impl FnOnce(f32) -> f32) {
         let mut s = self.synth.lock().unwrap();
-        s.bpm = (s.bpm - 5.0).clamp(30.0, 300.0);
-        self.status_msg = format!("BPM: {:.0}", s.bpm);
+        if s.ext_clock.is_some() {
+            self.status_msg = "BPM follows the external MIDI clock".to_string();
+            return;
+        }
+        s.bpm = f(s.bpm).clamp(30.0, 300.0);
+        self.mirror_cc(&s, CcParam::Bpm, s.bpm);
+        self.status_msg = format!("BPM: {}", bpm_label(s.bpm));
     }
 
+    pub fn bpm_fine_up(&mut self)   { self.bpm_nudge(0.1); }
+    pub fn bpm_fine_down(&mut self) { self.bpm_nudge(-0.1); }
+
     pub fn cycle_scale(&mut self) {
         self.release_all();
         self.scale_q.scale = self.scale_q.scale.next();
+        self.publish_scale();
         self.status_msg = if self.scale_q.scale == Scale::Off {
             "Scale: Off".to_string()
         } else {
@@ -240,9 +1445,59 @@ impl App {
         };
     }
 
+    /// Live quantize: snap sequencer notes to the scale as they play,
+    /// rather than only as they are entered.
+    pub fn toggle_live_quantize(&mut self) {
+        self.live_quantize = !self.live_quantize;
+        self.publish_scale();
+        self.status_msg = format!("Live quantize: {}", if self.live_quantize { "on" } else { "off" });
+    }
+
+    /// Hand the audio thread the scale to play sequencer notes in, and the
+    /// MIDI input thread the one to play live notes in.
+    fn publish_scale(&self) {
+        self.publish_scale_to(&mut self.synth.lock().unwrap());
+    }
+
+    fn publish_scale_to(&self, s: &mut Synth) {
+        let key = self.scale_q.active().then(|| self.scale_q.clone());
+        s.live_scale = key.clone().filter(|_| self.live_quantize);
+        s.key_scale  = key;
+    }
+
+    /// Open the custom scale editor on the current custom intervals.
+    pub fn scale_edit_open(&mut self) {
+        self.scale_draft = [false; 12];
+        for &i in self.scale_q.custom() { self.scale_draft[i as usize] = true; }
+        self.scale_cursor = 1;
+        self.input_mode = InputMode::ScaleEdit;
+    }
+
+    /// Move the editor cursor; the root (0) is always in and not selectable.
+    pub fn scale_edit_move(&mut self, delta: i32) {
+        self.scale_cursor = ((self.scale_cursor as i32 - 1 + delta).rem_euclid(11) + 1) as usize;
+    }
+
+    pub fn scale_edit_toggle(&mut self) {
+        let i = self.scale_cursor;
+        self.scale_draft[i] = !self.scale_draft[i];
+    }
+
+    /// Keep the edited intervals and switch to the custom scale.
+    fn scale_edit_commit(&mut self) {
+        let intervals: Vec<u8> = (0..12u8).filter(|&i| self.scale_draft[i as usize]).collect();
+        self.release_all();
+        self.scale_q.set_custom(&intervals);
+        self.scale_q.scale = Scale::Custom;
+        self.publish_scale();
+        self.status_msg = format!("Scale: {} Custom ({} notes)",
+            self.scale_q.root_name(), self.scale_q.custom().len());
+    }
+
     pub fn cycle_scale_root(&mut self) {
         self.release_all();
         self.scale_q.cycle_root();
+        self.publish_scale();
         self.status_msg = if self.scale_q.scale == Scale::Off {
             format!("Root: {}", self.scale_q.root_name())
         } else {
@@ -250,51 +1505,257 @@ impl App {
         };
     }
 
-    pub fn refresh_active_notes(&mut self) {
-        self.active_notes = self.synth.lock().unwrap().active_notes();
+    /// Move output to the next audio device without touching the session.
+    /// Held keys are released first so nothing sticks across the swap.
+    pub fn audio_next_device(&mut self) {
+        self.release_all();
+        self.status_msg = match self.audio.next_device() {
+            Ok(name) => format!("Audio out: {}", name),
+            Err(e)   => format!("Audio device error: {:#}", e),
+        };
     }
 
-    // ── UI read helpers ───────────────────────────────────────────────────
-
-    pub fn wave_name(&self) -> String {
-        self.synth.lock().unwrap().wave_type.name().to_string()
+    pub fn reset_xruns(&mut self) {
+        self.audio.reset_xruns();
+        self.status_msg = "Xrun counter reset".to_string();
     }
 
-    pub fn volume(&self) -> f32 { self.synth.lock().unwrap().volume }
-
-    pub fn active_note_names(&self) -> Vec<String> {
-        let mut notes = self.active_notes.clone();
-        notes.sort();
-        notes.iter().map(|&n| note_name(n)).collect()
+    /// Follow the synth's latched master peak, and clear it once it has
+    /// held for `PEAK_HOLD` without rising.
+    pub fn tick_peak_hold(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        if s.master_peak > self.peak_hold {
+            self.peak_hold  = s.master_peak;
+            self.peak_since = Instant::now();
+        } else if self.peak_since.elapsed() >= PEAK_HOLD {
+            s.master_peak   = 0.0;
+            self.peak_hold  = 0.0;
+            self.peak_since = Instant::now();
+        }
     }
 
-    pub fn highlighted_notes(&self) -> HashSet<u8> {
-        self.active_notes.iter().copied().collect()
+    pub fn reset_peak_hold(&mut self) {
+        self.synth.lock().unwrap().master_peak = 0.0;
+        self.peak_hold  = 0.0;
+        self.peak_since = Instant::now();
+        self.status_msg = "Peak hold reset".to_string();
     }
 
-    pub fn seq_playing(&self) -> bool {
-        self.synth.lock().unwrap().sequencer.playing
+    pub fn toggle_spectrogram(&mut self) {
+        self.spectrogram_on = !self.spectrogram_on;
+        self.spectrogram.clear();
+        self.status_msg = if self.spectrogram_on { "Spectrogram" } else { "Oscilloscope" }.to_string();
     }
 
-    pub fn seq2_playing(&self) -> bool {
-        self.synth.lock().unwrap().sequencer2.playing
+    /// Add a spectrogram row from the scope buffer every
+    /// `SPECTROGRAM_INTERVAL` while the spectrogram is shown.
+    pub fn tick_spectrogram(&mut self) {
+        if !self.spectrogram_on || self.spectrogram_at.elapsed() < SPECTROGRAM_INTERVAL { return; }
+        self.spectrogram_at = Instant::now();
+        let mut buf = Vec::with_capacity(SCOPE_LEN);
+        let sr = {
+            let s = self.synth.lock().unwrap();
+            s.scope_window(SCOPE_LEN, &mut buf);
+            s.sample_rate
+        };
+        self.spectrogram.push(&buf, sr);
     }
 
-    pub fn drum_playing(&self) -> bool {
-        self.synth.lock().unwrap().drum_machine.playing
+    pub fn refresh_active_notes(&mut self) {
+        self.active_notes = self.synth.lock().unwrap().active_notes();
+    }
+
+    // ── UI read helpers ───────────────────────────────────────────────────
+
+    pub fn active_note_names(&self) -> Vec<String> {
+        let mut notes = self.active_notes.clone();
+        notes.sort();
+        notes.iter().map(|&n| note_name(n)).collect()
+    }
+
+    pub fn highlighted_notes(&self) -> HashSet<u8> {
+        self.active_notes.iter().copied().collect()
+    }
+
+    /// Copy all display state out of `Synth` in one lock acquisition.
+    /// `scope_len` is how many oscilloscope samples the frame will draw.
+    pub fn snapshot(&self, scope_len: usize) -> Snapshot {
+        let s = self.synth.lock().unwrap();
+        let seq_view = |q: &crate::sequencer::Sequencer, cursor: usize| SeqView {
+            steps:        q.steps.clone(),
+            chord:        q.chord.clone(),
+            muted:        q.muted.clone(),
+            slide:        q.slide.clone(),
+            accent:       q.accent.clone(),
+            tie_repeats:  q.tie_repeats,
+            direction:    q.direction,
+            vel_humanize: q.vel_humanize,
+            octave:       q.octave.clone(),
+            nudge:        q.nudge.clone(),
+            locked:       q.locks.iter().map(|l| !l.is_empty()).collect(),
+            cursor_locks: q.locks.get(cursor).filter(|l| !l.is_empty()).map(|&l| s.lock_summary(l)),
+            num_steps:    q.num_steps,
+            current_step: q.current_step,
+            playing:      q.playing,
+        };
+        let dm = &s.drum_machine;
+        let r  = &s.fx_routing;
+        let mut scope = Vec::with_capacity(scope_len);
+        s.scope_window(scope_len, &mut scope);
+        let mut stereo = Vec::with_capacity(STEREO_LEN);
+        s.stereo_window(STEREO_LEN, &mut stereo);
+        Snapshot {
+            bpm:     s.bpm,
+            volume:  s.volume,
+            volume2: s.volume2,
+            wave:    s.wave_name(s.wave_type).to_string(),
+            wave2:   s.wave_name(s.wave_type2).to_string(),
+            band_limited: s.band_limited,
+            harmonics:  s.harmonics,
+            granular:   s.granular.clone(),
+            acid:       s.acid.clone(),
+            mono:       [s.mono, s.mono2],
+            adsr:       [s.adsr(false), s.adsr(true)],
+            lfos:       s.lfos,
+            inserts:    [&s.fx, &s.fx2].map(|c| c.effects.iter().map(|e| e.name()).collect()),
+            pan:        [s.pan, s.pan2],
+            seq:     seq_view(&s.sequencer, self.seq_cursor),
+            seq2:    seq_view(&s.sequencer2, self.seq2_cursor),
+            drums: DrumsView {
+                tracks: dm.tracks.iter().map(|t| DrumTrackView {
+                    kind: t.kind, steps: t.steps.clone(), conds: t.conds.clone(), nudge: t.nudge.clone(),
+                    muted: t.muted, volume: t.volume, pan: t.pan, pitch: t.pitch, label: t.label().to_string(),
+                    sample: t.sample.as_ref().map(|smp| smp.name.clone()),
+                    retrig: t.retrig, retrig_hits: t.retrig_hits,
+                }).collect(),
+                num_steps:    dm.num_steps,
+                current_step: dm.current_step,
+                playing:      dm.playing,
+                fill:         dm.fill,
+                loop_count:   dm.loop_count,
+                groove:       s.groove,
+            },
+            fx: FxView {
+                reverb: FxRow {
+                    enabled: s.reverb.enabled,
+                    params:  [s.reverb.room_size, s.reverb.damping, s.reverb.mix],
+                    sends:   [r.s1_reverb, r.s2_reverb, r.dr_reverb],
+                    wet:     Some(s.fx_meters[0].level()),
+                },
+                reverb_er: s.reverb.er_amount,
+                reverb_shimmer: s.reverb.shimmer,
+                delay: FxRow {
+                    enabled: s.delay.enabled,
+                    params:  [s.delay.delay_ms(), s.delay.feedback, s.delay.mix],
+                    sends:   [r.s1_delay, r.s2_delay, r.dr_delay],
+                    wet:     Some(s.fx_meters[1].level()),
+                },
+                dist: FxRow {
+                    enabled: s.distortion.enabled,
+                    params:  [s.distortion.drive, s.distortion.tone, s.distortion.level],
+                    sends:   [r.s1_dist, r.s2_dist, r.dr_dist],
+                    wet:     Some(s.fx_meters[2].level()),
+                },
+                dist_mode: s.distortion.mode,
+                dist_asym: s.distortion.asymmetry,
+                delay_taps: s.delay.taps.len(),
+                delay_reverse: s.delay.reverse,
+                delay_sync: s.delay.sync.then_some(s.delay.division),
+                delay_duck: (s.delay.duck_amount, s.delay.duck_release),
+                sidechain: FxRow {
+                    enabled: s.sidechain.enabled,
+                    params:  [s.sidechain.depth, s.sidechain.release_ms, s.sidechain.shape.index() as f32],
+                    sends:   [s.sidechain.duck_s1 as u8 as f32, s.sidechain.duck_s2 as u8 as f32, 0.0],
+                    wet:     None,
+                },
+                filter1: FilterRow {
+                    enabled: s.filter1.enabled, mode: s.filter1.mode,
+                    cutoff:  s.filter1.cutoff,  q:    s.filter1.q,
+                    gain_db: s.filter1.gain_db,
+                },
+                filter2: FilterRow {
+                    enabled: s.filter2.enabled, mode: s.filter2.mode,
+                    cutoff:  s.filter2.cutoff,  q:    s.filter2.q,
+                    gain_db: s.filter2.gain_db,
+                },
+                comb: FxRow {
+                    enabled: s.comb.enabled,
+                    params:  [s.comb.freq, s.comb.feedback, s.comb.mix],
+                    sends:   [r.s1_comb, r.s2_comb, r.dr_comb],
+                    wet:     Some(s.fx_meters[3].level()),
+                },
+                formant: FxRow {
+                    enabled: s.formant.enabled,
+                    params:  [s.formant.vowel, s.formant.q, s.formant.mix],
+                    sends:   [!s.formant.on_s2 as u8 as f32, s.formant.on_s2 as u8 as f32, 0.0],
+                    wet:     None,
+                },
+                deesser: FxRow {
+                    enabled: s.deesser.enabled,
+                    params:  [s.deesser.freq, s.deesser.threshold, s.deesser.amount],
+                    sends:   [0.0, 0.0, 1.0],
+                    wet:     None,
+                },
+                deesser_gr: s.deesser.reduction_db(),
+                spectral: FreezeRow { held: s.spectral.held(), mix: s.spectral.mix, fade_ms: s.spectral.fade_ms },
+                chorus: FxRow {
+                    enabled: s.chorus.enabled,
+                    params:  [s.chorus.rate, s.chorus.depth, s.chorus.mix],
+                    sends:   [r.s1_chorus, r.s2_chorus, r.dr_chorus],
+                    wet:     Some(s.fx_meters[4].level()),
+                },
+                crusher: FxRow {
+                    enabled: s.crusher.enabled,
+                    params:  [s.crusher.bits as f32, s.crusher.downsample as f32, s.crusher.mix],
+                    sends:   [r.s1_crush, r.s2_crush, r.dr_crush],
+                    wet:     Some(s.fx_meters[5].level()),
+                },
+                compressor: CompRow {
+                    enabled: s.compressor.enabled,
+                    params:  [s.compressor.threshold, s.compressor.ratio, s.compressor.attack_ms,
+                              s.compressor.release_ms, s.compressor.makeup_db],
+                    reduction_db: s.compressor.reduction_db(),
+                },
+            },
+            bus_levels: s.bus_meters.map(|m| m.level()),
+            crossfade:  s.crossfade,
+            drum_volume: s.drum_volume,
+            master_gain: s.master_gain,
+            mono_sum:    s.mono_sum,
+            metronome:   s.metronome.enabled,
+            count_in:    s.metronome.beats_left(s.step_pos),
+            cue:         s.cue,
+            midi_out:   s.midi_out.as_ref().map(|m| m.target().to_string()),
+            midi_notes: s.midi_notes,
+            ext_sync:   s.ext_clock.as_ref().map(|c| c.running),
+            bar:        (s.step_pos.max(0.0) as usize) / STEPS_PER_BAR,
+            frozen:     s.frozen.each_ref().map(|f| f.as_ref().map(|f| f.bpm)),
+            arp:        s.arp.enabled.then_some((s.arp.rate, s.arp.mode)),
+            scope,
+            stereo,
+            correlation: s.correlation.value(),
+        }
     }
 
     // ── Mode cycling ──────────────────────────────────────────────────────
 
-    /// Cycle focus: Keyboard → SynthSeq → SynthSeq2 → Drums → Effects → Keyboard.
+    /// Cycle focus: Keyboard → SynthSeq → SynthSeq2 → Drums → Effects → Harmonics → Sample →
+    /// Acid → Envelope → Modulation → Inserts → Keyboard.
     pub fn toggle_mode(&mut self) {
         self.release_all();
+        self.held_step = None;
         self.mode = match self.mode {
             AppMode::Play      => AppMode::SynthSeq,
             AppMode::SynthSeq  => AppMode::SynthSeq2,
             AppMode::SynthSeq2 => AppMode::Drums,
             AppMode::Drums     => AppMode::Effects,
-            AppMode::Effects   => AppMode::Play,
+            AppMode::Effects   => AppMode::Harmonics,
+            AppMode::Harmonics => AppMode::Sample,
+            AppMode::Sample    => AppMode::Acid,
+            AppMode::Acid      => AppMode::Envelope,
+            AppMode::Envelope  => AppMode::Modulation,
+            AppMode::Modulation => AppMode::Inserts,
+            AppMode::Inserts   => AppMode::Play,
         };
         self.status_msg = match self.mode {
             AppMode::Play      => "Focus: Keyboard".to_string(),
@@ -302,31 +1763,47 @@ impl App {
             AppMode::SynthSeq2 => "Focus: Synth Seq 2".to_string(),
             AppMode::Drums     => "Focus: Drums".to_string(),
             AppMode::Effects   => "Focus: Effects".to_string(),
+            AppMode::Harmonics => "Focus: Harmonics".to_string(),
+            AppMode::Sample    => "Focus: Sample".to_string(),
+            AppMode::Acid      => "Focus: Acid".to_string(),
+            AppMode::Envelope  => "Focus: Envelope".to_string(),
+            AppMode::Modulation => "Focus: Modulation".to_string(),
+            AppMode::Inserts   => "Focus: Inserts".to_string(),
         };
     }
 
     // ── Melodic sequencer 1 controls ──────────────────────────────────────
 
     pub fn seq_cursor_left(&mut self) {
+        self.held_step = None;
         let n = self.synth.lock().unwrap().sequencer.num_steps;
         self.seq_cursor = if self.seq_cursor == 0 { n - 1 } else { self.seq_cursor - 1 };
+        self.audition_step(false);
     }
 
     pub fn seq_cursor_right(&mut self) {
-        let n = self.synth.lock().unwrap().sequencer.num_steps;
+        self.held_step = None;
+        let n = {
+            let mut s = self.synth.lock().unwrap();
+            if let Some((_, note, _)) = self.paint_key { s.sequencer.set_step(self.seq_cursor, &self.chord_notes(note)); }
+            s.sequencer.num_steps
+        };
         self.seq_cursor = (self.seq_cursor + 1) % n;
+        self.audition_step(false);
     }
 
     pub fn seq_set_note(&mut self, key: char) {
-        let Some(raw) = key_to_note(key, self.base_octave) else { return };
+        let Some(raw) = self.keymap.note_for(key, self.base_octave) else { return };
         let note = self.scale_q.quantize(raw);
+        let notes = self.chord_notes(note);
         let cursor = self.seq_cursor;
         let n = {
             let mut s = self.synth.lock().unwrap();
-            s.sequencer.set_step(cursor, note);
+            s.sequencer.set_step(cursor, &notes);
             s.sequencer.num_steps
         };
-        self.status_msg = format!("Step {}: {}", cursor + 1, note_name(note));
+        self.paint_hold(key, note);
+        self.status_msg = format!("Step {}: {}", cursor + 1, notes_label(&notes));
         self.seq_cursor = (cursor + 1) % n;
     }
 
@@ -336,9 +1813,107 @@ impl App {
         self.status_msg = format!("Step {} cleared", cursor + 1);
     }
 
+    pub fn seq_toggle_mute(&mut self) {
+        let cursor = self.seq_cursor;
+        let mut s = self.synth.lock().unwrap();
+        if s.sequencer.steps.get(cursor).copied().flatten().is_none() {
+            self.status_msg = format!("Step {} is empty", cursor + 1);
+            return;
+        }
+        let muted = s.sequencer.toggle_mute(cursor);
+        self.status_msg = format!("Step {} {}", cursor + 1, if muted { "muted" } else { "unmuted" });
+    }
+
+    /// Flip the cursor step's slide into the next note.
+    pub fn seq_toggle_slide(&mut self) {
+        let cursor = self.seq_cursor;
+        let mut s = self.synth.lock().unwrap();
+        if s.sequencer.steps.get(cursor).copied().flatten().is_none() {
+            self.status_msg = format!("Step {} is empty", cursor + 1);
+            return;
+        }
+        let slide = s.sequencer.toggle_slide(cursor);
+        self.status_msg = format!("Step {} slide {}", cursor + 1, if slide { "on" } else { "off" });
+    }
+
+    /// Step sequencer 1's (2's when `seq2`) order of play on: forward,
+    /// reverse, ping-pong, random.
+    pub fn seq_cycle_direction(&mut self, seq2: bool) {
+        let mut s = self.synth.lock().unwrap();
+        let seq = if seq2 { &mut s.sequencer2 } else { &mut s.sequencer };
+        seq.direction = seq.direction.next();
+        self.status_msg = format!("{} direction: {}", if seq2 { "Seq2" } else { "Seq" }, seq.direction.name());
+    }
+
+    /// Tie or retrigger sequencer 1's (2's when `seq2`) repeated notes.
+    pub fn seq_toggle_tie(&mut self, seq2: bool) {
+        let mut s = self.synth.lock().unwrap();
+        let seq = if seq2 { &mut s.sequencer2 } else { &mut s.sequencer };
+        seq.tie_repeats = !seq.tie_repeats;
+        self.status_msg = format!("{} repeated notes: {}", if seq2 { "Seq2" } else { "Seq" },
+                                  if seq.tie_repeats { "tied" } else { "retriggered" });
+    }
+
+    /// Step sequencer 1's (2's when `seq2`) velocity humanize up by 10%,
+    /// back to off past `VEL_HUMANIZE_MAX`.
+    pub fn seq_cycle_vel_humanize(&mut self, seq2: bool) {
+        let mut s = self.synth.lock().unwrap();
+        let seq = if seq2 { &mut s.sequencer2 } else { &mut s.sequencer };
+        let next = ((seq.vel_humanize * 10.0).round() + 1.0) / 10.0;
+        seq.vel_humanize = if next > VEL_HUMANIZE_MAX + 1e-3 { 0.0 } else { next };
+        let name = if seq2 { "Seq2" } else { "Seq" };
+        self.status_msg = match seq.vel_humanize {
+            h if h > 0.0 => format!("{} velocity humanize: notes at {:.0}–100%", name, (1.0 - h) * 100.0),
+            _            => format!("{} velocity humanize off", name),
+        };
+    }
+
+    pub fn seq_octave_up(&mut self)   { self.seq_shift_octave(1); }
+    pub fn seq_octave_down(&mut self) { self.seq_shift_octave(-1); }
+
+    fn seq_shift_octave(&mut self, delta: i8) {
+        let cursor = self.seq_cursor;
+        let mut s = self.synth.lock().unwrap();
+        if s.sequencer.steps.get(cursor).copied().flatten().is_none() {
+            self.status_msg = format!("Step {} is empty", cursor + 1);
+            return;
+        }
+        let oct = s.sequencer.shift_octave(cursor, delta);
+        self.status_msg = format!("Step {} octave {:+}", cursor + 1, oct);
+    }
+
+    /// Move the cursor step of sequencer 1 (or 2) `delta` ticks later.
+    pub fn seq_nudge(&mut self, synth2: bool, delta: i8) {
+        let (cursor, name) = if synth2 { (self.seq2_cursor, "Seq2 step") } else { (self.seq_cursor, "Step") };
+        let mut s = self.synth.lock().unwrap();
+        let seq = if synth2 { &mut s.sequencer2 } else { &mut s.sequencer };
+        if seq.steps.get(cursor).copied().flatten().is_none() {
+            self.status_msg = format!("{} {} is empty", name, cursor + 1);
+            return;
+        }
+        let n = nudge_by(seq.nudge.get_mut(cursor), delta);
+        self.status_msg = format!("{} {} nudge {}", name, cursor + 1, nudge_label(n));
+    }
+
+    /// Quantize sequencer 1 (or 2) by `quantize_strength`.
+    pub fn seq_quantize(&mut self, synth2: bool) {
+        let strength = self.quantize_strength;
+        let mut s = self.synth.lock().unwrap();
+        let seq = if synth2 { &mut s.sequencer2 } else { &mut s.sequencer };
+        seq.quantize_to_grid(strength);
+        self.status_msg = format!("{}: Quantized {:.0}%", if synth2 { "Seq2" } else { "Seq" }, strength * 100.0);
+    }
+
+    /// Step the quantize strength down through 100, 75, 50 and 25%.
+    pub fn cycle_quantize_strength(&mut self) {
+        self.quantize_strength = if self.quantize_strength <= 0.25 { 1.0 } else { self.quantize_strength - 0.25 };
+        self.status_msg = format!("Quantize strength: {:.0}%", self.quantize_strength * 100.0);
+    }
+
     pub fn seq_toggle_play(&mut self) {
         let mut s = self.synth.lock().unwrap();
-        if let Some(note) = s.sequencer.toggle_play() { s.note_off(note); }
+        for note in s.sequencer.toggle_play() { s.seq_note_off(false, note); }
+        if !s.sequencer.playing { s.release_lock(false); }
         self.status_msg = if s.sequencer.playing { "Seq: Playing".to_string() }
                           else                   { "Seq: Paused".to_string() };
     }
@@ -352,28 +1927,73 @@ impl App {
         self.status_msg = format!("Seq steps: {}", n);
     }
 
+    /// Move `q`'s notes and chords by scale degrees, each chord note on its
+    /// own so diatonic chords stay in the key.  Refused (false, nothing
+    /// changed) if any would leave the MIDI range.
+    fn transpose_steps(&self, q: &mut crate::sequencer::Sequencer, degrees: i32) -> bool {
+        let moved = q.steps.iter().zip(&q.chord)
+            .map(|(st, chord)| match *st {
+                Some(root) => {
+                    let notes = stack_notes(root, chord).into_iter()
+                        .map(|n| self.scale_q.transpose_degrees(n, degrees))
+                        .collect::<Option<Vec<u8>>>()?;
+                    let above = notes[1..].iter().map(|&n| n.saturating_sub(notes[0])).collect();
+                    Some((Some(notes[0]), above))
+                }
+                None => Some((None, Vec::new())),
+            })
+            .collect::<Option<Vec<_>>>();
+        let Some(moved) = moved else { return false };
+        (q.steps, q.chord) = moved.into_iter().unzip();
+        true
+    }
+
+    pub fn seq_degree_up(&mut self)   { self.seq_transpose_degrees(1); }
+    pub fn seq_degree_down(&mut self) { self.seq_transpose_degrees(-1); }
+
+    /// Shift the whole pattern by scale degrees.  Refused if any note would
+    /// leave the MIDI range, so the melody's shape is never distorted.
+    fn seq_transpose_degrees(&mut self, degrees: i32) {
+        let mut s = self.synth.lock().unwrap();
+        if !self.transpose_steps(&mut s.sequencer, degrees) {
+            self.status_msg = "Seq: transpose out of range".to_string();
+            return;
+        }
+        self.status_msg = format!("Seq transposed {:+} {}", degrees, self.degree_unit());
+    }
+
     // ── Melodic sequencer 2 controls ──────────────────────────────────────
 
     pub fn seq2_cursor_left(&mut self) {
+        self.held_step = None;
         let n = self.synth.lock().unwrap().sequencer2.num_steps;
         self.seq2_cursor = if self.seq2_cursor == 0 { n - 1 } else { self.seq2_cursor - 1 };
+        self.audition_step(true);
     }
 
     pub fn seq2_cursor_right(&mut self) {
-        let n = self.synth.lock().unwrap().sequencer2.num_steps;
+        self.held_step = None;
+        let n = {
+            let mut s = self.synth.lock().unwrap();
+            if let Some((_, note, _)) = self.paint_key { s.sequencer2.set_step(self.seq2_cursor, &self.chord_notes(note)); }
+            s.sequencer2.num_steps
+        };
         self.seq2_cursor = (self.seq2_cursor + 1) % n;
+        self.audition_step(true);
     }
 
     pub fn seq2_set_note(&mut self, key: char) {
-        let Some(raw) = key_to_note(key, self.base_octave) else { return };
+        let Some(raw) = self.keymap.note_for(key, self.base_octave) else { return };
         let note = self.scale_q.quantize(raw);
+        let notes = self.chord_notes(note);
         let cursor = self.seq2_cursor;
         let n = {
             let mut s = self.synth.lock().unwrap();
-            s.sequencer2.set_step(cursor, note);
+            s.sequencer2.set_step(cursor, &notes);
             s.sequencer2.num_steps
         };
-        self.status_msg = format!("Seq2 step {}: {}", cursor + 1, note_name(note));
+        self.paint_hold(key, note);
+        self.status_msg = format!("Seq2 step {}: {}", cursor + 1, notes_label(&notes));
         self.seq2_cursor = (cursor + 1) % n;
     }
 
@@ -383,9 +2003,47 @@ impl App {
         self.status_msg = format!("Seq2 step {} cleared", cursor + 1);
     }
 
+    pub fn seq2_toggle_mute(&mut self) {
+        let cursor = self.seq2_cursor;
+        let mut s = self.synth.lock().unwrap();
+        if s.sequencer2.steps.get(cursor).copied().flatten().is_none() {
+            self.status_msg = format!("Seq2 step {} is empty", cursor + 1);
+            return;
+        }
+        let muted = s.sequencer2.toggle_mute(cursor);
+        self.status_msg = format!("Seq2 step {} {}", cursor + 1, if muted { "muted" } else { "unmuted" });
+    }
+
+    /// Flip the cursor step's slide into the next note (sequencer 2).
+    pub fn seq2_toggle_slide(&mut self) {
+        let cursor = self.seq2_cursor;
+        let mut s = self.synth.lock().unwrap();
+        if s.sequencer2.steps.get(cursor).copied().flatten().is_none() {
+            self.status_msg = format!("Seq2 step {} is empty", cursor + 1);
+            return;
+        }
+        let slide = s.sequencer2.toggle_slide(cursor);
+        self.status_msg = format!("Seq2 step {} slide {}", cursor + 1, if slide { "on" } else { "off" });
+    }
+
+    pub fn seq2_octave_up(&mut self)   { self.seq2_shift_octave(1); }
+    pub fn seq2_octave_down(&mut self) { self.seq2_shift_octave(-1); }
+
+    fn seq2_shift_octave(&mut self, delta: i8) {
+        let cursor = self.seq2_cursor;
+        let mut s = self.synth.lock().unwrap();
+        if s.sequencer2.steps.get(cursor).copied().flatten().is_none() {
+            self.status_msg = format!("Seq2 step {} is empty", cursor + 1);
+            return;
+        }
+        let oct = s.sequencer2.shift_octave(cursor, delta);
+        self.status_msg = format!("Seq2 step {} octave {:+}", cursor + 1, oct);
+    }
+
     pub fn seq2_toggle_play(&mut self) {
         let mut s = self.synth.lock().unwrap();
-        if let Some(note) = s.sequencer2.toggle_play() { s.note_off2(note); }
+        for note in s.sequencer2.toggle_play() { s.seq_note_off(true, note); }
+        if !s.sequencer2.playing { s.release_lock(true); }
         self.status_msg = if s.sequencer2.playing { "Seq2: Playing".to_string() }
                           else                    { "Seq2: Paused".to_string() };
     }
@@ -399,6 +2057,106 @@ impl App {
         self.status_msg = format!("Seq2 steps: {}", n);
     }
 
+    pub fn seq2_degree_up(&mut self)   { self.seq2_transpose_degrees(1); }
+    pub fn seq2_degree_down(&mut self) { self.seq2_transpose_degrees(-1); }
+
+    /// Shift the whole pattern by scale degrees.  Refused if any note would
+    /// leave the MIDI range, so the melody's shape is never distorted.
+    fn seq2_transpose_degrees(&mut self, degrees: i32) {
+        let mut s = self.synth.lock().unwrap();
+        if !self.transpose_steps(&mut s.sequencer2, degrees) {
+            self.status_msg = "Seq2: transpose out of range".to_string();
+            return;
+        }
+        self.status_msg = format!("Seq2 transposed {:+} {}", degrees, self.degree_unit());
+    }
+
+    // ── Parameter locks ───────────────────────────────────────────────────
+
+    /// The step of sequencer 1 (or 2) held for p-lock editing, if any.
+    fn held(&self, synth2: bool) -> Option<usize> {
+        self.held_step.filter(|&(s2, _)| s2 == synth2).map(|(_, step)| step)
+    }
+
+    pub fn seq_hold_step(&mut self)  { self.hold_step(false); }
+    pub fn seq2_hold_step(&mut self) { self.hold_step(true); }
+
+    /// Hold (or let go of) the cursor step.  While a step is held, its
+    /// synth's volume, wave and cutoff keys edit the step's locks instead of
+    /// the panel.  Moving the cursor or the focus lets go.
+    fn hold_step(&mut self, synth2: bool) {
+        let (cursor, name, wave_key) = if synth2 { (self.seq2_cursor, "Seq2", "F5") } else { (self.seq_cursor, "Seq", "F1") };
+        if self.held(synth2) == Some(cursor) {
+            self.held_step = None;
+            self.status_msg = format!("{} step {} let go", name, cursor + 1);
+        } else {
+            self.held_step = Some((synth2, cursor));
+            self.status_msg = format!(
+                "{} step {} held: [-=] volume, [{}] wave, [()] cutoff lock it  [k] let go  [K] clear locks",
+                name, cursor + 1, wave_key);
+        }
+    }
+
+    pub fn seq_clear_locks(&mut self)  { self.clear_locks(false); }
+    pub fn seq2_clear_locks(&mut self) { self.clear_locks(true); }
+
+    fn clear_locks(&mut self, synth2: bool) {
+        let (cursor, name) = if synth2 { (self.seq2_cursor, "Seq2") } else { (self.seq_cursor, "Seq") };
+        let mut s = self.synth.lock().unwrap();
+        let seq = if synth2 { &mut s.sequencer2 } else { &mut s.sequencer };
+        if let Some(lock) = seq.locks.get_mut(cursor) { *lock = PLock::NONE; }
+        self.status_msg = format!("{} step {} locks cleared", name, cursor + 1);
+    }
+
+    /// Apply `edit` to the held step's locks, given the panel values to
+    /// start from for anything not locked yet.  False when no step of that
+    /// sequencer is held, so the key adjusts the panel as usual.
+    fn edit_lock(&mut self, synth2: bool, edit: impl FnOnce(&mut PLock, PLock)) -> bool {
+        let Some(step) = self.held(synth2) else { return false };
+        let mut s = self.synth.lock().unwrap();
+        let base = s.unlocked(synth2);
+        let seq = if synth2 { &mut s.sequencer2 } else { &mut s.sequencer };
+        let Some(lock) = seq.locks.get_mut(step) else { return false };
+        edit(lock, base);
+        let lock = *lock;
+        self.status_msg = format!("{} step {} locks: {}",
+                                  if synth2 { "Seq2" } else { "Seq" }, step + 1, s.lock_summary(lock));
+        true
+    }
+
+    fn lock_volume(&mut self, synth2: bool, delta: f32) -> bool {
+        self.edit_lock(synth2, |l, base| {
+            l.volume = l.volume.or(base.volume).map(|v| (v + delta).clamp(0.0, 1.0));
+        })
+    }
+
+    fn lock_wave(&mut self, synth2: bool) -> bool {
+        let tables = self.synth.lock().unwrap().wavetables.len();
+        self.edit_lock(synth2, |l, base| l.wave = l.wave.or(base.wave).map(|w| w.next(tables)))
+    }
+
+    pub fn seq_cutoff_up(&mut self)    { self.cutoff_step(false, 1.0595); }
+    pub fn seq_cutoff_down(&mut self)  { self.cutoff_step(false, 1.0 / 1.0595); }
+    pub fn seq2_cutoff_up(&mut self)   { self.cutoff_step(true, 1.0595); }
+    pub fn seq2_cutoff_down(&mut self) { self.cutoff_step(true, 1.0 / 1.0595); }
+
+    /// Move synth 1 (or 2)'s filter cutoff a semitone-ish step, or the held
+    /// step's cutoff lock.
+    fn cutoff_step(&mut self, synth2: bool, factor: f32) {
+        let step = |c: f32| (c * factor).clamp(80.0, 18000.0);
+        if self.edit_lock(synth2, |l, base| l.cutoff = l.cutoff.or(base.cutoff).map(step)) { return; }
+        let param = if synth2 { CcParam::Filter2Cutoff } else { CcParam::Filter1Cutoff };
+        let mut s = self.synth.lock().unwrap();
+        let cutoff = s.param_mut(param);
+        *cutoff = step(*cutoff);
+        let v = *cutoff;
+        self.mirror_cc(&s, param, v);
+        self.automation.record(&mut s, param);
+        let enabled = if synth2 { s.filter2.enabled } else { s.filter1.enabled };
+        self.status_msg = format!("{} Cutoff: {:.0}Hz{}", if synth2 { "S2" } else { "S1" }, v,
+                                  if enabled { "" } else { " (filter off)" });
+    }
+
     // ── Drum machine controls ─────────────────────────────────────────────
 
     pub fn drum_track_up(&mut self) {
@@ -411,6 +2169,101 @@ impl App {
         self.drum_track = (self.drum_track + 1) % n;
     }
 
+    /// Add a track below the selected one, using the selected track's sound.
+    pub fn drum_add_track(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        let dm = &mut s.drum_machine;
+        let kind = dm.tracks.get(self.drum_track).map_or(DrumKind::Kick, |t| t.kind);
+        let Some(idx) = dm.add_track(kind) else {
+            self.status_msg = format!("Kit is full ({} tracks)", MAX_TRACKS);
+            return;
+        };
+        let to = (self.drum_track + 1).min(idx);
+        dm.move_track(idx, to);
+        self.drum_track = to;
+        self.status_msg = format!("Added {} track ({} tracks)", kind.name().trim_end(), dm.tracks.len());
+    }
+
+    pub fn drum_remove_track(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        let dm = &mut s.drum_machine;
+        let Some(t) = dm.remove_track(self.drum_track) else {
+            self.status_msg = "Can't remove the last track".to_string();
+            return;
+        };
+        self.drum_track = self.drum_track.min(dm.tracks.len() - 1);
+        self.status_msg = format!("Removed {} track ({} tracks)", t.kind.name().trim_end(), dm.tracks.len());
+    }
+
+    pub fn drum_move_track_up(&mut self)   { self.drum_move_track(-1); }
+    pub fn drum_move_track_down(&mut self) { self.drum_move_track(1); }
+
+    /// Swap the selected track with its neighbour; the cursor follows it.
+    fn drum_move_track(&mut self, delta: isize) {
+        let mut s = self.synth.lock().unwrap();
+        let n = s.drum_machine.tracks.len();
+        let to = self.drum_track as isize + delta;
+        if to < 0 || to >= n as isize { return; }
+        s.drum_machine.move_track(self.drum_track, to as usize);
+        self.drum_track = to as usize;
+        self.status_msg = format!("Track moved to {}", self.drum_track + 1);
+    }
+
+    /// Give the selected track the next drum sound; its pattern is kept.
+    pub fn drum_cycle_kind(&mut self) {
+        let track = self.drum_track;
+        let mut s = self.synth.lock().unwrap();
+        let Some(kind) = s.drum_machine.tracks.get(track).map(|t| t.kind.next()) else { return };
+        s.drum_machine.set_kind(track, kind);
+        self.status_msg = format!("Track {}: {}", track + 1, kind.name().trim_end());
+    }
+
+    /// Open the name prompt for the selected track, prefilled with its name.
+    pub fn drum_rename_prompt(&mut self) {
+        let name = self.synth.lock().unwrap().drum_machine.tracks
+            .get(self.drum_track).and_then(|t| t.name.clone()).unwrap_or_default();
+        self.open_prompt(InputMode::RenameTrack, &name);
+    }
+
+    /// Open the sample prompt for the selected track, prefilled with the
+    /// path of its sample.
+    pub fn drum_sample_prompt(&mut self) {
+        let track = self.drum_track;
+        let path = self.synth.lock().unwrap().drum_machine.tracks.get(track)
+            .and_then(|t| t.sample.as_ref()).map_or_else(String::new, |smp| smp.path.clone());
+        self.open_prompt(InputMode::LoadDrumSample(track), &path);
+    }
+
+    /// Load a WAV as `track`'s sound; an empty path goes back to the
+    /// synthesised kind.  Hits already sounding finish as they started.
+    fn load_drum_sample(&mut self, track: usize, path: &str) {
+        let smp = if path.is_empty() {
+            None
+        } else {
+            // Read outside the lock; only the swap happens under it.
+            match Sample::load(path) {
+                Ok(smp) => Some(Arc::new(smp)),
+                Err(e) => { self.status_msg = format!("Sample error: {:#}", e); return; }
+            }
+        };
+        let mut s = self.synth.lock().unwrap();
+        let Some(t) = s.drum_machine.tracks.get_mut(track) else { return };
+        self.status_msg = match &smp {
+            Some(smp) => format!("{}: sample {} ({:.2} s)", t.label(), smp.name, smp.seconds()),
+            None      => format!("{}: synthesised", t.label()),
+        };
+        t.sample = smp;
+    }
+
+    /// Name the selected track; an empty name goes back to the kind's name.
+    fn drum_rename_track(&mut self, name: &str) {
+        let track = self.drum_track;
+        let mut s = self.synth.lock().unwrap();
+        let Some(t) = s.drum_machine.tracks.get_mut(track) else { return };
+        t.name = (!name.is_empty()).then(|| name.to_string());
+        self.status_msg = format!("Track {}: {}", track + 1, t.label());
+    }
+
     pub fn drum_step_left(&mut self) {
         let n = self.synth.lock().unwrap().drum_machine.num_steps;
         self.drum_step = if self.drum_step == 0 { n - 1 } else { self.drum_step - 1 };
@@ -435,11 +2288,11 @@ impl App {
         let track = self.drum_track;
         self.synth.lock().unwrap().drum_machine.toggle_mute(track);
         let muted = self.synth.lock().unwrap().drum_machine.tracks[track].muted;
-        let kind  = self.synth.lock().unwrap().drum_machine.tracks[track].kind;
+        let name  = self.synth.lock().unwrap().drum_machine.tracks[track].label().to_string();
         self.status_msg = if muted {
-            format!("{} muted", kind.name())
+            format!("{} muted", name)
         } else {
-            format!("{} unmuted", kind.name())
+            format!("{} unmuted", name)
         };
     }
 
@@ -464,8 +2317,8 @@ impl App {
         let mut s = self.synth.lock().unwrap();
         s.drum_machine.track_volume_up(track);
         let vol  = s.drum_machine.tracks[track].volume;
-        let kind = s.drum_machine.tracks[track].kind;
-        self.status_msg = format!("{} vol: {}%", kind.name(), (vol * 100.0).round() as u32);
+        let name = s.drum_machine.tracks[track].label();
+        self.status_msg = format!("{} vol: {}%", name, (vol * 100.0).round() as u32);
     }
 
     pub fn drum_vol_down(&mut self) {
@@ -473,8 +2326,34 @@ impl App {
         let mut s = self.synth.lock().unwrap();
         s.drum_machine.track_volume_down(track);
         let vol  = s.drum_machine.tracks[track].volume;
-        let kind = s.drum_machine.tracks[track].kind;
-        self.status_msg = format!("{} vol: {}%", kind.name(), (vol * 100.0).round() as u32);
+        let name = s.drum_machine.tracks[track].label();
+        self.status_msg = format!("{} vol: {}%", name, (vol * 100.0).round() as u32);
+    }
+
+    /// Move the selected track's pan by `delta` (negative = left).
+    pub fn drum_pan(&mut self, delta: f32) {
+        let track = self.drum_track;
+        let mut s = self.synth.lock().unwrap();
+        s.drum_machine.track_pan(track, delta);
+        let t = &s.drum_machine.tracks[track];
+        self.status_msg = format!("{} pan: {}", t.label(), pan_label(t.pan));
+    }
+
+    pub fn drum_pitch_up(&mut self) {
+        self.drum_pitch(1.0);
+    }
+
+    pub fn drum_pitch_down(&mut self) {
+        self.drum_pitch(-1.0);
+    }
+
+    /// Tune the selected track by `delta` semitones.
+    fn drum_pitch(&mut self, delta: f32) {
+        let track = self.drum_track;
+        let mut s = self.synth.lock().unwrap();
+        s.drum_machine.track_pitch(track, delta);
+        let t = &s.drum_machine.tracks[track];
+        self.status_msg = format!("{} pitch: {:+} st", t.label(), t.pitch.round() as i32);
     }
 
     pub fn drum_prob_up(&mut self) {
@@ -482,8 +2361,8 @@ impl App {
         let mut s = self.synth.lock().unwrap();
         s.drum_machine.step_prob_up(track, step);
         let prob = s.drum_machine.tracks[track].steps[step];
-        let kind = s.drum_machine.tracks[track].kind;
-        self.status_msg = format!("{} step {}: {}%", kind.name(), step + 1, prob);
+        let name = s.drum_machine.tracks[track].label();
+        self.status_msg = format!("{} step {}: {}%", name, step + 1, prob);
     }
 
     pub fn drum_prob_down(&mut self) {
@@ -491,58 +2370,405 @@ impl App {
         let mut s = self.synth.lock().unwrap();
         s.drum_machine.step_prob_down(track, step);
         let prob = s.drum_machine.tracks[track].steps[step];
-        let kind = s.drum_machine.tracks[track].kind;
+        let name = s.drum_machine.tracks[track].label();
         self.status_msg = if prob == 0 {
-            format!("{} step {}: OFF", kind.name(), step + 1)
+            format!("{} step {}: OFF", name, step + 1)
         } else {
-            format!("{} step {}: {}%", kind.name(), step + 1, prob)
+            format!("{} step {}: {}%", name, step + 1, prob)
         };
     }
 
     pub fn drum_swing_up(&mut self) {
         let mut s = self.synth.lock().unwrap();
-        s.drum_machine.swing = (s.drum_machine.swing + 0.05).min(0.50);
-        self.status_msg = format!("Swing: {:.0}%", s.drum_machine.swing * 100.0);
+        s.groove.amount = (s.groove.amount + 0.05).min(0.50);
+        self.status_msg = format!("Swing: {:.0}%", s.groove.amount * 100.0);
+    }
+
+    pub fn drum_swing_down(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.groove.amount = (s.groove.amount - 0.05).max(0.0);
+        self.status_msg = format!("Swing: {:.0}%", s.groove.amount * 100.0);
+    }
+
+    pub fn cycle_groove(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.groove.template = s.groove.template.next();
+        self.status_msg = format!("Groove: {}", s.groove.template.name());
+    }
+
+    /// Switch between a track-wide groove and drums-only swing.
+    pub fn toggle_groove_melodic(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.groove.melodic = !s.groove.melodic;
+        self.status_msg = if s.groove.melodic { "Groove: drums + sequencers".to_string() }
+                          else                { "Groove: drums only".to_string() };
+    }
+
+    pub fn drum_euclidean(&mut self) {
+        let track = self.drum_track;
+        let (k, name, n) = {
+            let s = self.synth.lock().unwrap();
+            let dm = &s.drum_machine;
+            let k = dm.tracks[track].steps.iter().filter(|&&p| p > 0).count();
+            let k = if k == 0 { 4 } else { k };
+            (k, dm.tracks[track].label().to_string(), dm.num_steps)
+        };
+        self.synth.lock().unwrap().drum_machine.euclidean_fill(track, k);
+        self.status_msg = format!("{}: E({},{})", name, k, n);
+    }
+
+    pub fn drum_cycle_cond(&mut self) {
+        let (track, step) = (self.drum_track, self.drum_step);
+        let mut s = self.synth.lock().unwrap();
+        s.drum_machine.cycle_cond(track, step);
+        let t = &s.drum_machine.tracks[track];
+        self.status_msg = format!("{} step {}: {}", t.label(), step + 1, t.conds[step].label());
+    }
+
+    /// Move the cursor step of the selected track `delta` ticks later.
+    pub fn drum_nudge(&mut self, delta: i8) {
+        let (track, step) = (self.drum_track, self.drum_step);
+        let mut s = self.synth.lock().unwrap();
+        let t = &mut s.drum_machine.tracks[track];
+        if t.steps.get(step).copied().unwrap_or(0) == 0 {
+            self.status_msg = format!("{} step {} is off", t.label(), step + 1);
+            return;
+        }
+        let n = nudge_by(t.nudge.get_mut(step), delta);
+        self.status_msg = format!("{} step {} nudge {}", t.label(), step + 1, nudge_label(n));
+    }
+
+    pub fn drum_cycle_retrig(&mut self) {
+        let track = self.drum_track;
+        let mut s = self.synth.lock().unwrap();
+        s.drum_machine.cycle_retrig(track);
+        self.status_msg = Self::retrig_status(&s.drum_machine.tracks[track]);
+    }
+
+    pub fn drum_cycle_retrig_hits(&mut self) {
+        let track = self.drum_track;
+        let mut s = self.synth.lock().unwrap();
+        s.drum_machine.cycle_retrig_hits(track);
+        self.status_msg = Self::retrig_status(&s.drum_machine.tracks[track]);
+    }
+
+    fn retrig_status(t: &DrumTrack) -> String {
+        if t.retrig == 0 {
+            format!("{} retrig: OFF ({} hits)", t.label(), t.retrig_hits)
+        } else {
+            format!("{} retrig: {}% × {} hits", t.label(), t.retrig, t.retrig_hits)
+        }
+    }
+
+    /// Latching fill button: terminals report no key releases, so it stays
+    /// on until pressed again.
+    pub fn drum_fill_toggle(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.drum_machine.fill = !s.drum_machine.fill;
+        self.status_msg = if s.drum_machine.fill { "Fill: ON".to_string() }
+                          else                   { "Fill: OFF".to_string() };
+    }
+
+    /// Preview a drum track by its key (the n-th drum key plays track n;
+    /// default z x c v b n m ,) — fully polyphonic.
+    pub fn drum_preview(&mut self, key: char) {
+        let Some(idx) = self.keymap.drum_for(key) else { return };
+        self.synth.lock().unwrap().drum_machine.trigger_now(idx);
+    }
+
+    // ── Additive harmonics ────────────────────────────────────────────────
+
+    /// Change the selected harmonic's amplitude by `delta`, within 0–1.
+    pub fn harm_adjust(&mut self, delta: f32) {
+        let k = self.harm_cursor;
+        let mut s = self.synth.lock().unwrap();
+        let mut amps = s.harmonics;
+        amps[k] = ((amps[k] + delta).clamp(0.0, 1.0) * 20.0).round() / 20.0;
+        s.set_harmonics(amps);
+        self.status_msg = format!("Harmonic {}: {:.0}%", k + 1, amps[k] * 100.0);
+    }
+
+    /// Back to a plain sine.
+    pub fn harm_reset(&mut self) {
+        self.synth.lock().unwrap().set_harmonics(DEFAULT_HARMONICS);
+        self.status_msg = "Harmonics: reset to sine".to_string();
+    }
+
+    /// Play the additive table on synth 1, or synth 2.
+    pub fn harm_use(&mut self, synth2: bool) {
+        let mut s = self.synth.lock().unwrap();
+        let wave = WaveType::Wavetable(ADDITIVE_TABLE);
+        if synth2 { s.wave_type2 = wave; } else { s.wave_type = wave; }
+        self.status_msg = format!("{}: Additive", if synth2 { "Synth2 Wave" } else { "Wave" });
+    }
+
+    // ── Granular / sampler sample ─────────────────────────────────────────
+
+    /// Open the sample prompt, prefilled with the loaded sample's path.
+    pub fn sample_prompt(&mut self) {
+        let path = self.synth.lock().unwrap().granular.sample.as_ref()
+            .map_or_else(String::new, |smp| smp.path.clone());
+        self.open_prompt(InputMode::LoadSample, &path);
+    }
+
+    /// Load a WAV as the granular / sampler sample, replacing the last.
+    /// Voices already on it carry on in the new one.
+    pub fn load_sample(&mut self, path: &str) {
+        // Read outside the lock; only the swap happens under it.
+        match Sample::load(path) {
+            Ok(smp) => {
+                self.status_msg = format!("Sample: {} ({:.2} s)", smp.name, smp.seconds());
+                self.synth.lock().unwrap().granular.sample = Some(Arc::new(smp));
+            }
+            Err(e) => self.status_msg = format!("Sample error: {:#}", e),
+        }
+    }
+
+    /// Step the selected setting up (`dir` 1) or down (-1): size by about
+    /// 10%, density by 1 (5 above 10 per second), position by 1%, root by a
+    /// semitone.
+    pub fn grain_adjust(&mut self, dir: f32) {
+        let mut s = self.synth.lock().unwrap();
+        let g = &mut s.granular;
+        self.status_msg = match self.grain_sel {
+            0 => {
+                g.size_ms = (g.size_ms * 1.1f32.powf(dir)).round().clamp(GRAIN_MIN_MS, GRAIN_MAX_MS);
+                format!("Grain size: {:.0} ms", g.size_ms)
+            }
+            1 => {
+                let coarse = if dir > 0.0 { g.density >= 10.0 } else { g.density > 10.0 };
+                let step = if coarse { 5.0 } else { 1.0 };
+                g.density = (g.density + dir * step).clamp(DENSITY_MIN, DENSITY_MAX);
+                format!("Grain density: {:.0}/s", g.density)
+            }
+            2 => {
+                g.position = ((g.position + dir * 0.01) * 100.0).round().clamp(0.0, 100.0) / 100.0;
+                format!("Grain position: {:.0}%", g.position * 100.0)
+            }
+            _ => {
+                g.root = (g.root as f32 + dir).clamp(0.0, 127.0) as u8;
+                format!("Sample root: {}", note_name(g.root))
+            }
+        };
+    }
+
+    /// Play the sample on synth 1, or synth 2, as `wave`: `Granular` or
+    /// `Sampler`.
+    pub fn sample_use(&mut self, synth2: bool, wave: WaveType) {
+        let mut s = self.synth.lock().unwrap();
+        if s.granular.sample.is_none() {
+            self.status_msg = format!("{}: no sample loaded (o to open one)", wave.name());
+            return;
+        }
+        if synth2 { s.wave_type2 = wave; } else { s.wave_type = wave; }
+        self.status_msg = format!("{}: {}", if synth2 { "Synth2 Wave" } else { "Wave" }, wave.name());
+    }
+
+    // ── Acid (TB-303) mode ────────────────────────────────────────────────
+
+    /// Set synth 1 up as an acid bass: saw, mono, a resonant low-pass and
+    /// the filter envelope with accents.  Patterns (and their accents and
+    /// slides) are left alone.
+    pub fn acid_preset(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.wave_type = WaveType::Sawtooth;
+        s.set_mono(false, true);
+        if !s.filter1.enabled { s.filter1.reset_state(); }
+        s.filter1.enabled = true;
+        s.filter1.mode    = FilterMode::LowPass;
+        s.filter1.cutoff  = 320.0;
+        s.filter1.q       = 7.0;
+        s.acid.enabled  = true;
+        s.acid.env_mod  = 3.5;
+        s.acid.decay_ms = 300.0;
+        s.acid.accent   = 0.6;
+        self.status_msg = "Acid preset: Saw, mono, LP 320 Hz Q 7.0, env +3.5 oct".to_string();
+    }
+
+    /// Filter envelope and accents on or off; the rest of the setup stays.
+    pub fn acid_toggle(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.acid.enabled = !s.acid.enabled;
+        if !s.acid.enabled { s.filter1.cutoff_mod = 1.0; }
+        self.status_msg = format!("Acid: {}", if s.acid.enabled { "ON" } else { "OFF" });
+    }
+
+    /// Flip the accent on sequencer 1's cursor step.
+    pub fn acid_toggle_accent(&mut self) {
+        let cursor = self.seq_cursor;
+        let mut s = self.synth.lock().unwrap();
+        if s.sequencer.steps.get(cursor).copied().flatten().is_none() {
+            self.status_msg = format!("Step {} is empty", cursor + 1);
+            return;
+        }
+        let accent = s.sequencer.toggle_accent(cursor);
+        self.status_msg = format!("Step {} accent {}", cursor + 1, if accent { "on" } else { "off" });
+    }
+
+    /// Step the selected acid setting up (`dir` 1) or down (-1).  Cutoff
+    /// and resonance are synth 1's filter's, as in the Effects panel.
+    pub fn acid_adjust(&mut self, dir: f32) {
+        let mut s = self.synth.lock().unwrap();
+        self.status_msg = match self.acid_sel {
+            0 => {
+                s.filter1.cutoff = (s.filter1.cutoff * 1.0595f32.powf(dir)).clamp(80.0, 18000.0);
+                format!("S1 Cutoff: {:.0}Hz", s.filter1.cutoff)
+            }
+            1 => {
+                s.filter1.q = (s.filter1.q + dir * 0.25).clamp(0.5, 10.0);
+                format!("S1 Q: {:.2}", s.filter1.q)
+            }
+            2 => {
+                s.acid.env_mod = (s.acid.env_mod + dir * 0.25).clamp(0.0, ENV_MOD_MAX);
+                format!("Acid env: +{:.2} oct", s.acid.env_mod)
+            }
+            3 => {
+                s.acid.decay_ms = (s.acid.decay_ms * 1.1f32.powf(dir)).round().clamp(DECAY_MIN_MS, DECAY_MAX_MS);
+                format!("Acid decay: {:.0} ms", s.acid.decay_ms)
+            }
+            _ => {
+                s.acid.accent = ((s.acid.accent + dir * 0.05) * 20.0).round().clamp(0.0, 20.0) / 20.0;
+                format!("Acid accent: {:.0}%", s.acid.accent * 100.0)
+            }
+        };
+    }
+
+    // ── Amp envelopes ─────────────────────────────────────────────────────
+
+    /// Step the selected stage of the selected synth's envelope up (`dir` 1)
+    /// or down (-1): times by 10% to the nearest ms (at least 1 ms), the
+    /// sustain level by 5%.
+    pub fn env_adjust(&mut self, dir: f32) {
+        let (synth2, sel) = (self.env_synth2, self.env_sel);
+        let mut s = self.synth.lock().unwrap();
+        let v = s.adsr_stage(synth2, sel);
+        if sel == 2 {
+            *v = ((*v + dir * 0.05) * 20.0).round().clamp(0.0, 20.0) / 20.0;
+        } else {
+            let next = (*v * 1.1f32.powf(dir) * 1000.0).round() / 1000.0;
+            let next = if next == *v { *v + dir * 0.001 } else { next };
+            *v = next.clamp(ENV_TIME_MIN, ENV_TIME_MAX);
+        }
+        let v = *v;
+        let name = ["Attack", "Decay", "Sustain", "Release"][sel];
+        self.status_msg = if sel == 2 {
+            format!("S{} {}: {:.0}%", synth2 as u8 + 1, name, v * 100.0)
+        } else {
+            format!("S{} {}: {}", synth2 as u8 + 1, name, env_time_label(v))
+        };
+    }
+
+    // ── LFOs ──────────────────────────────────────────────────────────────
+
+    /// Step the selected setting of the selected LFO by `dir` (±1): shape,
+    /// rate and target through their lists (`+` makes the rate faster),
+    /// depth by 5%.
+    pub fn lfo_adjust(&mut self, dir: i32) {
+        fn cycle<T: Copy + PartialEq>(all: &[T], cur: T, dir: i32) -> T {
+            let i = all.iter().position(|&x| x == cur).unwrap_or(0) as i32;
+            all[(i + dir).rem_euclid(all.len() as i32) as usize]
+        }
+        let mut s = self.synth.lock().unwrap();
+        let l = &mut s.lfos[self.lfo_sel];
+        let what = match self.lfo_param {
+            0 => { l.shape = cycle(&LfoShape::ALL, l.shape, dir); l.shape.name().to_string() }
+            1 => { l.rate = cycle(&LfoRate::ALL, l.rate, dir); l.rate.name().to_string() }
+            2 => {
+                l.depth = ((l.depth + dir as f32 * 0.05) * 20.0).round().clamp(0.0, 20.0) / 20.0;
+                format!("{:.0}%", l.depth * 100.0)
+            }
+            _ => { l.target = cycle(&LfoTarget::ALL, l.target, dir); l.target.name().to_string() }
+        };
+        let name = ["Shape", "Rate", "Depth", "Target"][self.lfo_param];
+        self.status_msg = format!("LFO {} {}: {}", self.lfo_sel + 1, name, what);
+    }
+
+    // ── Synth-bus inserts ─────────────────────────────────────────────────
+
+    fn insert_chain(s: &mut Synth, synth2: bool) -> &mut EffectChain {
+        if synth2 { &mut s.fx2 } else { &mut s.fx }
+    }
+
+    /// Edit the other synth's chain, from its first entry.
+    pub fn insert_synth_toggle(&mut self) {
+        self.insert_synth2 = !self.insert_synth2;
+        self.insert_sel = 0;
+        self.status_msg = format!("Inserts: synth {}", self.insert_synth2 as u8 + 1);
+    }
+
+    /// Select the next (`later`) or previous entry of the chain, wrapping.
+    pub fn insert_sel_move(&mut self, later: bool) {
+        let n = {
+            let mut s = self.synth.lock().unwrap();
+            Self::insert_chain(&mut s, self.insert_synth2).effects.len()
+        };
+        if n == 0 { return; }
+        self.insert_sel = if later { (self.insert_sel + 1) % n } else { (self.insert_sel + n - 1) % n };
+    }
+
+    /// Put a new `insert_kind` at the end of the selected synth's chain.
+    pub fn insert_add(&mut self) {
+        let sr = self.synth.lock().unwrap().sample_rate;
+        self.insert_push(self.insert_synth2, self.insert_kind.build(sr));
     }
 
-    pub fn drum_swing_down(&mut self) {
+    /// Add `fx` at the end of synth 1's chain, or synth 2's, and select it.
+    /// A chain holds up to `MAX_INSERTS`.
+    pub fn insert_push(&mut self, synth2: bool, fx: Box<dyn AudioEffect>) {
+        let name = fx.name();
         let mut s = self.synth.lock().unwrap();
-        s.drum_machine.swing = (s.drum_machine.swing - 0.05).max(0.0);
-        self.status_msg = format!("Swing: {:.0}%", s.drum_machine.swing * 100.0);
+        let chain = Self::insert_chain(&mut s, synth2);
+        if chain.effects.len() >= MAX_INSERTS {
+            self.status_msg = format!("Synth {} already has {} inserts", synth2 as u8 + 1, MAX_INSERTS);
+            return;
+        }
+        chain.effects.push(fx);
+        let n = chain.effects.len();
+        drop(s);
+        if synth2 == self.insert_synth2 { self.insert_sel = n - 1; }
+        self.status_msg = format!("Synth {} insert {}: {}", synth2 as u8 + 1, n, name);
     }
 
-    pub fn drum_euclidean(&mut self) {
-        let track = self.drum_track;
-        let (k, kind, n) = {
-            let s = self.synth.lock().unwrap();
-            let dm = &s.drum_machine;
-            let k = dm.tracks[track].steps.iter().filter(|&&p| p > 0).count();
-            let k = if k == 0 { 4 } else { k };
-            (k, dm.tracks[track].kind, dm.num_steps)
+    /// Take entry `idx` out of synth 1's chain, or synth 2's.
+    pub fn insert_remove(&mut self, synth2: bool, idx: usize) {
+        let removed = {
+            let mut s = self.synth.lock().unwrap();
+            let chain = Self::insert_chain(&mut s, synth2);
+            (idx < chain.effects.len()).then(|| chain.effects.remove(idx))
         };
-        self.synth.lock().unwrap().drum_machine.euclidean_fill(track, k);
-        self.status_msg = format!("{}: E({},{})", kind.name(), k, n);
+        // Dropped here, outside the lock: a reverb's lines are large.
+        let Some(fx) = removed else { return };
+        let n = {
+            let mut s = self.synth.lock().unwrap();
+            Self::insert_chain(&mut s, synth2).effects.len()
+        };
+        if synth2 == self.insert_synth2 { self.insert_sel = self.insert_sel.min(n.saturating_sub(1)); }
+        self.status_msg = format!("Synth {}: removed {}", synth2 as u8 + 1, fx.name());
     }
 
-    /// Preview a drum track by key: z=Kick x=Snare c=C-Hat v=O-Hat b=Clap
-    /// n=L.Tom m=M.Tom ,=H.Tom  — all fully polyphonic.
-    pub fn drum_preview(&mut self, key: char) {
-        let idx: usize = match key {
-            'z' => 0, 'x' => 1, 'c' => 2, 'v' => 3,
-            'b' => 4, 'n' => 5, 'm' => 6, ',' => 7,
-            _ => return,
-        };
-        self.synth.lock().unwrap().drum_machine.trigger_now(idx);
+    /// Swap entry `idx` of synth 1's chain, or synth 2's, with the one
+    /// before it (`up`) or after it; the selection follows it.
+    pub fn insert_move(&mut self, synth2: bool, idx: usize, up: bool) {
+        let mut s = self.synth.lock().unwrap();
+        let chain = Self::insert_chain(&mut s, synth2);
+        let n = chain.effects.len();
+        let to = if up { idx.checked_sub(1) } else { Some(idx + 1).filter(|&t| t < n) };
+        let Some(to) = to.filter(|_| idx < n) else { return };
+        chain.effects.swap(idx, to);
+        let name = chain.effects[to].name();
+        drop(s);
+        if synth2 == self.insert_synth2 { self.insert_sel = to; }
+        self.status_msg = format!("Synth {} insert {}: {}", synth2 as u8 + 1, to + 1, name);
     }
 
     // ── Effects controls ──────────────────────────────────────────────────
 
     pub fn effects_sel_up(&mut self) {
-        self.effects_sel = if self.effects_sel == 0 { 5 } else { self.effects_sel - 1 };
+        self.effects_sel = if self.effects_sel == 0 { 12 } else { self.effects_sel - 1 };
     }
 
     pub fn effects_sel_down(&mut self) {
-        self.effects_sel = (self.effects_sel + 1) % 6;
+        self.effects_sel = (self.effects_sel + 1) % 13;
     }
 
     /// Left/right cycles through params 0–5 (0-2=effect params, 3-5=send levels).
@@ -557,6 +2783,7 @@ impl App {
     /// Enter in Effects: always toggle on/off for the selected effect.
     pub fn effects_on_off(&mut self) {
         let sel = self.effects_sel;
+        if sel == 9 { self.toggle_spectral_freeze(); return; }
         let msg = {
             let mut s = self.synth.lock().unwrap();
             match sel {
@@ -574,6 +2801,24 @@ impl App {
                 5 => { s.filter2.enabled = !s.filter2.enabled;
                        if s.filter2.enabled { s.filter2.reset_state(); }
                        format!("S2 Filter: {}", if s.filter2.enabled { "ON" } else { "OFF" }) }
+                6 => { s.comb.enabled = !s.comb.enabled;
+                       if s.comb.enabled { s.comb.reset(); }
+                       format!("Comb: {}", if s.comb.enabled { "ON" } else { "OFF" }) }
+                7 => { s.formant.enabled = !s.formant.enabled;
+                       if s.formant.enabled { s.formant.reset(); }
+                       format!("Formant: {}", if s.formant.enabled { "ON" } else { "OFF" }) }
+                8 => { s.deesser.enabled = !s.deesser.enabled;
+                       if s.deesser.enabled { s.deesser.reset(); }
+                       format!("De-esser: {}", if s.deesser.enabled { "ON" } else { "OFF" }) }
+                10 => { s.chorus.enabled = !s.chorus.enabled;
+                        if s.chorus.enabled { s.chorus.reset(); }
+                        format!("Chorus: {}", if s.chorus.enabled { "ON" } else { "OFF" }) }
+                11 => { s.crusher.enabled = !s.crusher.enabled;
+                        if s.crusher.enabled { s.crusher.reset(); }
+                        format!("Bitcrusher: {}", if s.crusher.enabled { "ON" } else { "OFF" }) }
+                12 => { s.compressor.enabled = !s.compressor.enabled;
+                        if s.compressor.enabled { s.compressor.reset(); }
+                        format!("Compressor: {}", if s.compressor.enabled { "ON" } else { "OFF" }) }
                 _ => String::new()
             }
         };
@@ -585,7 +2830,7 @@ impl App {
         let sel = self.effects_sel;
         let par = self.effects_param;
 
-        if par < 3 || sel >= 4 { return; }
+        if par < 3 || matches!(sel, 4 | 5 | 8 | 9 | 12) { return; }
 
         let ri = par - 3;
         let msg = {
@@ -600,8 +2845,20 @@ impl App {
                 (2, 0) => { s.fx_routing.s1_dist   = if s.fx_routing.s1_dist   > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.s1_dist,   "S1→Dst") }
                 (2, 1) => { s.fx_routing.s2_dist   = if s.fx_routing.s2_dist   > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.s2_dist,   "S2→Dst") }
                 (2, 2) => { s.fx_routing.dr_dist   = if s.fx_routing.dr_dist   > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.dr_dist,   "DR→Dst") }
+                (6, 0) => { s.fx_routing.s1_comb   = if s.fx_routing.s1_comb   > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.s1_comb,   "S1→Cmb") }
+                (6, 1) => { s.fx_routing.s2_comb   = if s.fx_routing.s2_comb   > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.s2_comb,   "S2→Cmb") }
+                (6, 2) => { s.fx_routing.dr_comb   = if s.fx_routing.dr_comb   > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.dr_comb,   "DR→Cmb") }
+                (10, 0) => { s.fx_routing.s1_chorus = if s.fx_routing.s1_chorus > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.s1_chorus, "S1→Cho") }
+                (10, 1) => { s.fx_routing.s2_chorus = if s.fx_routing.s2_chorus > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.s2_chorus, "S2→Cho") }
+                (10, 2) => { s.fx_routing.dr_chorus = if s.fx_routing.dr_chorus > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.dr_chorus, "DR→Cho") }
+                (11, 0) => { s.fx_routing.s1_crush  = if s.fx_routing.s1_crush  > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.s1_crush,  "S1→Bit") }
+                (11, 1) => { s.fx_routing.s2_crush  = if s.fx_routing.s2_crush  > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.s2_crush,  "S2→Bit") }
+                (11, 2) => { s.fx_routing.dr_crush  = if s.fx_routing.dr_crush  > 0.5 { 0.0 } else { 1.0 }; (s.fx_routing.dr_crush,  "DR→Bit") }
                 (3, 0) => { s.sidechain.duck_s1 = !s.sidechain.duck_s1; (s.sidechain.duck_s1 as u8 as f32, "SC→S1") }
                 (3, 1) => { s.sidechain.duck_s2 = !s.sidechain.duck_s2; (s.sidechain.duck_s2 as u8 as f32, "SC→S2") }
+                // On one bus at a time: the column picks it.
+                (7, 0) => { s.formant.on_s2 = false; s.formant.reset(); (1.0, "Fmt→S1") }
+                (7, 1) => { s.formant.on_s2 = true;  s.formant.reset(); (1.0, "Fmt→S2") }
                 _ => (0.0, ""),
             };
             format!("{}: {:.0}%", name, val * 100.0)
@@ -612,8 +2869,8 @@ impl App {
     pub fn effects_param_inc(&mut self) {
         let (sel, param) = (self.effects_sel, self.effects_param);
 
-        if param >= 3 {
-            if sel >= 4 { return; } // Filter rows have no routing sends
+        if param >= 3 && !matches!(sel, 4 | 5 | 12) {
+            if matches!(sel, 7..=9) { return; } // Insert rows have no routing sends
             let ri = param - 3;
             let msg = {
                 let mut s = self.synth.lock().unwrap();
@@ -627,6 +2884,15 @@ impl App {
                     (2, 0) => { s.fx_routing.s1_dist   = (s.fx_routing.s1_dist   + 0.05).clamp(0.0, 1.0); (s.fx_routing.s1_dist,   "S1→Dst") }
                     (2, 1) => { s.fx_routing.s2_dist   = (s.fx_routing.s2_dist   + 0.05).clamp(0.0, 1.0); (s.fx_routing.s2_dist,   "S2→Dst") }
                     (2, 2) => { s.fx_routing.dr_dist   = (s.fx_routing.dr_dist   + 0.05).clamp(0.0, 1.0); (s.fx_routing.dr_dist,   "DR→Dst") }
+                    (6, 0) => { s.fx_routing.s1_comb   = (s.fx_routing.s1_comb   + 0.05).clamp(0.0, 1.0); (s.fx_routing.s1_comb,   "S1→Cmb") }
+                    (6, 1) => { s.fx_routing.s2_comb   = (s.fx_routing.s2_comb   + 0.05).clamp(0.0, 1.0); (s.fx_routing.s2_comb,   "S2→Cmb") }
+                    (6, 2) => { s.fx_routing.dr_comb   = (s.fx_routing.dr_comb   + 0.05).clamp(0.0, 1.0); (s.fx_routing.dr_comb,   "DR→Cmb") }
+                    (10, 0) => { s.fx_routing.s1_chorus = (s.fx_routing.s1_chorus + 0.05).clamp(0.0, 1.0); (s.fx_routing.s1_chorus, "S1→Cho") }
+                    (10, 1) => { s.fx_routing.s2_chorus = (s.fx_routing.s2_chorus + 0.05).clamp(0.0, 1.0); (s.fx_routing.s2_chorus, "S2→Cho") }
+                    (10, 2) => { s.fx_routing.dr_chorus = (s.fx_routing.dr_chorus + 0.05).clamp(0.0, 1.0); (s.fx_routing.dr_chorus, "DR→Cho") }
+                    (11, 0) => { s.fx_routing.s1_crush  = (s.fx_routing.s1_crush  + 0.05).clamp(0.0, 1.0); (s.fx_routing.s1_crush,  "S1→Bit") }
+                    (11, 1) => { s.fx_routing.s2_crush  = (s.fx_routing.s2_crush  + 0.05).clamp(0.0, 1.0); (s.fx_routing.s2_crush,  "S2→Bit") }
+                    (11, 2) => { s.fx_routing.dr_crush  = (s.fx_routing.dr_crush  + 0.05).clamp(0.0, 1.0); (s.fx_routing.dr_crush,  "DR→Bit") }
                     _ => (0.0, ""),
                 };
                 format!("{}: {:.0}%", name, val * 100.0)
@@ -645,6 +2911,9 @@ impl App {
                                format!("Reverb Mix: {:.0}%", s.reverb.mix * 100.0) }
                     },
                     1 => match param {
+                        0 if s.delay.sync => {
+                               s.delay.step_division(true);
+                               format!("Delay Sync: {}", s.delay.division.name()) }
                         0 => { s.delay.time_ms = (s.delay.time_ms + 25.0).clamp(10.0, 1000.0);
                                format!("Delay Time: {:.0}ms", s.delay.time_ms) }
                         1 => { s.delay.feedback = (s.delay.feedback + 0.05).clamp(0.0, 0.95);
@@ -665,36 +2934,104 @@ impl App {
                                format!("SC Depth: {:.0}%", s.sidechain.depth * 100.0) }
                         1 => { s.sidechain.release_ms = (s.sidechain.release_ms + 25.0).clamp(10.0, 500.0);
                                format!("SC Release: {:.0}ms", s.sidechain.release_ms) }
-                        _ => String::new()
+                        _ => { s.sidechain.shape = s.sidechain.shape.next();
+                               format!("SC Shape: {}", s.sidechain.shape.name()) }
                     },
                     4 => match param {
                         0 => { s.filter1.mode = s.filter1.mode.next();
                                format!("S1 Filter: {}", s.filter1.mode.name()) }
                         1 => { s.filter1.cutoff = (s.filter1.cutoff * 1.0595).clamp(80.0, 18000.0);
                                format!("S1 Cutoff: {:.0}Hz", s.filter1.cutoff) }
-                        _ => { s.filter1.q = (s.filter1.q + 0.1).clamp(0.5, 10.0);
+                        2 => { s.filter1.q = (s.filter1.q + 0.1).clamp(0.5, 10.0);
                                format!("S1 Q: {:.1}", s.filter1.q) }
+                        3 => { s.filter1.gain_db = (s.filter1.gain_db + 0.5).clamp(-FILTER_MAX_GAIN_DB, FILTER_MAX_GAIN_DB);
+                               format!("S1 Gain: {:+.1}dB", s.filter1.gain_db) }
+                        _ => String::new(),
                     },
                     5 => match param {
                         0 => { s.filter2.mode = s.filter2.mode.next();
                                format!("S2 Filter: {}", s.filter2.mode.name()) }
                         1 => { s.filter2.cutoff = (s.filter2.cutoff * 1.0595).clamp(80.0, 18000.0);
                                format!("S2 Cutoff: {:.0}Hz", s.filter2.cutoff) }
-                        _ => { s.filter2.q = (s.filter2.q + 0.1).clamp(0.5, 10.0);
+                        2 => { s.filter2.q = (s.filter2.q + 0.1).clamp(0.5, 10.0);
                                format!("S2 Q: {:.1}", s.filter2.q) }
+                        3 => { s.filter2.gain_db = (s.filter2.gain_db + 0.5).clamp(-FILTER_MAX_GAIN_DB, FILTER_MAX_GAIN_DB);
+                               format!("S2 Gain: {:+.1}dB", s.filter2.gain_db) }
+                        _ => String::new(),
+                    },
+                    6 => match param {
+                        0 => { s.comb.freq = (s.comb.freq * 1.0595).clamp(COMB_MIN_HZ, COMB_MAX_HZ);
+                               format!("Comb Freq: {:.0}Hz", s.comb.freq) }
+                        1 => { s.comb.feedback = (s.comb.feedback + 0.05).clamp(0.0, 0.98);
+                               format!("Comb Feed: {:.0}%", s.comb.feedback * 100.0) }
+                        _ => { s.comb.mix = (s.comb.mix + 0.05).clamp(0.0, 1.0);
+                               format!("Comb Mix: {:.0}%", s.comb.mix * 100.0) }
+                    },
+                    7 => match param {
+                        0 => { s.formant.vowel = (s.formant.vowel + 0.25).clamp(0.0, 4.0);
+                               format!("Formant Vowel: {}", FormantFilter::vowel_label(s.formant.vowel)) }
+                        1 => { s.formant.q = (s.formant.q + 1.0).clamp(2.0, 20.0);
+                               format!("Formant Q: {:.0}", s.formant.q) }
+                        _ => { s.formant.mix = (s.formant.mix + 0.05).clamp(0.0, 1.0);
+                               format!("Formant Mix: {:.0}%", s.formant.mix * 100.0) }
+                    },
+                    8 => match param {
+                        0 => { s.deesser.freq = (s.deesser.freq * 1.0595).clamp(DEESS_MIN_HZ, DEESS_MAX_HZ);
+                               format!("De-ess Freq: {:.0}Hz", s.deesser.freq) }
+                        1 => { s.deesser.threshold = (s.deesser.threshold + 1.0).clamp(-40.0, 0.0);
+                               format!("De-ess Threshold: {:.0}dB", s.deesser.threshold) }
+                        _ => { s.deesser.amount = (s.deesser.amount + 0.05).clamp(0.0, 1.0);
+                               format!("De-ess Amount: {:.0}%", s.deesser.amount * 100.0) }
+                    },
+                    9 => match param {
+                        0 => { s.spectral.mix = (s.spectral.mix + 0.05).clamp(0.0, 1.0);
+                               format!("Freeze Mix: {:.0}%", s.spectral.mix * 100.0) }
+                        1 => { s.spectral.fade_ms = (s.spectral.fade_ms * 1.25).clamp(FREEZE_MIN_FADE_MS, FREEZE_MAX_FADE_MS);
+                               format!("Freeze Fade: {:.0}ms", s.spectral.fade_ms) }
+                        _ => String::new(),
+                    },
+                    10 => match param {
+                        0 => { s.chorus.rate = (s.chorus.rate * 1.12).clamp(CHORUS_RATE_MIN, CHORUS_RATE_MAX);
+                               format!("Chorus Rate: {:.2}Hz", s.chorus.rate) }
+                        1 => { s.chorus.depth = (s.chorus.depth + 0.05).clamp(0.0, 1.0);
+                               format!("Chorus Depth: {:.0}%", s.chorus.depth * 100.0) }
+                        _ => { s.chorus.mix = (s.chorus.mix + 0.05).clamp(0.0, 1.0);
+                               format!("Chorus Mix: {:.0}%", s.chorus.mix * 100.0) }
+                    },
+                    11 => match param {
+                        0 => { s.crusher.bits = s.crusher.bits.saturating_add(1).clamp(CRUSH_MIN_BITS, CRUSH_MAX_BITS);
+                               format!("Crush Bits: {}", s.crusher.bits) }
+                        1 => { s.crusher.downsample = s.crusher.downsample.saturating_add(1).clamp(1, CRUSH_MAX_DOWNSAMPLE);
+                               format!("Crush Downsample: ×{}", s.crusher.downsample) }
+                        _ => { s.crusher.mix = (s.crusher.mix + 0.05).clamp(0.0, 1.0);
+                               format!("Crush Mix: {:.0}%", s.crusher.mix * 100.0) }
+                    },
+                    12 => match param {
+                        0 => { s.compressor.threshold = (s.compressor.threshold + 1.0).clamp(-60.0, 0.0);
+                               format!("Comp Threshold: {:.0}dB", s.compressor.threshold) }
+                        1 => { s.compressor.ratio = (s.compressor.ratio * 1.2).clamp(1.0, 20.0);
+                               format!("Comp Ratio: {:.1}:1", s.compressor.ratio) }
+                        2 => { s.compressor.attack_ms = (s.compressor.attack_ms * 1.25).clamp(0.1, 100.0);
+                               format!("Comp Attack: {:.1}ms", s.compressor.attack_ms) }
+                        3 => { s.compressor.release_ms = (s.compressor.release_ms * 1.25).clamp(10.0, 2000.0);
+                               format!("Comp Release: {:.0}ms", s.compressor.release_ms) }
+                        4 => { s.compressor.makeup_db = (s.compressor.makeup_db + 0.5).clamp(0.0, 24.0);
+                               format!("Comp Makeup: +{:.1}dB", s.compressor.makeup_db) }
+                        _ => String::new(),
                     },
                     _ => String::new(),
                 }
             };
             self.status_msg = msg;
+            self.mirror_effect_param();
         }
     }
 
     pub fn effects_param_dec(&mut self) {
         let (sel, param) = (self.effects_sel, self.effects_param);
 
-        if param >= 3 {
-            if sel >= 4 { return; } // Filter rows have no routing sends
+        if param >= 3 && !matches!(sel, 4 | 5 | 12) {
+            if matches!(sel, 7..=9) { return; } // Insert rows have no routing sends
             let ri = param - 3;
             let msg = {
                 let mut s = self.synth.lock().unwrap();
@@ -708,6 +3045,15 @@ impl App {
                     (2, 0) => { s.fx_routing.s1_dist   = (s.fx_routing.s1_dist   - 0.05).clamp(0.0, 1.0); (s.fx_routing.s1_dist,   "S1→Dst") }
                     (2, 1) => { s.fx_routing.s2_dist   = (s.fx_routing.s2_dist   - 0.05).clamp(0.0, 1.0); (s.fx_routing.s2_dist,   "S2→Dst") }
                     (2, 2) => { s.fx_routing.dr_dist   = (s.fx_routing.dr_dist   - 0.05).clamp(0.0, 1.0); (s.fx_routing.dr_dist,   "DR→Dst") }
+                    (6, 0) => { s.fx_routing.s1_comb   = (s.fx_routing.s1_comb   - 0.05).clamp(0.0, 1.0); (s.fx_routing.s1_comb,   "S1→Cmb") }
+                    (6, 1) => { s.fx_routing.s2_comb   = (s.fx_routing.s2_comb   - 0.05).clamp(0.0, 1.0); (s.fx_routing.s2_comb,   "S2→Cmb") }
+                    (6, 2) => { s.fx_routing.dr_comb   = (s.fx_routing.dr_comb   - 0.05).clamp(0.0, 1.0); (s.fx_routing.dr_comb,   "DR→Cmb") }
+                    (10, 0) => { s.fx_routing.s1_chorus = (s.fx_routing.s1_chorus - 0.05).clamp(0.0, 1.0); (s.fx_routing.s1_chorus, "S1→Cho") }
+                    (10, 1) => { s.fx_routing.s2_chorus = (s.fx_routing.s2_chorus - 0.05).clamp(0.0, 1.0); (s.fx_routing.s2_chorus, "S2→Cho") }
+                    (10, 2) => { s.fx_routing.dr_chorus = (s.fx_routing.dr_chorus - 0.05).clamp(0.0, 1.0); (s.fx_routing.dr_chorus, "DR→Cho") }
+                    (11, 0) => { s.fx_routing.s1_crush  = (s.fx_routing.s1_crush  - 0.05).clamp(0.0, 1.0); (s.fx_routing.s1_crush,  "S1→Bit") }
+                    (11, 1) => { s.fx_routing.s2_crush  = (s.fx_routing.s2_crush  - 0.05).clamp(0.0, 1.0); (s.fx_routing.s2_crush,  "S2→Bit") }
+                    (11, 2) => { s.fx_routing.dr_crush  = (s.fx_routing.dr_crush  - 0.05).clamp(0.0, 1.0); (s.fx_routing.dr_crush,  "DR→Bit") }
                     _ => (0.0, ""),
                 };
                 format!("{}: {:.0}%", name, val * 100.0)
@@ -726,6 +3072,9 @@ impl App {
                                format!("Reverb Mix: {:.0}%", s.reverb.mix * 100.0) }
                     },
                     1 => match param {
+                        0 if s.delay.sync => {
+                               s.delay.step_division(false);
+                               format!("Delay Sync: {}", s.delay.division.name()) }
                         0 => { s.delay.time_ms = (s.delay.time_ms - 25.0).clamp(10.0, 1000.0);
                                format!("Delay Time: {:.0}ms", s.delay.time_ms) }
                         1 => { s.delay.feedback = (s.delay.feedback - 0.05).clamp(0.0, 0.95);
@@ -746,53 +3095,482 @@ impl App {
                                format!("SC Depth: {:.0}%", s.sidechain.depth * 100.0) }
                         1 => { s.sidechain.release_ms = (s.sidechain.release_ms - 25.0).clamp(10.0, 500.0);
                                format!("SC Release: {:.0}ms", s.sidechain.release_ms) }
-                        _ => String::new()
+                        _ => { s.sidechain.shape = s.sidechain.shape.prev();
+                               format!("SC Shape: {}", s.sidechain.shape.name()) }
                     },
                     4 => match param {
                         0 => { s.filter1.mode = s.filter1.mode.prev();
                                format!("S1 Filter: {}", s.filter1.mode.name()) }
                         1 => { s.filter1.cutoff = (s.filter1.cutoff / 1.0595).clamp(80.0, 18000.0);
                                format!("S1 Cutoff: {:.0}Hz", s.filter1.cutoff) }
-                        _ => { s.filter1.q = (s.filter1.q - 0.1).clamp(0.5, 10.0);
+                        2 => { s.filter1.q = (s.filter1.q - 0.1).clamp(0.5, 10.0);
                                format!("S1 Q: {:.1}", s.filter1.q) }
+                        3 => { s.filter1.gain_db = (s.filter1.gain_db - 0.5).clamp(-FILTER_MAX_GAIN_DB, FILTER_MAX_GAIN_DB);
+                               format!("S1 Gain: {:+.1}dB", s.filter1.gain_db) }
+                        _ => String::new(),
                     },
                     5 => match param {
                         0 => { s.filter2.mode = s.filter2.mode.prev();
                                format!("S2 Filter: {}", s.filter2.mode.name()) }
                         1 => { s.filter2.cutoff = (s.filter2.cutoff / 1.0595).clamp(80.0, 18000.0);
                                format!("S2 Cutoff: {:.0}Hz", s.filter2.cutoff) }
-                        _ => { s.filter2.q = (s.filter2.q - 0.1).clamp(0.5, 10.0);
+                        2 => { s.filter2.q = (s.filter2.q - 0.1).clamp(0.5, 10.0);
                                format!("S2 Q: {:.1}", s.filter2.q) }
+                        3 => { s.filter2.gain_db = (s.filter2.gain_db - 0.5).clamp(-FILTER_MAX_GAIN_DB, FILTER_MAX_GAIN_DB);
+                               format!("S2 Gain: {:+.1}dB", s.filter2.gain_db) }
+                        _ => String::new(),
+                    },
+                    6 => match param {
+                        0 => { s.comb.freq = (s.comb.freq / 1.0595).clamp(COMB_MIN_HZ, COMB_MAX_HZ);
+                               format!("Comb Freq: {:.0}Hz", s.comb.freq) }
+                        1 => { s.comb.feedback = (s.comb.feedback - 0.05).clamp(0.0, 0.98);
+                               format!("Comb Feed: {:.0}%", s.comb.feedback * 100.0) }
+                        _ => { s.comb.mix = (s.comb.mix - 0.05).clamp(0.0, 1.0);
+                               format!("Comb Mix: {:.0}%", s.comb.mix * 100.0) }
+                    },
+                    7 => match param {
+                        0 => { s.formant.vowel = (s.formant.vowel - 0.25).clamp(0.0, 4.0);
+                               format!("Formant Vowel: {}", FormantFilter::vowel_label(s.formant.vowel)) }
+                        1 => { s.formant.q = (s.formant.q - 1.0).clamp(2.0, 20.0);
+                               format!("Formant Q: {:.0}", s.formant.q) }
+                        _ => { s.formant.mix = (s.formant.mix - 0.05).clamp(0.0, 1.0);
+                               format!("Formant Mix: {:.0}%", s.formant.mix * 100.0) }
+                    },
+                    8 => match param {
+                        0 => { s.deesser.freq = (s.deesser.freq / 1.0595).clamp(DEESS_MIN_HZ, DEESS_MAX_HZ);
+                               format!("De-ess Freq: {:.0}Hz", s.deesser.freq) }
+                        1 => { s.deesser.threshold = (s.deesser.threshold - 1.0).clamp(-40.0, 0.0);
+                               format!("De-ess Threshold: {:.0}dB", s.deesser.threshold) }
+                        _ => { s.deesser.amount = (s.deesser.amount - 0.05).clamp(0.0, 1.0);
+                               format!("De-ess Amount: {:.0}%", s.deesser.amount * 100.0) }
+                    },
+                    9 => match param {
+                        0 => { s.spectral.mix = (s.spectral.mix - 0.05).clamp(0.0, 1.0);
+                               format!("Freeze Mix: {:.0}%", s.spectral.mix * 100.0) }
+                        1 => { s.spectral.fade_ms = (s.spectral.fade_ms / 1.25).clamp(FREEZE_MIN_FADE_MS, FREEZE_MAX_FADE_MS);
+                               format!("Freeze Fade: {:.0}ms", s.spectral.fade_ms) }
+                        _ => String::new(),
+                    },
+                    10 => match param {
+                        0 => { s.chorus.rate = (s.chorus.rate / 1.12).clamp(CHORUS_RATE_MIN, CHORUS_RATE_MAX);
+                               format!("Chorus Rate: {:.2}Hz", s.chorus.rate) }
+                        1 => { s.chorus.depth = (s.chorus.depth - 0.05).clamp(0.0, 1.0);
+                               format!("Chorus Depth: {:.0}%", s.chorus.depth * 100.0) }
+                        _ => { s.chorus.mix = (s.chorus.mix - 0.05).clamp(0.0, 1.0);
+                               format!("Chorus Mix: {:.0}%", s.chorus.mix * 100.0) }
+                    },
+                    11 => match param {
+                        0 => { s.crusher.bits = s.crusher.bits.saturating_sub(1).clamp(CRUSH_MIN_BITS, CRUSH_MAX_BITS);
+                               format!("Crush Bits: {}", s.crusher.bits) }
+                        1 => { s.crusher.downsample = s.crusher.downsample.saturating_sub(1).clamp(1, CRUSH_MAX_DOWNSAMPLE);
+                               format!("Crush Downsample: ×{}", s.crusher.downsample) }
+                        _ => { s.crusher.mix = (s.crusher.mix - 0.05).clamp(0.0, 1.0);
+                               format!("Crush Mix: {:.0}%", s.crusher.mix * 100.0) }
+                    },
+                    12 => match param {
+                        0 => { s.compressor.threshold = (s.compressor.threshold - 1.0).clamp(-60.0, 0.0);
+                               format!("Comp Threshold: {:.0}dB", s.compressor.threshold) }
+                        1 => { s.compressor.ratio = (s.compressor.ratio / 1.2).clamp(1.0, 20.0);
+                               format!("Comp Ratio: {:.1}:1", s.compressor.ratio) }
+                        2 => { s.compressor.attack_ms = (s.compressor.attack_ms / 1.25).clamp(0.1, 100.0);
+                               format!("Comp Attack: {:.1}ms", s.compressor.attack_ms) }
+                        3 => { s.compressor.release_ms = (s.compressor.release_ms / 1.25).clamp(10.0, 2000.0);
+                               format!("Comp Release: {:.0}ms", s.compressor.release_ms) }
+                        4 => { s.compressor.makeup_db = (s.compressor.makeup_db - 0.5).clamp(0.0, 24.0);
+                               format!("Comp Makeup: +{:.1}dB", s.compressor.makeup_db) }
+                        _ => String::new(),
                     },
                     _ => String::new(),
                 }
             };
             self.status_msg = msg;
+            self.mirror_effect_param();
         }
     }
 
-    /// Returns FX active indicators for the title bar (one lock acquisition).
-    pub fn fx_indicators(&self) -> String {
+    /// The mirrored parameter behind the selected effect knob, if any
+    /// (filter modes aren't one).
+    fn selected_effect_param(&self) -> Option<CcParam> {
+        Some(match (self.effects_sel, self.effects_param) {
+            (0, 0) => CcParam::ReverbRoom,
+            (0, 1) => CcParam::ReverbDamp,
+            (0, 2) => CcParam::ReverbMix,
+            (1, 0) => CcParam::DelayTime,
+            (1, 1) => CcParam::DelayFeedback,
+            (1, 2) => CcParam::DelayMix,
+            (2, 0) => CcParam::DistDrive,
+            (2, 1) => CcParam::DistTone,
+            (2, 2) => CcParam::DistLevel,
+            (3, 0) => CcParam::SidechainDepth,
+            (3, 1) => CcParam::SidechainRelease,
+            (4, 1) => CcParam::Filter1Cutoff,
+            (4, 2) => CcParam::Filter1Q,
+            (5, 1) => CcParam::Filter2Cutoff,
+            (5, 2) => CcParam::Filter2Q,
+            (6, 0) => CcParam::CombFreq,
+            (6, 1) => CcParam::CombFeedback,
+            (6, 2) => CcParam::CombMix,
+            (7, 0) => CcParam::FormantVowel,
+            (7, 2) => CcParam::FormantMix,
+            _      => return None,
+        })
+    }
+
+    /// Echo the selected effect knob (params 0–2) to the MIDI output.
+    fn mirror_effect_param(&mut self) {
+        let Some(param) = self.selected_effect_param() else { return };
+        let mut s = self.synth.lock().unwrap();
+        let value = *s.param_mut(param);
+        self.mirror_cc(&s, param, value);
+        self.automation.record(&mut s, param);
+    }
+
+    pub fn reverb_er_up(&mut self)   { self.reverb_er_adjust(0.05); }
+    pub fn reverb_er_down(&mut self) { self.reverb_er_adjust(-0.05); }
+
+    /// Change the reverb's early-reflection amount.
+    fn reverb_er_adjust(&mut self, delta: f32) {
+        let mut s = self.synth.lock().unwrap();
+        s.reverb.er_amount = (s.reverb.er_amount + delta).clamp(0.0, 1.0);
+        self.mirror_cc(&s, CcParam::ReverbEr, s.reverb.er_amount);
+        self.automation.record(&mut s, CcParam::ReverbEr);
+        self.status_msg = format!("Reverb ER: {:.0}%", s.reverb.er_amount * 100.0);
+    }
+
+    /// Change how much of the reverb tail is fed back an octave up.
+    fn reverb_shimmer_adjust(&mut self, delta: f32) {
+        let mut s = self.synth.lock().unwrap();
+        s.reverb.shimmer = (s.reverb.shimmer + delta).clamp(0.0, 1.0);
+        self.mirror_cc(&s, CcParam::ReverbShimmer, s.reverb.shimmer);
+        self.automation.record(&mut s, CcParam::ReverbShimmer);
+        self.status_msg = format!("Reverb Shimmer: {:.0}%", s.reverb.shimmer * 100.0);
+    }
+
+    /// Change how far the delay's echoes duck under its input.
+    fn delay_duck_adjust(&mut self, delta: f32) {
+        let mut s = self.synth.lock().unwrap();
+        s.delay.duck_amount = (s.delay.duck_amount + delta).clamp(0.0, 1.0);
+        self.mirror_cc(&s, CcParam::DelayDuck, s.delay.duck_amount);
+        self.automation.record(&mut s, CcParam::DelayDuck);
+        self.status_msg = format!("Delay Duck: {:.0}%", s.delay.duck_amount * 100.0);
+    }
+
+    /// Change how quickly the ducked echoes come back up.
+    fn delay_duck_release_adjust(&mut self, delta: f32) {
+        let mut s = self.synth.lock().unwrap();
+        s.delay.duck_release = (s.delay.duck_release + delta).clamp(50.0, 2000.0);
+        self.mirror_cc(&s, CcParam::DelayDuckRelease, s.delay.duck_release);
+        self.automation.record(&mut s, CcParam::DelayDuckRelease);
+        self.status_msg = format!("Delay Duck Release: {:.0}ms", s.delay.duck_release);
+    }
+
+    /// Change the distortion's asymmetry, snapping to exactly 0 on the way
+    /// through so the symmetric setting can be found again.
+    fn dist_asym_adjust(&mut self, delta: f32) {
+        let mut s = self.synth.lock().unwrap();
+        let v = ((s.distortion.asymmetry + delta) * 20.0).round() / 20.0;
+        s.distortion.asymmetry = v.clamp(-1.0, 1.0);
+        self.mirror_cc(&s, CcParam::DistAsym, s.distortion.asymmetry);
+        self.automation.record(&mut s, CcParam::DistAsym);
+        self.status_msg = format!("Dist Asym: {:+.0}%", s.distortion.asymmetry * 100.0);
+    }
+
+    pub fn dist_cycle_mode(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.distortion.mode = s.distortion.mode.next();
+        self.status_msg = format!("Dist Mode: {}", s.distortion.mode.name());
+    }
+
+    /// Open the delay note-value prompt, prefilled with the synced division
+    /// or the one nearest the free time.
+    pub fn delay_note_prompt(&mut self) {
         let s = self.synth.lock().unwrap();
-        let mut ind = String::new();
-        if s.reverb.enabled     { ind.push_str("  ▶RVB"); }
-        if s.delay.enabled      { ind.push_str("  ▶DLY"); }
-        if s.distortion.enabled { ind.push_str("  ▶DST"); }
-        if s.sidechain.enabled  { ind.push_str("  ▶SC"); }
-        if s.filter1.enabled    { ind.push_str("  ▶F1"); }
-        if s.filter2.enabled    { ind.push_str("  ▶F2"); }
-        ind
+        let current = if s.delay.sync { s.delay.division } else { NoteValue::nearest(s.delay.time_ms, s.bpm) }.name();
+        drop(s);
+        self.open_prompt(InputMode::DelayNote, &current);
+    }
+
+    /// Set the delay time from a note value such as `1/8.` at the current BPM,
+    /// or the division it follows if synced.
+    fn set_delay_note(&mut self, text: &str) {
+        let Some(note) = NoteValue::parse(text) else {
+            self.status_msg = format!("Delay: \"{}\" is not a note value (e.g. 1/8, 1/8., 1/8T)", text);
+            return;
+        };
+        let mut s = self.synth.lock().unwrap();
+        if s.delay.sync {
+            s.delay.division = note;
+            self.status_msg = format!("Delay Sync: {}", note.name());
+            return;
+        }
+        let ms = note.ms(s.bpm);
+        if !(10.0..=1000.0).contains(&ms) {
+            self.status_msg = format!("Delay: {} is {:.0}ms at {} BPM (10–1000ms)", note.name(), ms, bpm_label(s.bpm));
+            return;
+        }
+        s.delay.time_ms = ms;
+        self.mirror_cc(&s, CcParam::DelayTime, ms);
+        self.automation.record(&mut s, CcParam::DelayTime);
+        self.status_msg = format!("Delay Time: {:.0}ms ({})", ms, note.name());
+    }
+
+    /// Step the delay from free time through the synced divisions and back.
+    pub fn delay_sync_cycle(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        let next = if s.delay.sync {
+            SYNC_DIVISIONS.iter().position(|&d| d == s.delay.division).map(|i| i + 1)
+                .filter(|&i| i < SYNC_DIVISIONS.len())
+        } else {
+            Some(0)
+        };
+        s.delay.sync = next.is_some();
+        if let Some(i) = next { s.delay.division = SYNC_DIVISIONS[i]; }
+        self.status_msg = if s.delay.sync {
+            format!("Delay Sync: {} ({:.0}ms at {} BPM)", s.delay.division.name(),
+                    s.delay.division.ms(s.bpm), bpm_label(s.bpm))
+        } else {
+            format!("Delay: free time, {:.0}ms", s.delay.time_ms)
+        };
+    }
+
+    pub fn delay_reverse_toggle(&mut self) {
+        let mut s = self.synth.lock().unwrap();
+        s.delay.reverse = !s.delay.reverse;
+        self.status_msg = format!("Delay: {}", if s.delay.reverse { "reverse" } else { "forward" });
+    }
+
+    /// Lay the delay's taps out by the next preset, from the current time.
+    pub fn delay_tap_preset(&mut self) {
+        self.tap_preset = self.tap_preset.next();
+        let mut s = self.synth.lock().unwrap();
+        s.delay.taps = self.tap_preset.taps(s.delay.time_ms);
+        self.status_msg = format!("Delay taps: {} ({} taps)", self.tap_preset.name(), s.delay.taps.len());
+    }
+
+    /// Open the tap editor, prefilled with the current taps.
+    pub fn delay_taps_prompt(&mut self) {
+        let s = self.synth.lock().unwrap();
+        let current = s.delay.taps.iter().map(|t| t.label()).collect::<Vec<_>>().join(", ");
+        drop(s);
+        self.open_prompt(InputMode::DelayTaps, &current);
+    }
+
+    fn set_delay_taps(&mut self, text: &str) {
+        let Some(taps) = parse_taps(text) else {
+            self.status_msg = format!(
+                "Delay taps: want up to {} of \"ms level pan\" (10–1000, 0–1, -1–1), comma-separated", MAX_TAPS);
+            return;
+        };
+        self.status_msg = format!("Delay taps: {}", taps.len());
+        self.synth.lock().unwrap().delay.taps = taps;
+    }
+
+    // ── Remote control ────────────────────────────────────────────────────
+
+    /// Apply a remote command.  Play/mute commands set a state rather than
+    /// flip it, so repeating one is harmless.  Errs on a track or step that
+    /// doesn't exist.  Values are clamped; NaN, which `clamp` lets through,
+    /// can only arrive over OSC and `osc::command()` refuses it.
+    pub fn apply(&mut self, cmd: Command) -> Result<()> {
+        match cmd {
+            Command::Param(param, value) => {
+                self.set_param(param, value);
+            }
+            Command::Note { note, on } => {
+                let mut s = self.synth.lock().unwrap();
+                if on { s.key_on(note) } else { s.key_off(note) }
+            }
+            Command::SeqPlay { seq2, on } => {
+                let s = self.synth.lock().unwrap();
+                let playing = if seq2 { s.sequencer2.playing } else { s.sequencer.playing };
+                drop(s);
+                if playing != on {
+                    if seq2 { self.seq2_toggle_play() } else { self.seq_toggle_play() }
+                }
+            }
+            Command::DrumPlay(on) => {
+                if self.synth.lock().unwrap().drum_machine.playing != on { self.drum_toggle_play(); }
+            }
+            Command::SeqStep { seq2, step, note } => {
+                let mut s = self.synth.lock().unwrap();
+                let q = if seq2 { &mut s.sequencer2 } else { &mut s.sequencer };
+                let label = if seq2 { "Seq2" } else { "Seq" };
+                if step >= q.num_steps { bail!("{} has no step {}", label, step); }
+                match note {
+                    Some(n) => q.set_step(step, &[n]),
+                    None    => q.clear_step(step),
+                }
+                self.status_msg = format!("{} step {}: {}", label, step + 1, note.map_or("—".to_string(), note_name));
+            }
+            Command::DrumStep { track, step, prob } => {
+                let mut s = self.synth.lock().unwrap();
+                let t = s.drum_machine.tracks.get_mut(track).with_context(|| format!("no drum track {}", track))?;
+                let Some(slot) = t.steps.get_mut(step) else { bail!("{} has no step {}", t.label(), step) };
+                *slot = prob;
+                self.status_msg = format!("{} step {}: {}%", t.label(), step + 1, prob);
+            }
+            Command::DrumMute { track, on } => {
+                let mut s = self.synth.lock().unwrap();
+                let t = s.drum_machine.tracks.get_mut(track).with_context(|| format!("no drum track {}", track))?;
+                t.muted = on;
+                self.status_msg = format!("{} {}", t.label(), if on { "muted" } else { "unmuted" });
+            }
+            Command::DrumVolume { track, volume } => {
+                let mut s = self.synth.lock().unwrap();
+                let t = s.drum_machine.tracks.get_mut(track).with_context(|| format!("no drum track {}", track))?;
+                t.volume = volume.clamp(0.0, 1.0);
+                self.status_msg = format!("{} vol: {}%", t.label(), (t.volume * 100.0).round() as u32);
+            }
+        }
+        Ok(())
+    }
+
+    /// Set any mirrored parameter directly, in its own units.
+    pub fn set_param(&mut self, param: CcParam, value: f32) {
+        let (lo, hi, _) = param.range();
+        let v = value.clamp(lo, hi);
+        if param == CcParam::Bpm { return self.bpm_set(|_| v); }
+        let mut s = self.synth.lock().unwrap();
+        *s.param_mut(param) = v;
+        self.mirror_cc(&s, param, v);
+        self.automation.record(&mut s, param);
+        self.status_msg = format!("{}: {}", param.name(), if hi <= 1.0 { format!("{:.0}%", v * 100.0) } else { format!("{:.1}", v) });
+    }
+
+    // ── Clearing patterns ─────────────────────────────────────────────────
+
+    /// Ask before clearing the pattern in focus (sequencer 1 in acid mode).
+    pub fn clear_pattern_prompt(&mut self) {
+        let target = match self.mode {
+            AppMode::SynthSeq | AppMode::Acid => ClearTarget::Seq1,
+            AppMode::SynthSeq2 => ClearTarget::Seq2,
+            AppMode::Drums => ClearTarget::Drums,
+            _ => {
+                self.status_msg = "Clear: focus a sequencer or the drums first".to_string();
+                return;
+            }
+        };
+        self.open_prompt(InputMode::ConfirmClear(target), "");
+    }
+
+    /// Ask before clearing both sequencers and the drums.
+    pub fn clear_all_prompt(&mut self) {
+        self.open_prompt(InputMode::ConfirmClear(ClearTarget::All), "");
+    }
+
+    /// Empty the focused sequencer or drum pattern; see `clear()`.
+    pub fn clear_focused_pattern(&mut self) {
+        match self.mode {
+            AppMode::SynthSeq | AppMode::Acid => self.clear(ClearTarget::Seq1),
+            AppMode::SynthSeq2 => self.clear(ClearTarget::Seq2),
+            AppMode::Drums => self.clear(ClearTarget::Drums),
+            _ => {}
+        }
+    }
+
+    /// Empty both sequencers and the drums, keeping every synth, effect and
+    /// sequencer setting; see `clear()`.
+    pub fn clear_all(&mut self) {
+        self.clear(ClearTarget::All);
+    }
+
+    /// Empty `target`'s steps.  Lengths, tracks and settings stay; a frozen
+    /// render of a cleared bus goes, as it no longer matches.  The old steps
+    /// are kept for `undo_clear()` until the next clear.
+    fn clear(&mut self, target: ClearTarget) {
+        let all = target == ClearTarget::All;
+        let mut s = self.synth.lock().unwrap();
+        let mut filled = 0;
+        let mut cleared = Cleared { target, seq1: None, seq2: None, drums: None };
+        if all || target == ClearTarget::Seq1 {
+            filled += s.sequencer.filled();
+            s.release_lock(false);
+            s.unfreeze(Bus::Synth1);
+            cleared.seq1 = Some(s.sequencer.clear());
+        }
+        if all || target == ClearTarget::Seq2 {
+            filled += s.sequencer2.filled();
+            s.release_lock(true);
+            s.unfreeze(Bus::Synth2);
+            cleared.seq2 = Some(s.sequencer2.clear());
+        }
+        if all || target == ClearTarget::Drums {
+            filled += s.drum_machine.filled();
+            s.unfreeze(Bus::Drums);
+            cleared.drums = Some(s.drum_machine.clear_pattern());
+        }
+        drop(s);
+        self.held_step = None;
+        self.cleared = Some(cleared);
+        self.status_msg = format!("Cleared {} ({} steps) — [^Z] restores", target.name(), filled);
+    }
+
+    /// Put back the steps the last clear removed.
+    pub fn undo_clear(&mut self) {
+        let Some(c) = self.cleared.take() else {
+            self.status_msg = "Undo clear: nothing cleared".to_string();
+            return;
+        };
+        let mut s = self.synth.lock().unwrap();
+        if let Some(p) = c.seq1  { s.sequencer.restore(p); }
+        if let Some(p) = c.seq2  { s.sequencer2.restore(p); }
+        if let Some(p) = c.drums { s.drum_machine.restore_pattern(p); }
+        self.status_msg = format!("Restored {}", c.target.name());
     }
 
     // ── Persistence ───────────────────────────────────────────────────────
 
     pub fn save(&mut self, path: &str) {
+        match serde_json::to_string_pretty(&self.save_file()) {
+            Ok(json) => match std::fs::write(path, &json) {
+                Ok(_)  => self.status_msg = format!("Saved → {}", path),
+                Err(e) => self.status_msg = format!("Save error: {}", e),
+            },
+            Err(e) => self.status_msg = format!("Serialize error: {}", e),
+        }
+    }
+
+    /// The whole project as saved to disk.
+    pub fn save_file(&self) -> SaveFile {
         fn wave_idx(w: WaveType) -> u8 {
             match w { WaveType::Sine=>0, WaveType::Square=>1,
-                      WaveType::Sawtooth=>2, WaveType::Triangle=>3 }
+                      WaveType::Sawtooth=>2, WaveType::Triangle=>3, WaveType::Wavetable(_)=>4,
+                      WaveType::Granular=>5, WaveType::Sampler=>6 }
+        }
+        fn wave_table(s: &Synth, w: WaveType) -> Option<String> {
+            match w {
+                WaveType::Wavetable(i) => s.wavetables.get(i).map(|t| t.save_key().to_string()),
+                _ => None,
+            }
+        }
+        fn locks(s: &Synth, locks: &[PLock]) -> Vec<LockSave> {
+            locks.iter().enumerate().filter(|(_, l)| !l.is_empty()).map(|(step, l)| LockSave {
+                step,
+                cutoff:     l.cutoff,
+                wave:       l.wave.map(wave_idx),
+                wave_table: l.wave.and_then(|w| wave_table(s, w)),
+                volume:     l.volume,
+            }).collect()
         }
-        fn filter_mode_idx(m: FilterMode) -> u8 {
-            match m { FilterMode::LowPass=>0, FilterMode::HighPass=>1, FilterMode::BandPass=>2 }
+        fn env_save([attack, decay, sustain, release]: [f32; 4]) -> EnvSave {
+            EnvSave { attack, decay, sustain, release }
+        }
+        fn lfo_save(l: &Lfo) -> LfoSave {
+            LfoSave {
+                shape:    LfoShape::ALL.iter().position(|&x| x == l.shape).unwrap_or(0) as u8,
+                division: Some(LfoRate::ALL.iter().position(|&x| x == l.rate).unwrap_or(0) as u8),
+                rate:     None,
+                depth:    l.depth,
+                target:   LfoTarget::ALL.iter().position(|&x| x == l.target).unwrap_or(0) as u8,
+            }
+        }
+        fn inserts_save(chain: &EffectChain) -> Vec<InsertSave> {
+            chain.effects.iter().filter_map(|fx| InsertKind::of(fx.as_ref())).map(|k| match k {
+                InsertKind::Reverb     => InsertSave::Reverb,
+                InsertKind::Delay      => InsertSave::Delay,
+                InsertKind::Distortion => InsertSave::Distortion,
+                InsertKind::Filter     => InsertSave::Filter,
+            }).collect()
         }
 
         // Copy App-level fields before taking the synth lock.
@@ -801,94 +3579,232 @@ impl App {
             .position(|&sc| sc == self.scale_q.scale)
             .unwrap_or(0) as u8;
         let scale_root = self.scale_q.root;
+        let custom_scale = self.scale_q.custom().to_vec();
+        let automation = (!self.automation.lanes.is_empty()).then(|| AutomationSave {
+            loop_steps: self.automation.loop_steps,
+            lanes: self.automation.lanes.iter().map(|l| LaneSave {
+                param:  l.param.name().to_string(),
+                points: l.points.clone(),
+                bypass: l.bypass,
+            }).collect(),
+        });
 
-        let sf = {
-            let s = self.synth.lock().unwrap();
-
-            let seq1 = SeqSave {
-                num_steps: s.sequencer.num_steps,
-                steps: s.sequencer.steps.clone(),
-            };
-            let seq2 = SeqSave {
-                num_steps: s.sequencer2.num_steps,
-                steps: s.sequencer2.steps.clone(),
-            };
+        let s = self.synth.lock().unwrap();
 
-            let drums = DrumsSave {
-                num_steps: s.drum_machine.num_steps,
-                swing:     s.drum_machine.swing,
-                tracks: s.drum_machine.tracks.iter().map(|t| TrackSave {
-                    kind:   DrumKind::ALL.iter().position(|&k| k == t.kind).unwrap_or(0) as u8,
-                    steps:  t.steps.clone(),
-                    muted:  t.muted,
-                    volume: t.volume,
-                }).collect(),
-            };
+        let seq1 = SeqSave {
+            num_steps: s.sequencer.num_steps,
+            steps: s.sequencer.steps.clone(),
+            chord: s.sequencer.chord.clone(),
+            muted: s.sequencer.muted.clone(),
+            slide: s.sequencer.slide.clone(),
+            accent: s.sequencer.accent.clone(),
+            tie_repeats: s.sequencer.tie_repeats,
+            direction: Direction::ALL.iter().position(|&d| d == s.sequencer.direction).unwrap_or(0) as u8,
+            vel_humanize: s.sequencer.vel_humanize,
+            octave: s.sequencer.octave.clone(),
+            nudge:  s.sequencer.nudge.clone(),
+            locks: locks(&s, &s.sequencer.locks),
+        };
+        let seq2 = SeqSave {
+            num_steps: s.sequencer2.num_steps,
+            steps: s.sequencer2.steps.clone(),
+            chord: s.sequencer2.chord.clone(),
+            muted: s.sequencer2.muted.clone(),
+            slide: s.sequencer2.slide.clone(),
+            accent: s.sequencer2.accent.clone(),
+            tie_repeats: s.sequencer2.tie_repeats,
+            direction: Direction::ALL.iter().position(|&d| d == s.sequencer2.direction).unwrap_or(0) as u8,
+            vel_humanize: s.sequencer2.vel_humanize,
+            octave: s.sequencer2.octave.clone(),
+            nudge:  s.sequencer2.nudge.clone(),
+            locks: locks(&s, &s.sequencer2.locks),
+        };
 
-            let reverb = ReverbSave {
-                enabled:   s.reverb.enabled,
-                room_size: s.reverb.room_size,
-                damping:   s.reverb.damping,
-                mix:       s.reverb.mix,
-            };
-            let delay = DelaySave {
-                enabled:  s.delay.enabled,
-                time_ms:  s.delay.time_ms,
-                feedback: s.delay.feedback,
-                mix:      s.delay.mix,
-            };
-            let distortion = DistSave {
-                enabled: s.distortion.enabled,
-                drive:   s.distortion.drive,
-                tone:    s.distortion.tone,
-                level:   s.distortion.level,
-            };
-            let sidechain = SidechainSave {
-                enabled:    s.sidechain.enabled,
-                depth:      s.sidechain.depth,
-                release_ms: s.sidechain.release_ms,
-                duck_s1:    s.sidechain.duck_s1,
-                duck_s2:    s.sidechain.duck_s2,
-            };
-            let filter1 = FilterSave {
-                enabled: s.filter1.enabled,
-                mode:    filter_mode_idx(s.filter1.mode),
-                cutoff:  s.filter1.cutoff,
-                q:       s.filter1.q,
-            };
-            let filter2 = FilterSave {
-                enabled: s.filter2.enabled,
-                mode:    filter_mode_idx(s.filter2.mode),
-                cutoff:  s.filter2.cutoff,
-                q:       s.filter2.q,
-            };
-            let routing = RoutingSave {
-                s1_reverb: s.fx_routing.s1_reverb, s1_delay: s.fx_routing.s1_delay, s1_dist: s.fx_routing.s1_dist,
-                s2_reverb: s.fx_routing.s2_reverb, s2_delay: s.fx_routing.s2_delay, s2_dist: s.fx_routing.s2_dist,
-                dr_reverb: s.fx_routing.dr_reverb, dr_delay: s.fx_routing.dr_delay, dr_dist: s.fx_routing.dr_dist,
-            };
+        let drums = DrumsSave {
+            num_steps: s.drum_machine.num_steps,
+            swing:     s.groove.amount,
+            tracks: s.drum_machine.tracks.iter().map(|t| TrackSave {
+                kind:   DrumKind::ALL.iter().position(|&k| k == t.kind).unwrap_or(0) as u8,
+                steps:  t.steps.clone(),
+                muted:  t.muted,
+                volume: t.volume,
+                pan:    t.pan,
+                pitch:  t.pitch,
+                name:   t.name.clone(),
+                sample: t.sample.as_ref().map(|smp| smp.path.clone()),
+                conds:  t.conds.iter().enumerate()
+                    .filter(|(_, c)| **c != TrigCondition::Always)
+                    .map(|(i, c)| (i, c.label()))
+                    .collect(),
+                nudge:       t.nudge.clone(),
+                retrig:      t.retrig,
+                retrig_hits: t.retrig_hits,
+            }).collect(),
+        };
 
-            SaveFile {
-                bpm:        s.bpm,
-                base_octave,
-                scale:      scale_idx,
-                scale_root,
-                wave1:      wave_idx(s.wave_type),
-                wave2:      wave_idx(s.wave_type2),
-                volume:     s.volume,
-                volume2:    s.volume2,
-                seq1, seq2, drums,
-                reverb, delay, distortion, sidechain,
-                filter1, filter2, routing,
-            }
+        let reverb = ReverbSave {
+            enabled:   s.reverb.enabled,
+            room_size: s.reverb.room_size,
+            damping:   s.reverb.damping,
+            mix:       s.reverb.mix,
+            er_amount: s.reverb.er_amount,
+            shimmer:   s.reverb.shimmer,
+        };
+        let delay = DelaySave {
+            enabled:  s.delay.enabled,
+            time_ms:  s.delay.time_ms,
+            feedback: s.delay.feedback,
+            mix:      s.delay.mix,
+            taps:     s.delay.taps.iter()
+                .map(|t| TapSave { time_ms: t.time_ms, level: t.level, pan: t.pan })
+                .collect(),
+            reverse:  s.delay.reverse,
+            sync:     s.delay.sync,
+            division: Some(s.delay.division.name()),
+            duck_amount:  s.delay.duck_amount,
+            duck_release: s.delay.duck_release,
+        };
+        let distortion = DistSave {
+            enabled: s.distortion.enabled,
+            drive:   s.distortion.drive,
+            tone:    s.distortion.tone,
+            level:   s.distortion.level,
+            mode:    DistMode::ALL.iter().position(|&m| m == s.distortion.mode).unwrap_or(0) as u8,
+            asymmetry: s.distortion.asymmetry,
+        };
+        let sidechain = SidechainSave {
+            enabled:    s.sidechain.enabled,
+            depth:      s.sidechain.depth,
+            release_ms: s.sidechain.release_ms,
+            shape:      s.sidechain.shape.index(),
+            duck_s1:    s.sidechain.duck_s1,
+            duck_s2:    s.sidechain.duck_s2,
+        };
+        let filter1 = FilterSave {
+            enabled: s.filter1.enabled,
+            mode:    s.filter1.mode.index(),
+            cutoff:  s.filter1.cutoff,
+            q:       s.filter1.q,
+            gain_db: s.filter1.gain_db,
+        };
+        let filter2 = FilterSave {
+            enabled: s.filter2.enabled,
+            mode:    s.filter2.mode.index(),
+            cutoff:  s.filter2.cutoff,
+            q:       s.filter2.q,
+            gain_db: s.filter2.gain_db,
+        };
+        let routing = RoutingSave {
+            s1_reverb: s.fx_routing.s1_reverb, s1_delay: s.fx_routing.s1_delay, s1_dist: s.fx_routing.s1_dist,
+            s2_reverb: s.fx_routing.s2_reverb, s2_delay: s.fx_routing.s2_delay, s2_dist: s.fx_routing.s2_dist,
+            dr_reverb: s.fx_routing.dr_reverb, dr_delay: s.fx_routing.dr_delay, dr_dist: s.fx_routing.dr_dist,
+            s1_comb:   s.fx_routing.s1_comb,   s2_comb:  s.fx_routing.s2_comb,   dr_comb: s.fx_routing.dr_comb,
+            s1_chorus: s.fx_routing.s1_chorus, s2_chorus: s.fx_routing.s2_chorus, dr_chorus: s.fx_routing.dr_chorus,
+            s1_crush:  s.fx_routing.s1_crush,  s2_crush:  s.fx_routing.s2_crush,  dr_crush:  s.fx_routing.dr_crush,
+        };
+        let comb = CombSave {
+            enabled:  s.comb.enabled,
+            freq:     s.comb.freq,
+            feedback: s.comb.feedback,
+            mix:      s.comb.mix,
+        };
+        let chorus = ChorusSave {
+            enabled: s.chorus.enabled,
+            rate:    s.chorus.rate,
+            depth:   s.chorus.depth,
+            mix:     s.chorus.mix,
+        };
+        let crusher = CrushSave {
+            enabled:    s.crusher.enabled,
+            bits:       s.crusher.bits,
+            downsample: s.crusher.downsample,
+            mix:        s.crusher.mix,
+        };
+        let compressor = CompSave {
+            enabled:    s.compressor.enabled,
+            threshold:  s.compressor.threshold,
+            ratio:      s.compressor.ratio,
+            attack_ms:  s.compressor.attack_ms,
+            release_ms: s.compressor.release_ms,
+            makeup_db:  s.compressor.makeup_db,
+        };
+        let formant = FormantSave {
+            enabled: s.formant.enabled,
+            vowel:   s.formant.vowel,
+            q:       s.formant.q,
+            mix:     s.formant.mix,
+            on_s2:   s.formant.on_s2,
+        };
+        let deesser = DeEsserSave {
+            enabled:   s.deesser.enabled,
+            freq:      s.deesser.freq,
+            threshold: s.deesser.threshold,
+            amount:    s.deesser.amount,
         };
 
-        match serde_json::to_string_pretty(&sf) {
-            Ok(json) => match std::fs::write(path, &json) {
-                Ok(_)  => self.status_msg = format!("Saved → {}", path),
-                Err(e) => self.status_msg = format!("Save error: {}", e),
-            },
-            Err(e) => self.status_msg = format!("Serialize error: {}", e),
+        SaveFile {
+            bpm:        s.bpm,
+            base_octave,
+            scale:      scale_idx,
+            scale_root,
+            custom_scale,
+            scale_live: self.live_quantize,
+            count_in_bars: self.count_in_bars,
+            wave1:      wave_idx(s.wave_type),
+            wave2:      wave_idx(s.wave_type2),
+            wave1_table: wave_table(&s, s.wave_type),
+            wave2_table: wave_table(&s, s.wave_type2),
+            band_limited: s.band_limited,
+            harmonics:  s.harmonics,
+            granular:   Some(GranularSave {
+                sample:   s.granular.sample.as_ref().map(|smp| smp.path.clone()),
+                root:     s.granular.root,
+                size_ms:  s.granular.size_ms,
+                density:  s.granular.density,
+                position: s.granular.position,
+            }),
+            volume:     s.volume,
+            volume2:    s.volume2,
+            crossfade:  s.crossfade,
+            drum_volume: Some(s.drum_volume),
+            master_gain: s.master_gain,
+            mono1:      s.mono,
+            mono2:      s.mono2,
+            pan1:       s.pan,
+            pan2:       s.pan2,
+            env1:       Some(env_save(s.adsr(false))),
+            env2:       Some(env_save(s.adsr(true))),
+            lfo1:       Some(lfo_save(&s.lfos[0])),
+            lfo2:       Some(lfo_save(&s.lfos[1])),
+            inserts1:   inserts_save(&s.fx),
+            inserts2:   inserts_save(&s.fx2),
+            seq1, seq2, drums,
+            groove_template: GrooveTemplate::ALL.iter()
+                .position(|&t| t == s.groove.template).unwrap_or(0) as u8,
+            groove_melodic: s.groove.melodic,
+            reverb, delay, distortion, sidechain,
+            filter1, filter2, routing,
+            comb: Some(comb),
+            chorus: Some(chorus),
+            crusher: Some(crusher),
+            compressor: Some(compressor),
+            formant: Some(formant),
+            deesser: Some(deesser),
+            acid: Some(AcidSave {
+                enabled:  s.acid.enabled,
+                env_mod:  s.acid.env_mod,
+                decay_ms: s.acid.decay_ms,
+                accent:   s.acid.accent,
+            }),
+            automation,
+            arp: Some(ArpSave {
+                enabled: s.arp.enabled,
+                rate:    ArpRate::ALL.iter().position(|&r| r == s.arp.rate).unwrap_or(0) as u8,
+                mode:    ArpMode::ALL.iter().position(|&m| m == s.arp.mode).unwrap_or(0) as u8,
+            }),
+            markers: self.arrangement.markers.iter()
+                .map(|m| MarkerSave { bar: m.bar, name: m.name.clone() })
+                .collect(),
         }
     }
 
@@ -903,115 +3819,489 @@ impl App {
         };
 
         self.release_all();
+        let assets = SaveAssets::read(&sf);
+        let synth = Arc::clone(&self.synth);
+        let warning = self.apply_save(&mut synth.lock().unwrap(), sf, assets);
 
-        {
-            let mut s = self.synth.lock().unwrap();
+        // Reset cursors
+        self.seq_cursor  = 0;
+        self.seq2_cursor = 0;
+        self.drum_track  = 0;
+        self.drum_step   = 0;
+        self.insert_sel  = 0;
+        self.held_step   = None;
+
+        self.status_msg = match warning {
+            Some(w) => format!("Loaded ← {} ({} — using Sine)", path, w),
+            None    => format!("Loaded ← {}", path),
+        };
+    }
 
-            s.bpm = sf.bpm.clamp(30.0, 300.0);
+    /// Put a parsed save file into `s` and the app-level fields, clamping
+    /// as it goes.  Returns the first wave or lock that couldn't be
+    /// resolved, if any.
+    fn apply_save(&mut self, s: &mut Synth, sf: SaveFile, assets: SaveAssets) -> Option<String> {
+        // The sample goes first: the granular and sampler waves need it.
+        let grains = sf.granular.unwrap_or(GranularSave {
+            sample: None, root: DEFAULT_ROOT,
+            size_ms: Granular::new().size_ms, density: Granular::new().density, position: 0.0,
+        });
+        s.granular.sample   = assets.sample;
+        s.granular.root     = grains.root.min(127);
+        s.granular.size_ms  = grains.size_ms.clamp(GRAIN_MIN_MS, GRAIN_MAX_MS);
+        s.granular.density  = grains.density.clamp(DENSITY_MIN, DENSITY_MAX);
+        s.granular.position = grains.position.clamp(0.0, 1.0);
+        let tables = &assets.tables;
+        let (wave1, warn1) = Self::resolve_wave(s, tables, sf.wave1, sf.wave1_table.as_deref());
+        let (wave2, warn2) = Self::resolve_wave(s, tables, sf.wave2, sf.wave2_table.as_deref());
+        let (locks1, warn3) = Self::resolve_locks(s, tables, &sf.seq1.locks);
+        let (locks2, warn4) = Self::resolve_locks(s, tables, &sf.seq2.locks);
 
-            s.wave_type = match sf.wave1 {
-                1 => WaveType::Square, 2 => WaveType::Sawtooth,
-                3 => WaveType::Triangle, _ => WaveType::Sine,
-            };
-            s.wave_type2 = match sf.wave2 {
-                1 => WaveType::Square, 2 => WaveType::Sawtooth,
-                3 => WaveType::Triangle, _ => WaveType::Sine,
-            };
+        // Put back what any locks in force replaced before loading over it.
+        s.release_lock(false);
+        s.release_lock(true);
 
-            s.volume  = sf.volume.clamp(0.0, 1.0);
-            s.volume2 = sf.volume2.clamp(0.0, 1.0);
-
-            // Sequencer 1
-            let n1 = sf.seq1.num_steps.clamp(1, 32);
-            s.sequencer.num_steps = n1;
-            s.sequencer.steps = sf.seq1.steps;
-            s.sequencer.steps.resize(n1, None);
-
-            // Sequencer 2
-            let n2 = sf.seq2.num_steps.clamp(1, 32);
-            s.sequencer2.num_steps = n2;
-            s.sequencer2.steps = sf.seq2.steps;
-            s.sequencer2.steps.resize(n2, None);
-
-            // Drums
-            let nd = sf.drums.num_steps.clamp(1, 32);
-            s.drum_machine.num_steps = nd;
-            s.drum_machine.swing = sf.drums.swing.clamp(0.0, 0.5);
-            let n_tracks = s.drum_machine.tracks.len().min(sf.drums.tracks.len());
-            for i in 0..n_tracks {
-                let t = &sf.drums.tracks[i];
-                s.drum_machine.tracks[i].steps = t.steps.clone();
-                s.drum_machine.tracks[i].steps.resize(nd, 0);
-                s.drum_machine.tracks[i].muted  = t.muted;
-                s.drum_machine.tracks[i].volume = t.volume.clamp(0.0, 1.0);
+        // Renders of the old patterns don't belong to the new ones.
+        for bus in Bus::ALL { s.unfreeze(bus); }
+
+        s.bpm = sf.bpm.clamp(30.0, 300.0);
+
+        s.wave_type  = wave1;
+        s.wave_type2 = wave2;
+        s.band_limited = sf.band_limited;
+        s.set_harmonics(sf.harmonics.map(|a| a.clamp(0.0, 1.0)));
+        s.crossfade = sf.crossfade.clamp(0.0, 1.0);
+        // Older files scaled the drums by synth 1's volume.
+        s.drum_volume = sf.drum_volume.unwrap_or(sf.volume).clamp(0.0, 1.0);
+        s.master_gain = sf.master_gain.clamp(0.0, 1.0);
+        s.set_mono(false, sf.mono1);
+        s.set_mono(true, sf.mono2);
+        s.pan  = sf.pan1.clamp(-1.0, 1.0);
+        s.pan2 = sf.pan2.clamp(-1.0, 1.0);
+        for (synth2, env) in [(false, &sf.env1), (true, &sf.env2)] {
+            let adsr = env.as_ref().map_or(DEFAULT_ADSR, |e| [e.attack, e.decay, e.sustain, e.release]);
+            for (i, v) in adsr.into_iter().enumerate() {
+                let (lo, hi) = if i == 2 { (0.0, 1.0) } else { (ENV_TIME_MIN, ENV_TIME_MAX) };
+                *s.adsr_stage(synth2, i) = v.clamp(lo, hi);
             }
+        }
 
-            // Reverb
-            s.reverb.enabled   = sf.reverb.enabled;
-            s.reverb.room_size = sf.reverb.room_size.clamp(0.0, 1.0);
-            s.reverb.damping   = sf.reverb.damping.clamp(0.0, 1.0);
-            s.reverb.mix       = sf.reverb.mix.clamp(0.0, 1.0);
-
-            // Delay
-            s.delay.enabled  = sf.delay.enabled;
-            s.delay.time_ms  = sf.delay.time_ms.clamp(10.0, 1000.0);
-            s.delay.feedback = sf.delay.feedback.clamp(0.0, 0.95);
-            s.delay.mix      = sf.delay.mix.clamp(0.0, 1.0);
-
-            // Distortion
-            s.distortion.enabled = sf.distortion.enabled;
-            s.distortion.drive   = sf.distortion.drive.clamp(1.0, 10.0);
-            s.distortion.tone    = sf.distortion.tone.clamp(0.0, 1.0);
-            s.distortion.level   = sf.distortion.level.clamp(0.0, 1.0);
-
-            // Sidechain
-            s.sidechain.enabled    = sf.sidechain.enabled;
-            s.sidechain.depth      = sf.sidechain.depth.clamp(0.0, 1.0);
-            s.sidechain.release_ms = sf.sidechain.release_ms.clamp(10.0, 500.0);
-            s.sidechain.duck_s1    = sf.sidechain.duck_s1;
-            s.sidechain.duck_s2    = sf.sidechain.duck_s2;
-
-            // Filter 1
-            s.filter1.enabled = sf.filter1.enabled;
-            s.filter1.mode    = match sf.filter1.mode {
-                1 => FilterMode::HighPass, 2 => FilterMode::BandPass, _ => FilterMode::LowPass
-            };
-            s.filter1.cutoff = sf.filter1.cutoff.clamp(80.0, 18000.0);
-            s.filter1.q      = sf.filter1.q.clamp(0.5, 10.0);
-            if s.filter1.enabled { s.filter1.reset_state(); }
-
-            // Filter 2
-            s.filter2.enabled = sf.filter2.enabled;
-            s.filter2.mode    = match sf.filter2.mode {
-                1 => FilterMode::HighPass, 2 => FilterMode::BandPass, _ => FilterMode::LowPass
-            };
-            s.filter2.cutoff = sf.filter2.cutoff.clamp(80.0, 18000.0);
-            s.filter2.q      = sf.filter2.q.clamp(0.5, 10.0);
-            if s.filter2.enabled { s.filter2.reset_state(); }
-
-            // Routing
-            s.fx_routing.s1_reverb = sf.routing.s1_reverb.clamp(0.0, 1.0);
-            s.fx_routing.s1_delay  = sf.routing.s1_delay.clamp(0.0, 1.0);
-            s.fx_routing.s1_dist   = sf.routing.s1_dist.clamp(0.0, 1.0);
-            s.fx_routing.s2_reverb = sf.routing.s2_reverb.clamp(0.0, 1.0);
-            s.fx_routing.s2_delay  = sf.routing.s2_delay.clamp(0.0, 1.0);
-            s.fx_routing.s2_dist   = sf.routing.s2_dist.clamp(0.0, 1.0);
-            s.fx_routing.dr_reverb = sf.routing.dr_reverb.clamp(0.0, 1.0);
-            s.fx_routing.dr_delay  = sf.routing.dr_delay.clamp(0.0, 1.0);
-            s.fx_routing.dr_dist   = sf.routing.dr_dist.clamp(0.0, 1.0);
+        // LFOs (off in files from before them)
+        for (i, lfo) in [&sf.lfo1, &sf.lfo2].into_iter().enumerate() {
+            let mut l = Lfo::new([LfoTarget::Cutoff1, LfoTarget::Volume1][i]);
+            if let Some(ls) = lfo {
+                l.shape  = LfoShape::ALL.get(ls.shape as usize).copied().unwrap_or(LfoShape::Sine);
+                // Older files saved the rate in Hz.
+                l.rate   = match (ls.division, ls.rate) {
+                    (Some(d), _)     => LfoRate::ALL.get(d as usize).copied().unwrap_or(l.rate),
+                    (None, Some(hz)) => LfoRate::nearest_hz(hz, s.bpm),
+                    (None, None)     => l.rate,
+                };
+                l.depth  = ls.depth.clamp(0.0, 1.0);
+                l.target = LfoTarget::ALL.get(ls.target as usize).copied().unwrap_or(l.target);
+            }
+            s.lfos[i] = l;
+        }
+
+        // Insert chains
+        for (chain, saved) in [(&mut s.fx, &sf.inserts1), (&mut s.fx2, &sf.inserts2)] {
+            chain.effects = saved.iter().take(MAX_INSERTS).map(|i| match i {
+                InsertSave::Reverb     => InsertKind::Reverb,
+                InsertSave::Delay      => InsertKind::Delay,
+                InsertSave::Distortion => InsertKind::Distortion,
+                InsertSave::Filter     => InsertKind::Filter,
+            }.build(s.sample_rate)).collect();
+        }
+
+        s.volume  = sf.volume.clamp(0.0, 1.0);
+        s.volume2 = sf.volume2.clamp(0.0, 1.0);
+
+        // Sequencer 1
+        let n1 = sf.seq1.num_steps.clamp(1, MAX_STEPS);
+        s.sequencer.num_steps = n1;
+        s.sequencer.steps = sf.seq1.steps;
+        s.sequencer.steps.resize(n1, None);
+        s.sequencer.chord = sf.seq1.chord.into_iter()
+            .map(|c| c.into_iter().filter(|&i| (1..=MAX_CHORD_SPAN).contains(&i)).collect())
+            .collect();
+        s.sequencer.chord.resize(n1, Vec::new());
+        s.sequencer.muted = sf.seq1.muted;
+        s.sequencer.muted.resize(n1, false);
+        s.sequencer.slide = sf.seq1.slide;
+        s.sequencer.slide.resize(n1, false);
+        s.sequencer.accent = sf.seq1.accent;
+        s.sequencer.accent.resize(n1, false);
+        s.sequencer.tie_repeats = sf.seq1.tie_repeats;
+        s.sequencer.direction = Direction::ALL.get(sf.seq1.direction as usize).copied().unwrap_or(Direction::Forward);
+        s.sequencer.vel_humanize = sf.seq1.vel_humanize.clamp(0.0, VEL_HUMANIZE_MAX);
+        s.sequencer.octave = sf.seq1.octave.iter().map(|o| o.clamp(&-MAX_OCTAVE, &MAX_OCTAVE)).copied().collect();
+        s.sequencer.octave.resize(n1, 0);
+        s.sequencer.nudge = sf.seq1.nudge.iter().map(|n| n.clamp(&-MAX_NUDGE, &MAX_NUDGE)).copied().collect();
+        s.sequencer.nudge.resize(n1, 0);
+        s.sequencer.locks = vec![PLock::NONE; n1];
+        for (step, lock) in locks1 {
+            if step < n1 { s.sequencer.locks[step] = lock; }
+        }
+
+        // Sequencer 2
+        let n2 = sf.seq2.num_steps.clamp(1, MAX_STEPS);
+        s.sequencer2.num_steps = n2;
+        s.sequencer2.steps = sf.seq2.steps;
+        s.sequencer2.steps.resize(n2, None);
+        s.sequencer2.chord = sf.seq2.chord.into_iter()
+            .map(|c| c.into_iter().filter(|&i| (1..=MAX_CHORD_SPAN).contains(&i)).collect())
+            .collect();
+        s.sequencer2.chord.resize(n2, Vec::new());
+        s.sequencer2.muted = sf.seq2.muted;
+        s.sequencer2.muted.resize(n2, false);
+        s.sequencer2.slide = sf.seq2.slide;
+        s.sequencer2.slide.resize(n2, false);
+        s.sequencer2.accent = sf.seq2.accent;
+        s.sequencer2.accent.resize(n2, false);
+        s.sequencer2.tie_repeats = sf.seq2.tie_repeats;
+        s.sequencer2.direction = Direction::ALL.get(sf.seq2.direction as usize).copied().unwrap_or(Direction::Forward);
+        s.sequencer2.vel_humanize = sf.seq2.vel_humanize.clamp(0.0, VEL_HUMANIZE_MAX);
+        s.sequencer2.octave = sf.seq2.octave.iter().map(|o| o.clamp(&-MAX_OCTAVE, &MAX_OCTAVE)).copied().collect();
+        s.sequencer2.octave.resize(n2, 0);
+        s.sequencer2.nudge = sf.seq2.nudge.iter().map(|n| n.clamp(&-MAX_NUDGE, &MAX_NUDGE)).copied().collect();
+        s.sequencer2.nudge.resize(n2, 0);
+        s.sequencer2.locks = vec![PLock::NONE; n2];
+        for (step, lock) in locks2 {
+            if step < n2 { s.sequencer2.locks[step] = lock; }
+        }
+
+        // Drums
+        let nd = sf.drums.num_steps.clamp(1, MAX_STEPS);
+        s.drum_machine.num_steps = nd;
+        s.groove.amount   = sf.drums.swing.clamp(0.0, 0.5);
+        s.groove.template = GrooveTemplate::ALL.get(sf.groove_template as usize)
+            .copied().unwrap_or(GrooveTemplate::Swing16);
+        s.groove.melodic  = sf.groove_melodic;
+        // The saved track list is the kit, in order.  A file without
+        // tracks keeps the current kit.
+        if sf.drums.tracks.is_empty() {
+            for t in &mut s.drum_machine.tracks {
+                t.steps.resize(nd, 0);
+                t.conds.resize(nd, TrigCondition::Always);
+                t.nudge.resize(nd, 0);
+            }
+        } else {
+            s.drum_machine.tracks = sf.drums.tracks.iter().take(MAX_TRACKS).zip(assets.drum_samples).map(|(t, smp)| {
+                let kind = DrumKind::ALL.get(t.kind as usize).copied().unwrap_or(DrumKind::Kick);
+                let mut track = DrumTrack::new(kind, nd);
+                track.steps = t.steps.clone();
+                track.steps.resize(nd, 0);
+                track.nudge = t.nudge.iter().map(|n| n.clamp(&-MAX_NUDGE, &MAX_NUDGE)).copied().collect();
+                track.nudge.resize(nd, 0);
+                for (i, c) in &t.conds {
+                    if let (Some(slot), Some(c)) = (track.conds.get_mut(*i), TrigCondition::parse(c)) {
+                        *slot = c;
+                    }
+                }
+                track.muted  = t.muted;
+                track.volume = t.volume.clamp(0.0, 1.0);
+                track.pan    = t.pan.clamp(-1.0, 1.0);
+                track.pitch  = t.pitch.clamp(-MAX_PITCH, MAX_PITCH);
+                track.retrig = t.retrig.min(100);
+                track.retrig_hits = t.retrig_hits.clamp(2, MAX_RETRIG_HITS);
+                track.name   = t.name.clone().filter(|n| !n.trim().is_empty());
+                track.sample = smp;
+                track
+            }).collect();
+        }
+
+        // Reverb
+        s.reverb.enabled   = sf.reverb.enabled;
+        s.reverb.room_size = sf.reverb.room_size.clamp(0.0, 1.0);
+        s.reverb.damping   = sf.reverb.damping.clamp(0.0, 1.0);
+        s.reverb.mix       = sf.reverb.mix.clamp(0.0, 1.0);
+        s.reverb.er_amount = sf.reverb.er_amount.clamp(0.0, 1.0);
+        s.reverb.shimmer   = sf.reverb.shimmer.clamp(0.0, 1.0);
+
+        // Delay
+        s.delay.enabled  = sf.delay.enabled;
+        s.delay.time_ms  = sf.delay.time_ms.clamp(10.0, 1000.0);
+        s.delay.feedback = sf.delay.feedback.clamp(0.0, 0.95);
+        s.delay.mix      = sf.delay.mix.clamp(0.0, 1.0);
+        s.delay.reverse  = sf.delay.reverse;
+        s.delay.sync     = sf.delay.sync;
+        s.delay.division = sf.delay.division.as_deref().and_then(NoteValue::parse).unwrap_or(SYNC_DIVISIONS[1]);
+        s.delay.duck_amount  = sf.delay.duck_amount.clamp(0.0, 1.0);
+        s.delay.duck_release = sf.delay.duck_release.clamp(50.0, 2000.0);
+        s.delay.taps     = sf.delay.taps.iter().take(MAX_TAPS).map(|t| Tap {
+            time_ms: t.time_ms.clamp(10.0, 1000.0),
+            level:   t.level.clamp(0.0, 1.0),
+            pan:     t.pan.clamp(-1.0, 1.0),
+        }).collect();
+
+        // Distortion
+        s.distortion.enabled = sf.distortion.enabled;
+        s.distortion.drive   = sf.distortion.drive.clamp(1.0, 10.0);
+        s.distortion.tone    = sf.distortion.tone.clamp(0.0, 1.0);
+        s.distortion.level   = sf.distortion.level.clamp(0.0, 1.0);
+        s.distortion.asymmetry = sf.distortion.asymmetry.clamp(-1.0, 1.0);
+        s.distortion.mode    = DistMode::ALL.get(sf.distortion.mode as usize).copied().unwrap_or(DistMode::Clip);
+
+        // Sidechain
+        s.sidechain.enabled    = sf.sidechain.enabled;
+        s.sidechain.depth      = sf.sidechain.depth.clamp(0.0, 1.0);
+        s.sidechain.release_ms = sf.sidechain.release_ms.clamp(10.0, 500.0);
+        s.sidechain.shape      = SidechainShape::ALL.get(sf.sidechain.shape as usize)
+            .copied().unwrap_or(SidechainShape::Exponential);
+        s.sidechain.duck_s1    = sf.sidechain.duck_s1;
+        s.sidechain.duck_s2    = sf.sidechain.duck_s2;
+
+        // Arpeggiator (off in files from before it)
+        let arp = sf.arp.unwrap_or(ArpSave { enabled: false, rate: 3, mode: 0 });
+        s.set_arp(arp.enabled);
+        s.arp.rate = ArpRate::ALL.get(arp.rate as usize).copied().unwrap_or(ArpRate::Sixteenth);
+        s.arp.mode = ArpMode::ALL.get(arp.mode as usize).copied().unwrap_or(ArpMode::Up);
+
+        // Filter 1
+        s.filter1.enabled = sf.filter1.enabled;
+        s.filter1.mode    = FilterMode::ALL.get(sf.filter1.mode as usize).copied().unwrap_or(FilterMode::LowPass);
+        s.filter1.cutoff  = sf.filter1.cutoff.clamp(80.0, 18000.0);
+        s.filter1.q       = sf.filter1.q.clamp(0.5, 10.0);
+        s.filter1.gain_db = sf.filter1.gain_db.clamp(-FILTER_MAX_GAIN_DB, FILTER_MAX_GAIN_DB);
+        if s.filter1.enabled { s.filter1.reset_state(); }
+
+        // Filter 2
+        s.filter2.enabled = sf.filter2.enabled;
+        s.filter2.mode    = FilterMode::ALL.get(sf.filter2.mode as usize).copied().unwrap_or(FilterMode::LowPass);
+        s.filter2.cutoff  = sf.filter2.cutoff.clamp(80.0, 18000.0);
+        s.filter2.q       = sf.filter2.q.clamp(0.5, 10.0);
+        s.filter2.gain_db = sf.filter2.gain_db.clamp(-FILTER_MAX_GAIN_DB, FILTER_MAX_GAIN_DB);
+        if s.filter2.enabled { s.filter2.reset_state(); }
+
+        // Routing
+        s.fx_routing.s1_reverb = sf.routing.s1_reverb.clamp(0.0, 1.0);
+        s.fx_routing.s1_delay  = sf.routing.s1_delay.clamp(0.0, 1.0);
+        s.fx_routing.s1_dist   = sf.routing.s1_dist.clamp(0.0, 1.0);
+        s.fx_routing.s2_reverb = sf.routing.s2_reverb.clamp(0.0, 1.0);
+        s.fx_routing.s2_delay  = sf.routing.s2_delay.clamp(0.0, 1.0);
+        s.fx_routing.s2_dist   = sf.routing.s2_dist.clamp(0.0, 1.0);
+        s.fx_routing.dr_reverb = sf.routing.dr_reverb.clamp(0.0, 1.0);
+        s.fx_routing.dr_delay  = sf.routing.dr_delay.clamp(0.0, 1.0);
+        s.fx_routing.dr_dist   = sf.routing.dr_dist.clamp(0.0, 1.0);
+        s.fx_routing.s1_comb   = sf.routing.s1_comb.clamp(0.0, 1.0);
+        s.fx_routing.s2_comb   = sf.routing.s2_comb.clamp(0.0, 1.0);
+        s.fx_routing.dr_comb   = sf.routing.dr_comb.clamp(0.0, 1.0);
+        s.fx_routing.s1_chorus = sf.routing.s1_chorus.clamp(0.0, 1.0);
+        s.fx_routing.s2_chorus = sf.routing.s2_chorus.clamp(0.0, 1.0);
+        s.fx_routing.dr_chorus = sf.routing.dr_chorus.clamp(0.0, 1.0);
+        s.fx_routing.s1_crush  = sf.routing.s1_crush.clamp(0.0, 1.0);
+        s.fx_routing.s2_crush  = sf.routing.s2_crush.clamp(0.0, 1.0);
+        s.fx_routing.dr_crush  = sf.routing.dr_crush.clamp(0.0, 1.0);
+
+        // Comb (off in files from before it)
+        let comb = sf.comb.unwrap_or(CombSave { enabled: false, freq: 220.0, feedback: 0.9, mix: 0.5 });
+        s.comb.enabled  = comb.enabled;
+        s.comb.freq     = comb.freq.clamp(COMB_MIN_HZ, COMB_MAX_HZ);
+        s.comb.feedback = comb.feedback.clamp(0.0, 0.98);
+        s.comb.mix      = comb.mix.clamp(0.0, 1.0);
+        s.comb.reset();
+
+        // Chorus (off in files from before it)
+        let chorus = sf.chorus.unwrap_or(ChorusSave { enabled: false, rate: 0.8, depth: 0.5, mix: 0.5 });
+        s.chorus.enabled = chorus.enabled;
+        s.chorus.rate    = chorus.rate.clamp(CHORUS_RATE_MIN, CHORUS_RATE_MAX);
+        s.chorus.depth   = chorus.depth.clamp(0.0, 1.0);
+        s.chorus.mix     = chorus.mix.clamp(0.0, 1.0);
+        s.chorus.reset();
+
+        // Bitcrusher (off in files from before it)
+        let crusher = sf.crusher.unwrap_or(CrushSave { enabled: false, bits: 8, downsample: 4, mix: 0.5 });
+        s.crusher.enabled    = crusher.enabled;
+        s.crusher.bits       = crusher.bits.clamp(CRUSH_MIN_BITS, CRUSH_MAX_BITS);
+        s.crusher.downsample = crusher.downsample.clamp(1, CRUSH_MAX_DOWNSAMPLE);
+        s.crusher.mix        = crusher.mix.clamp(0.0, 1.0);
+        s.crusher.reset();
+
+        // Compressor (off in files from before it)
+        let comp = sf.compressor.unwrap_or(CompSave {
+            enabled: false, threshold: -18.0, ratio: 4.0, attack_ms: 10.0, release_ms: 150.0, makeup_db: 0.0,
+        });
+        s.compressor.enabled    = comp.enabled;
+        s.compressor.threshold  = comp.threshold.clamp(-60.0, 0.0);
+        s.compressor.ratio      = comp.ratio.clamp(1.0, 20.0);
+        s.compressor.attack_ms  = comp.attack_ms.clamp(0.1, 100.0);
+        s.compressor.release_ms = comp.release_ms.clamp(10.0, 2000.0);
+        s.compressor.makeup_db  = comp.makeup_db.clamp(0.0, 24.0);
+        s.compressor.reset();
+
+        // Formant (off in files from before it)
+        let formant = sf.formant.unwrap_or(FormantSave { enabled: false, vowel: 0.0, q: 8.0, mix: 1.0, on_s2: false });
+        s.formant.enabled = formant.enabled;
+        s.formant.vowel   = formant.vowel.clamp(0.0, 4.0);
+        s.formant.q       = formant.q.clamp(2.0, 20.0);
+        s.formant.mix     = formant.mix.clamp(0.0, 1.0);
+        s.formant.on_s2   = formant.on_s2;
+        s.formant.reset();
+
+        // De-esser (off in files from before it)
+        let deesser = sf.deesser.unwrap_or(DeEsserSave { enabled: false, freq: 6000.0, threshold: -24.0, amount: 0.5 });
+        s.deesser.enabled   = deesser.enabled;
+        s.deesser.freq      = deesser.freq.clamp(DEESS_MIN_HZ, DEESS_MAX_HZ);
+        s.deesser.threshold = deesser.threshold.clamp(-40.0, 0.0);
+        s.deesser.amount    = deesser.amount.clamp(0.0, 1.0);
+        s.deesser.reset();
+
+        let d = Acid::new();
+        let acid = sf.acid.unwrap_or(AcidSave {
+            enabled: false, env_mod: d.env_mod, decay_ms: d.decay_ms, accent: d.accent,
+        });
+        s.acid = d;
+        s.acid.enabled  = acid.enabled;
+        s.acid.env_mod  = acid.env_mod.clamp(0.0, ENV_MOD_MAX);
+        s.acid.decay_ms = acid.decay_ms.clamp(DECAY_MIN_MS, DECAY_MAX_MS);
+        s.acid.accent   = acid.accent.clamp(0.0, 1.0);
+        s.filter1.cutoff_mod = 1.0;
+
+        // Automation: lanes for unknown or unautomatable params are dropped,
+        // and points are wrapped into the loop and the param's range.
+        self.automation.lanes.clear();
+        if let Some(auto) = sf.automation {
+            let len = auto.loop_steps.clamp(1, MAX_STEPS);
+            self.automation.loop_steps = len;
+            self.automation.lanes = auto.lanes.into_iter().filter_map(|l| {
+                let &(param, _, _) = CcParam::ALL.iter().find(|(_, n, _)| *n == l.param)?;
+                if !Automation::automatable(param) { return None; }
+                let (lo, hi, _) = param.range();
+                let mut points: Vec<(f64, f32)> = l.points.into_iter()
+                    .filter(|(p, v)| p.is_finite() && v.is_finite())
+                    .map(|(p, v)| (p.rem_euclid(len as f64), v.clamp(lo, hi)))
+                    .collect();
+                points.sort_by(|a, b| a.0.total_cmp(&b.0));
+                if points.is_empty() { return None; }
+                let mut lane = Lane::new(param, points);
+                lane.bypass = l.bypass;
+                Some(lane)
+            }).collect();
+        }
+        self.automation.publish(s);
+
+        // Arrangement: `set` keeps the markers sorted, one per bar.
+        self.arrangement.markers.clear();
+        for m in &sf.markers {
+            let name = m.name.trim();
+            if !name.is_empty() { self.arrangement.set(m.bar, name); }
         }
 
         // App-level fields
         self.base_octave   = sf.base_octave.clamp(0, 8);
+        self.count_in_bars = sf.count_in_bars.min(MAX_COUNT_IN);
         self.scale_q.scale = Scale::ALL.get(sf.scale as usize).copied().unwrap_or(Scale::Off);
         self.scale_q.root  = sf.scale_root % 12;
+        if !sf.custom_scale.is_empty() { self.scale_q.set_custom(&sf.custom_scale); }
+        self.live_quantize = sf.scale_live;
+        self.publish_scale_to(s);
 
-        // Reset cursors
-        self.seq_cursor  = 0;
-        self.seq2_cursor = 0;
-        self.drum_step   = 0;
+        warn1.or(warn2).or(warn3).or(warn4)
+    }
+
+    /// Render the project offline to a mono WAV.  `input` is the path,
+    /// optionally followed by a bar count (without one the longest pattern
+    /// is rendered once through), a format (`16bit`, the default, `24bit` or
+    /// `float`) and `dither` / `nodither` (default: on for 16-bit only), in
+    /// any order.  A scratch synth gets the project by the same route as a
+    /// load, so effects and automation come along, and plays both
+    /// sequencers and the drums from their first step on a background
+    /// thread; `tick_export()` reports its progress.  The live synth
+    /// carries on untouched.
+    pub fn export_wav(&mut self, input: &str) {
+        if self.export.is_some() {
+            self.status_msg = "Export already running".to_string();
+            return;
+        }
+        // Options come off the end until a word isn't one, so paths may
+        // hold spaces.
+        let (mut path, mut bars, mut format, mut dither) = (input.trim_end(), None, None, None);
+        while let Some((rest, word)) = path.rsplit_once(' ') {
+            let rest = rest.trim_end();
+            if rest.is_empty() { break; }
+            match (word, WavFormat::parse(word), word.parse::<usize>()) {
+                (_, Some(f), _) if format.is_none() => format = Some(f),
+                ("dither", ..)   if dither.is_none() => dither = Some(true),
+                ("nodither", ..) if dither.is_none() => dither = Some(false),
+                (_, _, Ok(n))    if bars.is_none()   => bars = Some(n),
+                _ => break,
+            }
+            path = rest;
+        }
+        let sf = self.save_file();
+        let (sr, longest, wavetables) = {
+            let s = self.synth.lock().unwrap();
+            let steps = s.sequencer.loop_steps().max(s.sequencer2.loop_steps()).max(s.drum_machine.num_steps);
+            (s.sample_rate, steps.div_ceil(STEPS_PER_BAR), s.wavetables.clone())
+        };
+        let bars = bars.unwrap_or(longest).clamp(1, MAX_EXPORT_BARS);
+
+        // The tables come along so wave indices stay the same.
+        let mut r = Synth::new(sr);
+        r.wavetables = wavetables;
+        let assets = SaveAssets::read(&sf);
+        self.apply_save(&mut r, sf, assets);
+        r.sequencer.playing  = true;
+        r.sequencer2.playing = true;
+        r.drum_machine.toggle_play();
+
+        let mut spec = WavSpec::new(format.unwrap_or(WavFormat::Int16), sr as u32);
+        if let Some(d) = dither { spec.dither = d; }
+        let dithered = if spec.dither && spec.format != WavFormat::Float32 { ", dithered" } else { "" };
+        let plural = if bars == 1 { "" } else { "s" };
+        let label = format!("{} bar{} → {}", bars, plural, path);
+
+        let (tx, rx) = mpsc::channel();
+        let path = path.to_string();
+        std::thread::spawn(move || {
+            // Run to the end of the last bar on the synth's own step clock,
+            // so a BPM automation lane stretches the render as it plays.
+            let end = (bars * STEPS_PER_BAR) as f64;
+            let mut buf = Vec::new();
+            let mut shown = 0;
+            while r.step_pos < end {
+                buf.push(r.generate_sample());
+                let pct = (r.step_pos / end * 100.0) as u32;
+                if pct > shown {
+                    shown = pct;
+                    let _ = tx.send(ExportUpdate::Progress((r.step_pos / end) as f32));
+                }
+            }
+            let done = write_wav(&path, &buf, spec).map(|()| {
+                format!("Exported {} bar{} ({:.1} s, {}{}) → {}", bars, plural,
+                        buf.len() as f32 / sr, spec.format.name(), dithered, path)
+            });
+            let _ = tx.send(ExportUpdate::Done(done));
+        });
+        self.status_msg = format!("Exporting {}", label);
+        self.export = Some((rx, label));
+    }
+
+    /// Show how far the WAV export has got, and its result once done.
+    pub fn tick_export(&mut self) {
+        let Some((rx, label)) = &self.export else { return };
+        let done = loop {
+            match rx.try_recv() {
+                Ok(ExportUpdate::Progress(f)) => self.status_msg = format!("Exporting {} {:.0}%", label, f * 100.0),
+                Ok(ExportUpdate::Done(result)) => break result,
+                Err(TryRecvError::Empty)        => return,
+                Err(TryRecvError::Disconnected) => break Err(anyhow::anyhow!("render stopped")),
+            }
+        };
+        self.status_msg = match done {
+            Ok(msg) => msg,
+            Err(e)  => format!("Export error: {:#}", e),
+        };
+        self.export = None;
+    }
 
-        self.status_msg = format!("Loaded ← {}", path);
+    /// Write both sequencers to a MIDI file; see `save::export_midi()`.
+    pub fn export_midi_file(&mut self, path: &str) {
+        let result = export_midi(path, &self.synth.lock().unwrap());
+        self.status_msg = match result {
+            Ok(n)  => format!("Exported MIDI ({} note{}) → {}", n, if n == 1 { "" } else { "s" }, path),
+            Err(e) => format!("MIDI export error: {:#}", e),
+        };
     }
 
     /// Commit the current file-path input: call save or load, then reset input state.
@@ -1020,11 +4310,25 @@ impl App {
         let mode = self.input_mode.clone();
         self.input_mode = InputMode::None;
         self.input_buf.clear();
+        if mode == InputMode::RenameTrack { return self.drum_rename_track(&path); }
+        if mode == InputMode::Marker { return self.marker_set(&path); }
+        if mode == InputMode::ScaleEdit { return self.scale_edit_commit(); }
+        if mode == InputMode::DelayTaps { return self.set_delay_taps(&path); }
+        if let InputMode::LoadDrumSample(track) = mode { return self.load_drum_sample(track, &path); }
+        if let InputMode::ConfirmClear(target) = mode {
+            return if target == ClearTarget::All { self.clear_all() } else { self.clear_focused_pattern() };
+        }
         if path.is_empty() { return; }
         match mode {
             InputMode::Save => self.save(&path),
             InputMode::Load => self.load(&path),
-            InputMode::None => {}
+            InputMode::LoadWavetable => self.load_wavetable(&path),
+            InputMode::LoadSample => self.load_sample(&path),
+            InputMode::Export => self.export_wav(&path),
+            InputMode::ExportMidi => self.export_midi_file(&path),
+            InputMode::DelayNote => self.set_delay_note(&path),
+            InputMode::RenameTrack | InputMode::Marker | InputMode::ScaleEdit | InputMode::DelayTaps
+                | InputMode::LoadDrumSample(_) | InputMode::ConfirmClear(_) | InputMode::None => {}
         }
     }
 }
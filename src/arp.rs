@@ -0,0 +1,170 @@
+use crate::sequencer::StepClock;
+
+/// Fraction of the rate period an arpeggiated note sounds for.
+const GATE: f64 = 0.5;
+
+/// Note length of the arpeggio.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArpRate { Quarter, Eighth, EighthTriplet, Sixteenth, SixteenthTriplet, ThirtySecond }
+
+impl ArpRate {
+    pub const ALL: [ArpRate; 6] = [
+        Self::Quarter, Self::Eighth, Self::EighthTriplet,
+        Self::Sixteenth, Self::SixteenthTriplet, Self::ThirtySecond,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Quarter          => "1/4",
+            Self::Eighth           => "1/8",
+            Self::EighthTriplet    => "1/8T",
+            Self::Sixteenth        => "1/16",
+            Self::SixteenthTriplet => "1/16T",
+            Self::ThirtySecond     => "1/32",
+        }
+    }
+
+    /// Length in 16th-note steps of the master clock.
+    fn steps(self) -> f64 {
+        match self {
+            Self::Quarter          => 4.0,
+            Self::Eighth           => 2.0,
+            Self::EighthTriplet    => 4.0 / 3.0,
+            Self::Sixteenth        => 1.0,
+            Self::SixteenthTriplet => 2.0 / 3.0,
+            Self::ThirtySecond     => 0.5,
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&r| r == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// Order the held notes are played in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArpMode { Up, Down, UpDown, Random }
+
+impl ArpMode {
+    pub const ALL: [ArpMode; 4] = [Self::Up, Self::Down, Self::UpDown, Self::Random];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Up     => "Up",
+            Self::Down   => "Down",
+            Self::UpDown => "Up/Down",
+            Self::Random => "Random",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&m| m == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// Notes to end and start on this sample.
+#[derive(Default)]
+pub struct ArpEvent {
+    pub note_off: Option<u8>,
+    pub note_on:  Option<u8>,
+}
+
+/// Plays the held keyboard notes one at a time on the master clock.  While
+/// enabled, `Synth::key_on`/`key_off` hand notes here instead of starting
+/// voices; notes arrive already scale-quantized (and chord-expanded) by `App`.
+pub struct Arpeggiator {
+    pub enabled: bool,
+    pub rate:    ArpRate,
+    pub mode:    ArpMode,
+    /// Held notes, lowest first.
+    held:        Vec<u8>,
+    /// The note sounding and the step position its gate ends at.
+    sounding:    Option<(u8, f64)>,
+    /// Notes played since the keys were first pressed: the pattern position.
+    count:       usize,
+    seed:        u32,
+}
+
+impl Arpeggiator {
+    pub fn new() -> Self {
+        Self {
+            enabled:  false,
+            rate:     ArpRate::Sixteenth,
+            mode:     ArpMode::Up,
+            held:     Vec::new(),
+            sounding: None,
+            count:    0,
+            seed:     0x2545_f491,
+        }
+    }
+
+    pub fn press(&mut self, note: u8) {
+        if let Err(at) = self.held.binary_search(&note) { self.held.insert(at, note); }
+    }
+
+    /// Returns whether `note` was held.  A sounding note plays out its gate.
+    pub fn release(&mut self, note: u8) -> bool {
+        match self.held.binary_search(&note) {
+            Ok(at) => { self.held.remove(at); true }
+            Err(_) => false,
+        }
+    }
+
+    /// Forget the held notes; returns the sounding one to end.
+    pub fn clear(&mut self) -> Option<u8> {
+        self.held.clear();
+        self.cut()
+    }
+
+    /// End the sounding note now, as when the clock stops under it.
+    pub fn cut(&mut self) -> Option<u8> {
+        self.sounding.take().map(|(n, _)| n)
+    }
+
+    /// Advance by one sample.  A new note starts on every rate boundary of
+    /// the master clock while keys are held.
+    pub fn tick(&mut self, clock: StepClock) -> ArpEvent {
+        let mut ev = ArpEvent::default();
+        if self.sounding.is_some_and(|(_, off)| clock.pos > off) {
+            ev.note_off = self.cut();
+        }
+        if self.held.is_empty() {
+            self.count = 0;
+            return ev;
+        }
+        let len = self.rate.steps();
+        let at = (clock.pos / len).floor() * len;
+        if !clock.crosses(at) { return ev; }
+        if let Some(n) = self.cut() { ev.note_off = Some(n); }
+        let note = self.next_note();
+        self.sounding = Some((note, at + len * GATE));
+        ev.note_on = Some(note);
+        ev
+    }
+
+    fn next_note(&mut self) -> u8 {
+        let n = self.held.len();
+        let i = self.count;
+        self.count += 1;
+        let idx = match self.mode {
+            ArpMode::Up     => i % n,
+            ArpMode::Down   => n - 1 - i % n,
+            ArpMode::UpDown if n == 1 => 0,
+            ArpMode::UpDown => {
+                // Ping-pong without repeating the top and bottom notes.
+                let period = 2 * n - 2;
+                let p = i % period;
+                if p < n { p } else { period - p }
+            }
+            ArpMode::Random => {
+                self.seed ^= self.seed << 13;
+                self.seed ^= self.seed >> 17;
+                self.seed ^= self.seed << 5;
+                self.seed as usize % n
+            }
+        };
+        self.held[idx]
+    }
+}
@@ -0,0 +1,62 @@
+/// Steps per bar: the grid is 16th notes in 4/4.
+pub const STEPS_PER_BAR: usize = 16;
+
+/// Most section markers an arrangement holds.
+pub const MAX_MARKERS: usize = 32;
+
+/// A named section starting at the beginning of `bar` (0-based).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Marker {
+    pub bar:  usize,
+    pub name: String,
+}
+
+/// Section markers on the bar timeline.  Bars count from transport zero,
+/// so the markers sit on the same master clock every pattern follows.
+pub struct Arrangement {
+    /// Sorted by bar, at most one per bar.
+    pub markers: Vec<Marker>,
+}
+
+impl Arrangement {
+    pub fn new() -> Self {
+        Self { markers: Vec::new() }
+    }
+
+    /// Put a marker at `bar`, renaming the one already there.  Returns
+    /// false when the arrangement is full.
+    pub fn set(&mut self, bar: usize, name: &str) -> bool {
+        let at = self.markers.partition_point(|m| m.bar < bar);
+        if let Some(m) = self.markers.get_mut(at).filter(|m| m.bar == bar) {
+            m.name = name.to_string();
+        } else if self.markers.len() < MAX_MARKERS {
+            self.markers.insert(at, Marker { bar, name: name.to_string() });
+        } else {
+            return false;
+        }
+        true
+    }
+
+    /// Remove the marker that starts the section `bar` is in.
+    pub fn remove_section(&mut self, bar: usize) -> Option<Marker> {
+        let at = self.markers.partition_point(|m| m.bar <= bar).checked_sub(1)?;
+        Some(self.markers.remove(at))
+    }
+
+    /// The marker of the section `bar` is in, if any starts at or before it.
+    pub fn section_at(&self, bar: usize) -> Option<&Marker> {
+        self.markers.iter().rev().find(|m| m.bar <= bar)
+    }
+
+    /// The first marker after `bar`, wrapping round to the first one.
+    pub fn next_after(&self, bar: usize) -> Option<&Marker> {
+        self.markers.iter().find(|m| m.bar > bar).or(self.markers.first())
+    }
+
+    /// The marker before the current section's, wrapping round to the
+    /// last one.  Before the first marker this is the last one too.
+    pub fn prev_before(&self, bar: usize) -> Option<&Marker> {
+        let start = self.section_at(bar).map_or(0, |m| m.bar);
+        self.markers.iter().rev().find(|m| m.bar < start).or(self.markers.last())
+    }
+}
@@ -1,46 +1,313 @@
 use anyhow::{Context, Result};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Stream, StreamConfig};
+use cpal::{SampleFormat, SampleRate, Stream, StreamConfig, SupportedStreamConfig};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
 
 use crate::synth::Synth;
 
+/// First channel (0-based) of the stereo pair the cue bus is written to.
+/// Devices with fewer channels carry the main mix only.
+pub const CUE_CHANNEL: usize = 2;
+
+/// Where the rendered samples go.
+enum Backend {
+    /// A real CPAL output stream.
+    Cpal { _stream: Stream },
+    /// Silent sink: renders on a timer and discards the output.
+    Null { _sink: NullSink },
+}
+
+/// Figures written by the audio callback and read by the UI thread.
+#[derive(Default)]
+pub struct AudioStats {
+    /// Callback → playback latency reported by the backend, in µs (0 = unknown).
+    latency_us:    AtomicU32,
+    /// Frames rendered by the most recent callback.
+    buffer_frames: AtomicU32,
+    /// Sample rate of the open stream, in Hz.
+    sample_rate:   AtomicU32,
+    /// Output channels of the open stream.
+    channels:      AtomicU32,
+    /// Callbacks that took longer to render than the audio they produced,
+    /// or that arrived late enough that the device must have run dry.
+    xruns:         AtomicU64,
+}
+
+impl AudioStats {
+    /// Output latency in milliseconds.  Uses the backend's own timestamps when
+    /// it provides them, otherwise estimates one buffer's worth of audio.
+    pub fn latency_ms(&self) -> Option<f32> {
+        let us = self.latency_us.load(Ordering::Relaxed);
+        if us > 0 { return Some(us as f32 / 1000.0); }
+        let frames = self.buffer_frames.load(Ordering::Relaxed);
+        let sr     = self.sample_rate.load(Ordering::Relaxed);
+        if frames == 0 || sr == 0 { return None; }
+        Some(frames as f32 * 1000.0 / sr as f32)
+    }
+
+    pub fn xruns(&self) -> u64 {
+        self.xruns.load(Ordering::Relaxed)
+    }
+
+    pub fn reset_xruns(&self) {
+        self.xruns.store(0, Ordering::Relaxed);
+    }
+
+    fn reset(&self, sample_rate: f32, channels: usize) {
+        self.latency_us.store(0, Ordering::Relaxed);
+        self.buffer_frames.store(0, Ordering::Relaxed);
+        self.sample_rate.store(sample_rate as u32, Ordering::Relaxed);
+        self.channels.store(channels as u32, Ordering::Relaxed);
+    }
+}
+
 pub struct AudioEngine {
-    _stream: Stream,
+    backend: Backend,
+    synth:   Arc<Mutex<Synth>>,
+    stats:   Arc<AudioStats>,
+    /// Name of the open output device ("none" on the null sink).
+    device_name: String,
+    /// Rate asked for with `--sample-rate`; every device opened must run
+    /// at it.  `None` adopts each device's default.
+    rate:    Option<u32>,
 }
 
 impl AudioEngine {
-    pub fn new(synth: Arc<Mutex<Synth>>) -> Result<Self> {
+    /// Open the output device whose name contains `device` (the default
+    /// device when `None`) at `rate` (its default rate when `None`), or fall
+    /// back to the silent null sink when `no_audio` is set or the device
+    /// can't be opened.  The second value is the reason for a fallback, if
+    /// one happened.
+    pub fn start(synth: Arc<Mutex<Synth>>, no_audio: bool, device: Option<&str>, rate: Option<u32>)
+        -> (Self, Option<String>)
+    {
+        if no_audio {
+            return (Self { rate, ..Self::null(synth) }, None);
+        }
+        match Self::new(Arc::clone(&synth), device, rate) {
+            Ok(engine) => (engine, None),
+            Err(e)     => (Self { rate, ..Self::null(synth) }, Some(format!("{:#}", e))),
+        }
+    }
+
+    pub fn new(synth: Arc<Mutex<Synth>>, device: Option<&str>, rate: Option<u32>) -> Result<Self> {
         let host = cpal::default_host();
-        let device = host
-            .default_output_device()
-            .context("No output device found")?;
+        let device = match device {
+            Some(name) => {
+                let needle = name.to_lowercase();
+                host.output_devices()
+                    .context("Cannot enumerate output devices")?
+                    .find(|d| d.name().is_ok_and(|n| n.to_lowercase().contains(&needle)))
+                    .with_context(|| format!("No output device matching \"{}\" (see --list-audio-devices)", name))?
+            }
+            None => host.default_output_device().context("No output device found")?,
+        };
+        let stats = Arc::new(AudioStats::default());
+        let (stream, device_name) = open_stream(&device, rate, Arc::clone(&synth), Arc::clone(&stats))?;
+        Ok(Self { backend: Backend::Cpal { _stream: stream }, synth, stats, device_name, rate })
+    }
+
+    /// Run without an output device.  The synth is still rendered in real
+    /// time so the master clock, sequencers, and scope keep moving.
+    pub fn null(synth: Arc<Mutex<Synth>>) -> Self {
+        let sink = NullSink::spawn(Arc::clone(&synth));
+        Self {
+            backend: Backend::Null { _sink: sink }, synth,
+            stats: Arc::new(AudioStats::default()), device_name: "none".to_string(), rate: None,
+        }
+    }
+
+    /// True when running on the silent null sink.
+    pub fn is_null(&self) -> bool {
+        matches!(self.backend, Backend::Null { .. })
+    }
+
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+
+    /// Sample rate of the open stream in Hz (0 on the null sink).
+    pub fn sample_rate(&self) -> u32 {
+        if self.is_null() { 0 } else { self.stats.sample_rate.load(Ordering::Relaxed) }
+    }
+
+    /// Buffer overruns/underruns counted since start (or the last reset).
+    pub fn xruns(&self) -> u64 {
+        self.stats.xruns()
+    }
+
+    pub fn reset_xruns(&self) {
+        self.stats.reset_xruns();
+    }
+
+    /// Output latency of the open stream (`None` on the null sink or before
+    /// the first callback).
+    pub fn latency_ms(&self) -> Option<f32> {
+        if self.is_null() { None } else { self.stats.latency_ms() }
+    }
+
+    /// Output channels of the open stream (0 on the null sink).
+    pub fn channels(&self) -> usize {
+        if self.is_null() { 0 } else { self.stats.channels.load(Ordering::Relaxed) as usize }
+    }
 
-        let config = device
-            .default_output_config()
-            .context("No default output config")?;
+    /// Whether the open stream has the channels 3–4 the cue bus plays on.
+    pub fn has_cue_output(&self) -> bool {
+        self.channels() >= CUE_CHANNEL + 2
+    }
 
-        let sample_rate = config.sample_rate().0 as f32;
-        let channels = config.channels() as usize;
+    /// Switch to the next output device (wrapping around), rebuilding the
+    /// stream against the same shared `Synth`.  Patterns and voices are
+    /// untouched.  On failure the current backend is left running.
+    /// Returns the name of the newly opened device.
+    pub fn next_device(&mut self) -> Result<String> {
+        let host = cpal::default_host();
+        let devices: Vec<cpal::Device> = host
+            .output_devices()
+            .context("Cannot enumerate output devices")?
+            .collect();
+        if devices.is_empty() { anyhow::bail!("No output devices found"); }
 
-        // Update synth sample rate
-        {
-            let mut s = synth.lock().unwrap();
-            s.sample_rate = sample_rate;
+        let names: Vec<String> = devices.iter()
+            .map(|d| d.name().unwrap_or_else(|_| "unknown".to_string()))
+            .collect();
+        // Settled once up front: the loop below swaps in a null backend before
+        // every attempt, so `is_null()` stops describing what was playing.
+        let current = if self.is_null() { None } else { names.iter().position(|n| *n == self.device_name) };
+        let start = current.map_or(0, |i| i + 1);
+
+        // Try each other device in turn, starting after the current one.
+        let mut last_err = None;
+        for k in 0..devices.len() {
+            let i = (start + k) % devices.len();
+            if Some(i) == current { continue; }
+            // Tear the old stream down first: some backends refuse to open a
+            // second stream while one is still running.
+            self.backend = Backend::Null { _sink: NullSink::spawn(Arc::clone(&self.synth)) };
+            match open_stream(&devices[i], self.rate, Arc::clone(&self.synth), Arc::clone(&self.stats)) {
+                Ok((stream, name)) => {
+                    self.backend     = Backend::Cpal { _stream: stream };
+                    self.device_name = name.clone();
+                    return Ok(name);
+                }
+                Err(e) => last_err = Some(e),
+            }
+        }
+        // Nothing else opened; try to get the previous device back.
+        if let Some(i) = current {
+            if let Ok((stream, _)) = open_stream(&devices[i], self.rate, Arc::clone(&self.synth), Arc::clone(&self.stats)) {
+                self.backend = Backend::Cpal { _stream: stream };
+            }
         }
+        if self.is_null() { self.device_name = "none".to_string(); }
+        Err(last_err.unwrap_or_else(|| anyhow::anyhow!("No other output device")))
+    }
+}
 
-        let synth_clone = Arc::clone(&synth);
+/// `"name  (default Hz, min–max Hz)"` lines for `--list-audio-devices`.
+pub fn list_devices() -> Result<Vec<String>> {
+    let host = cpal::default_host();
+    Ok(host.output_devices()
+        .context("Cannot enumerate output devices")?
+        .map(|d| {
+            let name = d.name().unwrap_or_else(|_| "unknown".to_string());
+            let default = d.default_output_config().map_or(0, |c| c.sample_rate().0);
+            let ranges: Vec<_> = d.supported_output_configs().map(|c| c.collect()).unwrap_or_default();
+            match (ranges.iter().map(|c| c.min_sample_rate().0).min(), ranges.iter().map(|c| c.max_sample_rate().0).max()) {
+                (Some(lo), Some(hi)) => format!("{}  ({} Hz default, {}–{} Hz)", name, default, lo, hi),
+                _                    => format!("{}  ({} Hz default)", name, default),
+            }
+        })
+        .collect())
+}
 
-        let stream = match config.sample_format() {
-            cpal::SampleFormat::F32 => build_stream::<f32>(&device, &config.into(), synth_clone, channels)?,
-            cpal::SampleFormat::I16 => build_stream::<i16>(&device, &config.into(), synth_clone, channels)?,
-            cpal::SampleFormat::U16 => build_stream::<u16>(&device, &config.into(), synth_clone, channels)?,
-            fmt => anyhow::bail!("Unsupported sample format: {:?}", fmt),
-        };
+/// The config to open `device` with: its default, or at `rate` the
+/// supported config closest to the default (same channels, then same
+/// sample format).
+fn stream_config(device: &cpal::Device, rate: Option<u32>) -> Result<SupportedStreamConfig> {
+    let default = device.default_output_config().context("No default output config")?;
+    let Some(rate) = rate.filter(|&r| r != default.sample_rate().0) else { return Ok(default) };
+    let mut ranges: Vec<_> = device.supported_output_configs()
+        .context("Cannot read the device's output configs")?
+        .filter(|c| c.min_sample_rate().0 <= rate && rate <= c.max_sample_rate().0)
+        .filter(|c| matches!(c.sample_format(), SampleFormat::F32 | SampleFormat::I16 | SampleFormat::U16))
+        .collect();
+    ranges.sort_by_key(|c| (c.channels() != default.channels(), c.sample_format() != default.sample_format()));
+    match ranges.into_iter().next() {
+        Some(c) => Ok(c.with_sample_rate(SampleRate(rate))),
+        None    => anyhow::bail!("The device doesn't support {} Hz", rate),
+    }
+}
+
+/// Build and start an output stream on `device` at `rate`, or at its
+/// default rate when `None`.
+fn open_stream(device: &cpal::Device, rate: Option<u32>, synth: Arc<Mutex<Synth>>, stats: Arc<AudioStats>)
+    -> Result<(Stream, String)>
+{
+    let name = device.name().unwrap_or_else(|_| "unknown".to_string());
+    let config = stream_config(device, rate).with_context(|| format!("Cannot open {}", name))?;
+
+    let sample_rate = config.sample_rate().0 as f32;
+    let channels = config.channels() as usize;
+
+    // Update synth sample rate
+    synth.lock().unwrap().set_sample_rate(sample_rate);
+    stats.reset(sample_rate, channels);
 
-        stream.play().context("Failed to start audio stream")?;
+    let stream = match config.sample_format() {
+        SampleFormat::F32 => build_stream::<f32>(device, &config.into(), synth, stats, channels)?,
+        SampleFormat::I16 => build_stream::<i16>(device, &config.into(), synth, stats, channels)?,
+        SampleFormat::U16 => build_stream::<u16>(device, &config.into(), synth, stats, channels)?,
+        fmt => anyhow::bail!("Unsupported sample format: {:?}", fmt),
+    };
 
-        Ok(Self { _stream: stream })
+    stream.play().context("Failed to start audio stream")?;
+
+    Ok((stream, name))
+}
+
+// ── Null sink ─────────────────────────────────────────────────────────────────
+
+/// Background thread that renders `Synth` at its sample rate against the
+/// wall clock and throws the samples away.
+struct NullSink {
+    stop:   Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl NullSink {
+    /// Wake-up period of the render thread.
+    const TICK: Duration = Duration::from_millis(5);
+
+    fn spawn(synth: Arc<Mutex<Synth>>) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+        let handle = thread::spawn(move || {
+            let start = Instant::now();
+            let mut rendered: u64 = 0;
+            while !stop_flag.load(Ordering::Relaxed) {
+                thread::sleep(Self::TICK);
+                let mut s = synth.lock().unwrap();
+                let due = (start.elapsed().as_secs_f64() * s.sample_rate as f64) as u64;
+                // Never render more than ~100 ms in one go (e.g. after a stall)
+                // so the lock is not held long enough to freeze the UI.
+                let max_batch = (s.sample_rate * 0.1) as u64;
+                let batch = due.saturating_sub(rendered).min(max_batch);
+                for _ in 0..batch { s.generate_sample(); }
+                rendered = due.max(rendered + batch);
+            }
+        });
+        Self { stop, handle: Some(handle) }
+    }
+}
+
+impl Drop for NullSink {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(h) = self.handle.take() { let _ = h.join(); }
     }
 }
 
@@ -48,25 +315,58 @@ fn build_stream<T>(
     device: &cpal::Device,
     config: &StreamConfig,
     synth: Arc<Mutex<Synth>>,
+    stats: Arc<AudioStats>,
     channels: usize,
 ) -> Result<Stream>
 where
     T: cpal::Sample + cpal::SizedSample + cpal::FromSample<f32>,
 {
     let err_fn = |err| eprintln!("Audio stream error: {}", err);
+    let sample_rate = config.sample_rate.0 as f64;
+    let mut last_callback: Option<Instant> = None;
 
     let stream = device.build_output_stream(
         config,
-        move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
-            let mut synth = synth.lock().unwrap();
+        move |data: &mut [T], info: &cpal::OutputCallbackInfo| {
+            let started = Instant::now();
+            let ts = info.timestamp();
+            let latency = ts.playback.duration_since(&ts.callback).map_or(0, |d| d.as_micros() as u32);
+            stats.latency_us.store(latency, Ordering::Relaxed);
+
             let frame_count = data.len() / channels;
+            stats.buffer_frames.store(frame_count as u32, Ordering::Relaxed);
+            let buffer_dur = Duration::from_secs_f64(frame_count as f64 / sample_rate);
+
+            // A callback arriving more than 1.5 buffers after the previous one
+            // means the device already played out everything we gave it.
+            let late = last_callback.is_some_and(|t| started.duration_since(t) > buffer_dur * 3 / 2);
+            last_callback = Some(started);
+
+            let mut synth = synth.lock().unwrap();
             for frame in 0..frame_count {
-                let sample = synth.generate_sample();
-                let value = T::from_sample(sample);
-                for ch in 0..channels {
-                    data[frame * channels + ch] = value;
+                let (l, r) = synth.generate_frame();
+                let cue = synth.cue_out;
+                let out = &mut data[frame * channels..(frame + 1) * channels];
+                // Left and right on the first pair; a mono device gets the
+                // mid, and any further channels repeat the pair, except the
+                // cue pair while a bus is cued.
+                if channels == 1 {
+                    out[0] = T::from_sample((l + r) * 0.5);
+                } else {
+                    for (ch, x) in out.iter_mut().enumerate() {
+                        *x = T::from_sample(match cue {
+                            Some(c) if ch / 2 == CUE_CHANNEL / 2 => c,
+                            _ if ch % 2 == 0                   => l,
+                            _                                  => r,
+                        });
+                    }
                 }
             }
+            drop(synth);
+
+            if late || started.elapsed() > buffer_dur {
+                stats.xruns.fetch_add(1, Ordering::Relaxed);
+            }
         },
         err_fn,
         None,
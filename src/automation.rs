@@ -0,0 +1,135 @@
+use crate::midi::CcParam;
+use crate::synth::Synth;
+
+/// Changes further apart than this many steps are separate moves of the
+/// control: a new move doesn't erase what lies between it and the last one.
+const TOUCH_GAP: f64 = 2.0;
+
+/// Recorded values of one parameter over the automation loop.
+#[derive(Clone)]
+pub struct Lane {
+    pub param:  CcParam,
+    /// `(position in steps within the loop, value)`, sorted by position.
+    pub points: Vec<(f64, f32)>,
+    /// Left out of playback; the param keeps whatever value it is set to.
+    pub bypass: bool,
+    /// Absolute `step_pos` of the last value recorded in the current move.
+    last: Option<f64>,
+}
+
+impl Lane {
+    pub fn new(param: CcParam, points: Vec<(f64, f32)>) -> Self {
+        Self { param, points, bypass: false, last: None }
+    }
+
+    /// The lane's value at loop position `pos` of a `len`-step loop:
+    /// linear between neighbouring points (in log space for log-scaled
+    /// params), wrapping from the last point round to the first.
+    pub fn value_at(&self, pos: f64, len: f64) -> Option<f32> {
+        let (first, last) = (*self.points.first()?, *self.points.last()?);
+        let i = self.points.partition_point(|&(p, _)| p <= pos);
+        let (p0, v0) = if i == 0 { (last.0 - len, last.1) } else { self.points[i - 1] };
+        let (p1, v1) = self.points.get(i).copied().unwrap_or((first.0 + len, first.1));
+        let t = if p1 > p0 { ((pos - p0) / (p1 - p0)) as f32 } else { 0.0 };
+        Some(if self.param.range().2 {
+            (v0.ln() + (v1.ln() - v0.ln()) * t).exp()
+        } else {
+            v0 + (v1 - v0) * t
+        })
+    }
+
+    /// Whether the control is being moved for recording, which takes it
+    /// out of playback until the move ends.
+    fn held(&self, step_pos: f64) -> bool {
+        self.last.is_some_and(|l| step_pos - l <= TOUCH_GAP)
+    }
+
+    /// Overdub `value` at loop position `pos`.  While the control keeps
+    /// moving, points recorded on an earlier pass between the previous
+    /// change and this one are replaced.
+    fn record(&mut self, step_pos: f64, pos: f64, len: f64, value: f32) {
+        if let Some(last) = self.last.filter(|&l| step_pos - l <= TOUCH_GAP) {
+            let from = last.rem_euclid(len);
+            let over = |p: f64| if from <= pos { p > from && p <= pos } else { p > from || p <= pos };
+            self.points.retain(|&(p, _)| !over(p));
+        }
+        let at = self.points.partition_point(|&(p, _)| p < pos);
+        match self.points.get_mut(at) {
+            Some(pt) if pt.0 == pos => pt.1 = value,
+            _ => self.points.insert(at, (pos, value)),
+        }
+        self.last = Some(step_pos);
+    }
+}
+
+/// Parameter automation: one lane per recorded parameter, all sharing one
+/// loop.  Lives on `App`, which publishes a copy to the audio thread
+/// (`Playback`) whenever the lanes change.
+pub struct Automation {
+    /// Record param changes while the transport runs.
+    pub armed:      bool,
+    /// Loop length in steps.  Set by the first recorded change to the
+    /// longest pattern, and kept until every lane is gone.
+    pub loop_steps: usize,
+    pub lanes:      Vec<Lane>,
+}
+
+impl Automation {
+    pub fn new() -> Self {
+        Self { armed: false, loop_steps: 0, lanes: Vec::new() }
+    }
+
+    /// Tempo moves the loop itself, so it isn't automated.
+    pub fn automatable(param: CcParam) -> bool {
+        param != CcParam::Bpm
+    }
+
+    pub fn set_armed(&mut self, armed: bool) {
+        self.armed = armed;
+        for lane in &mut self.lanes { lane.last = None; }
+    }
+
+    /// Note the new value of `param`, just changed under the lock on `s`.
+    /// Kept only when armed and some sequencer or the drums are playing.
+    pub fn record(&mut self, s: &mut Synth, param: CcParam) {
+        if !self.armed || !s.transport_running() || !Self::automatable(param) { return; }
+        if self.lanes.is_empty() {
+            self.loop_steps = s.sequencer.num_steps
+                .max(s.sequencer2.num_steps)
+                .max(s.drum_machine.num_steps);
+        }
+        let len = self.loop_steps as f64;
+        let pos = s.step_pos.rem_euclid(len);
+        let i = match self.lanes.iter().position(|l| l.param == param) {
+            Some(i) => i,
+            None    => { self.lanes.push(Lane::new(param, Vec::new())); self.lanes.len() - 1 }
+        };
+        let value = *s.param_mut(param);
+        self.lanes[i].record(s.step_pos, pos, len, value);
+        self.publish(s);
+    }
+
+    /// Hand the current lanes to the audio thread.
+    pub fn publish(&self, s: &mut Synth) {
+        s.automation = Playback { loop_steps: self.loop_steps, lanes: self.lanes.clone() };
+    }
+}
+
+/// The audio thread's copy of the lanes.
+#[derive(Default)]
+pub struct Playback {
+    pub loop_steps: usize,
+    pub lanes:      Vec<Lane>,
+}
+
+impl Playback {
+    /// Every lane's value at `step_pos`, skipping bypassed lanes and ones
+    /// being recorded over.
+    pub fn values(&self, step_pos: f64) -> impl Iterator<Item = (CcParam, f32)> + '_ {
+        let len = self.loop_steps as f64;
+        let pos = if len > 0.0 { step_pos.rem_euclid(len) } else { 0.0 };
+        self.lanes.iter()
+            .filter(move |l| !l.bypass && !l.held(step_pos))
+            .filter_map(move |l| Some((l.param, l.value_at(pos, len)?)))
+    }
+}
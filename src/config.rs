@@ -0,0 +1,160 @@
+use anyhow::{bail, Context, Result};
+
+use crate::audio;
+use crate::keymap::Keymap;
+use crate::midi::{self, CcMap};
+
+/// Default silence after the last key-repeat before a held note is released
+/// when the terminal has no key-release events.
+pub const DEFAULT_RELEASE_MS: u64 = 600;
+
+/// Keymap read from the working directory when `--keymap` is not given.
+const DEFAULT_KEYMAP_PATH: &str = "keymap.toml";
+
+/// Startup options parsed from the command line.
+pub struct Config {
+    /// Skip the audio device entirely and render into a silent null sink.
+    pub no_audio: bool,
+    /// Output device given with `--audio-device`: part of its name
+    /// (`None` = the default device).
+    pub audio_device: Option<String>,
+    /// Sample rate given with `--sample-rate` (`None` = the device's default).
+    pub sample_rate: Option<u32>,
+    /// Fallback release threshold in milliseconds (see `App::fallback_release`).
+    pub release_ms: u64,
+    /// Keymap file given with `--keymap`.
+    pub keymap_path: Option<String>,
+    /// MIDI output port given with `--midi-out` (`None` = no MIDI).
+    pub midi_out: Option<String>,
+    /// CC map file given with `--midi-map`.
+    pub midi_map_path: Option<String>,
+    /// MIDI channel for notes and CCs, 0–15 (`--midi-channel` takes 1–16).
+    pub midi_channel: u8,
+    /// MIDI keyboard given with `--midi-in` (`"first"` when no port is named;
+    /// `None` = no MIDI input).
+    pub midi_in: Option<String>,
+    /// MIDI clock source given with `--midi-clock-in` (`None` = internal clock).
+    pub midi_clock_in: Option<String>,
+    /// UDP port for the OSC server (`--osc-port`; `None` = no OSC).
+    pub osc_port: Option<u16>,
+    /// TCP port for JSON remote control (`--remote`; `None` = off).
+    pub remote_port: Option<u16>,
+}
+
+impl Config {
+    pub fn from_args() -> Result<Self> {
+        let mut cfg = Self {
+            no_audio: false, audio_device: None, sample_rate: None, release_ms: DEFAULT_RELEASE_MS, keymap_path: None,
+            midi_out: None, midi_map_path: None, midi_channel: 0, midi_in: None, midi_clock_in: None,
+            osc_port: None, remote_port: None,
+        };
+        let mut args = std::env::args().skip(1).peekable();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--no-audio"      => cfg.no_audio = true,
+                "--audio-device"  => cfg.audio_device = Some(args.next().context("--audio-device needs a name")?),
+                "--sample-rate"   => {
+                    let v = args.next().context("--sample-rate needs a value")?;
+                    let hz: u32 = v.parse()
+                        .with_context(|| format!("Invalid --sample-rate value: {}", v))?;
+                    if !(8000..=192_000).contains(&hz) { bail!("--sample-rate must be between 8000 and 192000"); }
+                    cfg.sample_rate = Some(hz);
+                }
+                "--list-audio-devices" => {
+                    let devices = audio::list_devices()?;
+                    if devices.is_empty() { println!("No output devices found"); }
+                    for line in devices { println!("{}", line); }
+                    std::process::exit(0);
+                }
+                "--release-ms"    => {
+                    let v = args.next().context("--release-ms needs a value")?;
+                    cfg.release_ms = v.parse()
+                        .with_context(|| format!("Invalid --release-ms value: {}", v))?;
+                    if !(50..=5000).contains(&cfg.release_ms) {
+                        bail!("--release-ms must be between 50 and 5000");
+                    }
+                }
+                "--keymap"        => cfg.keymap_path = Some(args.next().context("--keymap needs a path")?),
+                "--dump-keymap"   => { print!("{}", Keymap::default_toml()); std::process::exit(0); }
+                "--midi-out"      => cfg.midi_out = Some(args.next().context("--midi-out needs a port")?),
+                "--midi-map"      => cfg.midi_map_path = Some(args.next().context("--midi-map needs a path")?),
+                "--midi-channel"  => {
+                    let v = args.next().context("--midi-channel needs a value")?;
+                    let ch: u8 = v.parse()
+                        .with_context(|| format!("Invalid --midi-channel value: {}", v))?;
+                    if !(1..=16).contains(&ch) { bail!("--midi-channel must be between 1 and 16"); }
+                    cfg.midi_channel = ch - 1;
+                }
+                "--midi-in"       => {
+                    // PORT is optional: bare `--midi-in` takes the first source.
+                    cfg.midi_in = Some(args.next_if(|a| !a.starts_with("--")).unwrap_or_else(|| "first".to_string()));
+                }
+                "--midi-clock-in" => cfg.midi_clock_in = Some(args.next().context("--midi-clock-in needs a port")?),
+                "--osc-port"      => {
+                    let v = args.next().context("--osc-port needs a port number")?;
+                    cfg.osc_port = Some(v.parse().with_context(|| format!("Invalid --osc-port value: {}", v))?);
+                }
+                "--remote"        => {
+                    let v = args.next().context("--remote needs a port number")?;
+                    cfg.remote_port = Some(v.parse().with_context(|| format!("Invalid --remote value: {}", v))?);
+                }
+                "--list-midi-ports" => {
+                    for line in midi::list_ports()? { println!("{}", line); }
+                    std::process::exit(0);
+                }
+                "-h" | "--help"   => { print_usage(); std::process::exit(0); }
+                other             => bail!("Unknown argument: {} (try --help)", other),
+            }
+        }
+        Ok(cfg)
+    }
+
+    /// The `--keymap` file, else `keymap.toml` if present, else the defaults.
+    pub fn keymap(&self) -> Result<Keymap> {
+        match &self.keymap_path {
+            Some(p) => Keymap::load(p),
+            None if std::path::Path::new(DEFAULT_KEYMAP_PATH).exists() => Keymap::load(DEFAULT_KEYMAP_PATH),
+            None => Ok(Keymap::defaults()),
+        }
+    }
+
+    /// The `--midi-map` file, else the default CC assignments.
+    pub fn cc_map(&self) -> Result<CcMap> {
+        match &self.midi_map_path {
+            Some(p) => CcMap::load(p),
+            None    => Ok(CcMap::defaults()),
+        }
+    }
+}
+
+fn print_usage() {
+    println!("Usage: tuibeat [OPTIONS]");
+    println!();
+    println!("Options:");
+    println!("  --no-audio         Run without an audio device (silent; clock runs on a timer)");
+    println!("  --audio-device <NAME>");
+    println!("                     Play through the output device whose name contains NAME");
+    println!("  --sample-rate <HZ> Open the output at HZ (8000-192000; default: the device's)");
+    println!("  --list-audio-devices");
+    println!("                     List output devices and their sample rates and exit");
+    println!("  --release-ms <MS>  Release held notes MS after the last key-repeat when the");
+    println!("                     terminal reports no key releases (default {}, 50-5000).", DEFAULT_RELEASE_MS);
+    println!("                     Set it just above your key-repeat delay: too low cuts");
+    println!("                     notes short, too high leaves them ringing after release.");
+    println!("  --keymap <PATH>    Key bindings file (default: ./keymap.toml if it exists)");
+    println!("  --dump-keymap      Print the default keymap as TOML and exit");
+    println!("  --midi-out <PORT>  Send notes and parameter changes (as CCs) to PORT: a");
+    println!("                     client:port address, part of a port name, or \"virtual\"");
+    println!("  --midi-channel <N> MIDI channel for notes and CCs (1-16, default 1)");
+    println!("  --midi-map <PATH>  CC assignments (TOML: [cc] param = number)");
+    println!("  --midi-in [PORT]   Play synth 1 from a MIDI keyboard on PORT, with velocity");
+    println!("                     (same PORT forms as --midi-out, or \"first\": the first");
+    println!("                     readable port, the default when PORT is left out)");
+    println!("  --midi-clock-in <PORT>");
+    println!("                     Follow MIDI clock and Start/Stop from PORT instead of the");
+    println!("                     internal BPM (same PORT forms as --midi-out)");
+    println!("  --list-midi-ports  List MIDI ports that can be connected to and exit");
+    println!("  --osc-port <PORT>  Accept OSC remote control messages on UDP PORT");
+    println!("  --remote <PORT>    Accept line-delimited JSON commands on TCP 127.0.0.1:PORT");
+    println!("  -h, --help         Show this help");
+}
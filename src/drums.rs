@@ -1,5 +1,10 @@
 use std::f32::consts::PI;
+use std::sync::Arc;
+
 use crate::effects::EffectChain;
+use crate::sample::Sample;
+use crate::sequencer::{next_step_count, nudge_steps, Groove, StepClock, MAX_NUDGE};
+use crate::synth::pan_gains;
 
 // ── Drum kind ─────────────────────────────────────────────────────────────────
 
@@ -27,6 +32,11 @@ impl DrumKind {
         DrumKind::HighTom,
     ];
 
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&k| k == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
     pub fn name(self) -> &'static str {
         match self {
             Self::Kick      => "Kick ",
@@ -53,6 +63,12 @@ impl DrumKind {
             Self::HighTom   => 0.30,
         }
     }
+
+    /// Has a pitched oscillator for the track's tuning to transpose.
+    /// The noise-only kinds take it as a change of decay instead.
+    fn tonal(self) -> bool {
+        !matches!(self, Self::ClosedHat | Self::OpenHat | Self::Clap)
+    }
 }
 
 // ── Noise ─────────────────────────────────────────────────────────────────────
@@ -68,8 +84,9 @@ fn xorshift(state: &mut u32) -> f32 {
 
 // ── Single drum voice ─────────────────────────────────────────────────────────
 
-/// One triggered drum hit.  Generates samples until it naturally decays.
-/// Multiple voices of the same (or different) kind run in parallel inside
+/// One triggered drum hit: the track's sample read once through, or else
+/// the kind's synthesised sound.  Generates samples until it naturally
+/// decays (or the sample ends).  Multiple voices of the same (or different) kind run in parallel inside
 /// `DrumMachine::voices`, giving full polyphony.
 struct DrumVoice {
     kind: DrumKind,
@@ -81,18 +98,47 @@ struct DrumVoice {
     noise: u32,
     sample_rate: f32,
     volume: f32,
+    /// Left and right gains from the track's pan when the hit started.
+    gains: (f32, f32),
+    /// Factor on the oscillator frequencies from the track's tuning.
+    ratio: f32,
+    /// Factor on elapsed time: the noise-only kinds decay faster when
+    /// tuned up, by the square root of `ratio`.
+    speed: f32,
+    /// The track's sample, played in place of the synthesis.
+    sample: Option<Arc<Sample>>,
+    /// Read position in the sample, in its samples.
+    pos: f64,
+    /// Read step per output sample: the tuning times the sample's rate
+    /// over ours.
+    step: f64,
 }
 
 impl DrumVoice {
-    fn new(kind: DrumKind, sample_rate: f32, seed: u32, volume: f32) -> Self {
+    /// A hit of `track`, with its sound, level, pan and tuning as they are now.
+    fn new(track: &DrumTrack, sample_rate: f32, seed: u32) -> Self {
+        let kind  = track.kind;
+        let ratio = (track.pitch / 12.0).exp2();
+        let speed = if kind.tonal() { 1.0 } else { ratio.sqrt() };
+        let step  = track.sample.as_ref().map_or(1.0, |smp| ratio as f64 * (smp.rate / sample_rate) as f64);
+        let dur_samples = match &track.sample {
+            Some(smp) => ((smp.data.len() - 1) as f64 / step).ceil() as u64,
+            None      => (kind.duration() / speed * sample_rate).ceil() as u64,
+        };
         Self {
             kind,
             sample_pos: 0,
-            dur_samples: (kind.duration() * sample_rate).ceil() as u64,
+            dur_samples,
             phase: 0.0,
             noise: seed | 1, // xorshift must never be 0
             sample_rate,
-            volume,
+            volume: track.volume,
+            gains: pan_gains(track.pan),
+            ratio,
+            speed,
+            sample: track.sample.clone(),
+            pos: 0.0,
+            step,
         }
     }
 
@@ -101,11 +147,22 @@ impl DrumVoice {
         self.sample_pos >= self.dur_samples
     }
 
+    #[inline]
+    fn remaining(&self) -> u64 {
+        self.dur_samples.saturating_sub(self.sample_pos)
+    }
+
     fn next_sample(&mut self) -> f32 {
         if self.is_finished() {
             return 0.0;
         }
-        let t = self.sample_pos as f32 / self.sample_rate;
+        if let Some(smp) = &self.sample {
+            let raw = smp.one_shot(self.pos).unwrap_or(0.0);
+            self.pos += self.step;
+            self.sample_pos += 1;
+            return (raw * self.volume).clamp(-1.0, 1.0);
+        }
+        let t = self.sample_pos as f32 / self.sample_rate * self.speed;
         let raw = match self.kind {
             DrumKind::Kick      => self.kick(t),
             DrumKind::Snare     => self.snare(t),
@@ -141,7 +198,7 @@ impl DrumVoice {
 
     fn kick(&mut self, t: f32) -> f32 {
         // Exponential pitch sweep 150 → 50 Hz, fast transient click
-        let freq = 50.0 + 100.0 * (-t * 32.0_f32).exp();
+        let freq = (50.0 + 100.0 * (-t * 32.0_f32).exp()) * self.ratio;
         let tone = self.sine(freq);
         let amp  = (-t * 11.0_f32).exp();
         let click = if t < 0.004 { self.noise() * 0.38 } else { 0.0 };
@@ -150,7 +207,7 @@ impl DrumVoice {
 
     fn snare(&mut self, t: f32) -> f32 {
         let noise = self.noise();
-        let tone  = self.sine(195.0);
+        let tone  = self.sine(195.0 * self.ratio);
         let amp   = (-t * 24.0_f32).exp();
         (noise * 0.72 + tone * 0.28) * amp
     }
@@ -180,7 +237,7 @@ impl DrumVoice {
     }
 
     fn tom(&mut self, t: f32, start_hz: f32, end_hz: f32, decay_s: f32) -> f32 {
-        let freq  = end_hz + (start_hz - end_hz) * (-t * 22.0_f32).exp();
+        let freq  = (end_hz + (start_hz - end_hz) * (-t * 22.0_f32).exp()) * self.ratio;
         let tone  = self.sine(freq);
         let noise = self.noise();
         let amp   = (-t / decay_s).exp();
@@ -188,6 +245,91 @@ impl DrumVoice {
     }
 }
 
+// ── Trig conditions ───────────────────────────────────────────────────────────
+
+/// When an active step plays, on top of its probability.  Ratios count
+/// passes through the pattern since the drums were started.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum TrigCondition {
+    #[default]
+    Always,
+    /// `Ratio(a, b)`: pass `a` of every `b` (1-based).
+    Ratio(u8, u8),
+    /// Every pass except the ones `Ratio(a, b)` plays on.
+    NotRatio(u8, u8),
+    /// Only while the fill button is on.
+    Fill,
+    NotFill,
+}
+
+impl TrigCondition {
+    /// Order the condition key steps through.
+    pub const CYCLE: [TrigCondition; 9] = [
+        Self::Always,
+        Self::Ratio(1, 2), Self::NotRatio(1, 2),
+        Self::Ratio(1, 3), Self::NotRatio(1, 3),
+        Self::Ratio(1, 4), Self::NotRatio(1, 4),
+        Self::Fill, Self::NotFill,
+    ];
+
+    pub fn next(self) -> Self {
+        let i = Self::CYCLE.iter().position(|&c| c == self).unwrap_or(0);
+        Self::CYCLE[(i + 1) % Self::CYCLE.len()]
+    }
+
+    /// Whether the step plays on pass `loop_count` (0-based).
+    pub fn passes(self, loop_count: u64, fill: bool) -> bool {
+        let on = |a: u8, b: u8| b > 0 && loop_count % b as u64 == (a as u64).saturating_sub(1);
+        match self {
+            Self::Always         => true,
+            Self::Ratio(a, b)    => on(a, b),
+            Self::NotRatio(a, b) => !on(a, b),
+            Self::Fill           => fill,
+            Self::NotFill        => !fill,
+        }
+    }
+
+    /// Name in the status line and save file, e.g. `1:2`, `!1:2`, `FILL`.
+    pub fn label(self) -> String {
+        match self {
+            Self::Always         => "ALWAYS".to_string(),
+            Self::Ratio(a, b)    => format!("{}:{}", a, b),
+            Self::NotRatio(a, b) => format!("!{}:{}", a, b),
+            Self::Fill           => "FILL".to_string(),
+            Self::NotFill        => "!FILL".to_string(),
+        }
+    }
+
+    /// Inverse of `label`; ratios need `1 <= a <= b <= 16`.
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ALWAYS" => return Some(Self::Always),
+            "FILL"   => return Some(Self::Fill),
+            "!FILL"  => return Some(Self::NotFill),
+            _ => {}
+        }
+        let (not, ratio) = match s.strip_prefix('!') { Some(r) => (true, r), None => (false, s) };
+        let (a, b) = ratio.split_once(':')?;
+        let (a, b): (u8, u8) = (a.parse().ok()?, b.parse().ok()?);
+        if a == 0 || a > b || b > 16 { return None; }
+        Some(if not { Self::NotRatio(a, b) } else { Self::Ratio(a, b) })
+    }
+
+    /// One-character grid glyph; `None` for `Always`.
+    pub fn glyph(self) -> Option<char> {
+        match self {
+            Self::Always         => None,
+            Self::Ratio(_, 2)    => Some('½'),
+            Self::Ratio(_, 3)    => Some('⅓'),
+            Self::Ratio(_, 4)    => Some('¼'),
+            Self::Ratio(..)      => Some('%'),
+            Self::NotRatio(..)   => Some('¬'),
+            Self::Fill           => Some('F'),
+            Self::NotFill        => Some('f'),
+        }
+    }
+}
+
 // ── Drum track ────────────────────────────────────────────────────────────────
 
 /// One row in the drum machine: a drum instrument, its step pattern,
@@ -195,28 +337,89 @@ impl DrumVoice {
 pub struct DrumTrack {
     pub kind:  DrumKind,
     pub steps: Vec<u8>,
+    /// Per-step trig condition, parallel to `steps`.
+    pub conds: Vec<TrigCondition>,
+    /// Per-step timing offset in ticks (`-MAX_NUDGE..=MAX_NUDGE`), parallel
+    /// to `steps`: negative = early.
+    pub nudge: Vec<i8>,
     pub muted: bool,
     pub volume: f32,
+    /// Stereo position, -1.0 (left) – 1.0 (right).
+    pub pan: f32,
+    /// Tuning in semitones (`-MAX_PITCH..=MAX_PITCH`).
+    pub pitch: f32,
+    /// Chance (0–100) that a step which fires is retriggered, rolled
+    /// separately from the step's own probability.
+    pub retrig: u8,
+    /// Hits a retriggered step plays, evenly spaced across the step (2–4).
+    pub retrig_hits: u8,
+    /// User-given name shown in place of the kind (`None` = use the kind).
+    pub name:  Option<String>,
+    /// One-shot played in place of the kind's synthesised sound (`None` =
+    /// synthesise).  The kind still decides the choke and the sidechain.
+    pub sample: Option<Arc<Sample>>,
     /// Per-track insert effects (e.g. compression, EQ). Empty = passthrough.
     #[allow(dead_code)]
     pub fx: EffectChain,
 }
 
 impl DrumTrack {
-    fn new(kind: DrumKind, num_steps: usize) -> Self {
+    pub fn new(kind: DrumKind, num_steps: usize) -> Self {
         Self {
             kind,
             steps: vec![0u8; num_steps],
+            conds: vec![TrigCondition::Always; num_steps],
+            nudge: vec![0; num_steps],
             muted: false,
             volume: 0.85,
+            pan: 0.0,
+            pitch: 0.0,
+            retrig: 0,
+            retrig_hits: 2,
+            name: None,
+            sample: None,
             fx: EffectChain::new(),
         }
     }
+
+    /// Name shown in the grid and status line.
+    pub fn label(&self) -> &str {
+        self.name.as_deref().unwrap_or_else(|| self.kind.name().trim_end())
+    }
+
+    /// Whether `step` is active and its condition lets it play this pass
+    /// (the probability roll comes after).
+    fn may_fire(&self, step: usize, loop_count: u64, fill: bool) -> bool {
+        !self.muted
+            && self.steps.get(step).copied().unwrap_or(0) > 0
+            && self.conds.get(step).copied().unwrap_or_default().passes(loop_count, fill)
+    }
 }
 
 // ── Drum machine ──────────────────────────────────────────────────────────────
 
-/// 8-track polyphonic step sequencer with synthesised drum voices.
+/// One track's per-step data, as taken by `DrumMachine::clear_pattern()`.
+pub struct TrackPattern {
+    steps: Vec<u8>,
+    conds: Vec<TrigCondition>,
+    nudge: Vec<i8>,
+}
+
+/// Hard limit on simultaneously sounding drum hits.  Only pathological
+/// patterns (very fast tempos, dense retriggers) ever get close.
+const MAX_VOICES: usize = 48;
+
+/// Most tracks a kit can hold (bounded by the panel height).
+pub const MAX_TRACKS: usize = 12;
+
+/// Most hits a retriggered step can play.
+pub const MAX_RETRIG_HITS: u8 = 4;
+
+/// Furthest a track can be tuned either way, in semitones.
+pub const MAX_PITCH: f32 = 24.0;
+
+/// Polyphonic step sequencer with synthesised drum voices.  Starts with one
+/// track per `DrumKind`; tracks can be added, removed and reordered.
 ///
 /// BPM is supplied externally from `Synth::bpm` so the drum machine always
 /// stays locked to the melodic sequencer without a separate clock.
@@ -228,7 +431,13 @@ pub struct DrumMachine {
     pub num_steps:    usize,
     pub current_step: usize,
     pub playing:      bool,
-    pub swing:        f32,  // 0.0 = straight, ~0.33 = shuffle, 0.5 = maximum
+    /// Fill button: `Fill` steps play and `!Fill` steps rest while it is on.
+    pub fill:         bool,
+    /// Passes through the pattern since play started, for ratio conditions.
+    pub loop_count:   u64,
+    /// The bus plays a frozen render: pattern hits are still decided (and
+    /// still key the sidechain) but make no sound.  Previews still play.
+    pub frozen:       bool,
     /// Master insert effects applied to the summed drum bus output.
     pub fx: EffectChain,
 
@@ -239,6 +448,10 @@ pub struct DrumMachine {
     seed: u32,
     /// Separate XOR-shift seed used only for probability rolls.
     prob_seed: u32,
+    /// Hits decided but still to play: `(track, musical position)`.
+    hits: Vec<(usize, f64)>,
+    /// A step has fired since play started, so the next step 0 begins a new pass.
+    fired: bool,
     /// Set to true each sample that a kick fires; cleared by Synth::generate_sample.
     pub kick_triggered: bool,
 }
@@ -252,89 +465,137 @@ impl DrumMachine {
             num_steps,
             current_step: 0,
             playing: false,
-            swing: 0.0,
+            fill: false,
+            loop_count: 0,
+            frozen: false,
             fx: EffectChain::new(),
             sample_rate,
-            voices: Vec::with_capacity(32),
+            voices: Vec::with_capacity(MAX_VOICES),
             seed: 0xBEEF_CAFE,
             prob_seed: 0xDEAD_BEEF,
+            hits: Vec::new(),
+            fired: false,
             kick_triggered: false,
         }
     }
 
-    fn samples_per_step(&self, bpm: f32) -> u64 {
-        ((self.sample_rate * 60.0) / (bpm * 4.0)).round() as u64
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
     }
 
-    /// Generate the next audio sample.  Called once per sample from the audio
-    /// thread inside `Synth::generate_sample`, using the shared master clock.
-    pub fn generate_sample(&mut self, bpm: f32, clock: u64) -> f32 {
-        let sps = self.samples_per_step(bpm).max(1);
-        let step_idx = (clock / sps) as usize % self.num_steps;
-        let phase_in = clock % sps;
-
-        // Odd steps are delayed by swing fraction of one step width
-        let swing_offset = if step_idx % 2 == 1 {
-            (self.swing * sps as f32).round() as u64
-        } else {
-            0
-        };
-
-        if self.playing && phase_in == swing_offset {
-            self.current_step = step_idx;
-            self.fire_step();
-        } else {
-            self.current_step = step_idx;
+    /// Generate the next stereo sample, left and right.  Called once per
+    /// sample from the audio thread inside `Synth::generate_frame`, using the
+    /// shared master clock and groove.
+    pub fn generate_sample(&mut self, clock: StepClock, groove: Groove) -> (f32, f32) {
+        self.current_step = clock.step() as usize % self.num_steps;
+
+        if self.playing {
+            // A grooved step fires up to half a step late, so the step whose
+            // trigger lands in this sample is either the current or the previous one.
+            let here = clock.pos.floor() as u64;
+            for k in [here.saturating_sub(1), here] {
+                if clock.crosses(groove.fire_pos(k)) {
+                    self.current_step = k as usize % self.num_steps;
+                    break;
+                }
+            }
+            // Steps are decided a whole nudge range ahead so early hits can
+            // be queued in time.  The first sample after starting also takes
+            // a step whose decision point has just gone by.
+            for k in [here, here + 1] {
+                let at = groove.fire_pos(k);
+                let ahead = at - nudge_steps(MAX_NUDGE);
+                if clock.crosses(ahead) || (!self.fired && ahead < clock.prev && at >= clock.prev) {
+                    self.fire_step(k as usize % self.num_steps, at);
+                }
+            }
+            if !self.hits.is_empty() { self.play_hits(clock); }
         }
 
-        // Mix all active drum voices, apply per-track fx, then sum
-        let mut mix = 0.0f32;
+        // Mix all active drum voices into left and right by their pan
+        let (mut left, mut right) = (0.0f32, 0.0f32);
         for v in &mut self.voices {
-            mix += v.next_sample();
+            let x = v.next_sample();
+            left  += x * v.gains.0;
+            right += x * v.gains.1;
         }
         self.voices.retain(|v| !v.is_finished());
 
-        // Master bus fx chain (empty = passthrough)
-        let out = self.fx.process(mix);
+        // Master bus fx chain (empty = passthrough).  The chain is mono, so
+        // it runs on the mid and the side passes by it.
+        let mid  = self.fx.process((left + right) * 0.5);
+        let side = (left - right) * 0.5;
 
         // Gentle headroom scaling + soft clip
-        (out * 0.22).tanh()
+        (((mid + side) * 0.22).tanh(), ((mid - side) * 0.22).tanh())
     }
 
-    fn fire_step(&mut self) {
-        // Hi-hat choke: kill any ringing open hat when a closed hat fires.
-        let closed_fires = self.tracks.iter().any(|t| {
-            t.kind == DrumKind::ClosedHat
-                && !t.muted
-                && t.steps.get(self.current_step).copied().unwrap_or(0) > 0
-        });
-        if closed_fires {
-            self.voices.retain(|v| v.kind != DrumKind::OpenHat);
-        }
+    /// Decide which tracks play `step`, which falls due at musical position
+    /// `at`, and queue their hits.
+    fn fire_step(&mut self, step: usize, at: f64) {
+        if step == 0 && self.fired { self.loop_count += 1; }
+        self.fired = true;
+        let (loop_count, fill) = (self.loop_count, self.fill);
 
-        for track in &mut self.tracks {
-            if track.muted { continue; }
-            let prob = track.steps.get(self.current_step).copied().unwrap_or(0);
-            if prob == 0 { continue; }
+        for (ti, track) in self.tracks.iter().enumerate() {
+            if !track.may_fire(step, loop_count, fill) { continue; }
+            let prob = track.steps[step];
 
             // Probability roll
-            if prob < 100 {
-                self.prob_seed ^= self.prob_seed << 13;
-                self.prob_seed ^= self.prob_seed >> 17;
-                self.prob_seed ^= self.prob_seed << 5;
-                let roll = (self.prob_seed % 100) as u8;
-                if roll >= prob { continue; }
-            }
+            if prob < 100 && Self::roll(&mut self.prob_seed) >= prob { continue; }
 
-            // Unique noise seed per trigger for timbral variation
-            self.seed = self.seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
-            if track.kind == DrumKind::Kick {
-                self.kick_triggered = true;
+            let hit = at + nudge_steps(track.nudge.get(step).copied().unwrap_or(0));
+            self.hits.push((ti, hit));
+
+            // Retrigger roll, independent of the step's probability
+            if track.retrig > 0 && Self::roll(&mut self.prob_seed) < track.retrig {
+                let n = track.retrig_hits.clamp(2, MAX_RETRIG_HITS);
+                for h in 1..n {
+                    self.hits.push((ti, hit + h as f64 / n as f64));
+                }
             }
-            self.voices.push(DrumVoice::new(track.kind, self.sample_rate, self.seed, track.volume));
         }
     }
 
+    /// XOR-shift `seed` and return a roll in 0–99.
+    fn roll(seed: &mut u32) -> u8 {
+        *seed ^= *seed << 13;
+        *seed ^= *seed >> 17;
+        *seed ^= *seed << 5;
+        (*seed % 100) as u8
+    }
+
+    /// Play the queued hits that fall due in this sample.  A hit whose
+    /// track has been muted since is dropped; removing or moving a track
+    /// drops them all.
+    fn play_hits(&mut self, clock: StepClock) {
+        let Self { hits, tracks, voices, seed, sample_rate, kick_triggered, frozen, .. } = self;
+        let due = |at: f64| at < clock.pos;
+        let live = |ti: usize| tracks.get(ti).filter(|t| !t.muted);
+
+        // Hi-hat choke: kill any ringing open hat when a closed hat fires.
+        // Done first, so an open hat due in the same sample still sounds.
+        let closed_fires = hits.iter().any(|&(ti, at)| {
+            due(at) && live(ti).is_some_and(|t| t.kind == DrumKind::ClosedHat)
+        });
+        if closed_fires && !*frozen {
+            voices.retain(|v| v.kind != DrumKind::OpenHat);
+        }
+
+        hits.retain(|&(ti, at)| {
+            if !due(at) { return true; }
+            if let Some(t) = live(ti) {
+                // Unique noise seed per trigger for timbral variation
+                *seed = seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                if t.kind == DrumKind::Kick { *kick_triggered = true; }
+                if !*frozen {
+                    Self::push_voice(voices, DrumVoice::new(t, *sample_rate, *seed));
+                }
+            }
+            false
+        });
+    }
+
     /// Immediately trigger a drum track (live preview / keyboard playing).
     /// Fully polyphonic — does not stop any already-playing voices.
     pub fn trigger_now(&mut self, track_idx: usize) {
@@ -346,16 +607,91 @@ impl DrumMachine {
         }
 
         self.seed = self.seed.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
-        self.voices.push(DrumVoice::new(track.kind, self.sample_rate, self.seed, track.volume));
+        let voice = DrumVoice::new(track, self.sample_rate, self.seed);
+        Self::push_voice(&mut self.voices, voice);
+    }
+
+    /// Add a voice to the pool.  When the pool is full, the voice closest to
+    /// finishing is stolen — it is the quietest and the least missed.
+    fn push_voice(voices: &mut Vec<DrumVoice>, voice: DrumVoice) {
+        if voices.len() < MAX_VOICES {
+            voices.push(voice);
+        } else if let Some(v) = voices.iter_mut().min_by_key(|v| v.remaining()) {
+            *v = voice;
+        }
+    }
+
+    /// Append an empty track.  Returns its index, or `None` when the kit
+    /// already has `MAX_TRACKS`.
+    pub fn add_track(&mut self, kind: DrumKind) -> Option<usize> {
+        if self.tracks.len() >= MAX_TRACKS { return None; }
+        self.tracks.push(DrumTrack::new(kind, self.num_steps));
+        Some(self.tracks.len() - 1)
+    }
+
+    /// Remove a track.  The last remaining track can't be removed.
+    pub fn remove_track(&mut self, idx: usize) -> Option<DrumTrack> {
+        if self.tracks.len() <= 1 || idx >= self.tracks.len() { return None; }
+        self.hits.clear();
+        Some(self.tracks.remove(idx))
+    }
+
+    /// Move the track at `from` so it ends up at index `to`.
+    pub fn move_track(&mut self, from: usize, to: usize) {
+        let n = self.tracks.len();
+        if from >= n || to >= n || from == to { return; }
+        self.hits.clear();
+        let t = self.tracks.remove(from);
+        self.tracks.insert(to, t);
+    }
+
+    /// Change a track's drum sound, keeping its pattern.
+    pub fn set_kind(&mut self, track: usize, kind: DrumKind) {
+        if let Some(t) = self.tracks.get_mut(track) { t.kind = kind; }
+    }
+
+    /// A stopped machine with this one's kit, pattern and fill state, for
+    /// rendering offline.  Effect chains are not copied.
+    pub fn pattern_copy(&self) -> Self {
+        let mut m = Self::new(self.sample_rate);
+        m.num_steps = self.num_steps;
+        m.fill      = self.fill;
+        m.tracks = self.tracks.iter().map(|t| DrumTrack {
+            kind:        t.kind,
+            steps:       t.steps.clone(),
+            conds:       t.conds.clone(),
+            nudge:       t.nudge.clone(),
+            muted:       t.muted,
+            volume:      t.volume,
+            pan:         t.pan,
+            pitch:       t.pitch,
+            retrig:      t.retrig,
+            retrig_hits: t.retrig_hits,
+            name:        t.name.clone(),
+            sample:      t.sample.clone(),
+            fx:          EffectChain::new(),
+        }).collect();
+        m
     }
 
     pub fn toggle_play(&mut self) {
         self.playing = !self.playing;
-        if !self.playing {
+        if self.playing {
+            self.rewind();
+        } else {
             self.voices.clear();
+            self.hits.clear();
         }
     }
 
+    /// Count passes from zero again: the next step fired starts pass one.
+    /// Hits queued for the old position are dropped.
+    pub fn rewind(&mut self) {
+        self.loop_count = 0;
+        self.fired = false;
+        self.hits.clear();
+    }
+
     pub fn toggle_step(&mut self, track: usize, step: usize) {
         if let Some(t) = self.tracks.get_mut(track) {
             if let Some(s) = t.steps.get_mut(step) {
@@ -369,6 +705,60 @@ impl DrumMachine {
             if let Some(s) = t.steps.get_mut(step) {
                 *s = 0;
             }
+            if let Some(c) = t.conds.get_mut(step) {
+                *c = TrigCondition::Always;
+            }
+            if let Some(n) = t.nudge.get_mut(step) {
+                *n = 0;
+            }
+        }
+    }
+
+    /// Empty every track's steps, keeping the tracks, their sounds and mix.
+    /// Returns each track's steps as they were, for `restore_pattern()`.
+    pub fn clear_pattern(&mut self) -> Vec<TrackPattern> {
+        let n = self.num_steps;
+        self.tracks.iter_mut().map(|t| TrackPattern {
+            steps: std::mem::replace(&mut t.steps, vec![0; n]),
+            conds: std::mem::replace(&mut t.conds, vec![TrigCondition::Always; n]),
+            nudge: std::mem::replace(&mut t.nudge, vec![0; n]),
+        }).collect()
+    }
+
+    /// Put back steps taken by `clear_pattern()`, by track position, cut or
+    /// padded to the current length.
+    pub fn restore_pattern(&mut self, pattern: Vec<TrackPattern>) {
+        let n = self.num_steps;
+        for (t, p) in self.tracks.iter_mut().zip(pattern) {
+            t.steps = p.steps; t.steps.resize(n, 0);
+            t.conds = p.conds; t.conds.resize(n, TrigCondition::Always);
+            t.nudge = p.nudge; t.nudge.resize(n, 0);
+        }
+    }
+
+    /// Steps set to fire, across all tracks.
+    pub fn filled(&self) -> usize {
+        self.tracks.iter().map(|t| t.steps.iter().filter(|&&s| s > 0).count()).sum()
+    }
+
+    /// Step a track's retrigger chance through off, 25, 50, 75, 100%.
+    pub fn cycle_retrig(&mut self, track: usize) {
+        if let Some(t) = self.tracks.get_mut(track) {
+            t.retrig = if t.retrig >= 100 { 0 } else { (t.retrig / 25 + 1) * 25 };
+        }
+    }
+
+    /// Step a track's retrigger hit count through 2 – `MAX_RETRIG_HITS`.
+    pub fn cycle_retrig_hits(&mut self, track: usize) {
+        if let Some(t) = self.tracks.get_mut(track) {
+            t.retrig_hits = if t.retrig_hits >= MAX_RETRIG_HITS { 2 } else { t.retrig_hits + 1 };
+        }
+    }
+
+    /// Step the condition of one step through `TrigCondition::CYCLE`.
+    pub fn cycle_cond(&mut self, track: usize, step: usize) {
+        if let Some(c) = self.tracks.get_mut(track).and_then(|t| t.conds.get_mut(step)) {
+            *c = c.next();
         }
     }
 
@@ -390,16 +780,29 @@ impl DrumMachine {
         }
     }
 
+    /// Move a track's pan by `delta`, within -1..1, snapped to 5% so
+    /// stepping back always lands on the centre exactly.
+    pub fn track_pan(&mut self, track: usize, delta: f32) {
+        if let Some(t) = self.tracks.get_mut(track) {
+            t.pan = ((t.pan + delta) * 20.0).round().clamp(-20.0, 20.0) / 20.0;
+        }
+    }
+
+    /// Tune a track by `delta` semitones, within `MAX_PITCH` either way.
+    /// Like the volume and pan, it applies from the next hit.
+    pub fn track_pitch(&mut self, track: usize, delta: f32) {
+        if let Some(t) = self.tracks.get_mut(track) {
+            t.pitch = (t.pitch + delta).round().clamp(-MAX_PITCH, MAX_PITCH);
+        }
+    }
+
     pub fn cycle_num_steps(&mut self) {
-        let next = match self.num_steps {
-            8  => 16,
-            16 => 24,
-            24 => 32,
-            _  => 8,
-        };
+        let next = next_step_count(self.num_steps);
         self.num_steps = next;
         for t in &mut self.tracks {
             t.steps.resize(next, 0);
+            t.conds.resize(next, TrigCondition::Always);
+            t.nudge.resize(next, 0);
         }
         if self.current_step >= next {
             self.current_step = 0;
@@ -428,6 +831,8 @@ impl DrumMachine {
         if let Some(t) = self.tracks.get_mut(track) {
             let k = k.min(n);
             t.steps = vec![0u8; n];
+            t.conds = vec![TrigCondition::Always; n];
+            t.nudge = vec![0; n];
             let mut bucket = 0usize;
             for i in 0..n {
                 bucket += k;
@@ -436,3 +841,33 @@ impl DrumMachine {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sequencer::{Groove, StepClock};
+
+    #[test]
+    fn overlapping_hits_steal_the_voice_nearest_its_end() {
+        let mut dm = DrumMachine::new(44_100.0);
+        let idle = StepClock { prev: 0.0, pos: 0.0 };
+        // Staggered so every voice has a different time left.
+        for _ in 0..MAX_VOICES + 16 {
+            dm.trigger_now(0);
+            for _ in 0..10 { dm.generate_sample(idle, Groove::STRAIGHT); }
+            assert!(dm.voices.len() <= MAX_VOICES);
+        }
+        assert_eq!(dm.voices.len(), MAX_VOICES);
+
+        let mut before: Vec<u64> = dm.voices.iter().map(|v| v.remaining()).collect();
+        before.sort();
+        dm.trigger_now(0);
+        let mut after: Vec<u64> = dm.voices.iter().map(|v| v.remaining()).collect();
+        after.sort();
+
+        let fresh = dm.voices.iter().map(|v| v.dur_samples).max().unwrap();
+        let mut expected = before[1..].to_vec();
+        expected.push(fresh);
+        assert_eq!(after, expected);
+    }
+}
@@ -1,5 +1,7 @@
 use std::f32::consts::PI;
 
+use crate::synth::pan_gains;
+
 /// Mono audio effect: one sample in, one sample out.
 #[allow(dead_code)]
 ///
@@ -52,6 +54,75 @@ impl Default for EffectChain {
     }
 }
 
+// ── Synth-bus inserts ─────────────────────────────────────────────────────────
+
+/// Most effects one synth bus's insert chain holds.
+pub const MAX_INSERTS: usize = 8;
+
+/// An effect that can go in a synth bus's insert chain.  Each insert is
+/// its own instance, switched on, at the effect's default settings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InsertKind { Reverb, Delay, Distortion, Filter }
+
+impl InsertKind {
+    pub const ALL: [InsertKind; 4] = [Self::Reverb, Self::Delay, Self::Distortion, Self::Filter];
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&k| k == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// The kind of an insert built by `build()`, from its `AudioEffect::name`.
+    pub fn of(fx: &dyn AudioEffect) -> Option<Self> {
+        Self::ALL.into_iter().find(|k| k.name() == fx.name())
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Reverb     => "Reverb",
+            Self::Delay      => "Delay",
+            Self::Distortion => "Distortion",
+            Self::Filter     => "Filter",
+        }
+    }
+
+    pub fn build(self, sample_rate: f32) -> Box<dyn AudioEffect> {
+        match self {
+            Self::Reverb     => Box::new(WithDry(Reverb { enabled: true, ..Reverb::new(sample_rate) })),
+            Self::Delay      => Box::new(WithDry(Delay { enabled: true, ..Delay::new(sample_rate) })),
+            Self::Distortion => Box::new(Distortion { enabled: true, ..Distortion::new() }),
+            Self::Filter     => Box::new(BiquadFilter { enabled: true, ..BiquadFilter::new(sample_rate) }),
+        }
+    }
+}
+
+/// Reverb and delay give only their wet signal (on the master bus they are
+/// sends); as an insert the dry signal passes through beside it.
+struct WithDry<E>(E);
+
+impl<E: AudioEffect> AudioEffect for WithDry<E> {
+    fn process(&mut self, sample: f32) -> f32 {
+        sample + self.0.process(sample)
+    }
+
+    fn name(&self) -> &'static str { self.0.name() }
+
+    fn reset(&mut self) { self.0.reset(); }
+}
+
+// ── Denormal guard ────────────────────────────────────────────────────────────
+
+/// Below this magnitude (~-300 dB) a recursive state is treated as silence.
+const DENORMAL_THRESHOLD: f32 = 1.0e-15;
+
+/// Snap vanishingly small values to exactly 0.0.  Feedback states that decay
+/// towards zero otherwise end up as subnormal floats, which are very slow on
+/// many CPUs; call this on every value written back into a recursive path.
+#[inline(always)]
+pub fn flush_denormal(x: f32) -> f32 {
+    if x.abs() < DENORMAL_THRESHOLD { 0.0 } else { x }
+}
+
 // ── Freeverb helpers (private) ────────────────────────────────────────────────
 
 struct CombFilter {
@@ -72,8 +143,8 @@ impl CombFilter {
     #[inline]
     fn process(&mut self, input: f32) -> f32 {
         let output = self.buf[self.pos];
-        self.damp_store = output * self.damp2 + self.damp_store * self.damp1;
-        self.buf[self.pos] = input + self.damp_store * self.feedback;
+        self.damp_store = flush_denormal(output * self.damp2 + self.damp_store * self.damp1);
+        self.buf[self.pos] = flush_denormal(input + self.damp_store * self.feedback);
         self.pos = (self.pos + 1) % self.buf.len();
         output
     }
@@ -96,117 +167,588 @@ impl AllpassFilter {
     fn process(&mut self, input: f32) -> f32 {
         let bufout = self.buf[self.pos];
         let output = -input + bufout;
-        self.buf[self.pos] = input + bufout * 0.5;
+        self.buf[self.pos] = flush_denormal(input + bufout * 0.5);
         self.pos = (self.pos + 1) % self.buf.len();
         output
     }
 }
 
-// ── Reverb (Freeverb: 8 comb + 4 allpass, tuned for 44100 Hz) ────────────────
+/// Multi-tap delay line: the first few wall reflections of a room.  Not
+/// recursive (the taps only read what was written), so needs no flushing.
+struct EarlyReflections {
+    buf: Vec<f32>,
+    pos: usize,
+    /// Tap delays in samples at the largest room size.
+    taps: [usize; 8],
+}
+
+impl EarlyReflections {
+    fn new(sample_rate: f32) -> Self {
+        let taps = ER_TAPS.map(|(n, _)| scale_tuning(n, sample_rate));
+        Self { buf: vec![0.0; taps[taps.len() - 1] + 1], pos: 0, taps }
+    }
+
+    /// Write `input` and sum the taps, their delays shrunk by `scale`
+    /// (`ER_MIN_SCALE`–1) for smaller rooms.
+    #[inline]
+    fn process(&mut self, input: f32, scale: f32) -> f32 {
+        let len = self.buf.len();
+        self.buf[self.pos] = input;
+        let mut out = 0.0f32;
+        for (&tap, &(_, gain)) in self.taps.iter().zip(&ER_TAPS) {
+            let d = ((tap as f32 * scale) as usize).max(1);
+            out += self.buf[(self.pos + len - d) % len] * gain;
+        }
+        self.pos = (self.pos + 1) % len;
+        out
+    }
+}
+
+/// Octave-up pitch shifter for the shimmer feedback.  Two heads read a
+/// short line at twice the write speed, half a window apart; each fades
+/// in and out under a sin² envelope and jumps back a window only where it
+/// is silent, and the two envelopes sum to one.
+struct OctaveShifter {
+    buf:    Vec<f32>,
+    pos:    usize,
+    window: usize,
+    phase:  usize,
+}
+
+/// Length of the shifter's grains.  Long enough to keep bass pitched,
+/// short enough not to smear the tail.
+const SHIMMER_WINDOW_S: f32 = 0.08;
+
+impl OctaveShifter {
+    fn new(sample_rate: f32) -> Self {
+        let window = ((SHIMMER_WINDOW_S * sample_rate) as usize).max(2);
+        Self { buf: vec![0.0; window + 1], pos: 0, window, phase: 0 }
+    }
+
+    #[inline]
+    fn process(&mut self, input: f32) -> f32 {
+        let len = self.buf.len();
+        self.buf[self.pos] = input;
+        let mut out = 0.0f32;
+        for offset in [0, self.window / 2] {
+            let p = (self.phase + offset) % self.window;
+            // The delay shrinks by one a sample: read at double speed.
+            let d = self.window - p;
+            let env = (std::f32::consts::PI * p as f32 / self.window as f32).sin();
+            out += self.buf[(self.pos + len - d) % len] * env * env;
+        }
+        self.pos = (self.pos + 1) % len;
+        self.phase = (self.phase + 1) % self.window;
+        out
+    }
+}
+
+// ── Reverb (Freeverb: 8 comb + 4 allpass) ────────────────────────────────────
+
+/// Freeverb comb / allpass lengths in samples, tuned for 44100 Hz.
+const COMB_TUNING:    [usize; 8] = [1116, 1188, 1277, 1356, 1422, 1491, 1557, 1617];
+const ALLPASS_TUNING: [usize; 4] = [556, 441, 341, 225];
+
+/// Early reflection taps `(delay in samples at 44100 Hz, gain)`, spread over
+/// 5–70 ms; alternating signs keep the sum from colouring the tone.
+const ER_TAPS: [(usize, f32); 8] = [
+    (221, 0.84), (489, -0.71), (767, 0.62), (1031, -0.55),
+    (1367, 0.47), (1789, -0.40), (2311, 0.33), (3079, -0.27),
+];
+
+/// Shimmer feedback at full amount, as a fraction of what the combs lose
+/// on each pass (`1 - feedback`).  The octave loop then stays just under
+/// self-sustaining at any room size: the tail rings on but dies away.
+const SHIMMER_GAIN: f32 = 0.75;
+
+/// Tap delays at room size 0, relative to the largest room.
+const ER_MIN_SCALE: f32 = 0.35;
+
+/// Rescale a 44.1 kHz tuning length so it spans the same time at `sample_rate`.
+fn scale_tuning(len: usize, sample_rate: f32) -> usize {
+    ((len as f32 * sample_rate / 44100.0).round() as usize).max(1)
+}
 
 pub struct Reverb {
     pub enabled:   bool,
     pub room_size: f32,  // 0.0–1.0  (comb feedback = room_size*0.28+0.7)
     pub damping:   f32,  // 0.0–1.0  (comb damp = damping*0.4)
     pub mix:       f32,  // 0.0–1.0  wet/dry
+    /// Early reflections (0.0–1.0): heard on their own and fed into the tank.
+    pub er_amount: f32,
+    /// Octave-up tail fed back into the tank (0.0–1.0).
+    pub shimmer:   f32,
+    early:    EarlyReflections,
     combs:    [CombFilter; 8],
     allpasses: [AllpassFilter; 4],
+    shifter:  OctaveShifter,
+    /// The shifter's last output, fed in with the next sample.
+    shimmer_fb: f32,
 }
 
 impl Reverb {
-    pub fn new() -> Self {
+    pub fn new(sample_rate: f32) -> Self {
         let mut r = Self {
-            enabled: false, room_size: 0.5, damping: 0.5, mix: 0.3,
-            combs: [
-                CombFilter::new(1116), CombFilter::new(1188),
-                CombFilter::new(1277), CombFilter::new(1356),
-                CombFilter::new(1422), CombFilter::new(1491),
-                CombFilter::new(1557), CombFilter::new(1617),
-            ],
-            allpasses: [
-                AllpassFilter::new(556), AllpassFilter::new(441),
-                AllpassFilter::new(341), AllpassFilter::new(225),
-            ],
+            enabled: false, room_size: 0.5, damping: 0.5, mix: 0.3, er_amount: 0.25, shimmer: 0.0,
+            early:     EarlyReflections::new(sample_rate),
+            combs:     COMB_TUNING.map(|n| CombFilter::new(scale_tuning(n, sample_rate))),
+            allpasses: ALLPASS_TUNING.map(|n| AllpassFilter::new(scale_tuning(n, sample_rate))),
+            shifter:   OctaveShifter::new(sample_rate),
+            shimmer_fb: 0.0,
         };
-        let fb = r.room_size * 0.28 + 0.7;
+        let fb = Self::comb_feedback(r.room_size);
         let dp = r.damping * 0.4;
         for c in &mut r.combs { c.set_feedback(fb); c.set_damp(dp); }
         r
     }
+
+    /// Rebuild the comb/allpass lines for a new sample rate so the decay time
+    /// and tone stay the same (clears the tail).
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.early     = EarlyReflections::new(sample_rate);
+        self.combs     = COMB_TUNING.map(|n| CombFilter::new(scale_tuning(n, sample_rate)));
+        self.allpasses = ALLPASS_TUNING.map(|n| AllpassFilter::new(scale_tuning(n, sample_rate)));
+        self.shifter   = OctaveShifter::new(sample_rate);
+        self.shimmer_fb = 0.0;
+    }
+
+    fn comb_feedback(room_size: f32) -> f32 {
+        room_size * 0.28 + 0.7
+    }
+
+    /// Approximate decay time to -60 dB, in seconds, for a room size: the
+    /// average comb loop repeated until its feedback has fallen by 60 dB.
+    /// Damping is ignored, so this is the low-frequency decay.
+    pub fn rt60(room_size: f32) -> f32 {
+        let loop_secs = COMB_TUNING.iter().sum::<usize>() as f32 / COMB_TUNING.len() as f32 / 44100.0;
+        -3.0 * loop_secs / Self::comb_feedback(room_size).log10()
+    }
 }
 
 impl AudioEffect for Reverb {
     fn process(&mut self, sample: f32) -> f32 {
         if !self.enabled { return 0.0; }
-        let fb = self.room_size * 0.28 + 0.7;
+        let fb = Self::comb_feedback(self.room_size);
         let dp = self.damping * 0.4;
         for c in &mut self.combs { c.set_feedback(fb); c.set_damp(dp); }
-        let input = sample * 0.015;
+        let scale = ER_MIN_SCALE + (1.0 - ER_MIN_SCALE) * self.room_size;
+        let er = self.early.process(sample, scale) * self.er_amount;
+        let input = (sample + er + self.shimmer_fb) * 0.015;
         let mut wet = 0.0f32;
         for c in &mut self.combs { wet += c.process(input); }
         for ap in &mut self.allpasses { wet = ap.process(wet); }
-        wet * self.mix * 3.0
+        // Shimmer: the tail an octave up goes back in; each pass adds
+        // another octave, until the damping takes the top off.
+        self.shimmer_fb = if self.shimmer > 0.0 {
+            let gain = self.shimmer * SHIMMER_GAIN * (1.0 - fb);
+            flush_denormal(self.shifter.process(wet * 3.0) * gain)
+        } else { 0.0 };
+        (wet * 3.0 + er) * self.mix
     }
 
     fn name(&self) -> &'static str { "Reverb" }
 
     fn reset(&mut self) {
+        self.early.buf.fill(0.0);
         for c in &mut self.combs { c.buf.fill(0.0); c.pos = 0; c.damp_store = 0.0; }
         for ap in &mut self.allpasses { ap.buf.fill(0.0); ap.pos = 0; }
+        self.shifter.buf.fill(0.0);
+        (self.shifter.pos, self.shifter.phase) = (0, 0);
+        self.shimmer_fb = 0.0;
     }
 }
 
 // ── Delay (ring-buffer echo) ──────────────────────────────────────────────────
 
+/// Most extra taps a delay holds.
+pub const MAX_TAPS: usize = 8;
+
+/// An extra read-out of the delay line: an echo at its own time, level
+/// and stereo position, outside the feedback loop.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Tap {
+    pub time_ms: f32,   // 10–1000 ms
+    pub level:   f32,   // 0.0–1.0
+    pub pan:     f32,   // -1.0 (left) – 1.0 (right)
+}
+
+impl Tap {
+    /// `ms level pan`, as the tap editor reads and writes it.
+    pub fn label(self) -> String {
+        format!("{:.0} {:.2} {:+.2}", self.time_ms, self.level, self.pan)
+    }
+}
+
+/// Tap layouts built from the delay time, so they follow a note-value time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TapPreset { Off, Spread, Triplet, Cascade, PingPong }
+
+impl TapPreset {
+    pub const ALL: [TapPreset; 5] = [Self::Off, Self::Spread, Self::Triplet, Self::Cascade, Self::PingPong];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Off      => "Off",
+            Self::Spread   => "Spread",
+            Self::Triplet  => "Triplet",
+            Self::Cascade  => "Cascade",
+            Self::PingPong => "Ping-pong",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&p| p == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+
+    /// The taps for a delay time of `time_ms`.  Each layout entry is a
+    /// fraction of that time, a level and a pan; taps that would fall
+    /// outside 10–1000 ms are dropped.
+    pub fn taps(self, time_ms: f32) -> Vec<Tap> {
+        let layout: &[(f32, f32, f32)] = match self {
+            Self::Off      => &[],
+            Self::Spread   => &[(0.5, 0.55, -0.7), (1.5, 0.4, 0.7)],
+            Self::Triplet  => &[(1.0 / 3.0, 0.5, -0.5), (2.0 / 3.0, 0.5, 0.5)],
+            Self::Cascade  => &[(0.25, 0.3, 0.0), (0.5, 0.45, -0.6), (0.75, 0.6, 0.6)],
+            Self::PingPong => &[(0.5, 0.7, -1.0), (1.5, 0.5, 1.0), (2.0, 0.35, -1.0)],
+        };
+        layout.iter()
+            .map(|&(f, level, pan)| Tap { time_ms: time_ms * f, level, pan })
+            .filter(|t| (10.0..=1000.0).contains(&t.time_ms))
+            .collect()
+    }
+}
+
+/// Parse the tap editor's `ms level pan` triples, separated by commas.
+/// Empty text means no taps.
+pub fn parse_taps(s: &str) -> Option<Vec<Tap>> {
+    let taps = s.split(',').filter(|t| !t.trim().is_empty()).map(|t| {
+        let mut f = t.split_whitespace().map(|x| x.parse::<f32>().ok());
+        let tap = Tap { time_ms: f.next()??, level: f.next()??, pan: f.next().unwrap_or(Some(0.0))? };
+        let ok = f.next().is_none()
+            && (10.0..=1000.0).contains(&tap.time_ms)
+            && (0.0..=1.0).contains(&tap.level)
+            && (-1.0..=1.0).contains(&tap.pan);
+        ok.then_some(tap)
+    }).collect::<Option<Vec<Tap>>>()?;
+    (taps.len() <= MAX_TAPS).then_some(taps)
+}
+
+/// Backwards playback of the delay input for `Delay::reverse`.  Records
+/// into its own line, two seconds long so a whole 1 s window can be read
+/// back, and plays it with two heads half a window apart.  Each head runs
+/// backwards through one window under a sin² envelope and jumps back only
+/// where that envelope is silent; the two envelopes sum to one, so the
+/// output has no clicks.
+struct ReverseLine {
+    buf:   Vec<f32>,
+    write: usize,
+    /// Position in the current window, in samples.
+    phase: usize,
+}
+
+impl ReverseLine {
+    fn new(sample_rate: f32) -> Self {
+        Self { buf: vec![0.0; 2 * sample_rate as usize], write: 0, phase: 0 }
+    }
+
+    /// The reversed signal for windows of `window` samples, then record
+    /// `input` with `feedback` of that signal mixed in.
+    fn process(&mut self, input: f32, window: usize, feedback: f32) -> f32 {
+        let len = self.buf.len();
+        let w = window.clamp(2, len / 2);
+        self.phase = (self.phase + 1) % w;
+        let mut out = 0.0;
+        for head in 0..2 {
+            let p = (self.phase + head * w / 2) % w;
+            // Reading back two samples for each one written moves the head
+            // backwards through time at normal speed.
+            let back = (2 * p).max(1);
+            let gain = (PI * p as f32 / w as f32).sin().powi(2);
+            out += self.buf[(self.write + len - back) % len] * gain;
+        }
+        self.buf[self.write] = flush_denormal(input + out * feedback);
+        self.write = (self.write + 1) % len;
+        out
+    }
+}
+
+/// Attack of the ducking envelope: fast enough to catch a transient.
+const DUCK_ATTACK_MS: f32 = 5.0;
+/// Input level at which the echoes are ducked fully (-12 dBFS).
+const DUCK_FULL: f32 = 0.25;
+
+/// The divisions a synced delay cycles through.
+pub const SYNC_DIVISIONS: [NoteValue; 5] = [
+    NoteValue { denom: 4,  feel: NoteFeel::Straight },
+    NoteValue { denom: 8,  feel: NoteFeel::Straight },
+    NoteValue { denom: 8,  feel: NoteFeel::Triplet },
+    NoteValue { denom: 16, feel: NoteFeel::Straight },
+    NoteValue { denom: 8,  feel: NoteFeel::Dotted },
+];
+
 pub struct Delay {
-    pub enabled:  bool,
-    pub time_ms:  f32,   // 10–1000 ms
-    pub feedback: f32,   // 0.0–0.95
-    pub mix:      f32,   // 0.0–1.0
-    buf:         Vec<f32>,
-    write:       usize,
-    sample_rate: f32,
+    pub enabled:      bool,
+    pub time_ms:      f32,   // 10–1000 ms
+    pub feedback:     f32,   // 0.0–0.95
+    pub mix:          f32,   // 0.0–1.0
+    /// Extra echoes read from the same line (empty = a single-tap delay).
+    pub taps:         Vec<Tap>,
+    /// The main echo plays each `time_ms` window of input backwards.
+    pub reverse:      bool,
+    /// How far the echoes dip while the input is loud (0 = off).
+    pub duck_amount:  f32,
+    /// Time the echoes take to come back up once the input drops.
+    pub duck_release: f32,   // 50–2000 ms
+    /// Follow the tempo: the main echo is one `division` at the BPM last
+    /// given to `set_bpm()`, in place of `time_ms`.
+    pub sync:         bool,
+    pub division:     NoteValue,
+    bpm:              f32,
+    buf:              Vec<f32>,
+    write:            usize,
+    rev:              ReverseLine,
+    /// Envelope of the input level that drives the ducking.
+    duck_env:         f32,
+    sample_rate:      f32,
 }
 
 impl Delay {
     pub fn new(sample_rate: f32) -> Self {
         Self {
             enabled: false, time_ms: 250.0, feedback: 0.4, mix: 0.3,
+            taps: Vec::new(), reverse: false, duck_amount: 0.0, duck_release: 300.0,
+            sync: false, division: SYNC_DIVISIONS[1], bpm: 120.0,
             buf: vec![0.0; sample_rate as usize],
-            write: 0, sample_rate,
+            write: 0, rev: ReverseLine::new(sample_rate), duck_env: 0.0, sample_rate,
         }
     }
+
+    /// Resize the ring buffer for a new sample rate (clears any echoes).
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.buf   = vec![0.0; sample_rate as usize];
+        self.write = 0;
+        self.rev   = ReverseLine::new(sample_rate);
+        self.duck_env = 0.0;
+    }
+
+    /// The tempo a synced delay follows.  `Synth` sets it every sample.
+    pub fn set_bpm(&mut self, bpm: f32) {
+        self.bpm = bpm;
+    }
+
+    /// Length of the main echo: the division at the tempo when synced,
+    /// held to the line's length like `time_ms`.
+    pub fn delay_ms(&self) -> f32 {
+        if self.sync { self.division.ms(self.bpm).clamp(10.0, 1000.0) } else { self.time_ms }
+    }
+
+    /// Move the synced division to the nearest longer (or shorter) one in
+    /// `SYNC_DIVISIONS`; at either end it stays.
+    pub fn step_division(&mut self, longer: bool) {
+        let len = |d: &NoteValue| d.ms(120.0);
+        let cur = len(&self.division);
+        let others = SYNC_DIVISIONS.iter().filter(|d| if longer { len(d) > cur } else { len(d) < cur });
+        let next = if longer { others.min_by(|a, b| len(a).total_cmp(&len(b))) }
+                   else      { others.max_by(|a, b| len(a).total_cmp(&len(b))) };
+        if let Some(&d) = next { self.division = d; }
+    }
+
+    /// The line's sample `time_ms` before the write position.
+    fn read(&self, time_ms: f32) -> f32 {
+        let delay_samp = ((time_ms / 1000.0 * self.sample_rate) as usize)
+            .clamp(1, self.buf.len() - 1);
+        self.buf[(self.write + self.buf.len() - delay_samp) % self.buf.len()]
+    }
+
+    /// One sample in, left and right out.  The main echo (the one that
+    /// feeds back) sits in the centre; each tap adds its own, panned.
+    /// The reverse line always records, so switching to reverse plays the
+    /// latest window at once; in reverse the forward line gets no feedback
+    /// and carries just the input for the taps.  Ducking follows the input
+    /// and turns the echoes down under it, fully at -12 dBFS, so they fill
+    /// the gaps between phrases; what is fed back is left alone.
+    pub fn process_stereo(&mut self, sample: f32) -> (f32, f32) {
+        if !self.enabled { return (0.0, 0.0); }
+        let time_ms = self.delay_ms();
+        let window = (time_ms / 1000.0 * self.sample_rate) as usize;
+        let rev_fb = if self.reverse { self.feedback } else { 0.0 };
+        let reversed = self.rev.process(sample, window, rev_fb);
+        let forward = self.read(time_ms);
+        let delayed = if self.reverse { reversed } else { forward };
+        let (mut l, mut r) = (delayed, delayed);
+        for t in &self.taps {
+            let echo = self.read(t.time_ms) * t.level;
+            let (gl, gr) = pan_gains(t.pan);
+            l += echo * gl;
+            r += echo * gr;
+        }
+        let fwd_fb = if self.reverse { 0.0 } else { self.feedback };
+        self.buf[self.write] = flush_denormal(sample + forward * fwd_fb);
+        self.write = (self.write + 1) % self.buf.len();
+        let gain = self.mix * self.duck_gain(sample);
+        (l * gain, r * gain)
+    }
+
+    /// Follow the input level (5 ms attack, `duck_release` release) and
+    /// return the wet gain it leaves.
+    fn duck_gain(&mut self, sample: f32) -> f32 {
+        if self.duck_amount <= 0.0 { return 1.0; }
+        let level = sample.abs();
+        let ms = if level > self.duck_env { DUCK_ATTACK_MS } else { self.duck_release };
+        let coeff = (-1000.0 / (ms * self.sample_rate)).exp();
+        self.duck_env = flush_denormal(level + (self.duck_env - level) * coeff);
+        1.0 - self.duck_amount * (self.duck_env / DUCK_FULL).min(1.0)
+    }
 }
 
 impl AudioEffect for Delay {
     fn process(&mut self, sample: f32) -> f32 {
-        if !self.enabled { return 0.0; }
-        let delay_samp = ((self.time_ms / 1000.0 * self.sample_rate) as usize)
-            .clamp(1, self.buf.len() - 1);
-        let read = (self.write + self.buf.len() - delay_samp) % self.buf.len();
-        let delayed = self.buf[read];
-        self.buf[self.write] = sample + delayed * self.feedback;
-        self.write = (self.write + 1) % self.buf.len();
-        delayed * self.mix
+        let (l, r) = self.process_stereo(sample);
+        (l + r) * 0.5
     }
 
     fn name(&self) -> &'static str { "Delay" }
 
-    fn reset(&mut self) { self.buf.fill(0.0); self.write = 0; }
+    fn reset(&mut self) {
+        self.buf.fill(0.0);
+        self.write = 0;
+        self.rev.buf.fill(0.0);
+        (self.rev.write, self.rev.phase) = (0, 0);
+        self.duck_env = 0.0;
+    }
+}
+
+/// Straight, dotted (×1.5) or triplet (×2/3) note lengths.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NoteFeel { Straight, Dotted, Triplet }
+
+/// A note length used to describe or set the delay time: `1/denom` of a
+/// whole note, with a feel.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NoteValue {
+    pub denom: u32,
+    pub feel:  NoteFeel,
+}
+
+impl NoteValue {
+    const DENOMS: [u32; 6] = [1, 2, 4, 8, 16, 32];
+    const FEELS:  [NoteFeel; 3] = [NoteFeel::Straight, NoteFeel::Dotted, NoteFeel::Triplet];
+
+    /// Length in milliseconds at `bpm` (quarter note = one beat).
+    pub fn ms(self, bpm: f32) -> f32 {
+        let factor = match self.feel {
+            NoteFeel::Straight => 1.0,
+            NoteFeel::Dotted   => 1.5,
+            NoteFeel::Triplet  => 2.0 / 3.0,
+        };
+        240_000.0 / bpm / self.denom as f32 * factor
+    }
+
+    /// The division closest to `ms` at `bpm`, compared by ratio so that
+    /// short and long times are judged alike.
+    pub fn nearest(ms: f32, bpm: f32) -> Self {
+        let mut best = Self { denom: 4, feel: NoteFeel::Straight };
+        let mut best_err = f32::INFINITY;
+        for denom in Self::DENOMS {
+            for feel in Self::FEELS {
+                let v = Self { denom, feel };
+                let err = (ms / v.ms(bpm)).ln().abs();
+                if err < best_err { best = v; best_err = err; }
+            }
+        }
+        best
+    }
+
+    /// Parse `"1/8"`, `"1/8."` / `"1/8d"` (dotted) or `"1/8t"` (triplet).
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        let (body, feel) = if let Some(b) = s.strip_suffix('.').or_else(|| s.strip_suffix('d')) {
+            (b, NoteFeel::Dotted)
+        } else if let Some(b) = s.strip_suffix('t') {
+            (b, NoteFeel::Triplet)
+        } else {
+            (s.as_str(), NoteFeel::Straight)
+        };
+        let denom: u32 = body.trim().strip_prefix("1/")?.parse().ok()?;
+        Self::DENOMS.contains(&denom).then_some(Self { denom, feel })
+    }
+
+    /// `"1/8"`, `"1/8."` or `"1/8T"`.
+    pub fn name(self) -> String {
+        let suffix = match self.feel {
+            NoteFeel::Straight => "",
+            NoteFeel::Dotted   => ".",
+            NoteFeel::Triplet  => "T",
+        };
+        format!("1/{}{}", self.denom, suffix)
+    }
 }
 
 // ── Distortion (waveshaper) ───────────────────────────────────────────────────
 
+/// How the distortion shapes the driven signal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DistMode {
+    /// Saturate: peaks are squashed flat (tanh / hard clip).
+    Clip,
+    /// Wavefold: peaks past ±1 are reflected back, so more drive adds
+    /// more folds (and harmonics) instead of just flattening the top.
+    Fold,
+}
+
+impl DistMode {
+    pub const ALL: [DistMode; 2] = [Self::Clip, Self::Fold];
+
+    pub fn name(self) -> &'static str {
+        match self { Self::Clip => "Clip", Self::Fold => "Fold" }
+    }
+
+    pub fn next(self) -> Self {
+        match self { Self::Clip => Self::Fold, Self::Fold => Self::Clip }
+    }
+}
+
+/// Triangle wavefolder: identity within ±1, reflected at every odd integer.
+#[inline]
+fn fold(x: f32) -> f32 {
+    let t = (x - 1.0).rem_euclid(4.0);
+    if t < 2.0 { 1.0 - t } else { t - 3.0 }
+}
+
 pub struct Distortion {
     pub enabled: bool,
-    pub drive:   f32,   // 1.0–10.0  gain before clipping
-    pub tone:    f32,   // 0.0–1.0   blend: 0=soft tanh, 1=hard clip
+    pub mode:    DistMode,
+    pub drive:   f32,   // 1.0–10.0  gain before the shaper
+    pub tone:    f32,   // 0.0–1.0   blend: Clip 0=soft tanh, 1=hard clip; Fold 0=sine, 1=triangle
     pub level:   f32,   // 0.0–1.0   output level
+    /// -1.0–1.0: bias added before the shaper so the two halves of the
+    /// wave clip differently (even harmonics).  0 = symmetric.
+    pub asymmetry: f32,
+    /// DC blocker state (last input, last output) for the biased signal.
+    dc_x: f32,
+    dc_y: f32,
 }
 
+/// Pole of the DC blocker after the biased shaper (≈7 Hz at 44.1 kHz).
+const DC_POLE: f32 = 0.999;
+
+/// Largest bias, in driven-signal units, at `asymmetry` ±1.
+const MAX_BIAS: f32 = 0.5;
+
 impl Distortion {
     pub fn new() -> Self {
-        Self { enabled: false, drive: 3.0, tone: 0.3, level: 0.7 }
+        Self { enabled: false, mode: DistMode::Clip, drive: 3.0, tone: 0.3, level: 0.7,
+               asymmetry: 0.0, dc_x: 0.0, dc_y: 0.0 }
+    }
+
+    fn shape(&self, x: f32) -> f32 {
+        let (soft, hard) = match self.mode {
+            DistMode::Clip => (x.tanh(), x.clamp(-1.0, 1.0)),
+            DistMode::Fold => ((x * PI * 0.5).sin(), fold(x)),
+        };
+        soft * (1.0 - self.tone) + hard * self.tone
     }
 }
 
@@ -214,47 +756,289 @@ impl AudioEffect for Distortion {
     fn process(&mut self, sample: f32) -> f32 {
         if !self.enabled { return 0.0; }
         let driven = sample * self.drive;
-        let soft   = driven.tanh();
-        let hard   = driven.clamp(-1.0, 1.0);
-        (soft * (1.0 - self.tone) + hard * self.tone) * self.level
+        if self.asymmetry == 0.0 { return self.shape(driven) * self.level; }
+        // The bias's own offset is taken off straight away; what the signal
+        // adds (the uneven clipping) goes through the DC blocker.
+        let bias   = self.asymmetry * MAX_BIAS;
+        let shaped = self.shape(driven + bias) - self.shape(bias);
+        let out = flush_denormal(shaped - self.dc_x + DC_POLE * self.dc_y);
+        self.dc_x = shaped;
+        self.dc_y = out;
+        out * self.level
     }
 
     fn name(&self) -> &'static str { "Distortion" }
 
-    fn reset(&mut self) {}
+    fn reset(&mut self) {
+        self.dc_x = 0.0;
+        self.dc_y = 0.0;
+    }
+}
+
+// ── Comb resonator (tuned feedback comb) ──────────────────────────────────────
+
+/// Lowest tuning of the comb; sets the length of its line.
+pub const COMB_MIN_HZ: f32 = 40.0;
+pub const COMB_MAX_HZ: f32 = 2000.0;
+
+/// A feedback comb tuned to `freq`: its delay is one period, so anything
+/// fed in rings at that pitch and its harmonics, Karplus-Strong style.  A
+/// two-point average in the loop softens the upper harmonics the way a
+/// plucked string does.
+pub struct Comb {
+    pub enabled:  bool,
+    pub freq:     f32,   // 40–2000 Hz
+    pub feedback: f32,   // 0.0–0.98
+    pub mix:      f32,   // 0.0–1.0
+    buf:         Vec<f32>,
+    write:       usize,
+    /// Last sample read from the line, for the loop's averaging filter.
+    last:        f32,
+    sample_rate: f32,
+}
+
+impl Comb {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            enabled: false, freq: 220.0, feedback: 0.9, mix: 0.5,
+            buf: vec![0.0; (sample_rate / COMB_MIN_HZ) as usize + 2],
+            write: 0, last: 0.0, sample_rate,
+        }
+    }
+
+    /// Resize the line for a new sample rate (clears the ringing).
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        *self = Self { enabled: self.enabled, freq: self.freq, feedback: self.feedback, mix: self.mix,
+                       ..Self::new(sample_rate) };
+    }
+}
+
+impl AudioEffect for Comb {
+    /// Wet only: the line's output, scaled by `sqrt(1 - feedback²)` so a
+    /// broadband input (noise, a drum hit) comes back at about the same
+    /// power however long it rings.
+    fn process(&mut self, sample: f32) -> f32 {
+        if !self.enabled { return 0.0; }
+        let len = self.buf.len();
+        // Linear interpolation keeps the tuning exact between samples;
+        // the averaging filter adds half a sample of delay, taken off here.
+        let period = (self.sample_rate / self.freq.clamp(COMB_MIN_HZ, COMB_MAX_HZ) - 0.5)
+            .clamp(1.0, (len - 2) as f32);
+        let (whole, frac) = (period as usize, period.fract());
+        let a = self.buf[(self.write + len - whole) % len];
+        let b = self.buf[(self.write + len - whole - 1) % len];
+        let delayed = a + (b - a) * frac;
+        let damped = (delayed + self.last) * 0.5;
+        self.last = delayed;
+        self.buf[self.write] = flush_denormal(sample + damped * self.feedback);
+        self.write = (self.write + 1) % len;
+        delayed * (1.0 - self.feedback * self.feedback).sqrt() * self.mix
+    }
+
+    fn name(&self) -> &'static str { "Comb" }
+
+    fn reset(&mut self) {
+        self.buf.fill(0.0);
+        self.write = 0;
+        self.last  = 0.0;
+    }
+}
+
+// ── Chorus (modulated delay) ──────────────────────────────────────────────────
+
+/// Range the chorus's delay sweeps through at full depth, in ms.
+pub const CHORUS_MIN_MS: f32 = 5.0;
+pub const CHORUS_MAX_MS: f32 = 30.0;
+
+/// Range of the chorus's LFO, in Hz.
+pub const CHORUS_RATE_MIN: f32 = 0.1;
+pub const CHORUS_RATE_MAX: f32 = 5.0;
+
+/// A short delay swept by a sine LFO, so the copy drifts in pitch against
+/// the dry signal and thickens it.  The left and right taps sweep a
+/// quarter-cycle apart, which widens the image.
+pub struct Chorus {
+    pub enabled: bool,
+    pub rate:    f32,   // 0.1–5 Hz
+    /// How much of the 5–30 ms range the delay sweeps (0 = a fixed delay
+    /// at its centre).
+    pub depth:   f32,   // 0.0–1.0
+    pub mix:     f32,   // 0.0–1.0
+    buf:         Vec<f32>,
+    write:       usize,
+    /// 0–1 through the LFO's cycle.
+    phase:       f32,
+    sample_rate: f32,
+}
+
+impl Chorus {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            enabled: false, rate: 0.8, depth: 0.5, mix: 0.5,
+            buf: vec![0.0; (CHORUS_MAX_MS / 1000.0 * sample_rate) as usize + 2],
+            write: 0, phase: 0.0, sample_rate,
+        }
+    }
+
+    /// Resize the line for a new sample rate.
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        *self = Self { enabled: self.enabled, rate: self.rate, depth: self.depth, mix: self.mix,
+                       ..Self::new(sample_rate) };
+    }
+
+    /// The line `t` (0–1 through the LFO's cycle) would be read at, with
+    /// linear interpolation between samples.
+    fn read(&self, t: f32) -> f32 {
+        let len = self.buf.len();
+        let centre = (CHORUS_MIN_MS + CHORUS_MAX_MS) * 0.5;
+        let swing = (CHORUS_MAX_MS - CHORUS_MIN_MS) * 0.5 * self.depth.clamp(0.0, 1.0);
+        let ms = centre + swing * (t * 2.0 * PI).sin();
+        let delay = (ms / 1000.0 * self.sample_rate).clamp(1.0, (len - 2) as f32);
+        let (whole, frac) = (delay as usize, delay.fract());
+        let a = self.buf[(self.write + len - whole) % len];
+        let b = self.buf[(self.write + len - whole - 1) % len];
+        a + (b - a) * frac
+    }
+
+    /// One sample in, left and right out: wet only, scaled by `mix`.
+    pub fn process_stereo(&mut self, sample: f32) -> (f32, f32) {
+        if !self.enabled { return (0.0, 0.0); }
+        self.buf[self.write] = flush_denormal(sample);
+        let l = self.read(self.phase);
+        let r = self.read((self.phase + 0.25).fract());
+        self.write = (self.write + 1) % self.buf.len();
+        let rate = self.rate.clamp(CHORUS_RATE_MIN, CHORUS_RATE_MAX);
+        self.phase = (self.phase + rate / self.sample_rate).fract();
+        (l * self.mix, r * self.mix)
+    }
+}
+
+impl AudioEffect for Chorus {
+    fn process(&mut self, sample: f32) -> f32 {
+        let (l, r) = self.process_stereo(sample);
+        (l + r) * 0.5
+    }
+
+    fn name(&self) -> &'static str { "Chorus" }
+
+    fn reset(&mut self) {
+        self.buf.fill(0.0);
+        self.write = 0;
+        self.phase = 0.0;
+    }
+}
+
+// ── Bitcrusher (bit-depth and sample-rate reduction) ──────────────────────────
+
+/// Range of the crusher's bit depth.
+pub const CRUSH_MIN_BITS: u8 = 1;
+pub const CRUSH_MAX_BITS: u8 = 16;
+
+/// Largest downsample factor: one output value held for this many samples.
+pub const CRUSH_MAX_DOWNSAMPLE: u8 = 64;
+
+/// Lo-fi reduction: each sample is rounded to `2^bits` steps across
+/// -1–1, and only every `downsample`th is taken, the one before held in
+/// between, so the stepped output aliases the way a cheap sampler's does.
+pub struct Bitcrusher {
+    pub enabled:    bool,
+    pub bits:       u8,    // 1–16
+    pub downsample: u8,    // 1–64
+    pub mix:        f32,   // 0.0–1.0
+    /// Samples left before the next one is taken.
+    counter:        u8,
+    held:           f32,
+}
+
+impl Bitcrusher {
+    pub fn new() -> Self {
+        Self { enabled: false, bits: 8, downsample: 4, mix: 0.5, counter: 0, held: 0.0 }
+    }
+
+    /// `sample` rounded to the bit depth.  Rounding about zero keeps
+    /// silence silent even at one bit.
+    fn quantize(&self, sample: f32) -> f32 {
+        let bits = self.bits.clamp(CRUSH_MIN_BITS, CRUSH_MAX_BITS);
+        let steps = (1u32 << (bits - 1)) as f32;
+        (sample.clamp(-1.0, 1.0) * steps).round() / steps
+    }
+}
+
+impl AudioEffect for Bitcrusher {
+    /// Wet only: the held, quantized sample scaled by `mix`.
+    fn process(&mut self, sample: f32) -> f32 {
+        if !self.enabled { return 0.0; }
+        if self.counter == 0 {
+            self.held = self.quantize(sample);
+            self.counter = self.downsample.clamp(1, CRUSH_MAX_DOWNSAMPLE);
+        }
+        self.counter -= 1;
+        self.held * self.mix
+    }
+
+    fn name(&self) -> &'static str { "Bitcrusher" }
+
+    fn reset(&mut self) {
+        self.counter = 0;
+        self.held    = 0.0;
+    }
 }
 
 // ── Biquad filter (RBJ Audio EQ Cookbook) ────────────────────────────────────
 
+/// The pass modes, then the EQ bands, which boost or cut by `gain_db`.
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub enum FilterMode { LowPass, HighPass, BandPass }
+pub enum FilterMode { LowPass, HighPass, BandPass, Peaking, LowShelf, HighShelf }
 
 impl FilterMode {
+    pub const ALL: [FilterMode; 6] =
+        [Self::LowPass, Self::HighPass, Self::BandPass, Self::Peaking, Self::LowShelf, Self::HighShelf];
+
     pub fn name(self) -> &'static str {
-        match self { Self::LowPass => "LP", Self::HighPass => "HP", Self::BandPass => "BP" }
+        match self {
+            Self::LowPass  => "LP", Self::HighPass => "HP", Self::BandPass  => "BP",
+            Self::Peaking  => "PK", Self::LowShelf => "LS", Self::HighShelf => "HS",
+        }
     }
     pub fn next(self) -> Self {
-        match self { Self::LowPass => Self::HighPass, Self::HighPass => Self::BandPass, Self::BandPass => Self::LowPass }
+        Self::ALL[(self.index() as usize + 1) % Self::ALL.len()]
     }
     pub fn prev(self) -> Self {
-        match self { Self::LowPass => Self::BandPass, Self::HighPass => Self::LowPass, Self::BandPass => Self::HighPass }
+        Self::ALL[(self.index() as usize + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+    /// Position in `ALL`, as saved.
+    pub fn index(self) -> u8 {
+        Self::ALL.iter().position(|&m| m == self).unwrap_or(0) as u8
+    }
+    /// Whether `gain_db` does anything in this mode.
+    pub fn has_gain(self) -> bool {
+        matches!(self, Self::Peaking | Self::LowShelf | Self::HighShelf)
     }
 }
 
-/// Two-pole biquad filter applied directly to a synth bus (not via EffectChain).
+/// Range of an EQ band's boost or cut.
+pub const FILTER_MAX_GAIN_DB: f32 = 24.0;
+
+/// Two-pole biquad filter: applied directly to each synth bus as `filter1` /
+/// `filter2`, and available as an insert.
 /// When disabled, passes signal through unchanged at zero cost.
 pub struct BiquadFilter {
     pub enabled: bool,
     pub mode:    FilterMode,
     pub cutoff:  f32,   // Hz, 80.0–18 000.0
     pub q:       f32,   // 0.5–10.0
+    /// Boost (or cut, below 0) of the peaking and shelf modes; the others
+    /// ignore it.
+    pub gain_db: f32,   // dB, -24–24
+    /// Factor on `cutoff` from the modulators (synth 1's acid envelope, the LFOs); 1 = none.
+    pub cutoff_mod: f32,
     sample_rate: f32,
     // Cached normalised coefficients
     b0: f32, b1: f32, b2: f32, a1: f32, a2: f32,
     // Direct Form I delay state
     x1: f32, x2: f32, y1: f32, y2: f32,
     // Track last computed params to detect when a recompute is needed
-    last_cutoff: f32, last_q: f32, last_mode: FilterMode,
+    last_cutoff: f32, last_q: f32, last_gain: f32, last_mode: FilterMode,
 }
 
 impl BiquadFilter {
@@ -264,51 +1048,557 @@ impl BiquadFilter {
             mode: FilterMode::LowPass,
             cutoff: 5000.0,
             q: 0.707,
+            gain_db: 0.0,
+            cutoff_mod: 1.0,
             sample_rate,
             b0: 0.0, b1: 0.0, b2: 0.0, a1: 0.0, a2: 0.0,
             x1: 0.0, x2: 0.0, y1: 0.0, y2: 0.0,
-            last_cutoff: -1.0, last_q: -1.0, last_mode: FilterMode::LowPass,
+            last_cutoff: -1.0, last_q: -1.0, last_gain: 0.0, last_mode: FilterMode::LowPass,
         };
         f.recompute();
         f
     }
 
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        self.recompute();
+    }
+
     /// Reset delay state (call when toggling on to avoid a transient pop).
     pub fn reset_state(&mut self) {
         self.x1 = 0.0; self.x2 = 0.0; self.y1 = 0.0; self.y2 = 0.0;
     }
 
+    /// The cutoff in force: the set one times any modulation.
+    #[inline]
+    fn cutoff_hz(&self) -> f32 {
+        self.cutoff * self.cutoff_mod
+    }
+
     fn recompute(&mut self) {
-        let w0    = 2.0 * PI * self.cutoff.min(self.sample_rate * 0.499) / self.sample_rate;
+        let w0    = 2.0 * PI * self.cutoff_hz().min(self.sample_rate * 0.499) / self.sample_rate;
         let cos_w = w0.cos();
         let sin_w = w0.sin();
         let alpha = sin_w / (2.0 * self.q);
 
-        let (b0, b1, b2) = match self.mode {
-            FilterMode::LowPass  => { let h = (1.0 - cos_w) / 2.0; (h, 1.0 - cos_w, h) }
-            FilterMode::HighPass => { let h = (1.0 + cos_w) / 2.0; (h, -(1.0 + cos_w), h) }
-            FilterMode::BandPass => { let h = sin_w / 2.0; (h, 0.0, -h) }
+        // The EQ bands' amplitude, and the shelves' slope term.
+        let a  = 10f32.powf(self.gain_db.clamp(-FILTER_MAX_GAIN_DB, FILTER_MAX_GAIN_DB) / 40.0);
+        let sa = 2.0 * a.sqrt() * alpha;
+
+        // The pass modes share their denominator.
+        let pass = (1.0 + alpha, -2.0 * cos_w, 1.0 - alpha);
+
+        let (b0, b1, b2, (a0, a1, a2)) = match self.mode {
+            FilterMode::LowPass  => { let h = (1.0 - cos_w) / 2.0; (h, 1.0 - cos_w, h, pass) }
+            FilterMode::HighPass => { let h = (1.0 + cos_w) / 2.0; (h, -(1.0 + cos_w), h, pass) }
+            FilterMode::BandPass => { let h = sin_w / 2.0; (h, 0.0, -h, pass) }
+            FilterMode::Peaking  => (1.0 + alpha * a, -2.0 * cos_w, 1.0 - alpha * a,
+                                     (1.0 + alpha / a, -2.0 * cos_w, 1.0 - alpha / a)),
+            FilterMode::LowShelf => (a * ((a + 1.0) - (a - 1.0) * cos_w + sa),
+                                     2.0 * a * ((a - 1.0) - (a + 1.0) * cos_w),
+                                     a * ((a + 1.0) - (a - 1.0) * cos_w - sa),
+                                     ((a + 1.0) + (a - 1.0) * cos_w + sa,
+                                      -2.0 * ((a - 1.0) + (a + 1.0) * cos_w),
+                                      (a + 1.0) + (a - 1.0) * cos_w - sa)),
+            FilterMode::HighShelf => (a * ((a + 1.0) + (a - 1.0) * cos_w + sa),
+                                      -2.0 * a * ((a - 1.0) + (a + 1.0) * cos_w),
+                                      a * ((a + 1.0) + (a - 1.0) * cos_w - sa),
+                                      ((a + 1.0) - (a - 1.0) * cos_w + sa,
+                                       2.0 * ((a - 1.0) - (a + 1.0) * cos_w),
+                                       (a + 1.0) - (a - 1.0) * cos_w - sa)),
         };
-        let a0 = 1.0 + alpha;
         self.b0 = b0 / a0;  self.b1 = b1 / a0;  self.b2 = b2 / a0;
-        self.a1 = -2.0 * cos_w / a0;
-        self.a2 = (1.0 - alpha) / a0;
+        self.a1 = a1 / a0;
+        self.a2 = a2 / a0;
 
-        self.last_cutoff = self.cutoff;
+        self.last_cutoff = self.cutoff_hz();
         self.last_q      = self.q;
+        self.last_gain   = self.gain_db;
         self.last_mode   = self.mode;
     }
 
     #[inline]
     pub fn process(&mut self, x: f32) -> f32 {
         if !self.enabled { return x; }
-        if self.cutoff != self.last_cutoff || self.q != self.last_q || self.mode != self.last_mode {
+        if self.cutoff_hz() != self.last_cutoff || self.q != self.last_q || self.gain_db != self.last_gain
+            || self.mode != self.last_mode {
             self.recompute();
         }
-        let y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2
-                             - self.a1 * self.y1 - self.a2 * self.y2;
+        let mut y = self.b0 * x + self.b1 * self.x1 + self.b2 * self.x2
+                                 - self.a1 * self.y1 - self.a2 * self.y2;
+        // Flush the output pair together: zeroing only one of y1/y2 can leave a
+        // resonant filter ringing forever just above the threshold.
+        if flush_denormal(y) == 0.0 && flush_denormal(self.y1) == 0.0 {
+            y = 0.0;
+            self.y1 = 0.0;
+        }
         self.x2 = self.x1;  self.x1 = x;
         self.y2 = self.y1;  self.y1 = y;
         y
     }
 }
+
+impl AudioEffect for BiquadFilter {
+    fn process(&mut self, sample: f32) -> f32 {
+        BiquadFilter::process(self, sample)
+    }
+
+    fn name(&self) -> &'static str { "Filter" }
+
+    fn reset(&mut self) { self.reset_state(); }
+}
+
+// ── Formant filter (parallel band-passes) ─────────────────────────────────────
+
+/// Vowel names, in the order `FormantFilter::vowel` morphs through.
+pub const VOWELS: [&str; 5] = ["A", "E", "I", "O", "U"];
+
+/// The first three formants of each vowel: centre (Hz) and relative gain.
+const FORMANTS: [[(f32, f32); 3]; 5] = [
+    [(800.0, 1.0), (1150.0, 0.50), (2900.0, 0.10)],  // A
+    [(350.0, 1.0), (2000.0, 0.20), (2800.0, 0.18)],  // E
+    [(270.0, 1.0), (2140.0, 0.25), (2950.0, 0.10)],  // I
+    [(450.0, 1.0), (800.0,  0.35), (2830.0, 0.08)],  // O
+    [(325.0, 1.0), (700.0,  0.25), (2700.0, 0.05)],  // U
+];
+
+/// Wet gain bringing a bright input (a saw) back to about its dry level.
+const FORMANT_MAKEUP: f32 = 2.0;
+
+/// Three band-passes in parallel, tuned to a vowel's formants, for
+/// "talking" synth sounds.  `vowel` is a position along A–E–I–O–U: between
+/// two vowels the centres and gains are blended, so sweeping it morphs.
+/// An insert on one synth bus (`on_s2` picks which), after its filter.
+pub struct FormantFilter {
+    pub enabled: bool,
+    pub vowel:   f32,   // 0.0–4.0
+    pub q:       f32,   // 2.0–20.0
+    pub mix:     f32,   // 0.0–1.0
+    /// Inserted on synth 2 instead of synth 1.
+    pub on_s2:   bool,
+    bands:       [BiquadFilter; 3],
+}
+
+impl FormantFilter {
+    pub fn new(sample_rate: f32) -> Self {
+        let band = |_| {
+            let mut f = BiquadFilter::new(sample_rate);
+            f.enabled = true;
+            f.mode    = FilterMode::BandPass;
+            f
+        };
+        Self { enabled: false, vowel: 0.0, q: 8.0, mix: 1.0, on_s2: false, bands: std::array::from_fn(band) }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        for b in &mut self.bands { b.set_sample_rate(sample_rate); }
+    }
+
+    /// The nearest vowel, or both neighbours while between two.
+    pub fn vowel_label(vowel: f32) -> String {
+        let near = vowel.round();
+        if (vowel - near).abs() < 0.05 { return VOWELS[near as usize].to_string(); }
+        let i = vowel.floor() as usize;
+        format!("{}>{}", VOWELS[i], VOWELS[i + 1])
+    }
+
+    /// Formant `k`'s centre and gain at the current vowel position.
+    fn formant(&self, k: usize) -> (f32, f32) {
+        let v = self.vowel.clamp(0.0, (VOWELS.len() - 1) as f32);
+        let i = (v.floor() as usize).min(VOWELS.len() - 2);
+        let t = v - i as f32;
+        let ((f0, g0), (f1, g1)) = (FORMANTS[i][k], FORMANTS[i + 1][k]);
+        (f0 + (f1 - f0) * t, g0 + (g1 - g0) * t)
+    }
+}
+
+impl AudioEffect for FormantFilter {
+    /// Dry/wet blend.  The RBJ band-pass peaks at `q` and passes energy in
+    /// proportion to its width, `1/q`, so scaling the sum by `1/sqrt(q)`
+    /// keeps a broadband input at the same level whatever the Q.
+    fn process(&mut self, sample: f32) -> f32 {
+        if !self.enabled { return sample; }
+        let mut wet = 0.0;
+        for k in 0..3 {
+            let (freq, gain) = self.formant(k);
+            let band = &mut self.bands[k];
+            band.cutoff = freq;
+            band.q      = self.q;
+            wet += band.process(sample) * gain;
+        }
+        wet *= FORMANT_MAKEUP / self.q.sqrt();
+        sample + (wet - sample) * self.mix
+    }
+
+    fn name(&self) -> &'static str { "Formant" }
+
+    fn reset(&mut self) {
+        for b in &mut self.bands { b.reset_state(); }
+    }
+}
+
+// ── De-esser (dynamic band cut) ───────────────────────────────────────────────
+
+pub const DEESS_MIN_HZ: f32 = 2000.0;
+pub const DEESS_MAX_HZ: f32 = 12000.0;
+
+/// Width of the sibilance band.
+const DEESS_Q: f32 = 1.5;
+
+/// Detector attack and release, in ms.
+const DEESS_ATTACK_MS:  f32 = 0.5;
+const DEESS_RELEASE_MS: f32 = 60.0;
+
+/// Deepest cut, in dB.
+const DEESS_MAX_CUT_DB: f32 = 24.0;
+
+/// Tames harsh hats and cymbals.  A band-pass around `freq` splits the
+/// sibilance band off the signal; as that band's level goes over
+/// `threshold` it is turned down by `amount` of the excess (1.0 holds it at
+/// the threshold), so only the harsh hits lose their top and the rest of
+/// the mix keeps its brightness.  An insert on the drum bus.
+pub struct DeEsser {
+    pub enabled:   bool,
+    pub freq:      f32,   // 2000–12000 Hz
+    pub threshold: f32,   // dB, -40–0
+    pub amount:    f32,   // 0.0–1.0
+    /// Band splits of the bus's mid and side.
+    bands:         [BiquadFilter; 2],
+    env:           f32,
+    /// Current cut in dB, for the panel.
+    reduction_db:  f32,
+    sample_rate:   f32,
+}
+
+impl DeEsser {
+    pub fn new(sample_rate: f32) -> Self {
+        let band = |_| {
+            let mut f = BiquadFilter::new(sample_rate);
+            f.enabled = true;
+            f.mode    = FilterMode::BandPass;
+            f.q       = DEESS_Q;
+            f
+        };
+        Self {
+            enabled: false, freq: 6000.0, threshold: -24.0, amount: 0.5,
+            bands: std::array::from_fn(band), env: 0.0, reduction_db: 0.0, sample_rate,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+        for b in &mut self.bands { b.set_sample_rate(sample_rate); }
+    }
+
+    pub fn reduction_db(&self) -> f32 { self.reduction_db }
+
+    /// De-ess a mid/side pair, detecting on the mid so both sides of the
+    /// image are cut together.
+    pub fn process_ms(&mut self, mid: f32, side: f32) -> (f32, f32) {
+        if !self.enabled { return (mid, side); }
+        // The RBJ band-pass peaks at its Q: divide it out for unity gain.
+        let mut split = [mid, side];
+        for (b, x) in self.bands.iter_mut().zip(split.iter_mut()) {
+            b.cutoff = self.freq;
+            *x = b.process(*x) / DEESS_Q;
+        }
+        let level = split[0].abs();
+        let ms = if level > self.env { DEESS_ATTACK_MS } else { DEESS_RELEASE_MS };
+        self.env = flush_denormal(level + (self.env - level) * (-1000.0 / (ms * self.sample_rate)).exp());
+
+        let over = 20.0 * self.env.max(1e-6).log10() - self.threshold;
+        self.reduction_db = (over.max(0.0) * self.amount).min(DEESS_MAX_CUT_DB);
+        let cut = 10f32.powf(-self.reduction_db / 20.0) - 1.0;
+        (mid + split[0] * cut, side + split[1] * cut)
+    }
+}
+
+impl AudioEffect for DeEsser {
+    fn process(&mut self, sample: f32) -> f32 {
+        self.process_ms(sample, 0.0).0
+    }
+
+    fn name(&self) -> &'static str { "De-esser" }
+
+    fn reset(&mut self) {
+        for b in &mut self.bands { b.reset_state(); }
+        self.env = 0.0;
+        self.reduction_db = 0.0;
+    }
+}
+
+// ── Compressor (master bus dynamics) ──────────────────────────────────────────
+
+/// A feed-forward peak compressor.  The louder of the two sides drives one
+/// envelope (fast `attack_ms` up, `release_ms` down); where it is over
+/// `threshold` the excess is cut to `1 / ratio` of itself and both sides
+/// are turned down together, so the image holds still.  `makeup_db` brings
+/// the level back up afterwards.  An insert on the master mix, ahead of
+/// the final `tanh()`.
+pub struct Compressor {
+    pub enabled:    bool,
+    pub threshold:  f32,   // dB, -60–0
+    pub ratio:      f32,   // 1–20
+    pub attack_ms:  f32,   // 0.1–100 ms
+    pub release_ms: f32,   // 10–2000 ms
+    pub makeup_db:  f32,   // 0–24 dB
+    env:            f32,
+    /// Current gain reduction in dB, for the panel.
+    reduction_db:   f32,
+    sample_rate:    f32,
+}
+
+impl Compressor {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            enabled: false, threshold: -18.0, ratio: 4.0, attack_ms: 10.0, release_ms: 150.0,
+            makeup_db: 0.0, env: 0.0, reduction_db: 0.0, sample_rate,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    pub fn reduction_db(&self) -> f32 { self.reduction_db }
+
+    /// Compress a stereo pair, linked.
+    pub fn process_stereo(&mut self, l: f32, r: f32) -> (f32, f32) {
+        if !self.enabled { return (l, r); }
+        let level = l.abs().max(r.abs());
+        let ms = if level > self.env { self.attack_ms } else { self.release_ms };
+        self.env = flush_denormal(level + (self.env - level) * (-1000.0 / (ms * self.sample_rate)).exp());
+
+        let over = 20.0 * self.env.max(1e-6).log10() - self.threshold;
+        self.reduction_db = over.max(0.0) * (1.0 - 1.0 / self.ratio.max(1.0));
+        let gain = 10f32.powf((self.makeup_db - self.reduction_db) / 20.0);
+        (l * gain, r * gain)
+    }
+}
+
+impl AudioEffect for Compressor {
+    fn process(&mut self, sample: f32) -> f32 {
+        self.process_stereo(sample, sample).0
+    }
+
+    fn name(&self) -> &'static str { "Compressor" }
+
+    fn reset(&mut self) {
+        self.env = 0.0;
+        self.reduction_db = 0.0;
+    }
+}
+
+// ── Spectral freeze (FFT resynthesis) ─────────────────────────────────────────
+
+/// Frame length of the freeze's FFT, ~43 ms at 48 kHz.
+const FREEZE_N: usize = 2048;
+/// Frames overlap by 75%.
+const FREEZE_HOP: usize = FREEZE_N / 4;
+/// Random phases leave the frames uncorrelated, so they overlap-add in
+/// power: the analysis window keeps 3/8 of it and four Hann² synthesis
+/// windows sum to 1.5, so the drone needs `sqrt(16/9)` to match the input.
+const FREEZE_OLA_GAIN: f32 = 4.0 / 3.0;
+
+pub const FREEZE_MIN_FADE_MS: f32 = 10.0;
+pub const FREEZE_MAX_FADE_MS: f32 = 2000.0;
+
+/// Holds a sound indefinitely.  `capture()` takes the magnitude spectrum of
+/// the last frame of input; while held, every hop a frame is resynthesised
+/// from it with fresh random phases and overlap-added, which sustains the
+/// timbre as a smooth drone rather than a looping grain.  `release()` lets
+/// it go.  Both fade over `fade_ms`.  An insert on the master mix, where
+/// `mix` blends the drone over the live signal.
+pub struct SpectralFreeze {
+    pub mix:     f32,   // 0.0–1.0
+    pub fade_ms: f32,   // 10–2000 ms
+    held:        bool,
+    /// Fade gain of the drone, 0–1.
+    level:       f32,
+    /// The last `FREEZE_N` input samples, a ring.
+    input:       Vec<f32>,
+    in_pos:      usize,
+    /// Captured magnitudes, bins 0..=N/2.
+    mags:        Vec<f32>,
+    window:      Vec<f32>,
+    /// Overlap-add ring, read and cleared one sample at a time.
+    out:         Vec<f32>,
+    out_pos:     usize,
+    /// Samples to the next frame.
+    to_hop:      usize,
+    re:          Vec<f32>,
+    im:          Vec<f32>,
+    seed:        u32,
+    sample_rate: f32,
+}
+
+impl SpectralFreeze {
+    pub fn new(sample_rate: f32) -> Self {
+        Self {
+            mix: 0.5, fade_ms: 200.0, held: false, level: 0.0,
+            input: vec![0.0; FREEZE_N], in_pos: 0,
+            mags: vec![0.0; FREEZE_N / 2 + 1],
+            window: crate::fft::hann(FREEZE_N),
+            out: vec![0.0; FREEZE_N], out_pos: 0, to_hop: 0,
+            re: vec![0.0; FREEZE_N], im: vec![0.0; FREEZE_N],
+            seed: 0x9e37_79b9, sample_rate,
+        }
+    }
+
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        self.sample_rate = sample_rate;
+    }
+
+    pub fn held(&self) -> bool { self.held }
+
+    /// Freeze the spectrum of the last frame of input.  Capturing while
+    /// held replaces the drone.
+    pub fn capture(&mut self) {
+        for i in 0..FREEZE_N {
+            self.re[i] = self.input[(self.in_pos + i) % FREEZE_N] * self.window[i];
+            self.im[i] = 0.0;
+        }
+        crate::fft::fft(&mut self.re, &mut self.im, false);
+        for (k, m) in self.mags.iter_mut().enumerate() {
+            *m = self.re[k].hypot(self.im[k]);
+        }
+        self.held = true;
+    }
+
+    pub fn release(&mut self) {
+        self.held = false;
+    }
+
+    /// One frame from the captured magnitudes with random phases, windowed
+    /// and added into the output ring from the read position on.
+    fn synth_frame(&mut self) {
+        let half = FREEZE_N / 2;
+        for k in 0..=half {
+            self.seed ^= self.seed << 13;
+            self.seed ^= self.seed >> 17;
+            self.seed ^= self.seed << 5;
+            let phase = self.seed as f32 / u32::MAX as f32 * 2.0 * PI;
+            let (s, c) = phase.sin_cos();
+            self.re[k] = self.mags[k] * c;
+            self.im[k] = self.mags[k] * s;
+        }
+        // A real signal: DC and Nyquist have no phase, the rest mirror.
+        self.im[0] = 0.0;
+        self.im[half] = 0.0;
+        for k in 1..half {
+            self.re[FREEZE_N - k] = self.re[k];
+            self.im[FREEZE_N - k] = -self.im[k];
+        }
+        crate::fft::fft(&mut self.re, &mut self.im, true);
+        for i in 0..FREEZE_N {
+            self.out[(self.out_pos + i) % FREEZE_N] += self.re[i] * self.window[i] * FREEZE_OLA_GAIN;
+        }
+    }
+
+    /// Freeze a stereo pair, capturing from its mid: the drone replaces
+    /// both sides by `mix`.
+    pub fn process_stereo(&mut self, l: f32, r: f32) -> (f32, f32) {
+        let (wet, g) = self.next_wet((l + r) * 0.5);
+        (l + (wet - l) * g, r + (wet - r) * g)
+    }
+
+    /// Feed one input sample; returns the drone and its blend gain.
+    fn next_wet(&mut self, sample: f32) -> (f32, f32) {
+        self.input[self.in_pos] = sample;
+        self.in_pos = (self.in_pos + 1) % FREEZE_N;
+        if !self.held && self.level == 0.0 { return (0.0, 0.0); }
+
+        let step = 1000.0 / (self.fade_ms.max(FREEZE_MIN_FADE_MS) * self.sample_rate);
+        self.level = if self.held { (self.level + step).min(1.0) } else { (self.level - step).max(0.0) };
+        if self.to_hop == 0 {
+            self.synth_frame();
+            self.to_hop = FREEZE_HOP;
+        }
+        self.to_hop -= 1;
+        let wet = self.out[self.out_pos];
+        self.out[self.out_pos] = 0.0;
+        self.out_pos = (self.out_pos + 1) % FREEZE_N;
+        if self.level == 0.0 { self.reset(); }
+        (wet, self.mix * self.level)
+    }
+}
+
+impl AudioEffect for SpectralFreeze {
+    fn process(&mut self, sample: f32) -> f32 {
+        let (wet, g) = self.next_wet(sample);
+        sample + (wet - sample) * g
+    }
+
+    fn name(&self) -> &'static str { "Freeze" }
+
+    /// Clears the drone, not the capture history.
+    fn reset(&mut self) {
+        self.out.fill(0.0);
+        self.to_hop = 0;
+        self.level  = 0.0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Seconds until a unit impulse's reverb tail, measured in 10 ms RMS
+    /// windows, has fallen 60 dB below its loudest window.
+    fn reverb_t60(sample_rate: f32) -> f32 {
+        let mut r = Reverb::new(sample_rate);
+        r.enabled = true;
+        r.mix = 1.0;
+        let out: Vec<f32> = (0..(4.0 * sample_rate) as usize)
+            .map(|i| r.process(if i == 0 { 1.0 } else { 0.0 }))
+            .collect();
+        let win = (0.01 * sample_rate) as usize;
+        let rms: Vec<f32> = out.chunks(win)
+            .map(|c| (c.iter().map(|x| x * x).sum::<f32>() / c.len() as f32).sqrt())
+            .collect();
+        let peak = rms.iter().copied().fold(0.0, f32::max);
+        let last = rms.iter().rposition(|&x| x > peak * 1e-3).unwrap();
+        (last + 1) as f32 * win as f32 / sample_rate
+    }
+
+    #[test]
+    fn reverb_decay_time_is_independent_of_sample_rate() {
+        let reference = reverb_t60(44_100.0);
+        for sr in [22_050.0, 96_000.0] {
+            let t = reverb_t60(sr);
+            assert!((t / reference - 1.0).abs() < 0.05, "{} Hz: {:.3}s vs {:.3}s at 44.1 kHz", sr, t, reference);
+        }
+    }
+
+    /// Feed `fx` a unit impulse, then `secs` seconds of silence.
+    fn ring_out(sample_rate: f32, secs: f32, mut fx: impl FnMut(f32)) {
+        fx(1.0);
+        for _ in 0..(secs * sample_rate) as usize { fx(0.0); }
+    }
+
+    #[test]
+    fn recursive_states_settle_to_exact_zero() {
+        let sr = 44_100.0;
+        for mode in FilterMode::ALL {
+            let mut f = BiquadFilter::new(sr);
+            (f.enabled, f.mode, f.cutoff, f.q, f.gain_db) = (true, mode, 1000.0, 10.0, 12.0);
+            ring_out(sr, 5.0, |x| { f.process(x); });
+            assert_eq!([f.x1, f.x2, f.y1, f.y2], [0.0; 4], "{:?}", mode);
+        }
+
+        let mut r = Reverb::new(sr);
+        r.enabled = true;
+        ring_out(sr, 20.0, |x| { r.process(x); });
+        for c in &r.combs {
+            assert_eq!(c.damp_store, 0.0);
+            assert!(c.buf.iter().all(|&v| v == 0.0));
+        }
+        for ap in &r.allpasses { assert!(ap.buf.iter().all(|&v| v == 0.0)); }
+
+        let mut d = Delay::new(sr);
+        (d.enabled, d.feedback, d.duck_amount) = (true, 0.9, 0.5);
+        ring_out(sr, 120.0, |x| { d.process_stereo(x); });
+        assert!(d.buf.iter().all(|&v| v == 0.0));
+        assert_eq!(d.duck_env, 0.0);
+    }
+}
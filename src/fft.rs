@@ -0,0 +1,46 @@
+use std::f32::consts::PI;
+
+/// In-place radix-2 FFT of `re` / `im`, whose common length must be a power
+/// of two.  `inverse` runs the inverse transform, scaled by `1/n` so a
+/// forward and inverse pair returns the input.
+pub fn fft(re: &mut [f32], im: &mut [f32], inverse: bool) {
+    let n = re.len();
+    debug_assert!(n.is_power_of_two() && im.len() == n);
+
+    // Bit-reversal permutation.
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 { j ^= bit; bit >>= 1; }
+        j |= bit;
+        if i < j { re.swap(i, j); im.swap(i, j); }
+    }
+
+    // Butterflies, doubling the span each pass.
+    let sign = if inverse { 1.0 } else { -1.0 };
+    let mut len = 2;
+    while len <= n {
+        let step = sign * 2.0 * PI / len as f32;
+        for start in (0..n).step_by(len) {
+            for k in 0..len / 2 {
+                let (wi, wr) = (step * k as f32).sin_cos();
+                let (a, b) = (start + k, start + k + len / 2);
+                let tr = re[b] * wr - im[b] * wi;
+                let ti = re[b] * wi + im[b] * wr;
+                re[b] = re[a] - tr;  im[b] = im[a] - ti;
+                re[a] += tr;         im[a] += ti;
+            }
+        }
+        len <<= 1;
+    }
+
+    if inverse {
+        let scale = 1.0 / n as f32;
+        for (r, i) in re.iter_mut().zip(im.iter_mut()) { *r *= scale; *i *= scale; }
+    }
+}
+
+/// Periodic Hann window of length `n`.
+pub fn hann(n: usize) -> Vec<f32> {
+    (0..n).map(|i| 0.5 - 0.5 * (2.0 * PI * i as f32 / n as f32).cos()).collect()
+}
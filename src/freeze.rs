@@ -0,0 +1,131 @@
+use crate::synth::Synth;
+
+/// A bus that can be frozen: rendered once and played back from a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bus { Synth1, Synth2, Drums }
+
+impl Bus {
+    pub const ALL: [Bus; 3] = [Bus::Synth1, Bus::Synth2, Bus::Drums];
+
+    /// Index into per-bus arrays (`Synth::frozen`, `bus_meters`).
+    pub fn index(self) -> usize {
+        match self { Bus::Synth1 => 0, Bus::Synth2 => 1, Bus::Drums => 2 }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self { Bus::Synth1 => "Synth 1", Bus::Synth2 => "Synth 2", Bus::Drums => "Drums" }
+    }
+}
+
+/// One loop of a bus, rendered offline.  Holds the bus signal before its
+/// volume, filter and effects, which stay live.
+pub struct Frozen {
+    /// The bus's mono signal; for the stereo drum bus, its mid.
+    pub buf:   Vec<f32>,
+    /// The drum bus's side, `(L-R)/2`, parallel to `buf`.  Empty for the
+    /// synth buses, which are mono until after their volume.
+    pub side:  Vec<f32>,
+    /// Loop length in steps.  The buffer is read by `step_pos` within the
+    /// loop, so it stays in place on the master clock.
+    pub steps: usize,
+    /// Tempo the loop was rendered at: another tempo stretches it.
+    pub bpm:   f32,
+}
+
+impl Frozen {
+    /// The buffer's sample at master clock position `step_pos`, linear
+    /// between neighbours and wrapping round the loop.
+    pub fn sample_at(&self, step_pos: f64) -> f32 {
+        self.read(&self.buf, step_pos)
+    }
+
+    /// The side channel at `step_pos`, as `sample_at`; 0 for a mono bus.
+    pub fn side_at(&self, step_pos: f64) -> f32 {
+        self.read(&self.side, step_pos)
+    }
+
+    fn read(&self, buf: &[f32], step_pos: f64) -> f32 {
+        let len = buf.len();
+        if len == 0 { return 0.0; }
+        let at = step_pos.rem_euclid(self.steps as f64) / self.steps as f64 * len as f64;
+        let i = at as usize % len;
+        let t = (at - at.floor()) as f32;
+        buf[i] + (buf[(i + 1) % len] - buf[i]) * t
+    }
+}
+
+/// One loop of a bus waiting to be rendered.  Set up from the live synth
+/// under its lock, then run outside it so the audio thread isn't held up.
+pub struct Render {
+    synth: Synth,
+    bus:   Bus,
+    steps: usize,
+}
+
+impl Render {
+    /// A scratch synth that plays just `bus`'s pattern, from step zero.
+    /// Automation and MIDI output are left out.
+    pub fn new(s: &Synth, bus: Bus) -> Self {
+        let mut r = Synth::new(s.sample_rate);
+        r.bpm          = s.bpm;
+        r.groove       = s.groove;
+        r.live_scale   = s.live_scale.clone();
+        r.wavetables   = s.wavetables.clone();
+        r.granular     = s.granular.clone();
+        r.band_limited = s.band_limited;
+        let steps = match bus {
+            Bus::Synth1 => {
+                r.wave_type = s.wave_type;
+                r.mono      = s.mono;
+                (r.attack, r.decay, r.sustain, r.release) = (s.attack, s.decay, s.sustain, s.release);
+                r.sequencer = s.sequencer.clone();
+                r.sequencer.playing = true;
+                r.sequencer.loop_steps()
+            }
+            Bus::Synth2 => {
+                r.wave_type2 = s.wave_type2;
+                r.mono2      = s.mono2;
+                (r.attack2, r.decay2, r.sustain2, r.release2) = (s.attack2, s.decay2, s.sustain2, s.release2);
+                r.sequencer2 = s.sequencer2.clone();
+                r.sequencer2.playing = true;
+                r.sequencer2.loop_steps()
+            }
+            Bus::Drums => {
+                r.drum_machine = s.drum_machine.pattern_copy();
+                r.drum_machine.toggle_play();
+                r.drum_machine.num_steps
+            }
+        };
+        Self { synth: r, bus, steps }
+    }
+
+    /// Render the loop.  Release tails that run past its end are folded
+    /// back onto its start, as they would sound when looping.
+    pub fn run(self) -> Frozen {
+        let Self { synth: mut r, bus, steps } = self;
+        let samples_per_step = 60.0 / (r.bpm as f64 * 4.0) * r.sample_rate as f64;
+        let len = ((steps as f64 * samples_per_step).round() as usize).max(1);
+        let mut buf = vec![0.0f32; len];
+        let stereo = bus == Bus::Drums;
+        let mut side = vec![0.0f32; if stereo { len } else { 0 }];
+        let b = bus.index();
+        for (i, x) in buf.iter_mut().enumerate() {
+            r.generate_sample();
+            *x = r.bus_raw[b];
+            if stereo { side[i] = r.drum_side_raw; }
+        }
+
+        // Stop where the loop would start again, then let the tails ring
+        // out for up to one more loop.
+        r.sequencer.playing = false;
+        r.sequencer2.playing = false;
+        r.drum_machine.playing = false;
+        for v in r.voices.values_mut().chain(r.voices2.values_mut()) { v.release(); }
+        for (i, x) in buf.iter_mut().enumerate() {
+            r.generate_sample();
+            *x += r.bus_raw[b];
+            if stereo { side[i] += r.drum_side_raw; }
+        }
+        Frozen { buf, side, steps, bpm: r.bpm }
+    }
+}
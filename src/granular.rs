@@ -0,0 +1,111 @@
+use std::f32::consts::PI;
+use std::sync::Arc;
+
+use crate::sample::Sample;
+use crate::synth::note_to_freq;
+
+/// Grain length range, in ms.
+pub const GRAIN_MIN_MS: f32 = 10.0;
+pub const GRAIN_MAX_MS: f32 = 500.0;
+
+/// Root note of a newly loaded project: C4.
+pub const DEFAULT_ROOT: u8 = 60;
+
+/// Grains started per second, per voice.
+pub const DENSITY_MIN: f32 = 1.0;
+pub const DENSITY_MAX: f32 = 100.0;
+
+/// Grains sounding at once in one voice; a grain due while all are busy
+/// is skipped.
+const MAX_GRAINS: usize = 32;
+
+/// Random spread of each grain's start around `position`, in ms.  Breaks up
+/// the comb-filter buzz of grains repeating the same spot.
+const SPRAY_MS: f32 = 20.0;
+
+/// The shared settings of sample playback: the loaded sample, its root and
+/// the grain parameters, read by every voice on `WaveType::Granular` (and,
+/// for the sample and root, `WaveType::Sampler`).
+#[derive(Clone)]
+pub struct Granular {
+    pub sample:   Option<Arc<Sample>>,
+    /// Note the sample plays at its own pitch; others transpose from it.
+    pub root:     u8,
+    /// Length of each grain.
+    pub size_ms:  f32,
+    /// Grains started per second.
+    pub density:  f32,
+    /// Where in the sample grains start, 0 (start) to 1 (end).
+    pub position: f32,
+}
+
+impl Granular {
+    pub fn new() -> Self {
+        Self { sample: None, root: DEFAULT_ROOT, size_ms: 80.0, density: 20.0, position: 0.0 }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Grain {
+    /// Read position in the sample, in its samples.
+    pos:  f32,
+    /// Read step per output sample.
+    step: f32,
+    age:  u32,
+    /// Length in output samples; 0 = free.
+    len:  u32,
+}
+
+/// One voice's grains and the countdown to its next.
+#[derive(Clone, Debug)]
+pub struct GrainCloud {
+    grains: [Grain; MAX_GRAINS],
+    /// Output samples until the next grain starts.
+    wait:   f32,
+    seed:   u32,
+}
+
+impl GrainCloud {
+    pub fn new(note: u8) -> Self {
+        Self { grains: [Grain::default(); MAX_GRAINS], wait: 0.0, seed: 0x9e37_79b9 ^ note as u32 }
+    }
+
+    /// Uniform in 0..1.
+    fn rand(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        (self.seed >> 8) as f32 / (1u32 << 24) as f32
+    }
+
+    /// Next output sample of a voice at `freq`.  Grains start at random
+    /// intervals averaging `1 / density` and read the sample transposed by
+    /// the note's distance from the root, under a Hann window.
+    pub fn next(&mut self, g: &Granular, freq: f32, sr: f32) -> f32 {
+        let Some(sample) = g.sample.as_deref() else { return 0.0 };
+        let len = ((g.size_ms * 0.001 * sr) as u32).max(1);
+
+        self.wait -= 1.0;
+        if self.wait <= 0.0 {
+            self.wait += sr / g.density * (0.5 + self.rand());
+            let spray = (self.rand() * 2.0 - 1.0) * SPRAY_MS * 0.001 * sample.rate;
+            let pos = g.position * sample.data.len() as f32 + spray;
+            let step = freq / note_to_freq(g.root) * sample.rate / sr;
+            if let Some(free) = self.grains.iter_mut().find(|gr| gr.len == 0) {
+                *free = Grain { pos, step, age: 0, len };
+            }
+        }
+
+        let mut out = 0.0;
+        for gr in self.grains.iter_mut().filter(|gr| gr.len > 0) {
+            let w = (PI * gr.age as f32 / gr.len as f32).sin();
+            out += sample.at(gr.pos) * w * w;
+            gr.pos += gr.step;
+            gr.age += 1;
+            if gr.age >= gr.len { gr.len = 0; }
+        }
+        // Grains overlapping on average: scale so dense clouds don't pile up.
+        let overlap = g.density * g.size_ms * 0.001;
+        out / overlap.max(1.0).sqrt()
+    }
+}
@@ -0,0 +1,719 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Context as _, Result};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use serde::Deserialize;
+
+use crate::app::AppMode;
+
+/// Where a binding applies.  Focus-specific bindings are looked up before
+/// global ones, so a panel can reuse a key that is global elsewhere.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Context { Global, Keyboard, SynthSeq, SynthSeq2, Drums, Effects, Harmonics, Sample, Acid, Envelope, Modulation, Inserts }
+
+impl Context {
+    const ALL: [Context; 12] = [
+        Context::Global, Context::Keyboard, Context::SynthSeq,
+        Context::SynthSeq2, Context::Drums, Context::Effects, Context::Harmonics, Context::Sample,
+        Context::Acid, Context::Envelope, Context::Modulation, Context::Inserts,
+    ];
+
+    /// Section name in `keymap.toml`.
+    pub fn section(self) -> &'static str {
+        match self {
+            Context::Global    => "global",
+            Context::Keyboard  => "keyboard",
+            Context::SynthSeq  => "synth_seq",
+            Context::SynthSeq2 => "synth_seq2",
+            Context::Drums     => "drums",
+            Context::Effects   => "effects",
+            Context::Harmonics => "harmonics",
+            Context::Sample    => "sample",
+            Context::Acid      => "acid",
+            Context::Envelope  => "envelope",
+            Context::Modulation => "modulation",
+            Context::Inserts   => "inserts",
+        }
+    }
+
+    fn of(mode: &AppMode) -> Self {
+        match mode {
+            AppMode::Play      => Context::Keyboard,
+            AppMode::SynthSeq  => Context::SynthSeq,
+            AppMode::SynthSeq2 => Context::SynthSeq2,
+            AppMode::Drums     => Context::Drums,
+            AppMode::Effects   => Context::Effects,
+            AppMode::Harmonics => Context::Harmonics,
+            AppMode::Sample    => Context::Sample,
+            AppMode::Acid      => Context::Acid,
+            AppMode::Envelope  => Context::Envelope,
+            AppMode::Modulation => Context::Modulation,
+            AppMode::Inserts   => Context::Inserts,
+        }
+    }
+}
+
+/// Everything a key can be bound to.  Names in `keymap.toml` are the
+/// snake_case `App` method names (see `ACTION_NAMES`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Action {
+    Quit, SavePrompt, LoadPrompt, WavetablePrompt, ExportPrompt, ExportMidiPrompt,
+    ToggleMode, CycleWave, CycleWave2, ToggleBandLimited,
+    CycleScale, CycleScaleRoot, ScaleEdit, LiveQuantize, MonoSumToggle, CueCycle, MetronomeToggle, SpectralFreezeToggle,
+    AudioNextDevice, ResetXruns, PeakHoldReset, SpectrogramToggle, ClearPatternPrompt, ClearAllPrompt, UndoClear,
+    BpmUp, BpmDown, BpmFineUp, BpmFineDown, CrossfadeLeft, CrossfadeRight,
+    MasterGainUp, MasterGainDown, DrumBusVolUp, DrumBusVolDown, OctaveUp, OctaveDown,
+    VolumeUp, VolumeDown, Synth2VolUp, Synth2VolDown, ToggleLatch, ClearLatched, CycleChord,
+    ArpToggle, ArpCycleRate, ArpCycleMode, MonoToggle, Mono2Toggle, SeqRecToggle, CountInCycle,
+    SynthPanLeft, SynthPanRight, Synth2PanLeft, Synth2PanRight,
+    SeqCursorLeft, SeqCursorRight, SeqTogglePlay, SeqClearStep, SeqToggleMute, SeqToggleSlide, SeqToggleTie, SeqVelHumanizeCycle,
+    SeqCycleSteps, SeqDirectionCycle,
+    SeqDegreeUp, SeqDegreeDown, SeqStepOctaveUp, SeqStepOctaveDown, SeqNudgeLeft, SeqNudgeRight,
+    SeqQuantize, Seq2Quantize, QuantizeStrengthCycle,
+    Seq2CursorLeft, Seq2CursorRight, Seq2TogglePlay, Seq2ClearStep, Seq2ToggleMute, Seq2ToggleSlide, Seq2ToggleTie, Seq2VelHumanizeCycle,
+    Seq2CycleSteps, Seq2DirectionCycle,
+    Seq2DegreeUp, Seq2DegreeDown, Seq2StepOctaveUp, Seq2StepOctaveDown, Seq2NudgeLeft, Seq2NudgeRight,
+    ToggleAudition, TogglePaint,
+    DrumTogglePlay, DrumTrackUp, DrumTrackDown, DrumStepLeft, DrumStepRight,
+    DrumToggleStep, DrumClearStep, DrumCycleSteps, DrumToggleMute,
+    DrumAddTrack, DrumRemoveTrack, DrumMoveTrackUp, DrumMoveTrackDown, DrumCycleKind,
+    DrumRenamePrompt, DrumSamplePrompt,
+    DrumVolUp, DrumVolDown, DrumPanLeft, DrumPanRight, DrumPitchUp, DrumPitchDown, DrumProbUp, DrumProbDown, DrumEuclidean,
+    DrumCycleCond, DrumFillToggle, DrumCycleRetrig, DrumCycleRetrigHits, DrumNudgeLeft, DrumNudgeRight,
+    DrumSwingUp, DrumSwingDown, CycleGroove, ToggleGrooveMelodic,
+    EffectsSelUp, EffectsSelDown, EffectsParamLeft, EffectsParamRight,
+    EffectsParamInc, EffectsParamDec, EffectsOnOff, EffectsRouteToggle, DelayNotePrompt,
+    DelayTapPreset, DelayTapsPrompt, DelayReverseToggle, DelaySyncCycle,
+    DelayDuckUp, DelayDuckDown, DelayDuckReleaseUp, DelayDuckReleaseDown,
+    ReverbErUp, ReverbErDown, ReverbShimmerUp, ReverbShimmerDown, DistCycleMode, DistAsymUp, DistAsymDown,
+    HarmSelLeft, HarmSelRight, HarmUp, HarmDown, HarmClear, HarmReset, HarmUse1, HarmUse2,
+    SamplePrompt, GrainSelLeft, GrainSelRight, GrainUp, GrainDown, GrainUse1, GrainUse2, SamplerUse1, SamplerUse2,
+    AcidPreset, AcidToggle, AcidAccentToggle, AcidParamLeft, AcidParamRight, AcidParamUp, AcidParamDown,
+    EnvSynthToggle, EnvParamLeft, EnvParamRight, EnvParamInc, EnvParamDec,
+    LfoSelToggle, LfoParamLeft, LfoParamRight, LfoParamInc, LfoParamDec,
+    InsertSynthToggle, InsertSelLeft, InsertSelRight, InsertCycleKind, InsertAdd, InsertRemove,
+    InsertMoveLeft, InsertMoveRight,
+    MidiToggleKeys, MidiToggleSeq1, MidiToggleSeq2, MidiToggleLocal,
+    AutoRecToggle, AutoBypassToggle,
+    MarkerPrompt, MarkerRemove, MarkerNext, MarkerPrev,
+    SeqHoldStep, Seq2HoldStep, SeqClearLocks, Seq2ClearLocks,
+    SeqCutoffUp, SeqCutoffDown, Seq2CutoffUp, Seq2CutoffDown,
+    SeqFreeze, Seq2Freeze, DrumFreeze,
+}
+
+const ACTION_NAMES: &[(Action, &str)] = &[
+    (Action::Quit, "quit"), (Action::SavePrompt, "save_prompt"),
+    (Action::LoadPrompt, "load_prompt"), (Action::WavetablePrompt, "wavetable_prompt"),
+    (Action::ExportPrompt, "export_prompt"), (Action::ExportMidiPrompt, "export_midi_prompt"),
+    (Action::ToggleMode, "toggle_mode"), (Action::CycleWave, "cycle_wave"),
+    (Action::CycleWave2, "cycle_wave2"), (Action::ToggleBandLimited, "toggle_band_limited"),
+    (Action::CycleScale, "cycle_scale"), (Action::CycleScaleRoot, "cycle_scale_root"),
+    (Action::ScaleEdit, "scale_edit"), (Action::LiveQuantize, "live_quantize"),
+    (Action::MonoSumToggle, "mono_sum_toggle"), (Action::CueCycle, "cue_cycle"),
+    (Action::MetronomeToggle, "metronome_toggle"), (Action::SpectralFreezeToggle, "spectral_freeze_toggle"),
+    (Action::AudioNextDevice, "audio_next_device"), (Action::ResetXruns, "reset_xruns"),
+    (Action::PeakHoldReset, "peak_hold_reset"), (Action::SpectrogramToggle, "spectrogram_toggle"),
+    (Action::ClearPatternPrompt, "clear_pattern_prompt"), (Action::ClearAllPrompt, "clear_all_prompt"),
+    (Action::UndoClear, "undo_clear"),
+    (Action::BpmUp, "bpm_up"), (Action::BpmDown, "bpm_down"),
+    (Action::BpmFineUp, "bpm_fine_up"), (Action::BpmFineDown, "bpm_fine_down"),
+    (Action::CrossfadeLeft, "crossfade_left"), (Action::CrossfadeRight, "crossfade_right"),
+    (Action::MasterGainUp, "master_gain_up"), (Action::MasterGainDown, "master_gain_down"),
+    (Action::DrumBusVolUp, "drum_bus_vol_up"), (Action::DrumBusVolDown, "drum_bus_vol_down"),
+    (Action::OctaveUp, "octave_up"), (Action::OctaveDown, "octave_down"),
+    (Action::VolumeUp, "volume_up"), (Action::VolumeDown, "volume_down"),
+    (Action::Synth2VolUp, "synth2_vol_up"), (Action::Synth2VolDown, "synth2_vol_down"),
+    (Action::ToggleLatch, "toggle_latch"), (Action::ClearLatched, "clear_latched"),
+    (Action::CycleChord, "cycle_chord"),
+    (Action::ArpToggle, "arp_toggle"), (Action::ArpCycleRate, "arp_cycle_rate"),
+    (Action::ArpCycleMode, "arp_cycle_mode"),
+    (Action::MonoToggle, "mono_toggle"), (Action::Mono2Toggle, "mono2_toggle"),
+    (Action::SeqRecToggle, "seq_rec_toggle"), (Action::CountInCycle, "count_in_cycle"),
+    (Action::SynthPanLeft, "synth_pan_left"), (Action::SynthPanRight, "synth_pan_right"),
+    (Action::Synth2PanLeft, "synth2_pan_left"), (Action::Synth2PanRight, "synth2_pan_right"),
+    (Action::SeqCursorLeft, "seq_cursor_left"), (Action::SeqCursorRight, "seq_cursor_right"),
+    (Action::SeqTogglePlay, "seq_toggle_play"), (Action::SeqClearStep, "seq_clear_step"),
+    (Action::SeqToggleMute, "seq_toggle_mute"), (Action::SeqToggleSlide, "seq_toggle_slide"),
+    (Action::SeqToggleTie, "seq_toggle_tie"), (Action::SeqVelHumanizeCycle, "seq_vel_humanize_cycle"),
+    (Action::SeqDirectionCycle, "seq_direction_cycle"),
+    (Action::SeqCycleSteps, "seq_cycle_steps"),
+    (Action::SeqDegreeUp, "seq_degree_up"), (Action::SeqDegreeDown, "seq_degree_down"),
+    (Action::SeqStepOctaveUp, "seq_octave_up"), (Action::SeqStepOctaveDown, "seq_octave_down"),
+    (Action::SeqNudgeLeft, "seq_nudge_left"), (Action::SeqNudgeRight, "seq_nudge_right"),
+    (Action::SeqQuantize, "seq_quantize"), (Action::Seq2Quantize, "seq2_quantize"),
+    (Action::QuantizeStrengthCycle, "quantize_strength_cycle"),
+    (Action::Seq2CursorLeft, "seq2_cursor_left"), (Action::Seq2CursorRight, "seq2_cursor_right"),
+    (Action::Seq2TogglePlay, "seq2_toggle_play"), (Action::Seq2ClearStep, "seq2_clear_step"),
+    (Action::Seq2ToggleMute, "seq2_toggle_mute"), (Action::Seq2ToggleSlide, "seq2_toggle_slide"),
+    (Action::Seq2ToggleTie, "seq2_toggle_tie"), (Action::Seq2VelHumanizeCycle, "seq2_vel_humanize_cycle"),
+    (Action::Seq2DirectionCycle, "seq2_direction_cycle"),
+    (Action::Seq2CycleSteps, "seq2_cycle_steps"),
+    (Action::Seq2DegreeUp, "seq2_degree_up"), (Action::Seq2DegreeDown, "seq2_degree_down"),
+    (Action::Seq2StepOctaveUp, "seq2_octave_up"), (Action::Seq2StepOctaveDown, "seq2_octave_down"),
+    (Action::Seq2NudgeLeft, "seq2_nudge_left"), (Action::Seq2NudgeRight, "seq2_nudge_right"),
+    (Action::ToggleAudition, "toggle_audition"), (Action::TogglePaint, "toggle_paint"),
+    (Action::DrumTogglePlay, "drum_toggle_play"),
+    (Action::DrumTrackUp, "drum_track_up"), (Action::DrumTrackDown, "drum_track_down"),
+    (Action::DrumStepLeft, "drum_step_left"), (Action::DrumStepRight, "drum_step_right"),
+    (Action::DrumToggleStep, "drum_toggle_step"), (Action::DrumClearStep, "drum_clear_step"),
+    (Action::DrumCycleSteps, "drum_cycle_steps"), (Action::DrumToggleMute, "drum_toggle_mute"),
+    (Action::DrumAddTrack, "drum_add_track"), (Action::DrumRemoveTrack, "drum_remove_track"),
+    (Action::DrumMoveTrackUp, "drum_move_track_up"), (Action::DrumMoveTrackDown, "drum_move_track_down"),
+    (Action::DrumCycleKind, "drum_cycle_kind"), (Action::DrumRenamePrompt, "drum_rename_track"),
+    (Action::DrumSamplePrompt, "drum_sample_prompt"),
+    (Action::DrumVolUp, "drum_vol_up"), (Action::DrumVolDown, "drum_vol_down"),
+    (Action::DrumPanLeft, "drum_pan_left"), (Action::DrumPanRight, "drum_pan_right"),
+    (Action::DrumPitchUp, "drum_pitch_up"), (Action::DrumPitchDown, "drum_pitch_down"),
+    (Action::DrumProbUp, "drum_prob_up"), (Action::DrumProbDown, "drum_prob_down"),
+    (Action::DrumEuclidean, "drum_euclidean"),
+    (Action::DrumCycleCond, "drum_cycle_cond"), (Action::DrumFillToggle, "drum_fill_toggle"),
+    (Action::DrumCycleRetrig, "drum_cycle_retrig"), (Action::DrumCycleRetrigHits, "drum_cycle_retrig_hits"),
+    (Action::DrumNudgeLeft, "drum_nudge_left"), (Action::DrumNudgeRight, "drum_nudge_right"),
+    (Action::DrumSwingUp, "drum_swing_up"), (Action::DrumSwingDown, "drum_swing_down"),
+    (Action::CycleGroove, "cycle_groove"), (Action::ToggleGrooveMelodic, "toggle_groove_melodic"),
+    (Action::HarmSelLeft, "harm_sel_left"), (Action::HarmSelRight, "harm_sel_right"),
+    (Action::HarmUp, "harm_up"), (Action::HarmDown, "harm_down"),
+    (Action::HarmClear, "harm_clear"), (Action::HarmReset, "harm_reset"),
+    (Action::HarmUse1, "harm_use1"), (Action::HarmUse2, "harm_use2"),
+    (Action::SamplePrompt, "sample_prompt"),
+    (Action::GrainSelLeft, "grain_sel_left"), (Action::GrainSelRight, "grain_sel_right"),
+    (Action::GrainUp, "grain_up"), (Action::GrainDown, "grain_down"),
+    (Action::GrainUse1, "grain_use1"), (Action::GrainUse2, "grain_use2"),
+    (Action::SamplerUse1, "sampler_use1"), (Action::SamplerUse2, "sampler_use2"),
+    (Action::AcidPreset, "acid_preset"), (Action::AcidToggle, "acid_toggle"),
+    (Action::AcidAccentToggle, "acid_toggle_accent"),
+    (Action::AcidParamLeft, "acid_param_left"), (Action::AcidParamRight, "acid_param_right"),
+    (Action::AcidParamUp, "acid_param_up"), (Action::AcidParamDown, "acid_param_down"),
+    (Action::EnvSynthToggle, "env_synth_toggle"),
+    (Action::EnvParamLeft, "env_param_left"), (Action::EnvParamRight, "env_param_right"),
+    (Action::EnvParamInc, "env_param_inc"), (Action::EnvParamDec, "env_param_dec"),
+    (Action::LfoSelToggle, "lfo_sel_toggle"),
+    (Action::InsertSynthToggle, "insert_synth_toggle"),
+    (Action::InsertSelLeft, "insert_sel_left"), (Action::InsertSelRight, "insert_sel_right"),
+    (Action::InsertCycleKind, "insert_cycle_kind"),
+    (Action::InsertAdd, "insert_add"), (Action::InsertRemove, "insert_remove"),
+    (Action::InsertMoveLeft, "insert_move_left"), (Action::InsertMoveRight, "insert_move_right"),
+    (Action::LfoParamLeft, "lfo_param_left"), (Action::LfoParamRight, "lfo_param_right"),
+    (Action::LfoParamInc, "lfo_param_inc"), (Action::LfoParamDec, "lfo_param_dec"),
+    (Action::EffectsSelUp, "effects_sel_up"), (Action::EffectsSelDown, "effects_sel_down"),
+    (Action::EffectsParamLeft, "effects_param_left"), (Action::EffectsParamRight, "effects_param_right"),
+    (Action::EffectsParamInc, "effects_param_inc"), (Action::EffectsParamDec, "effects_param_dec"),
+    (Action::EffectsOnOff, "effects_on_off"), (Action::EffectsRouteToggle, "effects_route_toggle"),
+    (Action::DelayNotePrompt, "delay_note_prompt"),
+    (Action::DelayTapPreset, "delay_tap_preset"), (Action::DelayTapsPrompt, "delay_taps_prompt"),
+    (Action::DelayReverseToggle, "delay_reverse_toggle"),
+    (Action::DelaySyncCycle, "delay_sync_cycle"),
+    (Action::DelayDuckUp, "delay_duck_up"), (Action::DelayDuckDown, "delay_duck_down"),
+    (Action::DelayDuckReleaseUp, "delay_duck_release_up"),
+    (Action::DelayDuckReleaseDown, "delay_duck_release_down"),
+    (Action::ReverbErUp, "reverb_er_up"), (Action::ReverbErDown, "reverb_er_down"),
+    (Action::ReverbShimmerUp, "reverb_shimmer_up"), (Action::ReverbShimmerDown, "reverb_shimmer_down"),
+    (Action::DistCycleMode, "dist_cycle_mode"),
+    (Action::DistAsymUp, "dist_asym_up"), (Action::DistAsymDown, "dist_asym_down"),
+    (Action::MidiToggleKeys, "midi_toggle_keys"), (Action::MidiToggleSeq1, "midi_toggle_seq1"),
+    (Action::MidiToggleSeq2, "midi_toggle_seq2"), (Action::MidiToggleLocal, "midi_toggle_local"),
+    (Action::AutoRecToggle, "auto_rec_toggle"), (Action::AutoBypassToggle, "auto_bypass_toggle"),
+    (Action::MarkerPrompt, "marker_prompt"), (Action::MarkerRemove, "marker_remove"),
+    (Action::MarkerNext, "marker_next"), (Action::MarkerPrev, "marker_prev"),
+    (Action::SeqHoldStep, "seq_hold_step"), (Action::Seq2HoldStep, "seq2_hold_step"),
+    (Action::SeqClearLocks, "seq_clear_locks"), (Action::Seq2ClearLocks, "seq2_clear_locks"),
+    (Action::SeqCutoffUp, "seq_cutoff_up"), (Action::SeqCutoffDown, "seq_cutoff_down"),
+    (Action::Seq2CutoffUp, "seq2_cutoff_up"), (Action::Seq2CutoffDown, "seq2_cutoff_down"),
+    (Action::SeqFreeze, "seq_freeze"), (Action::Seq2Freeze, "seq2_freeze"),
+    (Action::DrumFreeze, "drum_freeze"),
+];
+
+impl Action {
+    pub fn name(self) -> &'static str {
+        ACTION_NAMES.iter().find(|(a, _)| *a == self).map_or("?", |(_, n)| n)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        ACTION_NAMES.iter().find(|(_, n)| *n == name).map(|(a, _)| *a)
+    }
+
+    /// Whether holding the key repeats the action.  One-shot toggles don't.
+    pub fn repeats(self) -> bool {
+        use Action::*;
+        matches!(self,
+            BpmUp | BpmDown | BpmFineUp | BpmFineDown | CrossfadeLeft | CrossfadeRight |
+            MasterGainUp | MasterGainDown | DrumBusVolUp | DrumBusVolDown | CycleScale | CycleScaleRoot | OctaveUp | OctaveDown |
+            VolumeUp | VolumeDown | Synth2VolUp | Synth2VolDown |
+            SeqCursorLeft | SeqCursorRight | Seq2CursorLeft | Seq2CursorRight |
+            SeqDegreeUp | SeqDegreeDown | Seq2DegreeUp | Seq2DegreeDown |
+            SeqNudgeLeft | SeqNudgeRight | Seq2NudgeLeft | Seq2NudgeRight | DrumNudgeLeft | DrumNudgeRight |
+            DrumTrackUp | DrumTrackDown | DrumStepLeft | DrumStepRight |
+            DrumVolUp | DrumVolDown | DrumPanLeft | DrumPanRight | DrumPitchUp | DrumPitchDown | DrumProbUp | DrumProbDown | DrumSwingUp | DrumSwingDown |
+            EffectsSelUp | EffectsSelDown | EffectsParamLeft | EffectsParamRight |
+            EffectsParamInc | EffectsParamDec | ReverbErUp | ReverbErDown | ReverbShimmerUp | ReverbShimmerDown | DistAsymUp | DistAsymDown |
+            DelayDuckUp | DelayDuckDown | DelayDuckReleaseUp | DelayDuckReleaseDown |
+            HarmSelLeft | HarmSelRight | HarmUp | HarmDown | GrainSelLeft | GrainSelRight | GrainUp | GrainDown |
+            AcidParamLeft | AcidParamRight | AcidParamUp | AcidParamDown |
+            EnvParamLeft | EnvParamRight | EnvParamInc | EnvParamDec |
+            LfoParamLeft | LfoParamRight | LfoParamInc | LfoParamDec |
+            SeqCutoffUp | SeqCutoffDown | Seq2CutoffUp | Seq2CutoffDown |
+            SynthPanLeft | SynthPanRight | Synth2PanLeft | Synth2PanRight | InsertSelLeft | InsertSelRight)
+    }
+}
+
+/// Built-in bindings (the layout documented in CLAUDE.md).
+const DEFAULTS: &[(Context, Action, &[&str])] = &[
+    (Context::Global, Action::Quit,              &["Esc", "Ctrl+c"]),
+    (Context::Global, Action::SavePrompt,        &["Ctrl+s"]),
+    (Context::Global, Action::LoadPrompt,        &["Ctrl+l"]),
+    (Context::Global, Action::WavetablePrompt,   &["Ctrl+w"]),
+    (Context::Global, Action::ExportPrompt,      &["Alt+e"]),
+    (Context::Global, Action::ExportMidiPrompt,  &["Alt+m"]),
+    (Context::Global, Action::ToggleMode,        &["Tab", "F2"]),
+    (Context::Global, Action::CycleWave,         &["F1"]),
+    (Context::Global, Action::DrumTogglePlay,    &["F3"]),
+    (Context::Global, Action::SpectrogramToggle, &["Shift+F3"]),
+    (Context::Global, Action::ToggleBandLimited, &["F4"]),
+    (Context::Global, Action::SpectralFreezeToggle, &["Shift+F4"]),
+    (Context::Global, Action::CycleScale,        &["F6"]),
+    (Context::Global, Action::CycleScaleRoot,    &["F7"]),
+    (Context::Global, Action::ScaleEdit,         &["Shift+F6"]),
+    (Context::Global, Action::LiveQuantize,      &["Shift+F7"]),
+    (Context::Global, Action::AudioNextDevice,   &["F8"]),
+    (Context::Global, Action::MonoSumToggle,     &["Shift+F8"]),
+    (Context::Global, Action::ResetXruns,        &["F9"]),
+    (Context::Global, Action::PeakHoldReset,     &["Ctrl+F9"]),
+    (Context::Global, Action::ClearPatternPrompt, &["Ctrl+Delete"]),
+    (Context::Global, Action::ClearAllPrompt,    &["Ctrl+Shift+Delete"]),
+    (Context::Global, Action::UndoClear,         &["Ctrl+z"]),
+    (Context::Global, Action::CueCycle,          &["Shift+F9"]),
+    (Context::Global, Action::MidiToggleLocal,   &["F10"]),
+    (Context::Global, Action::MetronomeToggle,   &["Shift+F10"]),
+    (Context::Global, Action::AutoRecToggle,     &["Ctrl+r"]),
+    (Context::Global, Action::AutoBypassToggle,  &["Ctrl+b"]),
+    (Context::Global, Action::MarkerPrompt,      &["F11"]),
+    (Context::Global, Action::MarkerRemove,      &["Shift+F11"]),
+    (Context::Global, Action::MarkerNext,        &["F12"]),
+    (Context::Global, Action::MarkerPrev,        &["Shift+F12"]),
+    (Context::Global, Action::BpmUp,             &["PageUp"]),
+    (Context::Global, Action::BpmDown,           &["PageDown"]),
+    (Context::Global, Action::BpmFineUp,         &["Shift+PageUp"]),
+    (Context::Global, Action::BpmFineDown,       &["Shift+PageDown"]),
+    (Context::Global, Action::CrossfadeLeft,     &["Ctrl+Left"]),
+    (Context::Global, Action::CrossfadeRight,    &["Ctrl+Right"]),
+    (Context::Global, Action::MasterGainUp,      &["Ctrl+Up"]),
+    (Context::Global, Action::MasterGainDown,    &["Ctrl+Down"]),
+
+    (Context::Keyboard, Action::OctaveDown, &["Left"]),
+    (Context::Keyboard, Action::OctaveUp,   &["Right"]),
+    (Context::Keyboard, Action::VolumeUp,   &["Up"]),
+    (Context::Keyboard, Action::VolumeDown, &["Down"]),
+    (Context::Keyboard, Action::ToggleLatch,  &["Space"]),
+    (Context::Keyboard, Action::ClearLatched, &["Backspace", "Delete"]),
+    (Context::Keyboard, Action::MidiToggleKeys, &["Ctrl+o"]),
+    (Context::Keyboard, Action::CycleChord,     &["Ctrl+k"]),
+    (Context::Keyboard, Action::ArpToggle,      &["Ctrl+a"]),
+    (Context::Keyboard, Action::ArpCycleRate,   &["Ctrl+e"]),
+    (Context::Keyboard, Action::ArpCycleMode,   &["Ctrl+d"]),
+    (Context::Keyboard, Action::MonoToggle,     &["Ctrl+n"]),
+    (Context::Keyboard, Action::SeqRecToggle,   &["Ctrl+t"]),
+    (Context::Keyboard, Action::CountInCycle,   &["Ctrl+p"]),
+
+    (Context::SynthSeq, Action::SeqCursorLeft,  &["Left"]),
+    (Context::SynthSeq, Action::SeqCursorRight, &["Right"]),
+    (Context::SynthSeq, Action::BpmUp,          &["Up"]),
+    (Context::SynthSeq, Action::BpmDown,        &["Down"]),
+    (Context::SynthSeq, Action::SeqDegreeUp,    &["Alt+Up"]),
+    (Context::SynthSeq, Action::SeqDegreeDown,  &["Alt+Down"]),
+    (Context::SynthSeq, Action::SeqStepOctaveUp,   &["Shift+Up"]),
+    (Context::SynthSeq, Action::SeqStepOctaveDown, &["Shift+Down"]),
+    (Context::SynthSeq, Action::SeqNudgeLeft,   &["Alt+Left"]),
+    (Context::SynthSeq, Action::SeqNudgeRight,  &["Alt+Right"]),
+    (Context::SynthSeq, Action::SeqQuantize,    &["Q"]),
+    (Context::SynthSeq, Action::QuantizeStrengthCycle, &["Alt+q"]),
+    (Context::SynthSeq, Action::SeqTogglePlay,  &["Space", "Enter"]),
+    (Context::SynthSeq, Action::SeqClearStep,   &["Backspace", "Delete"]),
+    (Context::SynthSeq, Action::SeqToggleMute,  &["\\"]),
+    (Context::SynthSeq, Action::SeqToggleSlide, &["~"]),
+    (Context::SynthSeq, Action::SeqToggleTie,   &["Ctrl+g"]),
+    (Context::SynthSeq, Action::SeqVelHumanizeCycle, &["Alt+v"]),
+    (Context::SynthSeq, Action::SeqDirectionCycle, &["Alt+d"]),
+    (Context::SynthSeq, Action::ToggleAudition, &["a"]),
+    (Context::SynthSeq, Action::TogglePaint,    &["Ctrl+p"]),
+    (Context::SynthSeq, Action::MidiToggleSeq1, &["Ctrl+o"]),
+    (Context::SynthSeq, Action::SeqFreeze,      &["Ctrl+f"]),
+    (Context::SynthSeq, Action::MonoToggle,     &["Ctrl+n"]),
+    (Context::SynthSeq, Action::SeqHoldStep,    &["k"]),
+    (Context::SynthSeq, Action::SeqClearLocks,  &["K"]),
+    (Context::SynthSeq, Action::SeqCutoffDown,  &["("]),
+    (Context::SynthSeq, Action::SeqCutoffUp,    &[")"]),
+    (Context::SynthSeq, Action::SeqCycleSteps,  &["]"]),
+    (Context::SynthSeq, Action::SynthPanLeft,   &["<"]),
+    (Context::SynthSeq, Action::SynthPanRight,  &[">"]),
+    (Context::SynthSeq, Action::VolumeUp,       &["="]),
+    (Context::SynthSeq, Action::VolumeDown,     &["-"]),
+    (Context::SynthSeq, Action::OctaveDown,     &["["]),
+    (Context::SynthSeq, Action::OctaveUp,       &["{"]),
+
+    (Context::SynthSeq2, Action::Seq2CursorLeft,  &["Left"]),
+    (Context::SynthSeq2, Action::Seq2CursorRight, &["Right"]),
+    (Context::SynthSeq2, Action::BpmUp,           &["Up"]),
+    (Context::SynthSeq2, Action::BpmDown,         &["Down"]),
+    (Context::SynthSeq2, Action::Seq2DegreeUp,    &["Alt+Up"]),
+    (Context::SynthSeq2, Action::Seq2DegreeDown,  &["Alt+Down"]),
+    (Context::SynthSeq2, Action::Seq2StepOctaveUp,   &["Shift+Up"]),
+    (Context::SynthSeq2, Action::Seq2StepOctaveDown, &["Shift+Down"]),
+    (Context::SynthSeq2, Action::Seq2NudgeLeft,   &["Alt+Left"]),
+    (Context::SynthSeq2, Action::Seq2NudgeRight,  &["Alt+Right"]),
+    (Context::SynthSeq2, Action::Seq2Quantize,    &["Q"]),
+    (Context::SynthSeq2, Action::QuantizeStrengthCycle, &["Alt+q"]),
+    (Context::SynthSeq2, Action::Seq2TogglePlay,  &["Space", "Enter"]),
+    (Context::SynthSeq2, Action::Seq2ClearStep,   &["Backspace", "Delete"]),
+    (Context::SynthSeq2, Action::Seq2ToggleMute,  &["\\"]),
+    (Context::SynthSeq2, Action::Seq2ToggleSlide, &["~"]),
+    (Context::SynthSeq2, Action::Seq2ToggleTie,   &["Ctrl+g"]),
+    (Context::SynthSeq2, Action::Seq2VelHumanizeCycle, &["Alt+v"]),
+    (Context::SynthSeq2, Action::Seq2DirectionCycle, &["Alt+d"]),
+    (Context::SynthSeq2, Action::ToggleAudition,  &["a"]),
+    (Context::SynthSeq2, Action::TogglePaint,     &["Ctrl+p"]),
+    (Context::SynthSeq2, Action::MidiToggleSeq2,  &["Ctrl+o"]),
+    (Context::SynthSeq2, Action::Seq2Freeze,      &["Ctrl+f"]),
+    (Context::SynthSeq2, Action::Mono2Toggle,     &["Ctrl+n"]),
+    (Context::SynthSeq2, Action::Seq2HoldStep,    &["k"]),
+    (Context::SynthSeq2, Action::Seq2ClearLocks,  &["K"]),
+    (Context::SynthSeq2, Action::Seq2CutoffDown,  &["("]),
+    (Context::SynthSeq2, Action::Seq2CutoffUp,    &[")"]),
+    (Context::SynthSeq2, Action::Seq2CycleSteps,  &["]"]),
+    (Context::SynthSeq2, Action::Synth2PanLeft,   &["<"]),
+    (Context::SynthSeq2, Action::Synth2PanRight,  &[">"]),
+    (Context::SynthSeq2, Action::CycleWave2,      &["F5"]),
+    (Context::SynthSeq2, Action::Synth2VolUp,     &["="]),
+    (Context::SynthSeq2, Action::Synth2VolDown,   &["-"]),
+    (Context::SynthSeq2, Action::OctaveDown,      &["["]),
+    (Context::SynthSeq2, Action::OctaveUp,        &["{"]),
+
+    (Context::Drums, Action::DrumTrackUp,    &["Up"]),
+    (Context::Drums, Action::DrumTrackDown,  &["Down"]),
+    (Context::Drums, Action::DrumStepLeft,   &["Left"]),
+    (Context::Drums, Action::DrumStepRight,  &["Right"]),
+    (Context::Drums, Action::DrumNudgeLeft,  &["Alt+Left"]),
+    (Context::Drums, Action::DrumNudgeRight, &["Alt+Right"]),
+    (Context::Drums, Action::DrumTogglePlay, &["Enter"]),
+    (Context::Drums, Action::DrumClearStep,  &["Backspace", "Delete"]),
+    (Context::Drums, Action::DrumToggleStep, &["Space"]),
+    (Context::Drums, Action::DrumCycleSteps, &["]"]),
+    (Context::Drums, Action::DrumToggleMute, &["\\"]),
+    (Context::Drums, Action::DrumAddTrack,      &["Insert", "A"]),
+    (Context::Drums, Action::DrumRemoveTrack,   &["D"]),
+    (Context::Drums, Action::DrumMoveTrackUp,   &["Shift+Up"]),
+    (Context::Drums, Action::DrumMoveTrackDown, &["Shift+Down"]),
+    (Context::Drums, Action::DrumCycleKind,     &["k"]),
+    (Context::Drums, Action::DrumRenamePrompt,  &["r"]),
+    (Context::Drums, Action::DrumSamplePrompt,  &["o"]),
+    (Context::Drums, Action::DrumFreeze,        &["Ctrl+f"]),
+    (Context::Drums, Action::DrumVolUp,      &["="]),
+    (Context::Drums, Action::DrumVolDown,    &["-"]),
+    (Context::Drums, Action::DrumBusVolUp,   &["+"]),
+    (Context::Drums, Action::DrumBusVolDown, &["_"]),
+    (Context::Drums, Action::DrumPanLeft,    &["("]),
+    (Context::Drums, Action::DrumPanRight,   &[")"]),
+    (Context::Drums, Action::DrumPitchDown,  &["{"]),
+    (Context::Drums, Action::DrumPitchUp,    &["}"]),
+    (Context::Drums, Action::DrumProbUp,     &["p"]),
+    (Context::Drums, Action::DrumProbDown,   &["["]),
+    (Context::Drums, Action::DrumEuclidean,  &["e"]),
+    (Context::Drums, Action::DrumCycleCond,  &["t"]),
+    (Context::Drums, Action::DrumFillToggle, &["f"]),
+    (Context::Drums, Action::DrumCycleRetrig,     &["R"]),
+    (Context::Drums, Action::DrumCycleRetrigHits, &["N"]),
+    (Context::Drums, Action::DrumSwingDown,  &["<"]),
+    (Context::Drums, Action::DrumSwingUp,    &[">"]),
+    (Context::Drums, Action::CycleGroove,    &["g"]),
+    (Context::Drums, Action::ToggleGrooveMelodic, &["G"]),
+
+    (Context::Effects, Action::EffectsSelUp,       &["Up"]),
+    (Context::Effects, Action::EffectsSelDown,     &["Down"]),
+    (Context::Effects, Action::EffectsParamLeft,   &["Left"]),
+    (Context::Effects, Action::EffectsParamRight,  &["Right"]),
+    (Context::Effects, Action::EffectsParamInc,    &["="]),
+    (Context::Effects, Action::EffectsParamDec,    &["-"]),
+    (Context::Effects, Action::EffectsOnOff,       &["Enter"]),
+    (Context::Effects, Action::EffectsRouteToggle, &["Space"]),
+    (Context::Effects, Action::DelayNotePrompt,    &["n"]),
+    (Context::Effects, Action::DelayTapPreset,     &["t"]),
+    (Context::Effects, Action::DelayTapsPrompt,    &["T"]),
+    (Context::Effects, Action::DelayReverseToggle, &["r"]),
+    (Context::Effects, Action::DelaySyncCycle,     &["s"]),
+    (Context::Effects, Action::DelayDuckDown,      &["{"]),
+    (Context::Effects, Action::DelayDuckUp,        &["}"]),
+    (Context::Effects, Action::DelayDuckReleaseDown, &["<"]),
+    (Context::Effects, Action::DelayDuckReleaseUp,   &[">"]),
+    (Context::Effects, Action::ReverbErDown,       &["["]),
+    (Context::Effects, Action::ReverbErUp,         &["]"]),
+    (Context::Effects, Action::ReverbShimmerDown,  &["("]),
+    (Context::Effects, Action::ReverbShimmerUp,    &[")"]),
+    (Context::Effects, Action::DistCycleMode,      &["m"]),
+    (Context::Effects, Action::DistAsymDown,       &[","]),
+    (Context::Effects, Action::DistAsymUp,         &["."]),
+    (Context::Harmonics, Action::HarmSelLeft,  &["Left"]),
+    (Context::Harmonics, Action::HarmSelRight, &["Right"]),
+    (Context::Harmonics, Action::HarmUp,       &["Up", "="]),
+    (Context::Harmonics, Action::HarmDown,     &["Down", "-"]),
+    (Context::Harmonics, Action::HarmClear,    &["x"]),
+    (Context::Harmonics, Action::HarmReset,    &["r"]),
+    (Context::Harmonics, Action::HarmUse1,     &["1"]),
+    (Context::Harmonics, Action::HarmUse2,     &["2"]),
+    (Context::Sample, Action::SamplePrompt,  &["o"]),
+    (Context::Sample, Action::GrainSelLeft,  &["Left"]),
+    (Context::Sample, Action::GrainSelRight, &["Right"]),
+    (Context::Sample, Action::GrainUp,       &["Up", "="]),
+    (Context::Sample, Action::GrainDown,     &["Down", "-"]),
+    (Context::Sample, Action::GrainUse1,     &["1"]),
+    (Context::Sample, Action::GrainUse2,     &["2"]),
+    (Context::Sample, Action::SamplerUse1,   &["3"]),
+    (Context::Sample, Action::SamplerUse2,   &["4"]),
+    // Acid edits sequencer 1; the piano keys still enter its notes.
+    (Context::Acid, Action::SeqCursorLeft,    &["Left"]),
+    (Context::Acid, Action::SeqCursorRight,   &["Right"]),
+    (Context::Acid, Action::SeqTogglePlay,    &["Space", "Enter"]),
+    (Context::Acid, Action::SeqClearStep,     &["Backspace", "Delete"]),
+    (Context::Acid, Action::AcidAccentToggle, &["a"]),
+    (Context::Acid, Action::SeqToggleSlide,   &["~"]),
+    (Context::Acid, Action::AcidParamLeft,    &["<"]),
+    (Context::Acid, Action::AcidParamRight,   &[">"]),
+    (Context::Acid, Action::AcidParamUp,      &["Up", "="]),
+    (Context::Acid, Action::AcidParamDown,    &["Down", "-"]),
+    (Context::Acid, Action::OctaveDown,       &["["]),
+    (Context::Acid, Action::OctaveUp,         &["{"]),
+    (Context::Acid, Action::AcidPreset,       &["Ctrl+a"]),
+    (Context::Acid, Action::AcidToggle,       &["Ctrl+x"]),
+
+    (Context::Envelope, Action::EnvSynthToggle, &["Up", "Down"]),
+    (Context::Envelope, Action::EnvParamLeft,   &["Left"]),
+    (Context::Envelope, Action::EnvParamRight,  &["Right"]),
+    (Context::Envelope, Action::EnvParamInc,    &["=", "+"]),
+    (Context::Envelope, Action::EnvParamDec,    &["-"]),
+
+    (Context::Modulation, Action::LfoSelToggle,  &["Up", "Down"]),
+    (Context::Modulation, Action::LfoParamLeft,  &["Left"]),
+    (Context::Modulation, Action::LfoParamRight, &["Right"]),
+    (Context::Modulation, Action::LfoParamInc,   &["=", "+"]),
+    (Context::Modulation, Action::LfoParamDec,   &["-"]),
+
+    (Context::Inserts, Action::InsertSynthToggle, &["Up", "Down"]),
+    (Context::Inserts, Action::InsertSelLeft,     &["Left"]),
+    (Context::Inserts, Action::InsertSelRight,    &["Right"]),
+    (Context::Inserts, Action::InsertCycleKind,   &["k"]),
+    (Context::Inserts, Action::InsertAdd,         &["Insert", "A"]),
+    (Context::Inserts, Action::InsertRemove,      &["D", "Delete", "Backspace"]),
+    (Context::Inserts, Action::InsertMoveLeft,    &["Shift+Left"]),
+    (Context::Inserts, Action::InsertMoveRight,   &["Shift+Right"]),
+];
+
+/// Chromatic layout of the two piano rows, C upwards; a space leaves a gap.
+const DEFAULT_PIANO_LOWER: &str = "zsxdcvgbhnjm,l.;/";
+const DEFAULT_PIANO_UPPER: &str = "q2w3er5t6y7ui9o0p";
+/// Keys that preview drum tracks 1, 2, … in Drums focus (tracks past the
+/// end of the list have no preview key).
+const DEFAULT_DRUM_KEYS: &str = "zxcvbnm,";
+
+/// A key plus the modifiers that matter for matching: Ctrl / Alt, and Shift
+/// on non-character keys (on characters it is already in the character).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Key {
+    code: KeyCode,
+    mods: KeyModifiers,
+}
+
+impl Key {
+    fn new(code: KeyCode, mods: KeyModifiers) -> Self {
+        let keep = match code {
+            KeyCode::Char(_) | KeyCode::BackTab => KeyModifiers::CONTROL | KeyModifiers::ALT,
+            _ => KeyModifiers::CONTROL | KeyModifiers::ALT | KeyModifiers::SHIFT,
+        };
+        Self { code, mods: mods & keep }
+    }
+
+    fn of(ev: &KeyEvent) -> Self {
+        Self::new(ev.code, ev.modifiers)
+    }
+
+    /// Parse `"Ctrl+s"`, `"F5"`, `"PageUp"`, `"Space"`, `"="` …
+    fn parse(s: &str) -> Result<Self> {
+        let mut mods = KeyModifiers::NONE;
+        let mut rest = s;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            if lower.starts_with("ctrl+") && rest.len() > 5 {
+                mods |= KeyModifiers::CONTROL; rest = &rest[5..];
+            } else if lower.starts_with("alt+") && rest.len() > 4 {
+                mods |= KeyModifiers::ALT; rest = &rest[4..];
+            } else if lower.starts_with("shift+") && rest.len() > 6 {
+                mods |= KeyModifiers::SHIFT; rest = &rest[6..];
+            } else {
+                break;
+            }
+        }
+        let code = match rest.to_ascii_lowercase().as_str() {
+            "space"     => KeyCode::Char(' '),
+            "tab"       => KeyCode::Tab,
+            "backtab"   => KeyCode::BackTab,
+            "enter"     => KeyCode::Enter,
+            "esc"       => KeyCode::Esc,
+            "backspace" => KeyCode::Backspace,
+            "delete"    => KeyCode::Delete,
+            "insert"    => KeyCode::Insert,
+            "home"      => KeyCode::Home,
+            "end"       => KeyCode::End,
+            "pageup"    => KeyCode::PageUp,
+            "pagedown"  => KeyCode::PageDown,
+            "up"        => KeyCode::Up,
+            "down"      => KeyCode::Down,
+            "left"      => KeyCode::Left,
+            "right"     => KeyCode::Right,
+            f if f.len() > 1 && f.starts_with('f') && f[1..].parse::<u8>().is_ok() => {
+                KeyCode::F(f[1..].parse().unwrap())
+            }
+            _ => {
+                let mut chars = rest.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(_), None) if mods.contains(KeyModifiers::SHIFT) => {
+                        bail!("\"{}\": write the shifted character instead of Shift+", s)
+                    }
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => bail!("unknown key \"{}\"", s),
+                }
+            }
+        };
+        Ok(Self::new(code, mods))
+    }
+}
+
+/// Shape of `keymap.toml`: `[section]` tables of `action = "Key"` or
+/// `action = ["Key", "Key"]`, plus a `[piano]` table of key strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum KeyList { One(String), Many(Vec<String>) }
+
+/// Key → action dispatch table plus the piano/drum-pad key layout.
+pub struct Keymap {
+    bindings: HashMap<(Context, Key), Action>,
+    /// Piano key → semitone offset above C of the base octave.
+    piano: HashMap<char, i32>,
+    drum_keys: Vec<char>,
+}
+
+impl Keymap {
+    pub fn defaults() -> Self {
+        let mut map = Self { bindings: HashMap::new(), piano: HashMap::new(), drum_keys: Vec::new() };
+        for &(ctx, action, keys) in DEFAULTS {
+            for k in keys {
+                // Defaults are compile-time constants, so they always parse.
+                map.bindings.insert((ctx, Key::parse(k).unwrap()), action);
+            }
+        }
+        map.set_piano(DEFAULT_PIANO_LOWER, DEFAULT_PIANO_UPPER);
+        map.drum_keys = DEFAULT_DRUM_KEYS.chars().collect();
+        map
+    }
+
+    /// Defaults with the overrides in `path` applied.  Any action listed in a
+    /// section replaces all of that action's default keys in that section.
+    pub fn load(path: &str) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path))?;
+        let file: HashMap<String, HashMap<String, KeyList>> =
+            toml::from_str(&text).with_context(|| format!("Invalid keymap {}", path))?;
+
+        let mut map = Self::defaults();
+        for (section, entries) in file {
+            if section == "piano" {
+                map.apply_piano(&entries).with_context(|| format!("{}: [piano]", path))?;
+                continue;
+            }
+            let ctx = Context::ALL.iter().copied().find(|c| c.section() == section)
+                .with_context(|| format!("{}: unknown section [{}]", path, section))?;
+            for (name, keys) in entries {
+                let action = Action::from_name(&name)
+                    .with_context(|| format!("{}: [{}] unknown action \"{}\"", path, section, name))?;
+                let keys = match keys { KeyList::One(k) => vec![k], KeyList::Many(v) => v };
+                map.bindings.retain(|&(c, _), a| !(c == ctx && *a == action));
+                for k in keys {
+                    let key = Key::parse(&k).with_context(|| format!("{}: [{}] {}", path, section, name))?;
+                    map.bindings.insert((ctx, key), action);
+                }
+            }
+        }
+        Ok(map)
+    }
+
+    fn apply_piano(&mut self, entries: &HashMap<String, KeyList>) -> Result<()> {
+        let get = |name: &str| -> Result<Option<String>> {
+            match entries.get(name) {
+                None                      => Ok(None),
+                Some(KeyList::One(s))     => Ok(Some(s.clone())),
+                Some(KeyList::Many(_))    => bail!("{} must be a single string", name),
+            }
+        };
+        if let Some(name) = entries.keys().find(|k| !matches!(k.as_str(), "lower" | "upper" | "drums")) {
+            bail!("unknown key \"{}\" (expected lower, upper, drums)", name);
+        }
+        let lower = get("lower")?.unwrap_or_else(|| DEFAULT_PIANO_LOWER.to_string());
+        let upper = get("upper")?.unwrap_or_else(|| DEFAULT_PIANO_UPPER.to_string());
+        self.set_piano(&lower, &upper);
+        if let Some(d) = get("drums")? { self.drum_keys = d.chars().collect(); }
+        Ok(())
+    }
+
+    fn set_piano(&mut self, lower: &str, upper: &str) {
+        self.piano.clear();
+        for (row, octave) in [(lower, 0), (upper, 1)] {
+            for (st, c) in row.chars().enumerate() {
+                if c != ' ' { self.piano.insert(c, octave * 12 + st as i32); }
+            }
+        }
+    }
+
+    /// Action bound to `ev` in the focused panel, else a global one.
+    pub fn lookup(&self, mode: &AppMode, ev: &KeyEvent) -> Option<Action> {
+        let key = Key::of(ev);
+        self.bindings.get(&(Context::of(mode), key))
+            .or_else(|| self.bindings.get(&(Context::Global, key)))
+            .copied()
+    }
+
+    /// MIDI note for a piano key at `base_octave`, if the key is on the piano.
+    pub fn note_for(&self, key: char, base_octave: i32) -> Option<u8> {
+        let st = *self.piano.get(&key)?;
+        let note = base_octave * 12 + 12 + st;
+        if (0..=127).contains(&note) { Some(note as u8) } else { None }
+    }
+
+    /// Drum track previewed by `key`, if any.
+    pub fn drum_for(&self, key: char) -> Option<usize> {
+        self.drum_keys.iter().position(|&c| c == key)
+    }
+
+    /// Preview key of drum track `track`, if it has one.
+    pub fn drum_key(&self, track: usize) -> Option<char> {
+        self.drum_keys.get(track).copied()
+    }
+
+    /// The built-in keymap as a `keymap.toml` to start editing from.
+    pub fn default_toml() -> String {
+        let mut out = String::from(
+            "# tuibeat keymap.  Keys: a single character, Space, Tab, BackTab, Enter, Esc,\n\
+             # Backspace, Delete, Insert, Home, End, PageUp, PageDown, Up, Down, Left, Right,\n\
+             # F1-F12, optionally prefixed with Ctrl+, Alt+ and (not on characters) Shift+.\n\
+             # Focus sections win over [global].  Listing an action replaces its default\n\
+             # keys in that section.\n");
+        for ctx in Context::ALL {
+            out.push_str(&format!("\n[{}]\n", ctx.section()));
+            for &(c, action, keys) in DEFAULTS {
+                if c != ctx { continue; }
+                let keys: Vec<String> = keys.iter().map(|k| format!("{:?}", k)).collect();
+                let value = if keys.len() == 1 { keys[0].clone() } else { format!("[{}]", keys.join(", ")) };
+                out.push_str(&format!("{} = {}\n", action.name(), value));
+            }
+        }
+        out.push_str(&format!(
+            "\n[piano]\n# Chromatic from C; a space leaves a gap.\nlower = {:?}\nupper = {:?}\n\
+             # Keys previewing drum tracks 1, 2, ... in Drums focus.\ndrums = {:?}\n",
+            DEFAULT_PIANO_LOWER, DEFAULT_PIANO_UPPER, DEFAULT_DRUM_KEYS));
+        out
+    }
+}
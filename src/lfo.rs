@@ -0,0 +1,158 @@
+use std::f32::consts::TAU;
+
+/// How far a full-depth LFO moves a filter cutoff either way, in octaves.
+const CUTOFF_OCTAVES: f32 = 2.0;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LfoShape { Sine, Triangle, Square }
+
+impl LfoShape {
+    pub const ALL: [LfoShape; 3] = [Self::Sine, Self::Triangle, Self::Square];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Sine     => "Sine",
+            Self::Triangle => "Triangle",
+            Self::Square   => "Square",
+        }
+    }
+}
+
+/// Length of one LFO cycle as a note value, slowest first.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LfoRate { FourBars, TwoBars, Bar, Half, Quarter, QuarterTriplet, Eighth, EighthTriplet, Sixteenth }
+
+impl LfoRate {
+    pub const ALL: [LfoRate; 9] = [
+        Self::FourBars, Self::TwoBars, Self::Bar, Self::Half, Self::Quarter,
+        Self::QuarterTriplet, Self::Eighth, Self::EighthTriplet, Self::Sixteenth,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::FourBars       => "4 bars",
+            Self::TwoBars        => "2 bars",
+            Self::Bar            => "1 bar",
+            Self::Half           => "1/2",
+            Self::Quarter        => "1/4",
+            Self::QuarterTriplet => "1/4T",
+            Self::Eighth         => "1/8",
+            Self::EighthTriplet  => "1/8T",
+            Self::Sixteenth      => "1/16",
+        }
+    }
+
+    /// Length in 16th-note steps of the master clock.
+    pub fn steps(self) -> f64 {
+        match self {
+            Self::FourBars       => 64.0,
+            Self::TwoBars        => 32.0,
+            Self::Bar            => 16.0,
+            Self::Half           => 8.0,
+            Self::Quarter        => 4.0,
+            Self::QuarterTriplet => 8.0 / 3.0,
+            Self::Eighth         => 2.0,
+            Self::EighthTriplet  => 4.0 / 3.0,
+            Self::Sixteenth      => 1.0,
+        }
+    }
+
+    /// The division nearest `hz` at `bpm` (on a log scale), for files that
+    /// saved the rate in Hz.
+    pub fn nearest_hz(hz: f32, bpm: f32) -> Self {
+        let steps = bpm as f64 / 15.0 / hz.max(1e-3) as f64;
+        let dist = |r: &LfoRate| (r.steps() / steps).ln().abs();
+        Self::ALL.into_iter().min_by(|a, b| dist(a).total_cmp(&dist(b))).unwrap_or(Self::Quarter)
+    }
+}
+
+/// What an LFO moves.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LfoTarget { Cutoff1, Cutoff2, Volume1, Volume2 }
+
+impl LfoTarget {
+    pub const ALL: [LfoTarget; 4] = [Self::Cutoff1, Self::Cutoff2, Self::Volume1, Self::Volume2];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Cutoff1 => "S1 Cutoff",
+            Self::Cutoff2 => "S2 Cutoff",
+            Self::Volume1 => "S1 Volume",
+            Self::Volume2 => "S2 Volume",
+        }
+    }
+
+    /// Factor on the target for an LFO at `x` (-1–1) and `depth`:
+    /// exponential on a cutoff, so the sweep sounds even either side of
+    /// it; on a volume a dip below the set level (to `1 - depth` at the
+    /// trough), never above it.
+    pub fn factor(self, x: f32, depth: f32) -> f32 {
+        match self {
+            Self::Cutoff1 | Self::Cutoff2 => (x * depth * CUTOFF_OCTAVES).exp2(),
+            Self::Volume1 | Self::Volume2 => 1.0 - depth * (1.0 - x) * 0.5,
+        }
+    }
+}
+
+/// A low-frequency oscillator locked to the master clock: its phase is
+/// the playhead (`Synth::step_pos`) over the cycle length, so it starts
+/// each cycle on the division's grid, lines up with the patterns and
+/// follows a locate or an external clock.  `Synth` multiplies its target
+/// by the factor for one sample only, so the set value stays as the user
+/// left it.
+#[derive(Clone, Copy, Debug)]
+pub struct Lfo {
+    pub shape:  LfoShape,
+    /// One cycle per this note value.
+    pub rate:   LfoRate,
+    /// 0 (off) to 1.
+    pub depth:  f32,
+    pub target: LfoTarget,
+}
+
+impl Lfo {
+    pub fn new(target: LfoTarget) -> Self {
+        Self { shape: LfoShape::Sine, rate: LfoRate::Quarter, depth: 0.0, target }
+    }
+
+    /// The factor on the target with the playhead at `step_pos`.  Sine and
+    /// triangle start mid-swing heading up, in step with the square's high half.
+    pub fn factor_at(&self, step_pos: f64) -> f32 {
+        let p = (step_pos / self.rate.steps()).rem_euclid(1.0) as f32;
+        let x = match self.shape {
+            LfoShape::Sine     => (p * TAU).sin(),
+            LfoShape::Triangle => 1.0 - 4.0 * ((p + 0.25).fract() - 0.5).abs(),
+            LfoShape::Square   => if p < 0.5 { 1.0 } else { -1.0 },
+        };
+        self.target.factor(x, self.depth)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn phase_comes_from_the_playhead() {
+        let mut l = Lfo::new(LfoTarget::Volume1);
+        (l.shape, l.rate, l.depth) = (LfoShape::Square, LfoRate::Bar, 1.0);
+        // High for the first half of each bar, low for the second, also
+        // before zero (a count-in) and far into a song.
+        for bar in [-1.0, 0.0, 1.0, 1000.0] {
+            assert_eq!(l.factor_at(bar * 16.0), 1.0);
+            assert_eq!(l.factor_at(bar * 16.0 + 7.9), 1.0);
+            assert_eq!(l.factor_at(bar * 16.0 + 8.0), 0.0);
+        }
+        l.shape = LfoShape::Sine;
+        assert_eq!(l.factor_at(3.0), l.factor_at(3.0 + 16.0 * 7.0));
+    }
+
+    #[test]
+    fn hz_rates_map_to_the_nearest_division() {
+        // At 120 BPM a 16th lasts 1/8 s.
+        assert_eq!(LfoRate::nearest_hz(2.0, 120.0), LfoRate::Quarter);
+        assert_eq!(LfoRate::nearest_hz(0.5, 120.0), LfoRate::Bar);
+        assert_eq!(LfoRate::nearest_hz(20.0, 120.0), LfoRate::Sixteenth);
+        assert_eq!(LfoRate::nearest_hz(0.05, 120.0), LfoRate::FourBars);
+    }
+}
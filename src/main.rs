@@ -1,20 +1,41 @@
+mod acid;
 mod app;
+mod arp;
+mod arrange;
 mod audio;
+mod automation;
+mod config;
 mod drums;
 mod effects;
+mod fft;
+mod freeze;
+mod granular;
+mod keymap;
+mod lfo;
+mod metronome;
+mod midi;
+mod osc;
+mod remote;
+mod sample;
 mod save;
 mod scale;
 mod sequencer;
+mod spectrogram;
 mod synth;
 mod ui;
+mod wav;
+mod wavetable;
 
 use anyhow::Result;
 use app::{App, AppMode, InputMode};
 use audio::AudioEngine;
+use config::Config;
+use keymap::Keymap;
+use midi::{CcMap, ExtClock, MidiClockIn, MidiNoteIn, MidiOut};
 use crossterm::{
     event::{
         self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEventKind,
-        KeyboardEnhancementFlags, KeyModifiers, PopKeyboardEnhancementFlags,
+        KeyboardEnhancementFlags, PopKeyboardEnhancementFlags,
         PushKeyboardEnhancementFlags,
     },
     execute,
@@ -28,6 +49,9 @@ use std::{io, sync::{Arc, Mutex}, time::Duration};
 use synth::Synth;
 
 fn main() -> Result<()> {
+    let cfg = Config::from_args()?;
+    let keymap = cfg.keymap()?;
+    let cc_map = cfg.cc_map()?;
     enable_raw_mode()?;
     let mut stdout = io::stdout();
 
@@ -43,7 +67,7 @@ fn main() -> Result<()> {
 
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
-    let result = run(&mut terminal, enhanced);
+    let result = run(&mut terminal, enhanced, &cfg, keymap, cc_map);
 
     disable_raw_mode()?;
     if enhanced {
@@ -57,14 +81,69 @@ fn main() -> Result<()> {
     Ok(())
 }
 
-fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, enhanced: bool) -> Result<()> {
-    let synth  = Arc::new(Mutex::new(Synth::new(44100.0)));
-    let _audio = AudioEngine::new(Arc::clone(&synth))?;
-    let mut app = App::new(Arc::clone(&synth));
+fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, enhanced: bool, cfg: &Config,
+       keymap: Keymap, cc_map: CcMap) -> Result<()> {
+    let synth = Arc::new(Mutex::new(Synth::new(cfg.sample_rate.unwrap_or(44_100) as f32)));
+    let (audio, audio_err) = AudioEngine::start(Arc::clone(&synth), cfg.no_audio, cfg.audio_device.as_deref(), cfg.sample_rate);
+    let mut app = App::new(Arc::clone(&synth), audio);
+    app.fallback_release = Duration::from_millis(cfg.release_ms);
+    app.keymap = keymap;
+    app.cc_map = cc_map;
+    if let Some(e) = audio_err {
+        app.status_msg = format!("No audio ({}) — running silent", e);
+    }
+    if let Some(port) = &cfg.midi_out {
+        match MidiOut::open(port, cfg.midi_channel) {
+            Ok(out) => synth.lock().unwrap().midi_out = Some(out),
+            Err(e)  => app.status_msg = format!("No MIDI out ({:#})", e),
+        }
+    }
+    if let Some(port) = &cfg.midi_in {
+        match MidiNoteIn::open(port) {
+            Ok(input) => {
+                app.status_msg = format!("MIDI input from {}", input.source());
+                input.spawn(Arc::clone(&synth));
+            }
+            Err(e) => app.status_msg = format!("No MIDI in ({:#})", e),
+        }
+    }
+    if let Some(port) = &cfg.midi_clock_in {
+        match MidiClockIn::open(port) {
+            Ok(input) => {
+                app.status_msg = format!("Following MIDI clock from {}", input.source());
+                synth.lock().unwrap().ext_clock = Some(ExtClock::new());
+                input.spawn(Arc::clone(&synth));
+            }
+            Err(e) => app.status_msg = format!("No MIDI clock in ({:#}) — using internal BPM", e),
+        }
+    }
+    let osc = match cfg.osc_port.map(osc::spawn) {
+        Some(Ok(rx)) => Some(rx),
+        Some(Err(e)) => { app.status_msg = format!("No OSC ({:#})", e); None }
+        None         => None,
+    };
+    let remote = match cfg.remote_port.map(remote::spawn) {
+        Some(Ok(rx)) => Some(rx),
+        Some(Err(e)) => { app.status_msg = format!("No remote control ({:#})", e); None }
+        None         => None,
+    };
 
     loop {
         if !enhanced { app.tick_fallback_release(); }
+        app.tick_audition();
+        app.tick_export();
+        app.tick_peak_hold();
+        app.tick_spectrogram();
         app.refresh_active_notes();
+        for cmd in osc.iter().flat_map(|rx| rx.try_iter()) {
+            if let Err(e) = cmd.and_then(|c| app.apply(c).map_err(|e| format!("{:#}", e))) {
+                app.status_msg = format!("OSC: {}", e);
+            }
+        }
+        for p in remote.iter().flat_map(|rx| rx.try_iter()) {
+            let reply = remote::respond(&mut app, p.request);
+            let _ = p.reply.send(reply.to_string());
+        }
         terminal.draw(|f| ui::draw(f, &app, enhanced))?;
 
         if event::poll(Duration::from_millis(16))? {
@@ -72,70 +151,22 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, enhanced: bool) ->
                 Event::Key(key) => {
                     // ── Key release (enhanced mode only) ──────────────────
                     if key.kind == KeyEventKind::Release {
-                        if app.mode == AppMode::Play {
-                            if let KeyCode::Char(c) = key.code { app.key_release(c); }
+                        if let KeyCode::Char(c) = key.code {
+                            match app.mode {
+                                AppMode::Play => app.key_release(c),
+                                AppMode::SynthSeq | AppMode::SynthSeq2 => app.paint_release(c),
+                                _ => {}
+                            }
                         }
                         continue;
                     }
 
                     // ── Key repeat ────────────────────────────────────────
                     if key.kind == KeyEventKind::Repeat {
-                        match key.code {
-                            // Global BPM + scale
-                            KeyCode::PageUp   => app.bpm_up(),
-                            KeyCode::PageDown => app.bpm_down(),
-                            KeyCode::F(6)     => app.cycle_scale(),
-                            KeyCode::F(7)     => app.cycle_scale_root(),
-
-                            // Effects focus: navigation + param adjust (no Space repeat)
-                            KeyCode::Up    if app.mode == AppMode::Effects => app.effects_sel_up(),
-                            KeyCode::Down  if app.mode == AppMode::Effects => app.effects_sel_down(),
-                            KeyCode::Left  if app.mode == AppMode::Effects => app.effects_param_left(),
-                            KeyCode::Right if app.mode == AppMode::Effects => app.effects_param_right(),
-                            KeyCode::Char('=') if app.mode == AppMode::Effects => app.effects_param_inc(),
-                            KeyCode::Char('-') if app.mode == AppMode::Effects => app.effects_param_dec(),
-
-                            // Drums focus: navigation + drum vol repeat
-                            KeyCode::Up    if app.mode == AppMode::Drums => app.drum_track_up(),
-                            KeyCode::Down  if app.mode == AppMode::Drums => app.drum_track_down(),
-                            KeyCode::Left  if app.mode == AppMode::Drums => app.drum_step_left(),
-                            KeyCode::Right if app.mode == AppMode::Drums => app.drum_step_right(),
-                            KeyCode::Char('=') if app.mode == AppMode::Drums => app.drum_vol_up(),
-                            KeyCode::Char('-') if app.mode == AppMode::Drums => app.drum_vol_down(),
-                            KeyCode::Char('p') if app.mode == AppMode::Drums => app.drum_prob_up(),
-                            KeyCode::Char('[') if app.mode == AppMode::Drums => app.drum_prob_down(),
-                            KeyCode::Char('<') if app.mode == AppMode::Drums => app.drum_swing_down(),
-                            KeyCode::Char('>') if app.mode == AppMode::Drums => app.drum_swing_up(),
-
-                            // SynthSeq2 focus: cursor + BPM + volume + octave
-                            KeyCode::Enter if app.mode == AppMode::SynthSeq2 => app.seq2_toggle_play(),
-                            KeyCode::Left  if app.mode == AppMode::SynthSeq2 => app.seq2_cursor_left(),
-                            KeyCode::Right if app.mode == AppMode::SynthSeq2 => app.seq2_cursor_right(),
-                            KeyCode::Up    if app.mode == AppMode::SynthSeq2 => app.bpm_up(),
-                            KeyCode::Down  if app.mode == AppMode::SynthSeq2 => app.bpm_down(),
-                            KeyCode::Char('=') if app.mode == AppMode::SynthSeq2 => app.synth2_vol_up(),
-                            KeyCode::Char('-') if app.mode == AppMode::SynthSeq2 => app.synth2_vol_down(),
-                            KeyCode::Char('[') if app.mode == AppMode::SynthSeq2 => app.octave_down(),
-                            KeyCode::Char('{') if app.mode == AppMode::SynthSeq2 => app.octave_up(),
-
-                            // SynthSeq focus: cursor + BPM + volume + octave
-                            KeyCode::Enter if app.mode == AppMode::SynthSeq => app.seq_toggle_play(),
-                            KeyCode::Left  if app.mode == AppMode::SynthSeq => app.seq_cursor_left(),
-                            KeyCode::Right if app.mode == AppMode::SynthSeq => app.seq_cursor_right(),
-                            KeyCode::Up    if app.mode == AppMode::SynthSeq => app.bpm_up(),
-                            KeyCode::Down  if app.mode == AppMode::SynthSeq => app.bpm_down(),
-                            KeyCode::Char('=') if app.mode == AppMode::SynthSeq => app.volume_up(),
-                            KeyCode::Char('-') if app.mode == AppMode::SynthSeq => app.volume_down(),
-                            KeyCode::Char('[') if app.mode == AppMode::SynthSeq => app.octave_down(),
-                            KeyCode::Char('{') if app.mode == AppMode::SynthSeq => app.octave_up(),
-
-                            // Keyboard focus: octave + volume
-                            KeyCode::Left  => app.octave_down(),
-                            KeyCode::Right => app.octave_up(),
-                            KeyCode::Up    => app.volume_up(),
-                            KeyCode::Down  => app.volume_down(),
-
-                            _ => {
+                        match app.keymap.lookup(&app.mode, &key) {
+                            Some(action) if action.repeats() => app.perform(action),
+                            Some(_) => {}
+                            None => {
                                 if let KeyCode::Char(c) = key.code {
                                     if app.mode == AppMode::Play { app.key_press_fallback(c); }
                                 }
@@ -144,7 +175,32 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, enhanced: bool) ->
                         continue;
                     }
 
-                    // ── Input mode: intercept all keys for file-path prompt ──
+                    // ── Input mode: intercept all keys for the open prompt ──
+                    if app.input_mode == InputMode::ScaleEdit {
+                        match key.code {
+                            KeyCode::Left      => app.scale_edit_move(-1),
+                            KeyCode::Right     => app.scale_edit_move(1),
+                            KeyCode::Char(' ') => app.scale_edit_toggle(),
+                            KeyCode::Enter     => app.commit_input(),
+                            KeyCode::Esc => {
+                                app.input_mode = InputMode::None;
+                                app.status_msg = "Cancelled".to_string();
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
+                    if let InputMode::ConfirmClear(_) = app.input_mode {
+                        match key.code {
+                            KeyCode::Enter | KeyCode::Char('y') => app.commit_input(),
+                            KeyCode::Esc | KeyCode::Char('n') => {
+                                app.input_mode = InputMode::None;
+                                app.status_msg = "Cancelled".to_string();
+                            }
+                            _ => {}
+                        }
+                        continue;
+                    }
                     if app.input_mode != InputMode::None {
                         match key.code {
                             KeyCode::Esc => {
@@ -161,105 +217,20 @@ fn run(terminal: &mut Terminal<CrosstermBackend<io::Stdout>>, enhanced: bool) ->
                     }
 
                     // ── Key press ─────────────────────────────────────────
-                    match key.code {
-                        // Global quit
-                        KeyCode::Esc => break,
-                        KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => break,
-                        // Save / Load
-                        KeyCode::Char('s') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                            app.input_mode = InputMode::Save;
-                            app.input_buf  = "rusttuisynth.json".to_string();
-                        }
-                        KeyCode::Char('l') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                            app.input_mode = InputMode::Load;
-                            app.input_buf  = "rusttuisynth.json".to_string();
-                        }
-
-                        // Global: cycle focus, waveform, drum play, BPM, scale
-                        KeyCode::Tab          => app.toggle_mode(),
-                        KeyCode::F(2)         => app.toggle_mode(),
-                        KeyCode::F(1)         => app.cycle_wave(),
-                        KeyCode::F(3)         => app.drum_toggle_play(),
-                        KeyCode::F(6)         => app.cycle_scale(),
-                        KeyCode::F(7)         => app.cycle_scale_root(),
-                        KeyCode::PageUp       => app.bpm_up(),
-                        KeyCode::PageDown     => app.bpm_down(),
-
-                        // ── Effects focus ─────────────────────────────────
-                        KeyCode::Up    if app.mode == AppMode::Effects => app.effects_sel_up(),
-                        KeyCode::Down  if app.mode == AppMode::Effects => app.effects_sel_down(),
-                        KeyCode::Left  if app.mode == AppMode::Effects => app.effects_param_left(),
-                        KeyCode::Right if app.mode == AppMode::Effects => app.effects_param_right(),
-                        KeyCode::Char('=') if app.mode == AppMode::Effects => app.effects_param_inc(),
-                        KeyCode::Char('-') if app.mode == AppMode::Effects => app.effects_param_dec(),
-                        KeyCode::Enter     if app.mode == AppMode::Effects => app.effects_on_off(),
-                        KeyCode::Char(' ') if app.mode == AppMode::Effects => app.effects_route_toggle(),
-
-                        // ── Drums focus ───────────────────────────────────
-                        KeyCode::Up    if app.mode == AppMode::Drums => app.drum_track_up(),
-                        KeyCode::Down  if app.mode == AppMode::Drums => app.drum_track_down(),
-                        KeyCode::Left  if app.mode == AppMode::Drums => app.drum_step_left(),
-                        KeyCode::Right if app.mode == AppMode::Drums => app.drum_step_right(),
-                        KeyCode::Enter if app.mode == AppMode::Drums => app.drum_toggle_play(),
-                        KeyCode::Backspace | KeyCode::Delete if app.mode == AppMode::Drums => app.drum_clear_step(),
-                        KeyCode::Char(' ')  if app.mode == AppMode::Drums => app.drum_toggle_step(),
-                        KeyCode::Char(']')  if app.mode == AppMode::Drums => app.drum_cycle_steps(),
-                        KeyCode::Char('\\') if app.mode == AppMode::Drums => app.drum_toggle_mute(),
-                        KeyCode::Char('=')  if app.mode == AppMode::Drums => app.drum_vol_up(),
-                        KeyCode::Char('-')  if app.mode == AppMode::Drums => app.drum_vol_down(),
-                        KeyCode::Char('p')  if app.mode == AppMode::Drums => app.drum_prob_up(),
-                        KeyCode::Char('[')  if app.mode == AppMode::Drums => app.drum_prob_down(),
-                        KeyCode::Char('e')  if app.mode == AppMode::Drums => app.drum_euclidean(),
-                        KeyCode::Char('<')  if app.mode == AppMode::Drums => app.drum_swing_down(),
-                        KeyCode::Char('>')  if app.mode == AppMode::Drums => app.drum_swing_up(),
-
-                        // ── SynthSeq2 focus ───────────────────────────────
-                        KeyCode::Left  if app.mode == AppMode::SynthSeq2 => app.seq2_cursor_left(),
-                        KeyCode::Right if app.mode == AppMode::SynthSeq2 => app.seq2_cursor_right(),
-                        KeyCode::Up    if app.mode == AppMode::SynthSeq2 => app.bpm_up(),
-                        KeyCode::Down  if app.mode == AppMode::SynthSeq2 => app.bpm_down(),
-                        KeyCode::Char(' ') if app.mode == AppMode::SynthSeq2 => app.seq2_toggle_play(),
-                        KeyCode::Enter     if app.mode == AppMode::SynthSeq2 => app.seq2_toggle_play(),
-                        KeyCode::Backspace | KeyCode::Delete if app.mode == AppMode::SynthSeq2 => app.seq2_clear_step(),
-                        KeyCode::Char(']') if app.mode == AppMode::SynthSeq2 => app.seq2_cycle_steps(),
-                        KeyCode::F(5)      if app.mode == AppMode::SynthSeq2 => app.cycle_wave2(),
-                        KeyCode::Char('=') if app.mode == AppMode::SynthSeq2 => app.synth2_vol_up(),
-                        KeyCode::Char('-') if app.mode == AppMode::SynthSeq2 => app.synth2_vol_down(),
-                        KeyCode::Char('[') if app.mode == AppMode::SynthSeq2 => app.octave_down(),
-                        KeyCode::Char('{') if app.mode == AppMode::SynthSeq2 => app.octave_up(),
-
-                        // ── SynthSeq focus ────────────────────────────────
-                        KeyCode::Left  if app.mode == AppMode::SynthSeq => app.seq_cursor_left(),
-                        KeyCode::Right if app.mode == AppMode::SynthSeq => app.seq_cursor_right(),
-                        KeyCode::Up    if app.mode == AppMode::SynthSeq => app.bpm_up(),
-                        KeyCode::Down  if app.mode == AppMode::SynthSeq => app.bpm_down(),
-                        KeyCode::Char(' ') if app.mode == AppMode::SynthSeq => app.seq_toggle_play(),
-                        KeyCode::Enter     if app.mode == AppMode::SynthSeq => app.seq_toggle_play(),
-                        KeyCode::Backspace | KeyCode::Delete if app.mode == AppMode::SynthSeq => app.seq_clear_step(),
-                        KeyCode::Char(']') if app.mode == AppMode::SynthSeq => app.seq_cycle_steps(),
-                        KeyCode::Char('=') if app.mode == AppMode::SynthSeq => app.volume_up(),
-                        KeyCode::Char('-') if app.mode == AppMode::SynthSeq => app.volume_down(),
-                        KeyCode::Char('[') if app.mode == AppMode::SynthSeq => app.octave_down(),
-                        KeyCode::Char('{') if app.mode == AppMode::SynthSeq => app.octave_up(),
-
-                        // ── Keyboard focus ────────────────────────────────
-                        KeyCode::Left  => app.octave_down(),
-                        KeyCode::Right => app.octave_up(),
-                        KeyCode::Up    => app.volume_up(),
-                        KeyCode::Down  => app.volume_down(),
-
-                        // ── Piano / drum preview / sequencer note keys ────
-                        KeyCode::Char(c) => match app.mode {
+                    if let Some(action) = app.keymap.lookup(&app.mode, &key) {
+                        app.perform(action);
+                    } else if let KeyCode::Char(c) = key.code {
+                        // Piano / drum preview / sequencer note keys
+                        match app.mode {
                             AppMode::Play      => {
                                 if enhanced { app.key_press(c); } else { app.key_press_fallback(c); }
                             }
-                            AppMode::SynthSeq  => app.seq_set_note(c),
+                            AppMode::SynthSeq | AppMode::Acid => app.seq_set_note(c),
                             AppMode::SynthSeq2 => app.seq2_set_note(c),
                             AppMode::Drums     => app.drum_preview(c),
-                            AppMode::Effects   => {}
-                        },
-
-                        _ => {}
+                            AppMode::Effects | AppMode::Harmonics | AppMode::Sample | AppMode::Envelope
+                                | AppMode::Modulation | AppMode::Inserts => {}
+                        }
                     }
                 }
                 Event::FocusLost => { app.release_all(); }
@@ -0,0 +1,74 @@
+use crate::arrange::STEPS_PER_BAR;
+use crate::sequencer::StepClock;
+
+/// Longest record count-in, in bars.
+pub const MAX_COUNT_IN: u8 = 2;
+
+/// Steps per beat: quarter notes on the 16th-note grid.
+const STEPS_PER_BEAT: f64 = 4.0;
+
+/// Click pitches: the first beat of each bar an octave above the rest.
+const BAR_HZ:  f32 = 1760.0;
+const BEAT_HZ: f32 = 880.0;
+
+/// Click peak level, into the master mix.
+const CLICK_LEVEL: f32 = 0.35;
+
+/// Time constant of the click's decay.
+const CLICK_DECAY_S: f32 = 0.012;
+
+/// Beat clicks on the master clock.  Heard while `enabled` and the
+/// transport runs, and always during a count-in.
+pub struct Metronome {
+    pub enabled: bool,
+    /// Step position a record count-in ends at; cleared once it is reached,
+    /// which is when recording starts.
+    pub count_in_until: Option<f64>,
+    /// Length of that count-in in steps.
+    count_in_len: f64,
+    phase: f32,
+    freq:  f32,
+    env:   f32,
+}
+
+impl Metronome {
+    pub fn new() -> Self {
+        Self { enabled: false, count_in_until: None, count_in_len: 0.0, phase: 0.0, freq: BEAT_HZ, env: 0.0 }
+    }
+
+    /// Start a count-in of `bars` bars from the next beat after `pos`.
+    /// Zero bars starts none.
+    pub fn count_in(&mut self, pos: f64, bars: u8) {
+        self.count_in_len = bars as f64 * STEPS_PER_BAR as f64;
+        self.count_in_until = (bars > 0).then(|| {
+            (pos / STEPS_PER_BEAT).ceil() * STEPS_PER_BEAT + self.count_in_len
+        });
+    }
+
+    /// Beats left of the count-in at `pos`, counting the one sounding
+    /// (before its first click, all of them).
+    pub fn beats_left(&self, pos: f64) -> Option<u32> {
+        self.count_in_until.map(|end| {
+            ((end - pos).min(self.count_in_len) / STEPS_PER_BEAT).ceil().max(1.0) as u32
+        })
+    }
+
+    /// Advance by one sample; returns the click signal.
+    pub fn tick(&mut self, clock: StepClock, running: bool, sample_rate: f32) -> f32 {
+        if self.count_in_until.is_some_and(|end| clock.pos > end) { self.count_in_until = None; }
+        if self.count_in_until.is_some() || (self.enabled && running) {
+            let beat = (clock.pos / STEPS_PER_BEAT).floor() * STEPS_PER_BEAT;
+            if clock.crosses(beat) {
+                let on_bar = beat.rem_euclid(STEPS_PER_BAR as f64) == 0.0;
+                self.freq  = if on_bar { BAR_HZ } else { BEAT_HZ };
+                self.phase = 0.0;
+                self.env   = 1.0;
+            }
+        }
+        if self.env < 1e-4 { return 0.0; }
+        let out = (self.phase * std::f32::consts::TAU).sin() * self.env * CLICK_LEVEL;
+        self.phase = (self.phase + self.freq / sample_rate).fract();
+        self.env  *= (-1.0 / (CLICK_DECAY_S * sample_rate)).exp();
+        out
+    }
+}
@@ -0,0 +1,609 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+
+use crate::effects::{COMB_MAX_HZ, COMB_MIN_HZ};
+use crate::synth::Synth;
+
+// ── Mirrored parameters ───────────────────────────────────────────────────────
+
+/// Every App control whose changes can be mirrored as a MIDI CC.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CcParam {
+    Volume, Volume2, DrumVolume, MasterGain, Bpm, Crossfade,
+    ReverbRoom, ReverbDamp, ReverbMix, ReverbEr, ReverbShimmer,
+    DelayTime, DelayFeedback, DelayMix, DelayDuck, DelayDuckRelease,
+    DistDrive, DistTone, DistLevel, DistAsym,
+    SidechainDepth, SidechainRelease,
+    Filter1Cutoff, Filter1Q, Filter2Cutoff, Filter2Q,
+    CombFreq, CombFeedback, CombMix,
+    FormantVowel, FormantMix,
+}
+
+impl CcParam {
+    /// Name in the `[cc]` table of a MIDI map, and its default CC number.
+    /// 7 is the standard channel volume; the rest sit in the undefined 20–31,
+    /// 102–119 ranges.
+    pub const ALL: &'static [(CcParam, &'static str, u8)] = &[
+        (CcParam::Volume,           "volume",            7),
+        (CcParam::Volume2,          "volume2",           102),
+        (CcParam::DrumVolume,       "drum_volume",       110),
+        (CcParam::MasterGain,       "master_gain",       111),
+        (CcParam::Bpm,              "bpm",               103),
+        (CcParam::Crossfade,        "crossfade",         109),
+        (CcParam::ReverbRoom,       "reverb_room",       20),
+        (CcParam::ReverbDamp,       "reverb_damp",       21),
+        (CcParam::ReverbMix,        "reverb_mix",        22),
+        (CcParam::ReverbEr,         "reverb_er",         31),
+        (CcParam::ReverbShimmer,    "reverb_shimmer",    114),
+        (CcParam::DelayTime,        "delay_time",        23),
+        (CcParam::DelayFeedback,    "delay_feedback",    24),
+        (CcParam::DelayMix,         "delay_mix",         25),
+        (CcParam::DelayDuck,        "delay_duck",        112),
+        (CcParam::DelayDuckRelease, "delay_duck_release", 113),
+        (CcParam::DistDrive,        "dist_drive",        26),
+        (CcParam::DistTone,         "dist_tone",         27),
+        (CcParam::DistLevel,        "dist_level",        28),
+        (CcParam::DistAsym,         "dist_asym",         108),
+        (CcParam::SidechainDepth,   "sidechain_depth",   29),
+        (CcParam::SidechainRelease, "sidechain_release", 30),
+        (CcParam::Filter1Cutoff,    "filter1_cutoff",    104),
+        (CcParam::Filter1Q,         "filter1_q",         105),
+        (CcParam::Filter2Cutoff,    "filter2_cutoff",    106),
+        (CcParam::Filter2Q,         "filter2_q",         107),
+        (CcParam::CombFreq,         "comb_freq",         115),
+        (CcParam::CombFeedback,     "comb_feedback",     116),
+        (CcParam::CombMix,          "comb_mix",          117),
+        (CcParam::FormantVowel,     "formant_vowel",     118),
+        (CcParam::FormantMix,       "formant_mix",       119),
+    ];
+
+    /// Name in `ALL`.
+    pub fn name(self) -> &'static str {
+        CcParam::ALL.iter().find(|(p, _, _)| *p == self).map_or("?", |(_, n, _)| n)
+    }
+
+    /// The control's range, and whether it is scaled logarithmically.
+    pub fn range(self) -> (f32, f32, bool) {
+        match self {
+            Self::Bpm              => (30.0, 300.0, false),
+            Self::DelayTime        => (10.0, 1000.0, false),
+            Self::DelayFeedback    => (0.0, 0.95, false),
+            Self::DelayDuckRelease => (50.0, 2000.0, false),
+            Self::DistDrive        => (1.0, 10.0, false),
+            Self::DistAsym         => (-1.0, 1.0, false),
+            Self::SidechainRelease => (10.0, 500.0, false),
+            Self::Filter1Cutoff | Self::Filter2Cutoff => (80.0, 18000.0, true),
+            Self::Filter1Q | Self::Filter2Q           => (0.5, 10.0, false),
+            Self::CombFreq         => (COMB_MIN_HZ, COMB_MAX_HZ, true),
+            Self::CombFeedback     => (0.0, 0.98, false),
+            Self::FormantVowel     => (0.0, 4.0, false),
+            _                      => (0.0, 1.0, false),
+        }
+    }
+
+    /// `value` in the control's own units as a CC value 0–127.
+    pub fn to_cc(self, value: f32) -> u8 {
+        let (lo, hi, log) = self.range();
+        let t = if log { (value / lo).ln() / (hi / lo).ln() } else { (value - lo) / (hi - lo) };
+        (t.clamp(0.0, 1.0) * 127.0).round() as u8
+    }
+}
+
+// ── CC map ────────────────────────────────────────────────────────────────────
+
+/// Which CC each mirrored parameter is sent on.
+pub struct CcMap {
+    ccs: HashMap<CcParam, u8>,
+}
+
+/// Shape of a MIDI map file: a `[cc]` table of `param = number`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct MapFile {
+    #[serde(default)]
+    cc: HashMap<String, u8>,
+}
+
+impl CcMap {
+    pub fn defaults() -> Self {
+        Self { ccs: CcParam::ALL.iter().map(|&(p, _, cc)| (p, cc)).collect() }
+    }
+
+    /// The defaults with any parameters listed in the TOML file at `path`
+    /// moved to the given CC numbers.
+    pub fn load(path: &str) -> Result<Self> {
+        let text = std::fs::read_to_string(path).with_context(|| format!("Cannot read {}", path))?;
+        Self::parse(&text).with_context(|| format!("Invalid MIDI map {}", path))
+    }
+
+    fn parse(text: &str) -> Result<Self> {
+        let file: MapFile = toml::from_str(text)?;
+        let mut map = Self::defaults();
+        for (name, cc) in file.cc {
+            let Some(&(param, _, _)) = CcParam::ALL.iter().find(|(_, n, _)| *n == name) else {
+                bail!("unknown parameter \"{}\"", name);
+            };
+            if cc > 119 { bail!("{}: CC {} is out of range (0-119)", name, cc); }
+            map.ccs.insert(param, cc);
+        }
+        Ok(map)
+    }
+
+    pub fn cc(&self, param: CcParam) -> u8 {
+        self.ccs[&param]
+    }
+}
+
+// ── Note routing ──────────────────────────────────────────────────────────────
+
+/// Which note sources go to the MIDI output, and whether those sources
+/// still play the internal synths as well.
+#[derive(Clone, Copy)]
+pub struct NoteRouting {
+    /// Live keyboard notes (synth 1).
+    pub keys:  bool,
+    pub seq1:  bool,
+    pub seq2:  bool,
+    /// Routed sources also sound internally.  Off = MIDI only.
+    pub local: bool,
+}
+
+impl Default for NoteRouting {
+    fn default() -> Self {
+        Self { keys: true, seq1: true, seq2: true, local: true }
+    }
+}
+
+// ── External clock ────────────────────────────────────────────────────────────
+
+/// MIDI clock pulses per 16th-note step (24 PPQN).
+const PULSES_PER_STEP: f64 = 6.0;
+/// Pulses the tempo is measured over (two beats).  Timing jitter only
+/// affects the two ends of the span, so a longer one gives a steadier tempo
+/// at the cost of following real tempo changes more slowly.
+const TEMPO_WINDOW: usize = 48;
+/// A longer gap between pulses (≈ 10 BPM) restarts the tempo measurement.
+const MAX_PULSE_GAP_S: f64 = 0.25;
+/// Rate correction per step of phase error, limited to ±`MAX_CORRECTION`.
+const PHASE_GAIN: f64 = 0.5;
+const MAX_CORRECTION: f64 = 0.5;
+/// How far past the last pulse the playhead may run if pulses stop
+/// arriving without a Stop: one step.
+const MAX_LEAD_STEPS: f64 = 1.0;
+
+/// Transport messages from a MIDI clock source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockMsg {
+    Pulse,
+    Start,
+    Continue,
+    Stop,
+    /// Song position pointer, in 16th-note steps.
+    SongPosition(u32),
+}
+
+/// Master clock state driven by incoming MIDI clock (`--midi-clock-in`).
+///
+/// Pulses only come in every 1/24 beat and arrive with thread and buffer
+/// jitter, so the playhead isn't moved pulse by pulse: it advances every
+/// sample at the tempo measured over the last two beats, and is nudged
+/// faster or slower towards where the pulse count says it should be.
+pub struct ExtClock {
+    /// Between Start/Continue and Stop.
+    pub running: bool,
+    /// Position of the last pulse, in pulses from the start of the song.
+    pulses: u64,
+    /// Arrival times of the last `TEMPO_WINDOW` pulses.
+    times: VecDeque<Instant>,
+    /// Tempo measured over `times`; `None` until two pulses arrive.
+    bpm: Option<f32>,
+    /// Set by Start and song position: the playhead jumps to the pulse count.
+    relocate: bool,
+    /// After Start, playback begins on the next pulse, which is beat 0.
+    armed: bool,
+}
+
+impl ExtClock {
+    pub fn new() -> Self {
+        Self { running: false, pulses: 0, times: VecDeque::with_capacity(TEMPO_WINDOW), bpm: None,
+               relocate: false, armed: false }
+    }
+
+    /// Tempo of the incoming clock, once it can be measured.
+    pub fn bpm(&self) -> Option<f32> {
+        self.bpm
+    }
+
+    fn measure_bpm(&self) -> Option<f32> {
+        let (first, last) = (self.times.front()?, self.times.back()?);
+        let span = last.duration_since(*first).as_secs_f64();
+        if span <= 0.0 { return None; }
+        let period = span / (self.times.len() - 1) as f64;
+        Some((60.0 / (period * PULSES_PER_STEP * 4.0)) as f32)
+    }
+
+    /// Apply one message received at `now`.  Pulses are timed even while
+    /// stopped (sources keep sending them), so the tempo is known on Start.
+    pub fn handle(&mut self, msg: ClockMsg, now: Instant) {
+        match msg {
+            ClockMsg::Pulse => {
+                let gap = self.times.back().map(|t| now.duration_since(*t).as_secs_f64());
+                if gap.is_some_and(|g| g >= MAX_PULSE_GAP_S) { self.times.clear(); }
+                if self.times.len() == TEMPO_WINDOW { self.times.pop_front(); }
+                self.times.push_back(now);
+                self.bpm = self.measure_bpm();
+                if self.running && !std::mem::take(&mut self.armed) { self.pulses += 1; }
+            }
+            ClockMsg::Start => {
+                self.pulses   = 0;
+                self.relocate = true;
+                self.armed    = true;
+                self.running  = true;
+            }
+            ClockMsg::Continue => self.running = true,
+            ClockMsg::Stop     => { self.running = false; self.armed = false; }
+            ClockMsg::SongPosition(step) => {
+                self.pulses   = step as u64 * PULSES_PER_STEP as u64;
+                self.relocate = true;
+            }
+        }
+    }
+
+    /// Master position (in steps) after one more sample, from `pos`.
+    /// Holds still while stopped or before the tempo is known.
+    pub fn advance(&mut self, pos: f64, sample_rate: f32) -> f64 {
+        let last = self.pulses as f64 / PULSES_PER_STEP;
+        if std::mem::take(&mut self.relocate) { return last; }
+        let Some(bpm) = self.bpm.filter(|_| self.running && !self.armed) else { return pos };
+
+        // Between pulses the true position averages half a pulse past the last one.
+        let err  = last + 0.5 / PULSES_PER_STEP - pos;
+        let rate = bpm as f64 * 4.0 / (60.0 * sample_rate as f64)
+                 * (1.0 + (err * PHASE_GAIN).clamp(-MAX_CORRECTION, MAX_CORRECTION));
+        (pos + rate).min(last + MAX_LEAD_STEPS).max(pos)
+    }
+}
+
+// ── Output port (ALSA sequencer) ──────────────────────────────────────────────
+
+/// A `tuibeat:out` sequencer port, optionally connected to one destination.
+#[cfg(target_os = "linux")]
+pub struct MidiOut {
+    seq:  alsa::seq::Seq,
+    port: i32,
+    /// What the port is connected to, for display.
+    target: String,
+    /// Channel for notes and CCs, 0–15.
+    pub channel: u8,
+}
+
+#[cfg(target_os = "linux")]
+impl MidiOut {
+    /// Velocity of every note sent (the app has no velocity of its own).
+    const VELOCITY: u8 = 100;
+
+    /// Create the output port and connect it to `target`: a `client:port`
+    /// address, or part of a port's `client:port` name (case-insensitive).
+    /// `"virtual"` leaves it unconnected for other programs to subscribe to.
+    pub fn open(target: &str, channel: u8) -> Result<Self> {
+        use alsa::seq::{PortCap, PortSubscribe, PortType, Seq};
+        use std::ffi::CString;
+
+        let seq = Seq::open(None, Some(alsa::Direction::Playback), true)
+            .context("Cannot open the ALSA sequencer")?;
+        seq.set_client_name(&CString::new("tuibeat")?)?;
+        let port = seq.create_simple_port(
+            &CString::new("out")?,
+            PortCap::READ | PortCap::SUBS_READ,
+            PortType::MIDI_GENERIC | PortType::APPLICATION,
+        ).context("Cannot create a MIDI output port")?;
+
+        if target.eq_ignore_ascii_case("virtual") {
+            return Ok(Self { seq, port, target: "virtual".to_string(), channel });
+        }
+        let (dest, name) = find_port(&seq, writable(), target)?;
+        let sub = PortSubscribe::empty()?;
+        sub.set_sender(alsa::seq::Addr { client: seq.client_id()?, port });
+        sub.set_dest(dest);
+        seq.subscribe_port(&sub).with_context(|| format!("Cannot connect to {}", name))?;
+        Ok(Self { seq, port, target: name, channel })
+    }
+
+    pub fn target(&self) -> &str {
+        &self.target
+    }
+
+    pub fn cc(&self, cc: u8, value: u8) {
+        use alsa::seq::{EvCtrl, Event, EventType};
+        let ctrl = EvCtrl { channel: self.channel, param: cc as u32, value: value as i32 };
+        self.send(Event::new(EventType::Controller, &ctrl));
+    }
+
+    pub fn note_on(&self, note: u8) {
+        use alsa::seq::{EvNote, Event, EventType};
+        let ev = EvNote { channel: self.channel, note, velocity: Self::VELOCITY, ..Default::default() };
+        self.send(Event::new(EventType::Noteon, &ev));
+    }
+
+    pub fn note_off(&self, note: u8) {
+        use alsa::seq::{EvNote, Event, EventType};
+        let ev = EvNote { channel: self.channel, note, ..Default::default() };
+        self.send(Event::new(EventType::Noteoff, &ev));
+    }
+
+    /// CC 123: silence anything still held on the channel.
+    pub fn all_notes_off(&self) {
+        self.cc(123, 0);
+    }
+
+    /// Send straight to subscribers, bypassing any queue.  Errors (e.g. a
+    /// full output pool) drop the event rather than stall the caller.
+    fn send(&self, mut ev: alsa::seq::Event) {
+        ev.set_source(self.port);
+        ev.set_subs();
+        ev.set_direct();
+        let _ = self.seq.event_output_direct(&mut ev);
+    }
+}
+
+#[cfg(target_os = "linux")]
+impl Drop for MidiOut {
+    fn drop(&mut self) {
+        self.all_notes_off();
+    }
+}
+
+/// Ports of other clients with all of `caps`, as `(address, "client:port" name)`.
+#[cfg(target_os = "linux")]
+fn ports_with(seq: &alsa::seq::Seq, caps: alsa::seq::PortCap) -> Vec<(alsa::seq::Addr, String)> {
+    use alsa::seq::{ClientIter, PortIter};
+    let own = seq.client_id().unwrap_or(-1);
+    let mut out = Vec::new();
+    for client in ClientIter::new(seq).filter(|c| c.get_client() != own) {
+        let cname = client.get_name().unwrap_or("?").to_string();
+        for p in PortIter::new(seq, client.get_client()) {
+            if p.get_capability().contains(caps) {
+                out.push((p.addr(), format!("{}:{}", cname, p.get_name().unwrap_or("?"))));
+            }
+        }
+    }
+    out
+}
+
+/// Writable ports, i.e. ones `--midi-out` can send to.
+#[cfg(target_os = "linux")]
+fn writable() -> alsa::seq::PortCap {
+    alsa::seq::PortCap::WRITE | alsa::seq::PortCap::SUBS_WRITE
+}
+
+/// Readable ports, i.e. ones `--midi-in` and `--midi-clock-in` can listen to.
+#[cfg(target_os = "linux")]
+fn readable() -> alsa::seq::PortCap {
+    alsa::seq::PortCap::READ | alsa::seq::PortCap::SUBS_READ
+}
+
+#[cfg(target_os = "linux")]
+fn find_port(seq: &alsa::seq::Seq, caps: alsa::seq::PortCap, target: &str) -> Result<(alsa::seq::Addr, String)> {
+    let ports = ports_with(seq, caps);
+    let kind = if caps == writable() { "writable" } else { "readable" };
+    if let Ok(addr) = target.parse::<alsa::seq::Addr>() {
+        if let Some(p) = ports.into_iter().find(|(a, _)| *a == addr) { return Ok(p); }
+        bail!("No {} MIDI port at {}", kind, target);
+    }
+    let needle = target.to_lowercase();
+    ports.into_iter()
+        .find(|(_, name)| name.to_lowercase().contains(&needle))
+        .with_context(|| format!("No {} MIDI port matching \"{}\" (see --list-midi-ports)", kind, target))
+}
+
+/// The first readable port that is a real source: the ALSA system client
+/// (timer, announce) and the "Midi Through" loopback are skipped.
+#[cfg(target_os = "linux")]
+fn first_source(seq: &alsa::seq::Seq) -> Result<(alsa::seq::Addr, String)> {
+    ports_with(seq, readable()).into_iter()
+        .find(|(a, name)| a.client != alsa::seq::Addr::system_timer().client && !name.starts_with("Midi Through"))
+        .context("No readable MIDI port to connect from (see --list-midi-ports)")
+}
+
+/// `"client:port  in/out  name"` lines for `--list-midi-ports`: "out" ports
+/// suit `--midi-out`, "in" ports `--midi-in` and `--midi-clock-in`.
+#[cfg(target_os = "linux")]
+pub fn list_ports() -> Result<Vec<String>> {
+    let seq = alsa::seq::Seq::open(None, None, true).context("Cannot open the ALSA sequencer")?;
+    let ins  = ports_with(&seq, readable());
+    let outs = ports_with(&seq, writable());
+    let mut all: Vec<_> = ins.iter().chain(&outs).cloned().collect();
+    all.sort_by_key(|(a, _)| (a.client, a.port));
+    all.dedup_by_key(|(a, _)| (a.client, a.port));
+    Ok(all.into_iter()
+        .map(|(a, name)| {
+            let dirs = match (ins.iter().any(|(b, _)| *b == a), outs.iter().any(|(b, _)| *b == a)) {
+                (true, true) => "in/out", (true, false) => "in", _ => "out",
+            };
+            format!("{:>3}:{:<2}  {:<6}  {}", a.client, a.port, dirs, name)
+        })
+        .collect())
+}
+
+// ── Clock input port (ALSA sequencer) ─────────────────────────────────────────
+
+/// A `tuibeat:clock` sequencer port, optionally connected from one source.
+#[cfg(target_os = "linux")]
+pub struct MidiClockIn {
+    seq: alsa::seq::Seq,
+    /// What the port is connected from, for display.
+    source: String,
+}
+
+#[cfg(target_os = "linux")]
+impl MidiClockIn {
+    /// Create the input port and connect `source` to it (same forms as
+    /// `MidiOut::open`; `"virtual"` waits for another program to connect).
+    pub fn open(source: &str) -> Result<Self> {
+        let (seq, source) = open_input("clock", source)?;
+        Ok(Self { seq, source })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Read clock messages on a background thread for the rest of the run,
+    /// passing each to `Synth::ext_clock_msg`.
+    pub fn spawn(self, synth: Arc<Mutex<Synth>>) {
+        use alsa::seq::{EvCtrl, EventType};
+        std::thread::spawn(move || {
+            let mut input = self.seq.input();
+            while let Ok(ev) = input.event_input() {
+                let msg = match ev.get_type() {
+                    EventType::Clock    => ClockMsg::Pulse,
+                    EventType::Start    => ClockMsg::Start,
+                    EventType::Continue => ClockMsg::Continue,
+                    EventType::Stop     => ClockMsg::Stop,
+                    EventType::Songpos  => match ev.get_data::<EvCtrl>() {
+                        Some(c) => ClockMsg::SongPosition(c.value.max(0) as u32),
+                        None    => continue,
+                    },
+                    _ => continue,
+                };
+                synth.lock().unwrap().ext_clock_msg(msg, Instant::now());
+            }
+        });
+    }
+}
+
+/// A `tuibeat:<port>` input port, connected from `source` unless that is
+/// `"virtual"`, and the name of what it is connected from.
+#[cfg(target_os = "linux")]
+fn open_input(port: &str, source: &str) -> Result<(alsa::seq::Seq, String)> {
+    use alsa::seq::{PortCap, PortSubscribe, PortType, Seq};
+    use std::ffi::CString;
+
+    let seq = Seq::open(None, Some(alsa::Direction::Capture), false)
+        .context("Cannot open the ALSA sequencer")?;
+    seq.set_client_name(&CString::new("tuibeat")?)?;
+    let port = seq.create_simple_port(
+        &CString::new(port)?,
+        PortCap::WRITE | PortCap::SUBS_WRITE,
+        PortType::MIDI_GENERIC | PortType::APPLICATION,
+    ).with_context(|| format!("Cannot create a MIDI {} input port", port))?;
+
+    if source.eq_ignore_ascii_case("virtual") {
+        return Ok((seq, "virtual".to_string()));
+    }
+    let (from, name) = if source.eq_ignore_ascii_case("first") {
+        first_source(&seq)?
+    } else {
+        find_port(&seq, readable(), source)?
+    };
+    let sub = PortSubscribe::empty()?;
+    sub.set_sender(from);
+    sub.set_dest(alsa::seq::Addr { client: seq.client_id()?, port });
+    seq.subscribe_port(&sub).with_context(|| format!("Cannot connect from {}", name))?;
+    Ok((seq, name))
+}
+
+// ── Note input port (ALSA sequencer) ──────────────────────────────────────────
+
+/// A `tuibeat:in` sequencer port for a MIDI keyboard, optionally connected
+/// from one source.
+#[cfg(target_os = "linux")]
+pub struct MidiNoteIn {
+    seq: alsa::seq::Seq,
+    /// What the port is connected from, for display.
+    source: String,
+}
+
+#[cfg(target_os = "linux")]
+impl MidiNoteIn {
+    /// Create the input port and connect `source` to it, as `MidiClockIn::open`.
+    pub fn open(source: &str) -> Result<Self> {
+        let (seq, source) = open_input("in", source)?;
+        Ok(Self { seq, source })
+    }
+
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// Read notes on a background thread for the rest of the run, on any
+    /// channel, playing each through `Synth::midi_in_on/off`.  A note-on
+    /// of velocity 0 is a note-off.
+    pub fn spawn(self, synth: Arc<Mutex<Synth>>) {
+        use alsa::seq::{EvNote, EventType};
+        std::thread::spawn(move || {
+            let mut input = self.seq.input();
+            while let Ok(ev) = input.event_input() {
+                let on = match ev.get_type() {
+                    EventType::Noteon  => true,
+                    EventType::Noteoff => false,
+                    _ => continue,
+                };
+                let Some(n) = ev.get_data::<EvNote>() else { continue };
+                let mut s = synth.lock().unwrap();
+                if on && n.velocity > 0 {
+                    s.midi_in_on(n.note.min(127), n.velocity.min(127) as f32 / 127.0);
+                } else {
+                    s.midi_in_off(n.note.min(127));
+                }
+            }
+        });
+    }
+}
+
+/// Without ALSA there is no sequencer to talk to; opening always fails.
+#[cfg(not(target_os = "linux"))]
+pub struct MidiOut;
+
+#[cfg(not(target_os = "linux"))]
+impl MidiOut {
+    pub fn open(_target: &str, _channel: u8) -> Result<Self> {
+        bail!("MIDI output needs the ALSA sequencer (Linux only)")
+    }
+
+    pub fn target(&self) -> &str { "" }
+
+    pub fn cc(&self, _cc: u8, _value: u8) {}
+    pub fn note_on(&self, _note: u8) {}
+    pub fn note_off(&self, _note: u8) {}
+    pub fn all_notes_off(&self) {}
+}
+
+/// Without ALSA there is no clock to listen to; opening always fails.
+#[cfg(not(target_os = "linux"))]
+pub struct MidiClockIn;
+
+#[cfg(not(target_os = "linux"))]
+impl MidiClockIn {
+    pub fn open(_source: &str) -> Result<Self> {
+        bail!("MIDI clock input needs the ALSA sequencer (Linux only)")
+    }
+
+    pub fn source(&self) -> &str { "" }
+
+    pub fn spawn(self, _synth: Arc<Mutex<Synth>>) {}
+}
+
+/// Without ALSA there is no keyboard to listen to; opening always fails.
+#[cfg(not(target_os = "linux"))]
+pub struct MidiNoteIn;
+
+#[cfg(not(target_os = "linux"))]
+impl MidiNoteIn {
+    pub fn open(_source: &str) -> Result<Self> {
+        bail!("MIDI input needs the ALSA sequencer (Linux only)")
+    }
+
+    pub fn source(&self) -> &str { "" }
+
+    pub fn spawn(self, _synth: Arc<Mutex<Synth>>) {}
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn list_ports() -> Result<Vec<String>> {
+    bail!("MIDI output needs the ALSA sequencer (Linux only)")
+}
@@ -0,0 +1,312 @@
+use std::net::UdpSocket;
+use std::sync::mpsc::{self, Receiver};
+
+use anyhow::{bail, Context, Result};
+
+use crate::app::Command;
+use crate::midi::CcParam;
+
+// ── Wire format (OSC 1.0) ─────────────────────────────────────────────────────
+
+/// One OSC argument.  Doubles and 64-bit ints are narrowed on decode.
+#[derive(Clone, Debug, PartialEq)]
+pub enum OscArg {
+    Int(i32),
+    Float(f32),
+    Str(String),
+    Bool(bool),
+}
+
+impl OscArg {
+    /// Numeric value of any non-string argument (`T`/`F` are 1 and 0), so
+    /// controllers that send floats for everything still work.
+    fn num(&self) -> Option<f32> {
+        match self {
+            Self::Int(i)   => Some(*i as f32),
+            Self::Float(f) => Some(*f),
+            Self::Bool(b)  => Some(if *b { 1.0 } else { 0.0 }),
+            Self::Str(_)   => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct OscMessage {
+    pub addr: String,
+    pub args: Vec<OscArg>,
+}
+
+/// Reads 4-byte-aligned OSC fields from a packet.
+struct Reader<'a> {
+    b:   &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        let end = self.pos.checked_add(n).filter(|&e| e <= self.b.len()).context("truncated packet")?;
+        let out = &self.b[self.pos..end];
+        self.pos = end;
+        Ok(out)
+    }
+
+    fn word(&mut self) -> Result<[u8; 4]> {
+        Ok(self.take(4)?.try_into().unwrap())
+    }
+
+    /// NUL-terminated string padded to a multiple of 4 bytes.
+    fn string(&mut self) -> Result<String> {
+        let rest = &self.b[self.pos..];
+        let len  = rest.iter().position(|&c| c == 0).context("unterminated string")?;
+        let s    = std::str::from_utf8(&rest[..len]).context("string is not UTF-8")?.to_string();
+        self.take((len + 4) & !3)?;
+        Ok(s)
+    }
+
+    fn done(&self) -> bool {
+        self.pos >= self.b.len()
+    }
+}
+
+/// Every message in a packet, with bundles flattened in order (their time
+/// tags are ignored: everything applies on arrival).
+pub fn decode(packet: &[u8]) -> Result<Vec<OscMessage>> {
+    let mut out = Vec::new();
+    decode_into(packet, &mut out)?;
+    Ok(out)
+}
+
+fn decode_into(packet: &[u8], out: &mut Vec<OscMessage>) -> Result<()> {
+    let mut r = Reader { b: packet, pos: 0 };
+    if packet.starts_with(b"#bundle\0") {
+        r.take(16)?; // "#bundle\0" + time tag
+        while !r.done() {
+            let len = i32::from_be_bytes(r.word()?);
+            if len < 0 { bail!("negative bundle element size"); }
+            decode_into(r.take(len as usize)?, out)?;
+        }
+        return Ok(());
+    }
+
+    let addr = r.string()?;
+    if !addr.starts_with('/') { bail!("address \"{}\" does not start with /", addr); }
+    // A missing type tag string is allowed by old senders: no arguments.
+    let tags = if r.done() { ",".to_string() } else { r.string()? };
+    let Some(tags) = tags.strip_prefix(',') else { bail!("{}: missing type tags", addr) };
+    let mut args = Vec::new();
+    for t in tags.chars() {
+        match t {
+            'i' => args.push(OscArg::Int(i32::from_be_bytes(r.word()?))),
+            'f' => args.push(OscArg::Float(f32::from_be_bytes(r.word()?))),
+            'h' => {
+                let v = i64::from_be_bytes(r.take(8)?.try_into().unwrap());
+                args.push(OscArg::Int(v.clamp(i32::MIN as i64, i32::MAX as i64) as i32));
+            }
+            'd' => args.push(OscArg::Float(f64::from_be_bytes(r.take(8)?.try_into().unwrap()) as f32)),
+            's' | 'S' => args.push(OscArg::Str(r.string()?)),
+            'T' => args.push(OscArg::Bool(true)),
+            'F' => args.push(OscArg::Bool(false)),
+            'N' | 'I' => {}
+            'b' => {
+                let len = i32::from_be_bytes(r.word()?).max(0) as usize;
+                r.take((len + 3) & !3)?;
+            }
+            other => bail!("{}: unsupported argument type '{}'", addr, other),
+        }
+    }
+    out.push(OscMessage { addr, args });
+    Ok(())
+}
+
+// ── Address schema ────────────────────────────────────────────────────────────
+
+/// Map a message onto the App command it stands for (schema in CLAUDE.md).
+/// Track and step numbers in addresses are 0-based.
+pub fn command(msg: &OscMessage) -> Result<Command> {
+    let parts: Vec<&str> = msg.addr.trim_start_matches('/').split('/').collect();
+    let arg = |i: usize| -> Result<f32> {
+        let v = msg.args.get(i).and_then(OscArg::num)
+            .with_context(|| format!("{} needs a numeric argument", msg.addr))?;
+        if !v.is_finite() { bail!("{}: {} is not a finite number", msg.addr, v); }
+        Ok(v)
+    };
+    let index = |s: &str| -> Result<usize> {
+        s.parse().with_context(|| format!("{}: \"{}\" is not an index", msg.addr, s))
+    };
+    let on = |i: usize| -> Result<bool> { Ok(arg(i)? >= 0.5) };
+
+    Ok(match parts.as_slice() {
+        ["bpm"]                => Command::Param(CcParam::Bpm, arg(0)?),
+        ["synth", "volume"]    => Command::Param(CcParam::Volume, arg(0)?),
+        ["synth2", "volume"]   => Command::Param(CcParam::Volume2, arg(0)?),
+        ["param", name]        => {
+            let Some(&(param, _, _)) = CcParam::ALL.iter().find(|(_, n, _)| n == name) else {
+                bail!("unknown parameter \"{}\"", name);
+            };
+            Command::Param(param, arg(0)?)
+        }
+        ["synth", "note"]      => Command::Note { note: arg(0)?.clamp(0.0, 127.0) as u8, on: on(1)? },
+        ["seq", "play"]        => Command::SeqPlay { seq2: false, on: on(0)? },
+        ["seq2", "play"]       => Command::SeqPlay { seq2: true,  on: on(0)? },
+        [seq @ ("seq" | "seq2"), "step", n] => {
+            let note = arg(0)?;
+            Command::SeqStep {
+                seq2: *seq == "seq2",
+                step: index(n)?,
+                note: (0.0..=127.0).contains(&note).then_some(note as u8),
+            }
+        }
+        ["drum", "play"]       => Command::DrumPlay(on(0)?),
+        ["drum", t, "step", n] => Command::DrumStep { track: index(t)?, step: index(n)?, prob: if on(0)? { 100 } else { 0 } },
+        ["drum", t, "prob", n] => Command::DrumStep { track: index(t)?, step: index(n)?, prob: arg(0)?.clamp(0.0, 100.0) as u8 },
+        ["drum", t, "mute"]    => Command::DrumMute { track: index(t)?, on: on(0)? },
+        ["drum", t, "volume"]  => Command::DrumVolume { track: index(t)?, volume: arg(0)? },
+        _ => bail!("unknown address {}", msg.addr),
+    })
+}
+
+// ── Server ────────────────────────────────────────────────────────────────────
+
+/// Listen for OSC on UDP `port` (all interfaces) on a background thread.
+/// Each message arrives on the channel as the command it maps to, or as a
+/// description of why it was rejected.
+pub fn spawn(port: u16) -> Result<Receiver<Result<Command, String>>> {
+    let socket = UdpSocket::bind(("0.0.0.0", port))
+        .with_context(|| format!("Cannot listen on UDP port {}", port))?;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut buf = [0u8; 65536];
+        while let Ok(n) = socket.recv(&mut buf) {
+            let cmds = match decode(&buf[..n]) {
+                Ok(msgs) => msgs.iter().map(|m| command(m).map_err(|e| format!("{:#}", e))).collect(),
+                Err(e)   => vec![Err(format!("bad packet ({:#})", e))],
+            };
+            for c in cmds {
+                if tx.send(c).is_err() { return; }
+            }
+        }
+    });
+    Ok(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// An OSC string: NUL-terminated, padded to a multiple of 4.
+    fn pad(s: &str) -> Vec<u8> {
+        let mut b = s.as_bytes().to_vec();
+        b.resize((s.len() + 4) & !3, 0);
+        b
+    }
+
+    fn message(addr: &str, tags: &str, args: &[u8]) -> Vec<u8> {
+        [pad(addr), pad(tags), args.to_vec()].concat()
+    }
+
+    fn bundle(elements: &[Vec<u8>]) -> Vec<u8> {
+        let mut b = pad("#bundle");
+        b.extend_from_slice(&1u64.to_be_bytes()); // time tag "immediately"
+        for e in elements {
+            b.extend_from_slice(&(e.len() as i32).to_be_bytes());
+            b.extend_from_slice(e);
+        }
+        b
+    }
+
+    fn msg(addr: &str, args: Vec<OscArg>) -> OscMessage {
+        OscMessage { addr: addr.to_string(), args }
+    }
+
+    #[test]
+    fn strings_are_padded_to_four_bytes() {
+        // Lengths 2–5 cover every padding amount, including a full word of
+        // NULs after a 4-byte string.
+        for addr in ["/a", "/ab", "/abc", "/abcd"] {
+            let p = message(addr, ",f", &0.25f32.to_be_bytes());
+            assert_eq!(p.len() % 4, 0);
+            assert_eq!(decode(&p).unwrap(), vec![msg(addr, vec![OscArg::Float(0.25)])]);
+        }
+        // "/abc" and its NUL fill exactly one word; dropping the NUL leaves
+        // it unterminated.
+        assert!(decode(b"/abc").is_err());
+    }
+
+    #[test]
+    fn type_tags_pick_the_argument_decoding() {
+        let args = [7i32.to_be_bytes(), (-1.5f32).to_be_bytes()].concat();
+        assert_eq!(decode(&message("/x", ",if", &args)).unwrap(),
+                   vec![msg("/x", vec![OscArg::Int(7), OscArg::Float(-1.5)])]);
+        let args = [pad("hi"), 3i32.to_be_bytes().to_vec()].concat();
+        assert_eq!(decode(&message("/x", ",sTFNi", &args)).unwrap(),
+                   vec![msg("/x", vec![OscArg::Str("hi".into()), OscArg::Bool(true), OscArg::Bool(false), OscArg::Int(3)])]);
+        // Old senders may leave the type tags out entirely.
+        assert_eq!(decode(&pad("/x")).unwrap(), vec![msg("/x", vec![])]);
+    }
+
+    #[test]
+    fn bundles_flatten_in_order() {
+        let a = message("/a", ",i", &1i32.to_be_bytes());
+        let b = message("/b", ",i", &2i32.to_be_bytes());
+        let c = message("/c", ",i", &3i32.to_be_bytes());
+        let p = bundle(&[a, bundle(&[b]), c]);
+        let addrs: Vec<String> = decode(&p).unwrap().into_iter().map(|m| m.addr).collect();
+        assert_eq!(addrs, ["/a", "/b", "/c"]);
+    }
+
+    #[test]
+    fn malformed_packets_are_errors() {
+        let p = message("/bpm", ",ff", &[120f32.to_be_bytes(), 1f32.to_be_bytes()].concat());
+        let tags_end = pad("/bpm").len() + pad(",ff").len();
+        // Every truncation: an error once the type tags are in, never a panic.
+        for n in 0..p.len() {
+            let r = decode(&p[..n]);
+            if n > tags_end { assert!(r.is_err(), "{} bytes", n); }
+        }
+        let b = bundle(&[p]);
+        for n in 0..b.len() {
+            if n > 16 { assert!(decode(&b[..n]).is_err(), "bundle, {} bytes", n); }
+        }
+
+        assert!(decode(&message("bpm", ",f", &[0; 4])).is_err());           // no leading /
+        assert!(decode(&message("/bpm", "f", &[0; 4])).is_err());           // no comma
+        assert!(decode(&message("/bpm", ",q", &[0; 4])).is_err());          // unknown tag
+        assert!(decode(&[b"/\xff\0\0".to_vec(), pad(",")].concat()).is_err()); // not UTF-8
+        let mut neg = pad("#bundle");
+        neg.extend_from_slice(&[0; 8]);
+        neg.extend_from_slice(&(-4i32).to_be_bytes());
+        assert!(decode(&neg).is_err());
+    }
+
+    #[test]
+    fn addresses_map_to_commands() {
+        let f = |v: f32| OscArg::Float(v);
+        let cases = [
+            (msg("/bpm", vec![f(128.0)]),            Command::Param(CcParam::Bpm, 128.0)),
+            (msg("/synth2/volume", vec![OscArg::Int(1)]), Command::Param(CcParam::Volume2, 1.0)),
+            (msg("/param/delay_time", vec![f(250.0)]), Command::Param(CcParam::DelayTime, 250.0)),
+            (msg("/synth/note", vec![f(60.0), OscArg::Bool(true)]), Command::Note { note: 60, on: true }),
+            (msg("/seq2/play", vec![f(0.0)]),        Command::SeqPlay { seq2: true, on: false }),
+            (msg("/seq/step/3", vec![f(64.0)]),      Command::SeqStep { seq2: false, step: 3, note: Some(64) }),
+            (msg("/seq/step/3", vec![f(-1.0)]),      Command::SeqStep { seq2: false, step: 3, note: None }),
+            (msg("/drum/play", vec![f(1.0)]),        Command::DrumPlay(true)),
+            (msg("/drum/2/step/5", vec![f(1.0)]),    Command::DrumStep { track: 2, step: 5, prob: 100 }),
+            (msg("/drum/2/prob/5", vec![f(40.0)]),   Command::DrumStep { track: 2, step: 5, prob: 40 }),
+            (msg("/drum/1/mute", vec![f(1.0)]),      Command::DrumMute { track: 1, on: true }),
+            (msg("/drum/0/volume", vec![f(0.5)]),    Command::DrumVolume { track: 0, volume: 0.5 }),
+        ];
+        for (m, want) in cases {
+            assert_eq!(command(&m).unwrap(), want, "{}", m.addr);
+        }
+
+        assert!(command(&msg("/nope", vec![f(1.0)])).is_err());
+        assert!(command(&msg("/param/nope", vec![f(1.0)])).is_err());
+        assert!(command(&msg("/drum/x/mute", vec![f(1.0)])).is_err());
+        assert!(command(&msg("/bpm", vec![])).is_err());
+        assert!(command(&msg("/bpm", vec![OscArg::Str("fast".into())])).is_err());
+        for v in [f32::NAN, f32::INFINITY, f32::NEG_INFINITY] {
+            assert!(command(&msg("/bpm", vec![f(v)])).is_err());
+            assert!(command(&msg("/seq/step/0", vec![f(v)])).is_err());
+        }
+    }
+}
@@ -0,0 +1,138 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::mpsc::{self, Receiver, Sender};
+
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use crate::app::{App, Command};
+use crate::keymap::Action;
+use crate::midi::CcParam;
+
+// ── Requests ──────────────────────────────────────────────────────────────────
+
+/// One line of the protocol: `{"cmd": "<name>", ...fields}`.
+/// Sequencers are numbered 1 and 2; track and step numbers are 0-based.
+#[derive(Deserialize)]
+#[serde(tag = "cmd", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Request {
+    /// Run a key-bindable action by its `keymap.toml` name.
+    Action { name: String },
+    SetBpm { value: f32 },
+    /// Any `CcParam::ALL` name, in the control's own units.
+    SetParam { name: String, value: f32 },
+    Note { note: u8, on: bool },
+    SeqPlay { #[serde(default = "first_seq")] seq: u8, on: bool },
+    DrumPlay { on: bool },
+    /// `null` / missing note clears the step.
+    SeqStep { #[serde(default = "first_seq")] seq: u8, step: usize, note: Option<u8> },
+    DrumStep { track: usize, step: usize, #[serde(default = "full_prob")] prob: u8 },
+    DrumMute { track: usize, on: bool },
+    DrumVolume { track: usize, value: f32 },
+    /// The project in save-file form.
+    GetState,
+    /// Just the status line.
+    Status,
+}
+
+fn first_seq() -> u8 { 1 }
+fn full_prob() -> u8 { 100 }
+
+/// A parsed request and where its one-line JSON reply goes.
+pub struct Pending {
+    pub request: Request,
+    pub reply:   Sender<String>,
+}
+
+fn seq2(seq: u8) -> Result<bool> {
+    match seq {
+        1 => Ok(false),
+        2 => Ok(true),
+        n => bail!("no sequencer {} (1 or 2)", n),
+    }
+}
+
+fn param(name: &str) -> Result<CcParam> {
+    CcParam::ALL.iter().find(|(_, n, _)| *n == name).map(|&(p, _, _)| p)
+        .with_context(|| format!("unknown parameter \"{}\"", name))
+}
+
+/// The App command a request stands for, or `None` for queries and actions.
+fn command(req: &Request) -> Result<Option<Command>> {
+    Ok(Some(match *req {
+        Request::SetBpm { value }           => Command::Param(CcParam::Bpm, value),
+        Request::SetParam { ref name, value } => Command::Param(param(name)?, value),
+        Request::Note { note, on }          => {
+            if note > 127 { bail!("note {} is out of range (0-127)", note); }
+            Command::Note { note, on }
+        }
+        Request::SeqPlay { seq, on }        => Command::SeqPlay { seq2: seq2(seq)?, on },
+        Request::DrumPlay { on }            => Command::DrumPlay(on),
+        Request::SeqStep { seq, step, note } => {
+            if note.is_some_and(|n| n > 127) { bail!("note {} is out of range (0-127)", note.unwrap()); }
+            Command::SeqStep { seq2: seq2(seq)?, step, note }
+        }
+        Request::DrumStep { track, step, prob } => Command::DrumStep { track, step, prob: prob.min(100) },
+        Request::DrumMute { track, on }     => Command::DrumMute { track, on },
+        Request::DrumVolume { track, value } => Command::DrumVolume { track, volume: value },
+        Request::Action { .. } | Request::GetState | Request::Status => return Ok(None),
+    }))
+}
+
+/// Carry out a request on the UI thread and build its reply.  Every reply
+/// has `ok`; successful ones also carry the status line the change left.
+pub fn respond(app: &mut App, req: Request) -> Value {
+    let run = |app: &mut App| -> Result<Value> {
+        match command(&req)? {
+            Some(cmd) => app.apply(cmd)?,
+            None => match &req {
+                Request::Action { name } => {
+                    let action = Action::from_name(name)
+                        .with_context(|| format!("unknown action \"{}\"", name))?;
+                    app.perform(action);
+                }
+                Request::GetState => return Ok(json!({ "ok": true, "state": app.save_file() })),
+                _ => {}
+            },
+        }
+        Ok(json!({ "ok": true, "status": app.status_msg }))
+    };
+    run(app).unwrap_or_else(|e| json!({ "ok": false, "error": format!("{:#}", e) }))
+}
+
+// ── Server ────────────────────────────────────────────────────────────────────
+
+/// Listen on TCP `127.0.0.1:port` for line-delimited JSON requests, one
+/// thread per connection.  Each request is handed over on the returned
+/// channel and its connection waits for the reply before reading the next.
+pub fn spawn(port: u16) -> Result<Receiver<Pending>> {
+    let listener = TcpListener::bind(("127.0.0.1", port))
+        .with_context(|| format!("Cannot listen on TCP port {}", port))?;
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let tx = tx.clone();
+            std::thread::spawn(move || { let _ = serve(stream, tx); });
+        }
+    });
+    Ok(rx)
+}
+
+fn serve(stream: TcpStream, tx: Sender<Pending>) -> Result<()> {
+    let mut out = stream.try_clone()?;
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        if line.trim().is_empty() { continue; }
+        let reply = match serde_json::from_str::<Request>(&line) {
+            Ok(request) => {
+                let (reply, answer) = mpsc::channel();
+                tx.send(Pending { request, reply })?;
+                answer.recv()?
+            }
+            Err(e) => json!({ "ok": false, "error": format!("bad request: {}", e) }).to_string(),
+        };
+        writeln!(out, "{}", reply)?;
+    }
+    Ok(())
+}
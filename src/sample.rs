@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+use crate::wavetable::decode_wav;
+
+/// Bins in `Sample::peaks`, enough for a full-width overview.
+pub const PEAK_BINS: usize = 512;
+
+/// A sound loaded from a WAV file for the granular and sampler voices: the
+/// first channel, at the file's own rate (voices scale their read speed by it).
+pub struct Sample {
+    pub name:  String,
+    /// File it was loaded from; saved so a project can re-read it.
+    pub path:  String,
+    pub data:  Vec<f32>,
+    /// Sample rate of `data`, in Hz.
+    pub rate:  f32,
+    /// Peak level of each of `PEAK_BINS` equal slices of `data`, for drawing.
+    pub peaks: Vec<f32>,
+}
+
+impl Sample {
+    /// Read a WAV file (PCM 8/16/24/32-bit or 32-bit float).
+    pub fn load(path: &str) -> Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| format!("Cannot read {}", path))?;
+        let (data, rate) = decode_wav(&bytes).with_context(|| format!("{} is not a usable WAV", path))?;
+        if data.is_empty() { bail!("{} has no samples", path); }
+        if rate == 0 { bail!("{} has a sample rate of 0", path); }
+
+        let peaks = (0..PEAK_BINS).map(|b| {
+            let lo = b * data.len() / PEAK_BINS;
+            let hi = ((b + 1) * data.len() / PEAK_BINS).max(lo + 1).min(data.len());
+            data[lo..hi].iter().fold(0.0f32, |m, s| m.max(s.abs()))
+        }).collect();
+        let name = Path::new(path)
+            .file_stem()
+            .map_or_else(|| path.to_string(), |s| s.to_string_lossy().into_owned());
+        Ok(Self { name, path: path.to_string(), data, rate: rate as f32, peaks })
+    }
+
+    /// Length in seconds.
+    pub fn seconds(&self) -> f32 {
+        self.data.len() as f32 / self.rate
+    }
+
+    /// The sample at fractional index `pos`, linear between neighbours and
+    /// wrapping round the end.
+    #[inline]
+    pub fn at(&self, pos: f32) -> f32 {
+        let len = self.data.len();
+        let pos = pos.rem_euclid(len as f32);
+        let i0 = (pos as usize).min(len - 1);
+        let i1 = (i0 + 1) % len;
+        let t = pos - i0 as f32;
+        self.data[i0] + (self.data[i1] - self.data[i0]) * t
+    }
+
+    /// The sample at fractional index `pos` read once through: linear
+    /// between neighbours, `None` from the last sample on.
+    #[inline]
+    pub fn one_shot(&self, pos: f64) -> Option<f32> {
+        let i0 = pos as usize;
+        if pos < 0.0 || i0 + 1 >= self.data.len() { return None; }
+        let t = (pos - i0 as f64) as f32;
+        Some(self.data[i0] + (self.data[i0 + 1] - self.data[i0]) * t)
+    }
+}
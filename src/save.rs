@@ -1,5 +1,11 @@
+use anyhow::{Context, Result};
 use serde::{Deserialize, Serialize};
 
+use crate::granular::DEFAULT_ROOT;
+use crate::sequencer::Sequencer;
+use crate::synth::Synth;
+use crate::wavetable::{DEFAULT_HARMONICS, HARMONICS};
+
 #[derive(Serialize, Deserialize)]
 pub struct SaveFile {
     // Global
@@ -7,16 +13,79 @@ pub struct SaveFile {
     pub base_octave: i32,
     pub scale: u8,        // index into Scale::ALL
     pub scale_root: u8,
+    /// `Scale::Custom` intervals from the root (absent in older files).
+    #[serde(default)]
+    pub custom_scale: Vec<u8>,
+    /// Sequencer notes quantized as they play.
+    #[serde(default)]
+    pub scale_live: bool,
+    /// Bars of count-in before real-time recording.
+    #[serde(default = "default_count_in")]
+    pub count_in_bars: u8,
     // Synths
-    pub wave1: u8,        // 0=Sine 1=Square 2=Saw 3=Tri
+    pub wave1: u8,        // 0=Sine 1=Square 2=Saw 3=Tri 4=Wavetable 5=Granular 6=Sampler
     pub wave2: u8,
+    /// Table name (built-in) or WAV path when the wave is 4.
+    #[serde(default)]
+    pub wave1_table: Option<String>,
+    #[serde(default)]
+    pub wave2_table: Option<String>,
+    /// PolyBLEP oscillators (files from before the option default to on).
+    #[serde(default = "default_true")]
+    pub band_limited: bool,
+    /// Additive table harmonics 1–8; a sine in older files.
+    #[serde(default = "default_harmonics")]
+    pub harmonics: [f32; HARMONICS],
+    #[serde(default)]
+    pub granular: Option<GranularSave>,
     pub volume: f32,
     pub volume2: f32,
+    /// Drum bus level.  Absent in older files, whose drums followed
+    /// `volume`.
+    #[serde(default)]
+    pub drum_volume: Option<f32>,
+    #[serde(default = "default_master_gain")]
+    pub master_gain: f32,
+    /// Synth 1 ↔ 2 crossfader; centred in older files.
+    #[serde(default = "default_crossfade")]
+    pub crossfade: f32,
+    /// Mono (legato) synths; poly in older files.
+    #[serde(default)]
+    pub mono1: bool,
+    #[serde(default)]
+    pub mono2: bool,
+    /// Stereo positions of the synths, -1 (left) – 1 (right).
+    #[serde(default)]
+    pub pan1: f32,
+    #[serde(default)]
+    pub pan2: f32,
+    /// Amp envelopes; the built-in one in older files.
+    #[serde(default)]
+    pub env1: Option<EnvSave>,
+    #[serde(default)]
+    pub env2: Option<EnvSave>,
+    /// The two LFOs; off in older files.
+    #[serde(default)]
+    pub lfo1: Option<LfoSave>,
+    #[serde(default)]
+    pub lfo2: Option<LfoSave>,
+    /// Synth 1's and synth 2's insert chains, in order; empty in older files.
+    #[serde(default)]
+    pub inserts1: Vec<InsertSave>,
+    #[serde(default)]
+    pub inserts2: Vec<InsertSave>,
     // Sequencers
     pub seq1: SeqSave,
     pub seq2: SeqSave,
     // Drums
     pub drums: DrumsSave,
+    /// Index into `GrooveTemplate::ALL`; the amount is `drums.swing`.
+    #[serde(default)]
+    pub groove_template: u8,
+    /// Groove also applied to the melodic sequencers (off in older files,
+    /// whose swing only ever affected the drums).
+    #[serde(default)]
+    pub groove_melodic: bool,
     // Effects
     pub reverb: ReverbSave,
     pub delay: DelaySave,
@@ -25,38 +94,183 @@ pub struct SaveFile {
     pub filter1: FilterSave,
     pub filter2: FilterSave,
     pub routing: RoutingSave,
+    #[serde(default)]
+    pub comb: Option<CombSave>,
+    #[serde(default)]
+    pub chorus: Option<ChorusSave>,
+    #[serde(default)]
+    pub crusher: Option<CrushSave>,
+    #[serde(default)]
+    pub compressor: Option<CompSave>,
+    #[serde(default)]
+    pub formant: Option<FormantSave>,
+    #[serde(default)]
+    pub deesser: Option<DeEsserSave>,
+    /// Synth 1's acid filter envelope and accent.
+    #[serde(default)]
+    pub acid: Option<AcidSave>,
+    // Automation
+    #[serde(default)]
+    pub automation: Option<AutomationSave>,
+    // Arpeggiator
+    #[serde(default)]
+    pub arp: Option<ArpSave>,
+    // Arrangement
+    #[serde(default)]
+    pub markers: Vec<MarkerSave>,
+}
+
+fn default_true() -> bool { true }
+
+fn default_crossfade() -> f32 { 0.5 }
+
+fn default_count_in() -> u8 { 1 }
+
+fn default_master_gain() -> f32 { 1.0 }
+
+fn default_harmonics() -> [f32; HARMONICS] { DEFAULT_HARMONICS }
+
+#[derive(Serialize, Deserialize)]
+pub struct SeqSave {
+    pub num_steps: usize,
+    pub steps: Vec<Option<u8>>,
+    /// Chord notes above each step's root, in semitones.
+    #[serde(default)]
+    pub chord: Vec<Vec<u8>>,
+    #[serde(default)]
+    pub muted: Vec<bool>,
+    /// Steps that slide into the next note.
+    #[serde(default)]
+    pub slide: Vec<bool>,
+    /// Accented steps (heard through synth 1's acid mode).
+    #[serde(default)]
+    pub accent: Vec<bool>,
+    /// Repeated notes hold rather than retrigger.
+    #[serde(default)]
+    pub tie_repeats: bool,
+    /// Velocity humanize amount, 0–0.5.
+    #[serde(default)]
+    pub vel_humanize: f32,
+    /// Index into `Direction::ALL`; forward in older files.
+    #[serde(default)]
+    pub direction: u8,
+    #[serde(default)]
+    pub octave: Vec<i8>,
+    /// Per-step timing offsets in 1/24 steps.
+    #[serde(default)]
+    pub nudge: Vec<i8>,
+    /// Only steps with at least one lock.
+    #[serde(default)]
+    pub locks: Vec<LockSave>,
 }
 
+/// One step's p-locks; `wave` / `wave_table` as for `wave1` / `wave1_table`.
 #[derive(Serialize, Deserialize)]
-pub struct SeqSave { pub num_steps: usize, pub steps: Vec<Option<u8>> }
+pub struct LockSave {
+    pub step:   usize,
+    pub cutoff: Option<f32>,
+    pub wave:   Option<u8>,
+    #[serde(default)]
+    pub wave_table: Option<String>,
+    pub volume: Option<f32>,
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct DrumsSave { pub num_steps: usize, pub swing: f32, pub tracks: Vec<TrackSave> }
 
 #[derive(Serialize, Deserialize)]
-pub struct TrackSave { pub kind: u8, pub steps: Vec<u8>, pub muted: bool, pub volume: f32 }
+pub struct TrackSave {
+    pub kind: u8, pub steps: Vec<u8>, pub muted: bool, pub volume: f32,
+    /// Stereo position, -1 (left) – 1 (right).
+    #[serde(default)]
+    pub pan: f32,
+    /// Tuning in semitones (0 = the kind's own pitch).
+    #[serde(default)]
+    pub pitch: f32,
+    #[serde(default)]
+    pub name: Option<String>,
+    /// Path of the one-shot the track plays (`None` = synthesised).
+    #[serde(default)]
+    pub sample: Option<String>,
+    /// Steps with a trig condition other than always, as `(step, label)`.
+    #[serde(default)]
+    pub conds: Vec<(usize, String)>,
+    /// Per-step timing offsets in 1/24 steps.
+    #[serde(default)]
+    pub nudge: Vec<i8>,
+    /// Retrigger chance (0–100) and hit count.
+    #[serde(default)]
+    pub retrig: u8,
+    #[serde(default = "default_retrig_hits")]
+    pub retrig_hits: u8,
+}
+
+fn default_retrig_hits() -> u8 { 2 }
 
 #[derive(Serialize, Deserialize)]
-pub struct ReverbSave { pub enabled: bool, pub room_size: f32, pub damping: f32, pub mix: f32 }
+pub struct ReverbSave {
+    pub enabled: bool, pub room_size: f32, pub damping: f32, pub mix: f32,
+    /// Missing in older files, which had no early reflections.
+    #[serde(default)]
+    pub er_amount: f32,
+    #[serde(default)]
+    pub shimmer: f32,
+}
 
 #[derive(Serialize, Deserialize)]
-pub struct DelaySave { pub enabled: bool, pub time_ms: f32, pub feedback: f32, pub mix: f32 }
+pub struct DelaySave {
+    pub enabled: bool, pub time_ms: f32, pub feedback: f32, pub mix: f32,
+    /// Extra taps; none in older files.
+    #[serde(default)]
+    pub taps: Vec<TapSave>,
+    #[serde(default)]
+    pub reverse: bool,
+    /// No ducking in older files.
+    #[serde(default)]
+    pub duck_amount: f32,
+    #[serde(default = "default_duck_release")]
+    pub duck_release: f32,
+    /// Tempo sync and its division (as `"1/8."`); free time in older files.
+    #[serde(default)]
+    pub sync: bool,
+    #[serde(default)]
+    pub division: Option<String>,
+}
+
+fn default_duck_release() -> f32 { 300.0 }
+
+#[derive(Serialize, Deserialize)]
+pub struct TapSave { pub time_ms: f32, pub level: f32, pub pan: f32 }
 
 #[derive(Serialize, Deserialize)]
-pub struct DistSave { pub enabled: bool, pub drive: f32, pub tone: f32, pub level: f32 }
+pub struct DistSave {
+    pub enabled: bool, pub drive: f32, pub tone: f32, pub level: f32,
+    /// `DistMode` index: 0=clip 1=fold.
+    #[serde(default)]
+    pub mode: u8,
+    /// -1–1; symmetric (0) in older files.
+    #[serde(default)]
+    pub asymmetry: f32,
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct SidechainSave {
     pub enabled: bool, pub depth: f32, pub release_ms: f32,
+    /// `SidechainShape` index: 0=exponential 1=linear 2=S-curve.
+    #[serde(default)]
+    pub shape: u8,
     pub duck_s1: bool, pub duck_s2: bool,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct FilterSave {
     pub enabled: bool,
-    pub mode: u8,     // 0=LP 1=HP 2=BP
+    pub mode: u8,     // 0=LP 1=HP 2=BP 3=Peaking 4=LowShelf 5=HighShelf
     pub cutoff: f32,
     pub q: f32,
+    /// Boost or cut of the EQ modes; none in older files.
+    #[serde(default)]
+    pub gain_db: f32,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -64,4 +278,232 @@ pub struct RoutingSave {
     pub s1_reverb: f32, pub s1_delay: f32, pub s1_dist: f32,
     pub s2_reverb: f32, pub s2_delay: f32, pub s2_dist: f32,
     pub dr_reverb: f32, pub dr_delay: f32, pub dr_dist: f32,
+    /// Comb sends; none in older files.
+    #[serde(default)] pub s1_comb: f32,
+    #[serde(default)] pub s2_comb: f32,
+    #[serde(default)] pub dr_comb: f32,
+    /// Chorus sends; none in older files.
+    #[serde(default)] pub s1_chorus: f32,
+    #[serde(default)] pub s2_chorus: f32,
+    #[serde(default)] pub dr_chorus: f32,
+    /// Bitcrusher sends; none in older files.
+    #[serde(default)] pub s1_crush: f32,
+    #[serde(default)] pub s2_crush: f32,
+    #[serde(default)] pub dr_crush: f32,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CombSave { pub enabled: bool, pub freq: f32, pub feedback: f32, pub mix: f32 }
+
+#[derive(Serialize, Deserialize)]
+pub struct ChorusSave { pub enabled: bool, pub rate: f32, pub depth: f32, pub mix: f32 }
+
+#[derive(Serialize, Deserialize)]
+pub struct CrushSave { pub enabled: bool, pub bits: u8, pub downsample: u8, pub mix: f32 }
+
+/// Threshold and makeup in dB, attack and release in ms.
+#[derive(Serialize, Deserialize)]
+pub struct CompSave {
+    pub enabled:    bool,
+    pub threshold:  f32,
+    pub ratio:      f32,
+    pub attack_ms:  f32,
+    pub release_ms: f32,
+    pub makeup_db:  f32,
+}
+
+/// `vowel` is a position along A–E–I–O–U (0–4).
+#[derive(Serialize, Deserialize)]
+pub struct FormantSave { pub enabled: bool, pub vowel: f32, pub q: f32, pub mix: f32, pub on_s2: bool }
+
+/// `threshold` in dB.
+#[derive(Serialize, Deserialize)]
+pub struct DeEsserSave { pub enabled: bool, pub freq: f32, pub threshold: f32, pub amount: f32 }
+
+/// `sample` is the WAV path, re-read on load; `root` its MIDI note (C4 in
+/// files from before the sampler).
+#[derive(Serialize, Deserialize)]
+pub struct GranularSave {
+    pub sample:   Option<String>,
+    #[serde(default = "default_root")]
+    pub root:     u8,
+    pub size_ms:  f32,
+    pub density:  f32,
+    pub position: f32,
+}
+
+fn default_root() -> u8 { DEFAULT_ROOT }
+
+/// Times in seconds; `sustain` is a level, 0–1.
+#[derive(Serialize, Deserialize)]
+pub struct EnvSave { pub attack: f32, pub decay: f32, pub sustain: f32, pub release: f32 }
+
+/// `shape` indexes `LfoShape::ALL`, `division` `LfoRate::ALL`, `target`
+/// `LfoTarget::ALL`.
+#[derive(Serialize, Deserialize)]
+pub struct LfoSave {
+    pub shape:    u8,
+    #[serde(default)]
+    pub division: Option<u8>,
+    /// Free-running rate in Hz, only in files from before `division`.
+    #[serde(default, skip_serializing)]
+    pub rate:     Option<f32>,
+    pub depth:    f32,
+    pub target:   u8,
+}
+
+/// One insert, tagged by effect (`{"effect": "Reverb"}`).  Inserts run at
+/// their effect's default settings, so the tag is all there is to save.
+#[derive(Serialize, Deserialize, Clone, Copy)]
+#[serde(tag = "effect")]
+pub enum InsertSave { Reverb, Delay, Distortion, Filter }
+
+#[derive(Serialize, Deserialize)]
+pub struct AcidSave { pub enabled: bool, pub env_mod: f32, pub decay_ms: f32, pub accent: f32 }
+
+/// `rate` / `mode` index `ArpRate::ALL` / `ArpMode::ALL`.
+#[derive(Serialize, Deserialize)]
+pub struct ArpSave { pub enabled: bool, pub rate: u8, pub mode: u8 }
+
+#[derive(Serialize, Deserialize)]
+pub struct AutomationSave { pub loop_steps: usize, pub lanes: Vec<LaneSave> }
+
+/// `param` is a `CcParam::ALL` name; points are `[step, value]` pairs.
+#[derive(Serialize, Deserialize)]
+pub struct LaneSave {
+    pub param: String, pub points: Vec<(f64, f32)>,
+    #[serde(default)]
+    pub bypass: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct MarkerSave { pub bar: usize, pub name: String }
+
+// ── MIDI file export ──────────────────────────────────────────────────────────
+
+/// Ticks per quarter note in exported files: 24 per 16th step.
+const MIDI_PPQ: u16 = 96;
+const TICKS_PER_STEP: u32 = MIDI_PPQ as u32 / 4;
+
+/// Velocity of every exported note.
+const MIDI_VELOCITY: u8 = 100;
+
+/// Write both melodic sequencers to `path` as a type-1 Standard MIDI File:
+/// one track each, synth 1 on channel 1 and synth 2 on channel 2, the
+/// first also carrying the tempo and a 4/4 time signature.  Each track
+/// is one loop in the sequencer's direction, every note a 16th long;
+/// empty and muted steps are rests.  Returns the number of notes written.
+pub fn export_midi(path: &str, s: &Synth) -> Result<usize> {
+    let (b, notes) = encode_midi(s);
+    std::fs::write(path, b).with_context(|| format!("Cannot write {}", path))?;
+    Ok(notes)
+}
+
+/// The bytes `export_midi()` writes, and the number of notes in them.
+fn encode_midi(s: &Synth) -> (Vec<u8>, usize) {
+    let tempo = (60_000_000.0 / s.bpm as f64).round() as u32;
+    let mut meta = vec![0x00, 0xff, 0x51, 0x03];
+    meta.extend_from_slice(&tempo.to_be_bytes()[1..]);
+    meta.extend_from_slice(&[0x00, 0xff, 0x58, 0x04, 4, 2, 24, 8]);
+
+    let (track1, n1) = midi_track(&s.sequencer, 0, "Synth 1", &meta);
+    let (track2, n2) = midi_track(&s.sequencer2, 1, "Synth 2", &[]);
+
+    let mut b = Vec::with_capacity(14 + track1.len() + track2.len());
+    b.extend_from_slice(b"MThd");
+    b.extend_from_slice(&6u32.to_be_bytes());
+    b.extend_from_slice(&1u16.to_be_bytes());                          // format
+    b.extend_from_slice(&2u16.to_be_bytes());                          // tracks
+    b.extend_from_slice(&MIDI_PPQ.to_be_bytes());
+    b.extend_from_slice(&track1);
+    b.extend_from_slice(&track2);
+    (b, n1 + n2)
+}
+
+/// One `MTrk` chunk for `seq` on `channel` (0-based), opening with its
+/// name and the `meta` events given, and the number of notes in it.
+fn midi_track(seq: &Sequencer, channel: u8, name: &str, meta: &[u8]) -> (Vec<u8>, usize) {
+    let mut ev = vec![0x00, 0xff, 0x03, name.len() as u8];
+    ev.extend_from_slice(name.as_bytes());
+    ev.extend_from_slice(meta);
+
+    // Ticks since the last event written.
+    let mut wait = 0;
+    let mut notes = 0;
+    for k in 0..seq.loop_steps() {
+        let i = seq.step_at(k as u64);
+        let played = if seq.muted.get(i).copied().unwrap_or(false) { Vec::new() } else { seq.played_notes(i) };
+        if played.is_empty() { wait += TICKS_PER_STEP; continue; }
+        // A chord's notes start together and end together.
+        for (j, &note) in played.iter().enumerate() {
+            push_vlq(&mut ev, if j == 0 { wait } else { 0 });
+            ev.extend_from_slice(&[0x90 | channel, note, MIDI_VELOCITY]);
+        }
+        for (j, &note) in played.iter().enumerate() {
+            push_vlq(&mut ev, if j == 0 { TICKS_PER_STEP } else { 0 });
+            ev.extend_from_slice(&[0x80 | channel, note, 0]);
+        }
+        wait = 0;
+        notes += played.len();
+    }
+    // End of track after the last step, so the loop length survives rests.
+    push_vlq(&mut ev, wait);
+    ev.extend_from_slice(&[0xff, 0x2f, 0x00]);
+
+    let mut chunk = Vec::with_capacity(8 + ev.len());
+    chunk.extend_from_slice(b"MTrk");
+    chunk.extend_from_slice(&(ev.len() as u32).to_be_bytes());
+    chunk.extend_from_slice(&ev);
+    (chunk, notes)
+}
+
+/// A MIDI variable-length quantity: 7 bits per byte, most significant
+/// first, the high bit set on all but the last.
+fn push_vlq(b: &mut Vec<u8>, v: u32) {
+    let mut bytes = vec![(v & 0x7f) as u8];
+    let mut v = v >> 7;
+    while v > 0 {
+        bytes.push((v & 0x7f) as u8 | 0x80);
+        v >>= 7;
+    }
+    b.extend(bytes.iter().rev());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(id: &[u8], body: &[u8]) -> Vec<u8> {
+        [id, &(body.len() as u32).to_be_bytes(), body].concat()
+    }
+
+    #[test]
+    fn midi_export_headers_tempo_and_timing() {
+        let mut s = Synth::new(44_100.0);
+        s.bpm = 120.0;
+        s.sequencer.set_step(0, &[60, 64, 67]);
+        s.sequencer.set_step(8, &[72]);
+        let (b, notes) = encode_midi(&s);
+        assert_eq!(notes, 4);
+
+        // Type 1, two tracks, 96 PPQ.
+        let header = chunk(b"MThd", &[0, 1, 0, 2, 0, 96]);
+        let track1 = chunk(b"MTrk", &[
+            &[0x00, 0xff, 0x03, 7][..], b"Synth 1",
+            &[0x00, 0xff, 0x51, 0x03, 0x07, 0xa1, 0x20],  // 500 000 µs per quarter
+            &[0x00, 0xff, 0x58, 0x04, 4, 2, 24, 8],
+            // The chord starts and ends together, one 16th (24 ticks) long.
+            &[0x00, 0x90, 60, 100, 0x00, 0x90, 64, 100, 0x00, 0x90, 67, 100],
+            &[24, 0x80, 60, 0, 0x00, 0x80, 64, 0, 0x00, 0x80, 67, 0],
+            // Seven rests (168 ticks) need a two-byte quantity.
+            &[0x81, 0x28, 0x90, 72, 100, 24, 0x80, 72, 0],
+            // The trailing rests still count towards the loop.
+            &[0x81, 0x28, 0xff, 0x2f, 0x00],
+        ].concat());
+        let track2 = chunk(b"MTrk", &[
+            &[0x00, 0xff, 0x03, 7][..], b"Synth 2",
+            &[0x83, 0x00, 0xff, 0x2f, 0x00],  // 16 rests: 384 ticks
+        ].concat());
+        assert_eq!(b, [header, track1, track2].concat());
+    }
 }
@@ -10,10 +10,18 @@ pub enum Scale {
     Blues,
     Dorian,
     Mixolydian,
+    /// User-defined: the intervals are `ScaleQuantizer::custom`.
+    Custom,
+    Phrygian,
+    Lydian,
+    Locrian,
+    HarmonicMinor,
+    MelodicMinor,
 }
 
 impl Scale {
-    pub const ALL: [Scale; 8] = [
+    /// Also the save-file index, so new scales go on the end.
+    pub const ALL: [Scale; 14] = [
         Self::Off,
         Self::Major,
         Self::Minor,
@@ -22,6 +30,12 @@ impl Scale {
         Self::Blues,
         Self::Dorian,
         Self::Mixolydian,
+        Self::Custom,
+        Self::Phrygian,
+        Self::Lydian,
+        Self::Locrian,
+        Self::HarmonicMinor,
+        Self::MelodicMinor,
     ];
 
     pub fn name(self) -> &'static str {
@@ -34,25 +48,18 @@ impl Scale {
             Self::Blues      => "Blues",
             Self::Dorian     => "Dorian",
             Self::Mixolydian => "Mixolydian",
+            Self::Custom     => "Custom",
+            Self::Phrygian   => "Phrygian",
+            Self::Lydian     => "Lydian",
+            Self::Locrian    => "Locrian",
+            Self::HarmonicMinor => "Harm Minor",
+            Self::MelodicMinor  => "Mel Minor",
         }
     }
 
-    /// Abbreviated name for the status bar.
-    pub fn short_name(self) -> &'static str {
-        match self {
-            Self::Off        => "Off",
-            Self::Major      => "Maj",
-            Self::Minor      => "Min",
-            Self::PentaMajor => "PMaj",
-            Self::PentaMinor => "PMin",
-            Self::Blues      => "Blues",
-            Self::Dorian     => "Dor",
-            Self::Mixolydian => "Mix",
-        }
-    }
-
-    /// Semitone intervals from the root note (root = 0).
-    pub fn intervals(self) -> &'static [u8] {
+    /// Semitone intervals from the root note (root = 0).  `Custom` has
+    /// none of its own: use `ScaleQuantizer::intervals`.
+    fn intervals(self) -> &'static [u8] {
         match self {
             Self::Off        => &[0,1,2,3,4,5,6,7,8,9,10,11],
             Self::Major      => &[0,2,4,5,7,9,11],
@@ -62,6 +69,12 @@ impl Scale {
             Self::Blues      => &[0,3,5,6,7,10],
             Self::Dorian     => &[0,2,3,5,7,9,10],
             Self::Mixolydian => &[0,2,4,5,7,9,10],
+            Self::Custom     => &[],
+            Self::Phrygian   => &[0,1,3,5,7,8,10],
+            Self::Lydian     => &[0,2,4,6,7,9,11],
+            Self::Locrian    => &[0,1,3,5,6,8,10],
+            Self::HarmonicMinor => &[0,2,3,5,7,8,11],
+            Self::MelodicMinor  => &[0,2,3,5,7,9,11],   // ascending form
         }
     }
 
@@ -71,16 +84,46 @@ impl Scale {
     }
 }
 
+/// Name of pitch class `pc` (0 = C … 11 = B).
+pub fn pitch_class_name(pc: u8) -> &'static str {
+    ["C","C#","D","D#","E","F","F#","G","G#","A","A#","B"][pc as usize % 12]
+}
+
 // ── Quantizer ─────────────────────────────────────────────────────────────────
 
+#[derive(Clone)]
 pub struct ScaleQuantizer {
     pub scale: Scale,
     pub root:  u8,   // 0 = C, 1 = C#, … 11 = B
+    /// Intervals of `Scale::Custom`: sorted, below 12, always holding the
+    /// root (0).  Set through `set_custom`.
+    custom:    Vec<u8>,
 }
 
 impl ScaleQuantizer {
     pub fn new() -> Self {
-        Self { scale: Scale::Off, root: 0 }
+        Self { scale: Scale::Off, root: 0, custom: Scale::Major.intervals().to_vec() }
+    }
+
+    /// Semitone intervals of the selected scale from the root.
+    pub fn intervals(&self) -> &[u8] {
+        match self.scale {
+            Scale::Custom => &self.custom,
+            s             => s.intervals(),
+        }
+    }
+
+    pub fn custom(&self) -> &[u8] {
+        &self.custom
+    }
+
+    /// Set the custom scale's intervals.  Out-of-range and repeated ones are
+    /// dropped, and the root is always kept.
+    pub fn set_custom(&mut self, intervals: &[u8]) {
+        let mut iv: Vec<u8> = std::iter::once(0).chain(intervals.iter().copied().filter(|&i| i < 12)).collect();
+        iv.sort_unstable();
+        iv.dedup();
+        self.custom = iv;
     }
 
     pub fn active(&self) -> bool {
@@ -91,7 +134,7 @@ impl ScaleQuantizer {
     /// When scale is Off, returns `note` unchanged.
     pub fn quantize(&self, note: u8) -> u8 {
         if self.scale == Scale::Off { return note; }
-        let intervals = self.scale.intervals();
+        let intervals = self.intervals();
         let root  = self.root as i32;
         let note  = note as i32;
 
@@ -115,11 +158,146 @@ impl ScaleQuantizer {
         (note + best_offset).clamp(0, 127) as u8
     }
 
+    /// Move `note` by `degrees` steps of the selected scale, so intervals stay
+    /// in key (+2 in Major turns C–E into E–G).  An out-of-scale note is
+    /// snapped first; with the scale Off this is a plain semitone shift.
+    /// Returns `None` if the result falls outside the MIDI range.
+    pub fn transpose_degrees(&self, note: u8, degrees: i32) -> Option<u8> {
+        if self.scale == Scale::Off {
+            let n = note as i32 + degrees;
+            return (0..=127).contains(&n).then_some(n as u8);
+        }
+        let intervals = self.intervals();
+        let len  = intervals.len() as i32;
+        let root = self.root as i32;
+        let rel  = self.quantize(note) as i32 - root;
+
+        let idx = intervals.iter().position(|&iv| iv as i32 == rel.rem_euclid(12))? as i32;
+        let degree = rel.div_euclid(12) * len + idx + degrees;
+        let n = root + degree.div_euclid(len) * 12 + intervals[degree.rem_euclid(len) as usize] as i32;
+        (0..=127).contains(&n).then_some(n as u8)
+    }
+
     pub fn root_name(&self) -> &'static str {
-        ["C","C#","D","D#","E","F","F#","G","G#","A","A#","B"][self.root as usize]
+        pitch_class_name(self.root)
     }
 
     pub fn cycle_root(&mut self) {
         self.root = (self.root + 1) % 12;
     }
 }
+
+// ── Chord memory ──────────────────────────────────────────────────────────────
+
+/// Chord a single key plays in chord mode.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChordShape { Triad, Major, Minor, Seventh, Sus4, Power }
+
+impl ChordShape {
+    pub const ALL: [ChordShape; 6] = [
+        Self::Triad, Self::Major, Self::Minor, Self::Seventh, Self::Sus4, Self::Power,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Triad   => "Triad",
+            Self::Major   => "Major",
+            Self::Minor   => "Minor",
+            Self::Seventh => "7th",
+            Self::Sus4    => "Sus4",
+            Self::Power   => "Power",
+        }
+    }
+
+    /// Semitones above the played note with no scale: major triad, major,
+    /// minor, dominant seventh, sus4, root-fifth-octave.
+    fn intervals(self) -> &'static [i32] {
+        match self {
+            Self::Triad   => &[0, 4, 7],
+            Self::Major   => &[0, 4, 7],
+            Self::Minor   => &[0, 3, 7],
+            Self::Seventh => &[0, 4, 7, 10],
+            Self::Sus4    => &[0, 5, 7],
+            Self::Power   => &[0, 7, 12],
+        }
+    }
+
+    /// Scale degrees above the played note when a scale is active, so the
+    /// chord's quality follows the key.  Major, minor and the power chord
+    /// keep their quality whatever the key.
+    fn degrees(self) -> Option<&'static [i32]> {
+        match self {
+            Self::Triad   => Some(&[0, 2, 4]),
+            Self::Seventh => Some(&[0, 2, 4, 6]),
+            Self::Sus4    => Some(&[0, 3, 4]),
+            Self::Major | Self::Minor | Self::Power => None,
+        }
+    }
+
+    /// Whether the chord is built from the active scale rather than fixed.
+    pub fn follows_scale(self) -> bool {
+        self.degrees().is_some()
+    }
+}
+
+impl ScaleQuantizer {
+    /// The notes of `shape` built up from `note`: stacked in the scale when
+    /// one is active (diatonic chords), else from fixed intervals.  Members
+    /// above the MIDI range are left out.
+    pub fn chord(&self, note: u8, shape: ChordShape) -> Vec<u8> {
+        match shape.degrees().filter(|_| self.active()) {
+            Some(degrees) => degrees.iter().filter_map(|&d| self.transpose_degrees(note, d)).collect(),
+            None => shape.intervals().iter()
+                .map(|&i| note as i32 + i)
+                .filter(|n| (0..=127).contains(n))
+                .map(|n| n as u8)
+                .collect(),
+        }
+    }
+}
+
+// ── Chord names ───────────────────────────────────────────────────────────────
+
+/// Chord shapes as pitch-class intervals from the root, with their suffix.
+const CHORDS: [(&[u8], &str); 14] = [
+    (&[0, 4, 7],     ""),
+    (&[0, 3, 7],     "m"),
+    (&[0, 3, 6],     "dim"),
+    (&[0, 4, 8],     "aug"),
+    (&[0, 2, 7],     "sus2"),
+    (&[0, 5, 7],     "sus4"),
+    (&[0, 4, 7, 10], "7"),
+    (&[0, 4, 7, 11], "maj7"),
+    (&[0, 3, 7, 10], "m7"),
+    (&[0, 3, 7, 11], "m(maj7)"),
+    (&[0, 3, 6, 10], "m7b5"),
+    (&[0, 3, 6, 9],  "dim7"),
+    (&[0, 5, 7, 10], "7sus4"),
+    (&[0, 4, 7, 9],  "6"),
+];
+
+/// Name of the chord `notes` spell (`"Cmaj7"`), in any octave or inversion.
+/// `None` for fewer than three distinct pitch classes, shapes not in the
+/// table, and ambiguous ones: when the notes spell several chords (C6 is
+/// also Am7; dim7 and aug are symmetric), the one rooted on the lowest note
+/// wins, and if none is there is no answer.
+pub fn chord_name(notes: &[u8]) -> Option<String> {
+    let classes: u16 = notes.iter().fold(0, |m, &n| m | 1 << (n % 12));
+    if classes.count_ones() < 3 { return None; }
+    let bass = notes.iter().min()? % 12;
+    let matches: Vec<(u8, &str)> = (0..12u8)
+        .filter_map(|root| {
+            let shape: u16 = (0..12).filter(|i| classes & 1 << ((root + i) % 12) != 0)
+                .fold(0, |m, i| m | 1 << i);
+            CHORDS.iter()
+                .find(|(ivs, _)| ivs.iter().fold(0u16, |m, &i| m | 1 << i) == shape)
+                .map(|&(_, suffix)| (root, suffix))
+        })
+        .collect();
+    let (root, suffix) = match matches[..] {
+        [one] => one,
+        _     => *matches.iter().find(|(r, _)| *r == bass)?,
+    };
+    let name = ["C","C#","D","D#","E","F","F#","G","G#","A","A#","B"][root as usize];
+    Some(format!("{}{}", name, suffix))
+}
@@ -1,89 +1,621 @@
+use crate::scale::ScaleQuantizer;
+use crate::synth::WaveType;
+
+/// Musical position of the shared master clock, in 16th-note steps.
+///
+/// `Synth` advances it by `bpm * 4 / (60 * sample_rate)` every sample, so a
+/// tempo change only alters the rate of travel — the position itself never
+/// jumps, and no step is skipped or repeated.
+#[derive(Clone, Copy, Debug)]
+pub struct StepClock {
+    /// Position at the start of this sample.
+    pub prev: f64,
+    /// Position at the start of the next sample.
+    pub pos:  f64,
+}
+
+impl StepClock {
+    /// True when musical position `at` falls inside this sample (`prev <= at < pos`).
+    #[inline]
+    pub fn crosses(&self, at: f64) -> bool {
+        self.prev <= at && at < self.pos
+    }
+
+    /// Absolute index of the step the playhead is in.
+    #[inline]
+    pub fn step(&self) -> u64 {
+        self.prev.floor() as u64
+    }
+}
+
+/// Shape of the groove: how late each step of a repeating cell fires,
+/// as a fraction of `Groove::amount`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GrooveTemplate {
+    /// Odd 16ths late — the classic drum-machine swing.
+    Swing16,
+    /// Only the off-beat 8th (third 16th of each beat) late.
+    Swing8,
+    /// Each beat drags progressively: 0, ½, 1, ½.
+    Lazy,
+}
+
+impl GrooveTemplate {
+    pub const ALL: [GrooveTemplate; 3] = [Self::Swing16, Self::Swing8, Self::Lazy];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Swing16 => "16th",
+            Self::Swing8  => "8th",
+            Self::Lazy    => "Lazy",
+        }
+    }
+
+    fn cell(self) -> &'static [f32] {
+        match self {
+            Self::Swing16 => &[0.0, 1.0],
+            Self::Swing8  => &[0.0, 0.0, 1.0, 0.0],
+            Self::Lazy    => &[0.0, 0.5, 1.0, 0.5],
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&t| t == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// Order a melodic sequencer plays its steps in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Direction {
+    Forward,
+    Reverse,
+    /// Forward then back, without playing the end steps twice.
+    PingPong,
+    /// A pseudo-random step each time.
+    Random,
+}
+
+impl Direction {
+    pub const ALL: [Direction; 4] = [Self::Forward, Self::Reverse, Self::PingPong, Self::Random];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Forward  => "Fwd",
+            Self::Reverse  => "Rev",
+            Self::PingPong => "Ping-pong",
+            Self::Random   => "Random",
+        }
+    }
+
+    pub fn next(self) -> Self {
+        let i = Self::ALL.iter().position(|&d| d == self).unwrap_or(0);
+        Self::ALL[(i + 1) % Self::ALL.len()]
+    }
+}
+
+/// Timing feel shared by the drum machine and (unless `melodic` is off)
+/// both melodic sequencers, so everything shuffles together.
+#[derive(Clone, Copy, Debug)]
+pub struct Groove {
+    pub template: GrooveTemplate,
+    /// 0.0 = straight, ~0.33 = shuffle, 0.5 = maximum.
+    pub amount:   f32,
+    /// Apply to the melodic sequencers too; off = drums-only swing.
+    pub melodic:  bool,
+}
+
+impl Groove {
+    pub const STRAIGHT: Groove = Groove { template: GrooveTemplate::Swing16, amount: 0.0, melodic: false };
+
+    /// Musical position (in steps) at which absolute step `k` fires.  Always
+    /// within `k..k + 0.5`, so steps never overtake each other.
+    #[inline]
+    pub fn fire_pos(&self, k: u64) -> f64 {
+        let cell = self.template.cell();
+        k as f64 + (cell[k as usize % cell.len()] * self.amount) as f64
+    }
+}
+
+/// Finest per-step timing offset, as a fraction of a step.
+pub const NUDGE_TICKS_PER_STEP: f64 = 24.0;
+
+/// Largest per-step nudge either way, in ticks (a quarter of a step).
+pub const MAX_NUDGE: i8 = 6;
+
+/// A nudge of `ticks` in steps.
+#[inline]
+pub fn nudge_steps(ticks: i8) -> f64 {
+    ticks as f64 / NUDGE_TICKS_PER_STEP
+}
+
+/// A nudge as shown in the status line, e.g. `-2/24` (of a step).
+pub fn nudge_label(ticks: i8) -> String {
+    if ticks == 0 { "none".to_string() } else { format!("{:+}/{}", ticks, NUDGE_TICKS_PER_STEP) }
+}
+
+/// Move a nudge by `delta` ticks, within ±`MAX_NUDGE`.  Returns the new nudge.
+pub fn nudge_by(nudge: Option<&mut i8>, delta: i8) -> i8 {
+    match nudge {
+        Some(n) => { *n = (*n + delta).clamp(-MAX_NUDGE, MAX_NUDGE); *n }
+        None    => 0,
+    }
+}
+
 /// An event fired when the sequencer crosses a step boundary.
 pub struct StepEvent {
-    pub note_off: Option<u8>,
-    pub note_on:  Option<u8>,
+    /// Notes to release: the last step's root and any chord notes.
+    pub note_off: Vec<u8>,
+    /// Notes to start, root first.
+    pub note_on:  Vec<u8>,
+    /// The last step slides: `note_off` keeps sounding and glides into
+    /// `note_on` (note by note, root to root) rather than being released
+    /// and retriggered.
+    pub slide:    bool,
+    /// The step whose note just fired is accented.
+    pub accent:   bool,
+    /// Level of `note_on`, 0–1: 1 unless velocity humanize is on.
+    pub velocity: f32,
+    /// Locks of the step whose note just fired (none for a rest or a muted step).
+    pub lock:     PLock,
+}
+
+/// A step's parameter locks: values its synth takes while the step's note
+/// plays, in place of the panel settings.  `None` = not locked.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PLock {
+    /// The synth's bus filter cutoff, Hz.
+    pub cutoff: Option<f32>,
+    pub wave:   Option<WaveType>,
+    pub volume: Option<f32>,
+}
+
+impl PLock {
+    pub const NONE: PLock = PLock { cutoff: None, wave: None, volume: None };
+
+    pub fn is_empty(&self) -> bool {
+        *self == Self::NONE
+    }
 }
 
 /// Sample-accurate melodic step sequencer.
 ///
-/// BPM is **not** stored here — it is passed to `tick()` every sample from
-/// `Synth::bpm` so the melodic and drum sequencers always share one master clock.
+/// Neither BPM nor a clock is stored here — the shared `StepClock` and
+/// `Groove` are passed to `tick()` every sample from `Synth` so the melodic and
+/// drum sequencers always follow one master clock.
+#[derive(Clone)]
 pub struct Sequencer {
     pub steps:        Vec<Option<u8>>,
+    /// Chord notes each step plays above its root, in semitones (empty for
+    /// a single note).  Same length as `steps`.
+    pub chord:        Vec<Vec<u8>>,
+    /// Muted steps keep their note but don't trigger.  Same length as `steps`.
+    pub muted:        Vec<bool>,
+    /// Per-step octave shift (`-MAX_OCTAVE..=MAX_OCTAVE`) applied on playback.
+    pub octave:       Vec<i8>,
+    /// Per-step parameter locks.  Same length as `steps`.
+    pub locks:        Vec<PLock>,
+    /// Per-step timing offset in ticks (`-MAX_NUDGE..=MAX_NUDGE`), on top
+    /// of the groove: negative = early.
+    pub nudge:        Vec<i8>,
+    /// Per-step 303-style slide: the step's note glides into the next
+    /// note instead of ending.  Same length as `steps`.
+    pub slide:        Vec<bool>,
+    /// Per-step accent, played louder and brighter by synth 1's acid mode.
+    /// Same length as `steps`.
+    pub accent:       Vec<bool>,
+    /// A step repeating the note still sounding holds it instead of
+    /// releasing and re-attacking it.
+    pub tie_repeats:  bool,
+    /// How far each note's velocity may fall at random below full,
+    /// 0 (exact) to `VEL_HUMANIZE_MAX`.
+    pub vel_humanize: f32,
+    /// Order of play; see `step_at()`.
+    pub direction:    Direction,
     pub num_steps:    usize,
     pub current_step: usize,
     pub playing:      bool,
+    /// Notes actually triggered by the last step, for their note-offs.
+    /// Tracked rather than recomputed so editing a sounding step can't
+    /// strand them.
+    sounding:         Vec<u8>,
+    /// `sounding` came from a slide step.
+    slide_out:        bool,
+    /// XOR-shift state for velocity humanize.
+    seed:             u32,
+    /// A step whose trigger landed in the same sample as the one fired,
+    /// played on the next sample.  Nudges can bring two steps together
+    /// (a late swung step and an early next one) but never past each other.
+    deferred:         Option<u64>,
+}
 
-    sample_rate: f32,
+/// A sequencer's per-step data without its settings or playback state:
+/// what `Sequencer::clear()` removes and `restore()` puts back.
+pub struct SeqPattern {
+    steps:  Vec<Option<u8>>,
+    chord:  Vec<Vec<u8>>,
+    muted:  Vec<bool>,
+    octave: Vec<i8>,
+    locks:  Vec<PLock>,
+    nudge:  Vec<i8>,
+    slide:  Vec<bool>,
+    accent: Vec<bool>,
 }
 
+/// Largest per-step octave shift either way.
+pub const MAX_OCTAVE: i8 = 2;
+
+/// Widest a step's chord may reach above its root, in semitones.
+pub const MAX_CHORD_SPAN: u8 = 24;
+
+/// Longest pattern of any step sequencer, melodic or drum.
+pub const MAX_STEPS: usize = 64;
+
+/// The pattern length after `n` in the steps cycle:
+/// 8 → 16 → 24 → 32 → 48 → 64 → 8.
+pub fn next_step_count(n: usize) -> usize {
+    match n { 8 => 16, 16 => 24, 24 => 32, 32 => 48, 48 => MAX_STEPS, _ => 8 }
+}
+
+/// `root` and the chord notes `above` it (semitones), root first; notes
+/// past the MIDI range are left out.
+pub fn stack_notes(root: u8, above: &[u8]) -> Vec<u8> {
+    std::iter::once(root)
+        .chain(above.iter().filter_map(|&i| root.checked_add(i).filter(|&n| n <= 127)))
+        .collect()
+}
+
+/// Largest velocity humanize: notes land anywhere from half to full level.
+pub const VEL_HUMANIZE_MAX: f32 = 0.5;
+
 impl Sequencer {
-    pub fn new(sample_rate: f32) -> Self {
+    pub fn new() -> Self {
         Self {
             steps:        vec![None; 16],
+            chord:        vec![Vec::new(); 16],
+            muted:        vec![false; 16],
+            octave:       vec![0; 16],
+            locks:        vec![PLock::NONE; 16],
+            nudge:        vec![0; 16],
+            slide:        vec![false; 16],
+            accent:       vec![false; 16],
+            tie_repeats:  false,
+            vel_humanize: 0.0,
+            direction:    Direction::Forward,
             num_steps:    16,
             current_step: 0,
             playing:      false,
-            sample_rate,
+            sounding:     Vec::new(),
+            slide_out:    false,
+            seed:         0x2545_f491,
+            deferred:     None,
         }
     }
 
-    fn samples_per_step(&self, bpm: f32) -> u64 {
-        ((self.sample_rate * 60.0) / (bpm * 4.0)).round() as u64
-    }
-
     /// Called once per audio sample with the shared master clock.
-    /// Returns `Some(StepEvent)` on step boundaries.
-    pub fn tick(&mut self, bpm: f32, clock: u64) -> Option<StepEvent> {
-        if !self.playing { return None; }
-
-        let sps = self.samples_per_step(bpm).max(1);
-        let step_idx = (clock / sps) as usize % self.num_steps;
-        let phase_in = clock % sps;
+    /// Returns `Some(StepEvent)` when a step fires.  With `scale`, the
+    /// step's notes are snapped to it as they play; the stored notes are kept.
+    pub fn tick(&mut self, clock: StepClock, groove: Groove, scale: Option<&ScaleQuantizer>) -> Option<StepEvent> {
+        if !self.playing { self.deferred = None; return None; }
+        self.current_step = self.step_at(clock.step());
 
+        // A grooved, nudged step fires up to three quarters of a step late or
+        // a quarter early, so the step whose trigger lands in this sample is
+        // the current one or either neighbour.  If two do, the later waits
+        // a sample.
+        let here = clock.pos.floor() as u64;
+        let mut due = self.deferred.take();
+        for k in [here.checked_sub(1), Some(here), Some(here + 1)].into_iter().flatten() {
+            let nudge = nudge_steps(self.nudge.get(self.step_at(k)).copied().unwrap_or(0));
+            if clock.crosses(groove.fire_pos(k) + nudge) {
+                if due.is_none() { due = Some(k); } else { self.deferred = Some(k); }
+            }
+        }
+        let k = due?;
+        let step_idx = self.step_at(k);
         self.current_step = step_idx;
+        let note_off = std::mem::take(&mut self.sounding);
+        if !self.muted[step_idx] { self.sounding = self.played_notes(step_idx); }
+        if let Some(q) = scale {
+            for n in &mut self.sounding { *n = q.quantize(*n); }
+            // Chord notes snapped onto one pitch play it once.
+            let mut seen = Vec::with_capacity(self.sounding.len());
+            self.sounding.retain(|n| if seen.contains(n) { false } else { seen.push(*n); true });
+        }
+        let on = !self.sounding.is_empty();
+        let lock = if on { self.locks[step_idx] } else { PLock::NONE };
+        if self.tie_repeats && on && note_off == self.sounding {
+            // Same notes again: no off/on, the voices just carry on.
+            self.slide_out = self.slide[step_idx];
+            return Some(StepEvent { note_off: Vec::new(), note_on: Vec::new(), slide: false, accent: false, velocity: 1.0, lock });
+        }
+        // A slide needs a note on both sides; into a rest it just ends.
+        let slide = self.slide_out && !note_off.is_empty() && on;
+        self.slide_out = on && self.slide[step_idx];
+        let accent = on && self.accent[step_idx];
+        let velocity = self.velocity();
+        Some(StepEvent { note_off, note_on: self.sounding.clone(), slide, accent, velocity, lock })
+    }
 
-        if phase_in == 0 {
-            let prev = if step_idx == 0 { self.num_steps - 1 } else { step_idx - 1 };
-            Some(StepEvent {
-                note_off: self.steps[prev],
-                note_on:  self.steps[step_idx],
-            })
-        } else {
-            None
+    /// The pattern step played at absolute clock step `k`.  Derived from
+    /// the clock alone, with no state carried between steps, so every
+    /// direction stays on the master clock and a relocate lands where it
+    /// should; Random hashes `k` with an XOR-shift.
+    pub fn step_at(&self, k: u64) -> usize {
+        let n = self.num_steps.max(1);
+        match self.direction {
+            Direction::Forward  => k as usize % n,
+            Direction::Reverse  => n - 1 - k as usize % n,
+            Direction::PingPong => {
+                let m = k as usize % self.loop_steps();
+                if m < n { m } else { 2 * (n - 1) - m }
+            }
+            Direction::Random => {
+                let mut x = (k as u32 ^ (k >> 32) as u32).wrapping_mul(0x9e37_79b9) ^ 0x2545_f491;
+                for _ in 0..2 {
+                    x ^= x << 13;
+                    x ^= x >> 17;
+                    x ^= x << 5;
+                }
+                (x >> 8) as usize % n
+            }
         }
     }
 
-    /// Toggle play/pause.  Returns the note currently held (for note-off).
-    pub fn toggle_play(&mut self) -> Option<u8> {
-        self.playing = !self.playing;
-        if self.playing {
-            None
-        } else {
-            self.steps.get(self.current_step).copied().flatten()
+    /// Steps before the order of play repeats: `2n - 2` in ping-pong, else
+    /// `n` (Random never repeats; a freeze takes `n` of its steps).
+    pub fn loop_steps(&self) -> usize {
+        match self.direction {
+            Direction::PingPong if self.num_steps > 1 => 2 * self.num_steps - 2,
+            _ => self.num_steps.max(1),
         }
     }
 
+    /// Velocity of the next note: full, or at random down to
+    /// `1 - vel_humanize` when humanize is on.
+    fn velocity(&mut self) -> f32 {
+        if self.vel_humanize <= 0.0 { return 1.0; }
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        1.0 - self.vel_humanize * ((self.seed >> 8) as f32 / (1u32 << 24) as f32)
+    }
+
+    /// The notes step `idx` plays, root first: its stored notes shifted by
+    /// its octave offset, or unshifted if the root would leave the MIDI
+    /// range.  Chord notes above 127 are left out.
+    pub fn played_notes(&self, idx: usize) -> Vec<u8> {
+        let Some(note) = self.steps.get(idx).copied().flatten() else { return Vec::new() };
+        let shifted = note as i32 + 12 * self.octave.get(idx).copied().unwrap_or(0) as i32;
+        let root = if (0..=127).contains(&shifted) { shifted as u8 } else { note };
+        stack_notes(root, self.chord.get(idx).map_or(&[], |c| c.as_slice()))
+    }
+
+    /// Toggle play/pause.  Returns the notes currently held (for note-off).
+    pub fn toggle_play(&mut self) -> Vec<u8> {
+        self.playing = !self.playing;
+        std::mem::take(&mut self.sounding)
+    }
+
+    /// Hand back the notes currently held (for note-off) without pausing.
+    pub fn release(&mut self) -> Vec<u8> {
+        std::mem::take(&mut self.sounding)
+    }
+
     #[allow(dead_code)]
-    pub fn stop(&mut self) -> Option<u8> {
-        let note = if self.playing { self.steps.get(self.current_step).copied().flatten() } else { None };
+    pub fn stop(&mut self) -> Vec<u8> {
         self.playing      = false;
         self.current_step = 0;
-        note
+        std::mem::take(&mut self.sounding)
     }
 
     pub fn cycle_num_steps(&mut self) {
-        let next = match self.num_steps { 8 => 16, 16 => 24, 24 => 32, _ => 8 };
+        let next = next_step_count(self.num_steps);
         self.num_steps = next;
         self.steps.resize(next, None);
+        self.chord.resize(next, Vec::new());
+        self.muted.resize(next, false);
+        self.octave.resize(next, 0);
+        self.locks.resize(next, PLock::NONE);
+        self.nudge.resize(next, 0);
+        self.slide.resize(next, false);
+        self.accent.resize(next, false);
         if self.current_step >= next { self.current_step = 0; }
     }
 
-    pub fn set_step(&mut self, step: usize, note: u8) {
-        if step < self.steps.len() { self.steps[step] = Some(note); }
+    /// Write `notes` on a step: the first is its root, any others chord
+    /// notes above it (ones not within `MAX_CHORD_SPAN` above are dropped).
+    pub fn set_step(&mut self, step: usize, notes: &[u8]) {
+        let Some(&root) = notes.first() else { return };
+        if step < self.steps.len() {
+            self.steps[step] = Some(root);
+            self.chord[step] = notes[1..].iter()
+                .filter_map(|&n| n.checked_sub(root).filter(|i| (1..=MAX_CHORD_SPAN).contains(i)))
+                .collect();
+        }
+    }
+
+    /// Write `notes` (a root and any chord notes), played live at master
+    /// clock position `pos`, on the nearest step, with the rest of their
+    /// timing kept as a nudge (within ±`MAX_NUDGE`).  Anything else on the
+    /// step is cleared.
+    pub fn record(&mut self, pos: f64, notes: &[u8]) {
+        let nearest = pos.round();
+        let step = self.step_at(nearest.max(0.0) as u64);
+        self.clear_step(step);
+        self.set_step(step, notes);
+        let ticks = ((pos - nearest) * NUDGE_TICKS_PER_STEP).round() as i8;
+        self.nudge[step] = ticks.clamp(-MAX_NUDGE, MAX_NUDGE);
+    }
+
+    /// Pull every note's nudge towards its step by `strength` (0 = leave
+    /// it, 1 = right onto the grid), so a loose take tightens up but keeps
+    /// some of its feel below 1.  Only the stored steps change.
+    pub fn quantize_to_grid(&mut self, strength: f32) {
+        let keep = 1.0 - strength.clamp(0.0, 1.0);
+        for (nudge, step) in self.nudge.iter_mut().zip(&self.steps) {
+            if step.is_some() { *nudge = (*nudge as f32 * keep).round() as i8; }
+        }
     }
 
     pub fn clear_step(&mut self, step: usize) {
-        if step < self.steps.len() { self.steps[step] = None; }
+        if step < self.steps.len() {
+            self.steps[step]  = None;
+            self.chord[step].clear();
+            self.muted[step]  = false;
+            self.octave[step] = 0;
+            self.locks[step]  = PLock::NONE;
+            self.nudge[step]  = 0;
+            self.slide[step]  = false;
+            self.accent[step] = false;
+        }
+    }
+
+    /// Empty every step, keeping the length and the sequencer's settings.
+    /// Returns the steps as they were, for `restore()`.
+    pub fn clear(&mut self) -> SeqPattern {
+        let n = self.num_steps;
+        SeqPattern {
+            steps:  std::mem::replace(&mut self.steps,  vec![None; n]),
+            chord:  std::mem::replace(&mut self.chord,  vec![Vec::new(); n]),
+            muted:  std::mem::replace(&mut self.muted,  vec![false; n]),
+            octave: std::mem::replace(&mut self.octave, vec![0; n]),
+            locks:  std::mem::replace(&mut self.locks,  vec![PLock::NONE; n]),
+            nudge:  std::mem::replace(&mut self.nudge,  vec![0; n]),
+            slide:  std::mem::replace(&mut self.slide,  vec![false; n]),
+            accent: std::mem::replace(&mut self.accent, vec![false; n]),
+        }
+    }
+
+    /// Put back steps taken by `clear()`, cut or padded to the current length.
+    pub fn restore(&mut self, p: SeqPattern) {
+        let n = self.num_steps;
+        self.steps  = p.steps;  self.steps.resize(n, None);
+        self.chord  = p.chord;  self.chord.resize(n, Vec::new());
+        self.muted  = p.muted;  self.muted.resize(n, false);
+        self.octave = p.octave; self.octave.resize(n, 0);
+        self.locks  = p.locks;  self.locks.resize(n, PLock::NONE);
+        self.nudge  = p.nudge;  self.nudge.resize(n, 0);
+        self.slide  = p.slide;  self.slide.resize(n, false);
+        self.accent = p.accent; self.accent.resize(n, false);
+    }
+
+    /// Steps holding a note.
+    pub fn filled(&self) -> usize {
+        self.steps.iter().filter(|s| s.is_some()).count()
+    }
+
+    /// Flip a step's mute flag.  Returns the new state.
+    pub fn toggle_mute(&mut self, step: usize) -> bool {
+        match self.muted.get_mut(step) {
+            Some(m) => { *m = !*m; *m }
+            None    => false,
+        }
+    }
+
+    /// Flip a step's slide flag.  Returns the new state.
+    pub fn toggle_slide(&mut self, step: usize) -> bool {
+        match self.slide.get_mut(step) {
+            Some(s) => { *s = !*s; *s }
+            None    => false,
+        }
+    }
+
+    /// Flip a step's accent.  Returns the new state.
+    pub fn toggle_accent(&mut self, step: usize) -> bool {
+        match self.accent.get_mut(step) {
+            Some(a) => { *a = !*a; *a }
+            None    => false,
+        }
+    }
+
+    /// Move a step's octave shift by `delta`, within ±`MAX_OCTAVE`.
+    /// Returns the new shift.
+    pub fn shift_octave(&mut self, step: usize, delta: i8) -> i8 {
+        match self.octave.get_mut(step) {
+            Some(o) => { *o = (*o + delta).clamp(-MAX_OCTAVE, MAX_OCTAVE); *o }
+            None    => 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SAMPLE_RATE: f64 = 44_100.0;
+
+    /// Run `seq` for `secs` seconds with the tempo at each moment given by
+    /// `bpm(t)`, advancing the clock the way `Synth` does.  Returns every
+    /// event fired and where the clock ended.
+    fn run(seq: &mut Sequencer, groove: Groove, secs: f64, bpm: impl Fn(f64) -> f64) -> (Vec<StepEvent>, f64) {
+        let mut events = Vec::new();
+        let mut pos = 0.0;
+        for i in 0..(secs * SAMPLE_RATE) as usize {
+            let prev = pos;
+            pos += bpm(i as f64 / SAMPLE_RATE) * 4.0 / (60.0 * SAMPLE_RATE);
+            let clock = StepClock { prev, pos };
+            if let Some(ev) = seq.tick(clock, groove, None) { events.push(ev); }
+        }
+        (events, pos)
+    }
+
+    #[test]
+    fn tempo_ramp_fires_every_step_once_in_order() {
+        let swing = Groove { template: GrooveTemplate::Swing16, amount: 0.5, melodic: true };
+        for groove in [Groove::STRAIGHT, swing] {
+            let mut seq = Sequencer::new();
+            for i in 0..seq.num_steps { seq.set_step(i, &[60 + i as u8]); }
+            seq.playing = true;
+            // 60 → 300 BPM over 10 s, then back down to 60.
+            let ramp = |t: f64| if t < 10.0 { 60.0 + 24.0 * t } else { 300.0 - 24.0 * (t - 10.0) };
+            let (events, end) = run(&mut seq, groove, 20.0, ramp);
+
+            let due = (0..).take_while(|&k| groove.fire_pos(k) < end).count();
+            assert_eq!(events.len(), due);
+            for (k, ev) in events.iter().enumerate() {
+                assert_eq!(ev.note_on, vec![60 + (k % 16) as u8], "event {} out of order", k);
+            }
+        }
+    }
+
+    #[test]
+    fn nudges_meeting_in_one_sample_fire_both_steps() {
+        let swing = Groove { template: GrooveTemplate::Swing16, amount: 0.5, melodic: true };
+        let mut seq = Sequencer::new();
+        for i in 0..seq.num_steps { seq.set_step(i, &[60 + i as u8]); }
+        // Step 1 swings to 1.5 and +6/24 takes it to 1.75, where -6/24 puts step 2.
+        seq.nudge[1] = MAX_NUDGE;
+        seq.nudge[2] = -MAX_NUDGE;
+        seq.playing = true;
+        let (events, end) = run(&mut seq, swing, 2.5, |_| 120.0);
+        assert!(end > 16.0);
+        let notes: Vec<u8> = events.iter().take(16).map(|ev| ev.note_on[0]).collect();
+        assert_eq!(notes, (60..76).collect::<Vec<u8>>());
+    }
+
+    #[test]
+    fn tied_repeat_holds_the_voice() {
+        let mut seq = Sequencer::new();
+        seq.set_step(0, &[60]);
+        seq.set_step(1, &[60]);
+        seq.tie_repeats = true;
+        seq.playing = true;
+        let (events, _) = run(&mut seq.clone(), Groove::STRAIGHT, 0.3, |_| 120.0);
+        assert_eq!(events[0].note_on, vec![60]);
+        assert!(events[1].note_on.is_empty());
+        assert!(events[1].note_off.is_empty());
+
+        let mut synth = crate::synth::Synth::new(SAMPLE_RATE as f32);
+        synth.sequencer = seq;
+        // Into step 1 at 120 BPM (8 steps a second), before step 2 ends the
+        // note.  A retrigger would restart the envelope from silence.
+        let mut lowest = f32::MAX;
+        for i in 0..(0.2 * SAMPLE_RATE) as usize {
+            synth.generate_frame();
+            if i > (0.05 * SAMPLE_RATE) as usize { lowest = lowest.min(synth.voices[&60].level); }
+        }
+        assert_eq!(synth.voices.len(), 1);
+        assert!(lowest > 0.1, "voice dipped to {}", lowest);
     }
 }
@@ -0,0 +1,64 @@
+use std::collections::VecDeque;
+
+use crate::fft::{fft, hann};
+
+/// Frequency bands per row, log-spaced from `LOW_HZ` to `HIGH_HZ`.
+pub const BANDS: usize = 64;
+const LOW_HZ: f32 = 40.0;
+const HIGH_HZ: f32 = 16_000.0;
+
+/// Rows kept: two per line of a 10-line panel.
+pub const ROWS: usize = 20;
+
+/// Level mapped to the bottom of the colour scale, in dB below full scale.
+const FLOOR_DB: f32 = -72.0;
+
+/// Scrolling history of the output's spectrum, one row of band levels
+/// (0 at `FLOOR_DB` or below, 1 at full scale) per `push()`, oldest first.
+pub struct Spectrogram {
+    pub rows: VecDeque<[f32; BANDS]>,
+    window:   Vec<f32>,
+    re:       Vec<f32>,
+    im:       Vec<f32>,
+}
+
+impl Spectrogram {
+    pub fn new() -> Self {
+        Self { rows: VecDeque::with_capacity(ROWS + 1), window: Vec::new(), re: Vec::new(), im: Vec::new() }
+    }
+
+    /// Analyse `samples` (a power-of-two count, e.g. the scope window) and
+    /// add their spectrum as the newest row.  Each band takes the loudest
+    /// bin it covers, or the nearest bin where bands are narrower than one.
+    pub fn push(&mut self, samples: &[f32], sample_rate: f32) {
+        let n = samples.len();
+        if n < 2 || !n.is_power_of_two() { return; }
+        if self.window.len() != n { self.window = hann(n); }
+        self.re.clear();
+        self.re.extend(samples.iter().zip(&self.window).map(|(s, w)| s * w));
+        self.im.clear();
+        self.im.resize(n, 0.0);
+        fft(&mut self.re, &mut self.im, false);
+
+        // A full-scale sine peaks at n/4 under the Hann window.
+        let scale = 4.0 / n as f32;
+        let bin_hz = sample_rate / n as f32;
+        let last = n / 2 - 1;
+        let mag = |k: usize| (self.re[k] * self.re[k] + self.im[k] * self.im[k]).sqrt() * scale;
+        let edge = |b: usize| LOW_HZ * (HIGH_HZ / LOW_HZ).powf(b as f32 / BANDS as f32) / bin_hz;
+
+        let mut row = [0.0; BANDS];
+        for (b, out) in row.iter_mut().enumerate() {
+            let lo = (edge(b).round() as usize).clamp(1, last);
+            let hi = (edge(b + 1).round() as usize).clamp(lo, last);
+            let peak = (lo..=hi).map(mag).fold(0.0f32, f32::max);
+            *out = if peak > 0.0 { (1.0 - 20.0 * peak.log10() / FLOOR_DB).clamp(0.0, 1.0) } else { 0.0 };
+        }
+        self.rows.push_back(row);
+        while self.rows.len() > ROWS { self.rows.pop_front(); }
+    }
+
+    pub fn clear(&mut self) {
+        self.rows.clear();
+    }
+}
@@ -1,26 +1,55 @@
 use std::collections::HashMap;
 use std::f32::consts::PI;
 
+use crate::acid::Acid;
+use crate::arp::Arpeggiator;
+use crate::automation::Playback;
 use crate::drums::DrumMachine;
-use crate::effects::{AudioEffect, BiquadFilter, Delay, Distortion, EffectChain, Reverb};
-use crate::sequencer::Sequencer;
+use crate::effects::{flush_denormal, AudioEffect, BiquadFilter, Bitcrusher, Chorus, Comb, Compressor, DeEsser, Delay, Distortion, EffectChain,
+                     FormantFilter, InsertKind, Reverb, SpectralFreeze};
+use crate::freeze::{Bus, Frozen};
+use crate::granular::{GrainCloud, Granular};
+use crate::lfo::{Lfo, LfoTarget};
+use crate::metronome::Metronome;
+use crate::midi::{CcParam, ClockMsg, ExtClock, MidiOut, NoteRouting};
+use crate::scale::ScaleQuantizer;
+use crate::sequencer::{Groove, GrooveTemplate, PLock, Sequencer, StepClock, StepEvent};
+use crate::wavetable::{Wavetable, ADDITIVE_TABLE, DEFAULT_HARMONICS, HARMONICS};
 
 // ── Waveform ──────────────────────────────────────────────────────────────────
 
 #[derive(Clone, Copy, Debug, PartialEq)]
-pub enum WaveType { Sine, Square, Sawtooth, Triangle }
+pub enum WaveType {
+    Sine, Square, Sawtooth, Triangle,
+    /// Index into `Synth::wavetables`.
+    Wavetable(usize),
+    /// Grains of `Synth::granular`'s sample.  Chosen from Sample focus, not
+    /// in the F1 / F5 cycle.
+    Granular,
+    /// `Synth::granular`'s sample played once from the start, transposed
+    /// from its root.  Chosen from Sample focus too.
+    Sampler,
+}
 
 impl WaveType {
-    pub fn next(self) -> Self {
+    /// Cycle through the basic waves, then each of the `tables` wavetables.
+    pub fn next(self, tables: usize) -> Self {
         match self {
             Self::Sine => Self::Square, Self::Square => Self::Sawtooth,
-            Self::Sawtooth => Self::Triangle, Self::Triangle => Self::Sine,
+            Self::Sawtooth => Self::Triangle,
+            Self::Triangle if tables > 0 => Self::Wavetable(0),
+            Self::Wavetable(i) if i + 1 < tables => Self::Wavetable(i + 1),
+            Self::Triangle | Self::Wavetable(_) | Self::Granular | Self::Sampler => Self::Sine,
         }
     }
+    /// Display name; wavetables are named via `Synth::wave_name`.
     pub fn name(self) -> &'static str {
         match self {
             Self::Sine => "Sine", Self::Square => "Square",
             Self::Sawtooth => "Sawtooth", Self::Triangle => "Triangle",
+            Self::Wavetable(_) => "Table",
+            Self::Granular => "Granular",
+            Self::Sampler  => "Sampler",
         }
     }
 }
@@ -39,12 +68,28 @@ pub struct Voice {
     pub stage:         EnvelopeStage,
     pub level:         f32,
     pub release_level: f32,
+    /// Grain state, used on `WaveType::Granular`.
+    grains:            GrainCloud,
+    /// Read position in the sample on `WaveType::Sampler`, in its samples.
+    pub sample_pos:    f64,
+    /// A slide in progress: the target frequency and the factor applied
+    /// to `frequency` each sample until it gets there.
+    glide:             Option<(f32, f32)>,
+    /// Level of the note, 0–1, on top of the envelope.
+    pub velocity:      f32,
 }
 
 impl Voice {
     pub fn new(note: u8) -> Self {
         Self { frequency: note_to_freq(note), phase: 0.0,
-               stage: EnvelopeStage::Attack, level: 0.0, release_level: 0.0 }
+               stage: EnvelopeStage::Attack, level: 0.0, release_level: 0.0,
+               grains: GrainCloud::new(note), sample_pos: 0.0, glide: None, velocity: 1.0 }
+    }
+
+    /// Glide from `from` Hz to `to` Hz over `samples`, evenly in pitch.
+    pub fn glide(&mut self, from: f32, to: f32, samples: f32) {
+        self.frequency = from;
+        self.glide = Some((to, (to / from).powf(1.0 / samples.max(1.0))));
     }
 
     pub fn release(&mut self) {
@@ -56,8 +101,9 @@ impl Voice {
 
     pub fn is_finished(&self) -> bool { self.stage == EnvelopeStage::Off }
 
-    pub fn next_sample(&mut self, sr: f32, wave: WaveType,
-                       attack: f32, decay: f32, sustain: f32, release: f32) -> f32 {
+    #[allow(clippy::too_many_arguments)]
+    pub fn next_sample(&mut self, sr: f32, wave: WaveType, tables: &[Wavetable], granular: &Granular,
+                       band_limited: bool, attack: f32, decay: f32, sustain: f32, release: f32) -> f32 {
         let dt = 1.0 / sr;
         match self.stage {
             EnvelopeStage::Attack => {
@@ -76,18 +122,119 @@ impl Voice {
             EnvelopeStage::Off => return 0.0,
         }
 
+        if let Some((target, ratio)) = self.glide {
+            self.frequency *= ratio;
+            if (ratio >= 1.0) == (self.frequency >= target) {
+                self.frequency = target;
+                self.glide = None;
+            }
+        }
+
+        let inc = self.frequency / sr;
         let sample = match wave {
             WaveType::Sine     => (self.phase * 2.0 * PI).sin(),
-            WaveType::Square   => if (self.phase * 2.0 * PI).sin() >= 0.0 { 1.0 } else { -1.0 },
-            WaveType::Sawtooth => 2.0 * self.phase - 1.0,
+            WaveType::Square   => {
+                let naive = if self.phase < 0.5 { 1.0 } else { -1.0 };
+                if band_limited {
+                    naive + poly_blep(self.phase, inc) - poly_blep((self.phase + 0.5) % 1.0, inc)
+                } else {
+                    naive
+                }
+            }
+            WaveType::Sawtooth => {
+                let naive = 2.0 * self.phase - 1.0;
+                if band_limited { naive - poly_blep(self.phase, inc) } else { naive }
+            }
             WaveType::Triangle => {
                 if self.phase < 0.5 { 4.0 * self.phase - 1.0 } else { 3.0 - 4.0 * self.phase }
             }
+            WaveType::Wavetable(i) => tables.get(i).map_or(0.0, |t| t.sample(self.phase)),
+            WaveType::Granular => self.grains.next(granular, self.frequency, sr),
+            WaveType::Sampler  => {
+                let read = granular.sample.as_deref()
+                    .and_then(|smp| smp.one_shot(self.sample_pos).map(|x| (x, smp.rate)));
+                let Some((x, rate)) = read else {
+                    // Played out (or nothing loaded): the voice is done.
+                    self.stage = EnvelopeStage::Off;
+                    return 0.0;
+                };
+                self.sample_pos += (self.frequency / note_to_freq(granular.root) * rate / sr) as f64;
+                x
+            }
         };
 
-        self.phase += self.frequency / sr;
+        self.phase += inc;
         if self.phase >= 1.0 { self.phase -= 1.0; }
-        sample * self.level
+        sample * self.level * self.velocity
+    }
+}
+
+// ── Mono (legato) note handling ───────────────────────────────────────────────
+
+/// Held notes of a synth in mono mode, newest last: the newest sounds, and
+/// releasing it falls back to the one held before.
+#[derive(Default)]
+struct MonoNotes {
+    held:     Vec<u8>,
+    /// Key of the single voice in `voices`.
+    sounding: Option<u8>,
+}
+
+impl MonoNotes {
+    /// Move the voice (or start one) to `note`.  With a key already held it
+    /// is retuned and keeps its envelope; otherwise it re-attacks from its
+    /// current level.
+    fn note_on(&mut self, voices: &mut HashMap<u8, Voice>, note: u8) {
+        let legato = !self.held.is_empty();
+        self.held.retain(|&n| n != note);
+        self.held.push(note);
+        let voice = match self.sounding.and_then(|n| voices.remove(&n)) {
+            Some(mut v) => {
+                v.frequency = note_to_freq(note);
+                v.glide = None;
+                if !legato { v.stage = EnvelopeStage::Attack; v.sample_pos = 0.0; }
+                v
+            }
+            None => Voice::new(note),
+        };
+        voices.insert(note, voice);
+        self.sounding = Some(note);
+    }
+
+    /// Releasing the sounding note retunes to the newest still held, or
+    /// releases the voice when none is.  Other notes just leave the stack
+    /// (or release a voice left from before mono was on).
+    fn note_off(&mut self, voices: &mut HashMap<u8, Voice>, note: u8) {
+        self.held.retain(|&n| n != note);
+        if self.sounding != Some(note) {
+            if let Some(v) = voices.get_mut(&note) { v.release(); }
+            return;
+        }
+        match (self.held.last().copied(), voices.remove(&note)) {
+            (Some(back), Some(mut v)) => {
+                v.frequency = note_to_freq(back);
+                voices.insert(back, v);
+                self.sounding = Some(back);
+            }
+            (_, Some(mut v)) => { v.release(); voices.insert(note, v); }
+            (_, None) => {}
+        }
+    }
+}
+
+/// PolyBLEP correction for a discontinuity at phase 0: a polynomial over the
+/// sample either side of the jump that removes most of the aliasing of the
+/// naive saw/square.  `dt` is the phase increment per sample.
+#[inline]
+fn poly_blep(phase: f32, dt: f32) -> f32 {
+    if phase < dt {
+        let t = phase / dt;
+        2.0 * t - t * t - 1.0
+    } else if phase > 1.0 - dt {
+        let t = (phase - 1.0) / dt;
+        t * t + 2.0 * t + 1.0
+    } else {
+        0.0
     }
 }
 
@@ -97,27 +244,57 @@ impl Voice {
 /// Dry signal always passes through; routing additionally sends a weighted
 /// copy into the effect's wet bus.
 pub struct FxRouting {
-    pub s1_reverb: f32, pub s1_delay: f32, pub s1_dist: f32,
-    pub s2_reverb: f32, pub s2_delay: f32, pub s2_dist: f32,
-    pub dr_reverb: f32, pub dr_delay: f32, pub dr_dist: f32,
+    pub s1_reverb: f32, pub s1_delay: f32, pub s1_dist: f32, pub s1_comb: f32, pub s1_chorus: f32, pub s1_crush: f32,
+    pub s2_reverb: f32, pub s2_delay: f32, pub s2_dist: f32, pub s2_comb: f32, pub s2_chorus: f32, pub s2_crush: f32,
+    pub dr_reverb: f32, pub dr_delay: f32, pub dr_dist: f32, pub dr_comb: f32, pub dr_chorus: f32, pub dr_crush: f32,
 }
 
 impl FxRouting {
     pub fn new() -> Self {
         Self {
-            s1_reverb: 0.0, s1_delay: 0.0, s1_dist: 0.0,
-            s2_reverb: 0.0, s2_delay: 0.0, s2_dist: 0.0,
-            dr_reverb: 0.0, dr_delay: 0.0, dr_dist: 0.0,
+            s1_reverb: 0.0, s1_delay: 0.0, s1_dist: 0.0, s1_comb: 0.0, s1_chorus: 0.0, s1_crush: 0.0,
+            s2_reverb: 0.0, s2_delay: 0.0, s2_dist: 0.0, s2_comb: 0.0, s2_chorus: 0.0, s2_crush: 0.0,
+            dr_reverb: 0.0, dr_delay: 0.0, dr_dist: 0.0, dr_comb: 0.0, dr_chorus: 0.0, dr_crush: 0.0,
         }
     }
 }
 
 // ── Sidechain compressor ──────────────────────────────────────────────────────
 
+/// How the ducking recovers after a kick.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SidechainShape {
+    /// Fast at first, then easing back: the release time is its time constant.
+    Exponential,
+    /// Straight back up over the release time.
+    Linear,
+    /// Holds down, swells back, then settles, over the release time.
+    SCurve,
+}
+
+impl SidechainShape {
+    pub const ALL: [SidechainShape; 3] = [Self::Exponential, Self::Linear, Self::SCurve];
+
+    pub fn name(self) -> &'static str {
+        match self { Self::Exponential => "Exp", Self::Linear => "Lin", Self::SCurve => "S" }
+    }
+    pub fn next(self) -> Self {
+        match self { Self::Exponential => Self::Linear, Self::Linear => Self::SCurve, Self::SCurve => Self::Exponential }
+    }
+    pub fn prev(self) -> Self {
+        match self { Self::Exponential => Self::SCurve, Self::Linear => Self::Exponential, Self::SCurve => Self::Linear }
+    }
+    /// Index in `ALL`, as saved.
+    pub fn index(self) -> u8 {
+        Self::ALL.iter().position(|&s| s == self).unwrap_or(0) as u8
+    }
+}
+
 pub struct Sidechain {
     pub enabled:    bool,
     pub depth:      f32,    // 0.0–1.0
     pub release_ms: f32,    // 10.0–500.0
+    pub shape:      SidechainShape,
     pub duck_s1:    bool,
     pub duck_s2:    bool,
     pub envelope:   f32,    // per-sample state (0.0–1.0)
@@ -125,9 +302,81 @@ pub struct Sidechain {
 
 impl Sidechain {
     pub fn new() -> Self {
-        Self { enabled: false, depth: 0.8, release_ms: 150.0,
+        Self { enabled: false, depth: 0.8, release_ms: 150.0, shape: SidechainShape::Exponential,
                duck_s1: true, duck_s2: true, envelope: 0.0 }
     }
+
+    /// Advance the envelope one sample, restarting it on a kick, and return
+    /// the gain for the ducked buses.  The exponential shape decays the
+    /// envelope; the others run it down linearly and shape the result.
+    fn next_gain(&mut self, kick: bool, sample_rate: f32) -> f32 {
+        if kick { self.envelope = 1.0; }
+        let release = self.release_ms * 0.001 * sample_rate;
+        let env = match self.shape {
+            SidechainShape::Exponential => self.envelope * (-1.0_f32 / release).exp(),
+            SidechainShape::Linear | SidechainShape::SCurve => self.envelope - 1.0 / release,
+        };
+        self.envelope = flush_denormal(env.clamp(0.0, 1.0));
+        let e = self.envelope;
+        let duck = match self.shape {
+            SidechainShape::SCurve => e * e * (3.0 - 2.0 * e),
+            _ => e,
+        };
+        1.0 - duck * self.depth
+    }
+}
+
+// ── Level meters ──────────────────────────────────────────────────────────────
+
+/// Time constant of a meter's fall after a peak, in seconds.
+const METER_RELEASE_S: f32 = 0.12;
+
+/// Peak follower behind the UI level meters: jumps to each new peak, then
+/// falls back exponentially.
+#[derive(Clone, Copy, Default)]
+pub struct PeakMeter {
+    level: f32,
+}
+
+impl PeakMeter {
+    #[inline]
+    fn feed(&mut self, x: f32, fall: f32) {
+        let a = x.abs();
+        self.level = if a > self.level { a } else { flush_denormal(self.level * fall) };
+    }
+
+    pub fn level(&self) -> f32 {
+        self.level
+    }
+}
+
+/// Time constant of the stereo correlation's averages, in seconds.
+const CORRELATION_S: f32 = 0.3;
+
+/// Running phase correlation of a stereo signal: +1 mono, 0 unrelated
+/// sides, -1 out of phase.  Averages `L·R`, `L²` and `R²` over about
+/// `CORRELATION_S`.
+#[derive(Clone, Copy, Default)]
+pub struct Correlation {
+    lr: f32,
+    ll: f32,
+    rr: f32,
+}
+
+impl Correlation {
+    #[inline]
+    fn feed(&mut self, l: f32, r: f32, k: f32) {
+        self.lr = flush_denormal(self.lr + (l * r - self.lr) * k);
+        self.ll = flush_denormal(self.ll + (l * l - self.ll) * k);
+        self.rr = flush_denormal(self.rr + (r * r - self.rr) * k);
+    }
+
+    /// The correlation, or `None` while the output is silent.
+    pub fn value(&self) -> Option<f32> {
+        let power = self.ll * self.rr;
+        if power < 1e-12 { return None; }
+        Some((self.lr / power.sqrt()).clamp(-1.0, 1.0))
+    }
 }
 
 // ── Synth ─────────────────────────────────────────────────────────────────────
@@ -136,42 +385,112 @@ pub struct Synth {
     pub sample_rate: f32,
     pub bpm:         f32,       // master clock shared by all sequencers
     pub master_clock: u64,      // incremented every sample
+    /// Musical position in 16th-note steps, advanced by the current BPM each
+    /// sample.  Continuous across tempo changes.
+    pub step_pos:    f64,
+    /// Swing/groove read by the drum machine and both melodic sequencers.
+    pub groove:      Groove,
+    /// Beat click, also the count-in before real-time recording.
+    pub metronome:   Metronome,
+    /// Scale both melodic sequencers' notes are snapped to as they play
+    /// (live quantize, published by `App`).  `None` plays them as written.
+    pub live_scale:  Option<ScaleQuantizer>,
+    /// Scale live notes from the MIDI input are snapped to: the keyboard's
+    /// (published by `App` whenever a scale is set).
+    pub key_scale:   Option<ScaleQuantizer>,
+    /// Two LFOs on the synth buses' cutoffs and volumes; depth 0 = off.
+    pub lfos:        [Lfo; 2],
+    /// Note each held MIDI input note plays after `key_scale`, for its
+    /// note-off: the scale may change while it is held.  Several input
+    /// notes can snap to one played note, which sounds until all are up.
+    midi_held:       HashMap<u8, u8>,
+
+    /// Tables selectable as `WaveType::Wavetable(i)` by either synth.
+    /// Built-ins first, then any loaded from WAV files.
+    pub wavetables:  Vec<Wavetable>,
+    /// Amplitudes (0–1) of harmonics 1–8 in the "Additive" table.
+    pub harmonics:   [f32; HARMONICS],
+    /// Sample and grain settings for `WaveType::Granular`, on either synth.
+    pub granular:    Granular,
+    /// PolyBLEP-corrected saw/square.  Off = the cheaper naive (aliasing) shapes.
+    pub band_limited: bool,
 
     // ── Synth 1 ───────────────────────────────────────────────────────────
     pub wave_type:   WaveType,
     pub voices:      HashMap<u8, Voice>,
+    /// One voice, played legato (`mono_notes` tracks the held keys).
+    pub mono:        bool,
+    mono_notes:      MonoNotes,
     pub attack:  f32,
     pub decay:   f32,
     pub sustain: f32,
     pub release: f32,
     pub volume:  f32,
     pub sequencer:    Sequencer,
-    /// Insert effects applied to the melodic synth 1 bus.
+    /// Filter envelope and accents on sequencer 1's notes.
+    pub acid:         Acid,
+    /// Plays held keyboard notes in turn when enabled.
+    pub arp:          Arpeggiator,
+    /// Insert effects applied to the melodic synth 1 bus, after its filter
+    /// (edited in Inserts focus).
     pub fx: EffectChain,
 
     // ── Synth 2 (sequencer-driven) ────────────────────────────────────────
     pub wave_type2:  WaveType,
     pub voices2:     HashMap<u8, Voice>,
+    pub mono2:       bool,
+    mono_notes2:     MonoNotes,
     pub attack2:  f32,
     pub decay2:   f32,
     pub sustain2: f32,
     pub release2: f32,
     pub volume2:  f32,
     pub sequencer2:   Sequencer,
-    /// Insert effects applied to the melodic synth 2 bus.
+    /// Insert effects applied to the melodic synth 2 bus, after its filter.
     pub fx2: EffectChain,
 
     // ── Drum machine ──────────────────────────────────────────────────────
     pub drum_machine: DrumMachine,
+    /// Drum bus level, 0.0–1.0.
+    pub drum_volume:  f32,
+
+    /// Output level after everything else, 0.0–1.0.
+    pub master_gain:  f32,
+    /// Monitor the output summed to mono: both channels carry (L+R)/2.
+    /// A check of how the mix translates to mono playback, never saved.
+    pub mono_sum:     bool,
+    /// Bus pre-listened on the cue output (channels 3–4), if any.
+    pub cue:          Option<Bus>,
+    /// The cue signal of the last frame; `None` while nothing is cued.
+    pub cue_out:      Option<f32>,
+
+    /// Stereo positions of synth 1 and synth 2, -1.0 (left) – 1.0 (right).
+    pub pan:  f32,
+    pub pan2: f32,
+
+    /// Synth 1 ↔ synth 2 crossfader, 0.0 (synth 1 only) – 1.0 (synth 2 only).
+    /// Both play at their own volume in the centre.
+    pub crossfade: f32,
 
     // ── Per-bus filters (applied before EffectChain on each bus) ─────────
     pub filter1: BiquadFilter,
     pub filter2: BiquadFilter,
+    /// Vowel filter, after the filter of the bus it is on.
+    pub formant: FormantFilter,
+    /// Insert on the drum bus.
+    pub deesser: DeEsser,
 
     // ── Master effects (parallel aux-send, wet-only output) ───────────────
     pub reverb:     Reverb,
     pub delay:      Delay,
     pub distortion: Distortion,
+    pub comb:       Comb,
+    pub chorus:     Chorus,
+    pub crusher:    Bitcrusher,
+    /// Master inserts, after the sends return: the freeze, then the
+    /// compressor.
+    pub spectral:   SpectralFreeze,
+    pub compressor: Compressor,
 
     // ── Per-instrument send routing ───────────────────────────────────────
     pub fx_routing: FxRouting,
@@ -179,71 +498,241 @@ pub struct Synth {
     // ── Sidechain compressor ──────────────────────────────────────────────
     pub sidechain: Sidechain,
 
+    // ── MIDI ──────────────────────────────────────────────────────────────
+    /// Port that notes and control changes are sent to (`--midi-out`).
+    pub midi_out: Option<MidiOut>,
+    /// Which note sources are sent to `midi_out`.
+    pub midi_notes: NoteRouting,
+    /// External clock driving `step_pos` in place of `bpm` (`--midi-clock-in`).
+    pub ext_clock: Option<ExtClock>,
+
+    // ── Parameter locks ───────────────────────────────────────────────────
+    /// Per sequencer, the values the sounding step's locks replaced, put
+    /// back when the next step fires.
+    lock_saved: [PLock; 2],
+
+    // ── Automation ────────────────────────────────────────────────────────
+    /// Lanes played back while the transport runs (published by `App`).
+    pub automation: Playback,
+
+    // ── Freeze ────────────────────────────────────────────────────────────
+    /// Per bus (`Bus::index`), a render played back in place of the bus's
+    /// pattern while its transport runs.  Live notes still sound.
+    pub frozen:  [Option<Frozen>; 3],
+    /// Each bus's signal in the last sample, before its volume: what
+    /// `freeze::Render` records.  The drum bus is stereo; this is its mid.
+    pub bus_raw: [f32; 3],
+    /// Side, `(L-R)/2`, of the drum bus in the last sample, before its volume.
+    pub drum_side_raw: f32,
+
+    // ── Meters ────────────────────────────────────────────────────────────
+    /// Wet output of the reverb, delay, distortion, comb, chorus and bitcrusher
+    /// sends, in that order.
+    pub fx_meters: [PeakMeter; 6],
+    /// Synth 1, synth 2 and drum buses after their volume, before any effects.
+    pub bus_meters: [PeakMeter; 3],
+    /// Highest output sample, either channel after the master gain, since
+    /// the last `reset_master_peak()`.  Latched: it never falls on its own.
+    pub master_peak: f32,
+    /// Phase correlation of the output.
+    pub correlation: Correlation,
+
     // ── Oscilloscope ring buffer ──────────────────────────────────────────
-    pub scope_buf: Vec<f32>,
-    pub scope_pos: usize,
+    scope_buf: Vec<f32>,
+    /// Newest output frames, for the goniometer; written at `scope_pos`
+    /// like `scope_buf`.
+    stereo_buf: Vec<(f32, f32)>,
+    scope_pos: usize,
 }
 
+/// Length of the oscilloscope ring buffer, in samples.
+pub const SCOPE_LEN: usize = 512;
+
+/// Length of the goniometer's ring buffer, in frames.
+pub const STEREO_LEN: usize = 256;
+
+/// Automation lanes are applied once every this many samples.
+const AUTOMATION_INTERVAL: u64 = 32;
+
+/// Envelope of a new synth: attack, decay, sustain level, release.
+pub const DEFAULT_ADSR: [f32; 4] = [0.01, 0.1, 0.7, 0.3];
+
+/// Range of the attack, decay and release times, in seconds.
+pub const ENV_TIME_MIN: f32 = 0.001;
+pub const ENV_TIME_MAX: f32 = 4.0;
+
+/// Time a sequencer slide takes to reach the next note.  Fixed, as on the
+/// TB-303.
+const SLIDE_MS: f32 = 60.0;
+
 impl Synth {
     pub fn new(sample_rate: f32) -> Self {
         Self {
             sample_rate,
             bpm:          120.0,
             master_clock: 0,
+            step_pos:     0.0,
+            groove:       Groove { template: GrooveTemplate::Swing16, amount: 0.0, melodic: true },
+            metronome:    Metronome::new(),
+            live_scale:   None,
+            key_scale:    None,
+            lfos:         [Lfo::new(LfoTarget::Cutoff1), Lfo::new(LfoTarget::Volume1)],
+            midi_held:    HashMap::new(),
+            wavetables:   Wavetable::builtins(),
+            harmonics:    DEFAULT_HARMONICS,
+            granular:     Granular::new(),
+            band_limited: true,
 
             wave_type:  WaveType::Sine,
             voices:     HashMap::new(),
-            attack:  0.01, decay: 0.1, sustain: 0.7, release: 0.3,
+            mono:       false,
+            mono_notes: MonoNotes::default(),
+            attack:  DEFAULT_ADSR[0], decay:  DEFAULT_ADSR[1], sustain:  DEFAULT_ADSR[2], release:  DEFAULT_ADSR[3],
             volume:  0.5,
-            sequencer:    Sequencer::new(sample_rate),
+            sequencer:    Sequencer::new(),
+            acid:         Acid::new(),
+            arp:          Arpeggiator::new(),
             fx:           EffectChain::new(),
 
             wave_type2: WaveType::Sine,
             voices2:    HashMap::new(),
-            attack2: 0.01, decay2: 0.1, sustain2: 0.7, release2: 0.3,
+            mono2:       false,
+            mono_notes2: MonoNotes::default(),
+            attack2: DEFAULT_ADSR[0], decay2: DEFAULT_ADSR[1], sustain2: DEFAULT_ADSR[2], release2: DEFAULT_ADSR[3],
             volume2: 0.5,
-            sequencer2:   Sequencer::new(sample_rate),
+            sequencer2:   Sequencer::new(),
             fx2:          EffectChain::new(),
 
             drum_machine: DrumMachine::new(sample_rate),
+            drum_volume:  0.5,
+            master_gain:  1.0,
+            mono_sum:     false,
+            cue:          None,
+            cue_out:      None,
+            crossfade:    0.5,
+            pan:          0.0,
+            pan2:         0.0,
 
             filter1: BiquadFilter::new(sample_rate),
             filter2: BiquadFilter::new(sample_rate),
+            formant: FormantFilter::new(sample_rate),
+            deesser: DeEsser::new(sample_rate),
 
-            reverb:      Reverb::new(),
+            reverb:      Reverb::new(sample_rate),
             delay:       Delay::new(sample_rate),
             distortion:  Distortion::new(),
+            comb:        Comb::new(sample_rate),
+            chorus:      Chorus::new(sample_rate),
+            crusher:     Bitcrusher::new(),
+            spectral:    SpectralFreeze::new(sample_rate),
+            compressor:  Compressor::new(sample_rate),
 
             fx_routing:  FxRouting::new(),
 
             sidechain:  Sidechain::new(),
-            scope_buf:  vec![0.0f32; 512],
+            midi_out:   None,
+            midi_notes: NoteRouting::default(),
+            ext_clock:  None,
+            lock_saved: [PLock::NONE; 2],
+            automation: Playback::default(),
+            frozen:     [None, None, None],
+            bus_raw:    [0.0; 3],
+            drum_side_raw: 0.0,
+            fx_meters:  [PeakMeter::default(); 6],
+            bus_meters: [PeakMeter::default(); 3],
+            master_peak: 0.0,
+            correlation: Correlation::default(),
+            scope_buf:  vec![0.0f32; SCOPE_LEN],
+            stereo_buf: vec![(0.0, 0.0); STEREO_LEN],
             scope_pos:  0,
         }
     }
 
+    /// Switch every rate-dependent component to a new output sample rate
+    /// (e.g. after opening a device that runs at 48 kHz).
+    pub fn set_sample_rate(&mut self, sample_rate: f32) {
+        if sample_rate == self.sample_rate { return; }
+        self.sample_rate = sample_rate;
+        self.drum_machine.set_sample_rate(sample_rate);
+        self.filter1.set_sample_rate(sample_rate);
+        self.formant.set_sample_rate(sample_rate);
+        self.deesser.set_sample_rate(sample_rate);
+        self.filter2.set_sample_rate(sample_rate);
+        self.delay.set_sample_rate(sample_rate);
+        self.reverb.set_sample_rate(sample_rate);
+        self.comb.set_sample_rate(sample_rate);
+        self.chorus.set_sample_rate(sample_rate);
+        self.spectral.set_sample_rate(sample_rate);
+        self.compressor.set_sample_rate(sample_rate);
+        // Inserts are rebuilt at the new rate (clearing their tails).
+        for chain in [&mut self.fx, &mut self.fx2] {
+            for fx in &mut chain.effects {
+                if let Some(kind) = InsertKind::of(fx.as_ref()) { *fx = kind.build(sample_rate); }
+            }
+        }
+    }
+
     // ── Synth 1 note control ──────────────────────────────────────────────
 
     pub fn note_on(&mut self, note: u8) {
+        if self.mono { return self.mono_notes.note_on(&mut self.voices, note); }
         self.voices.insert(note, Voice::new(note));
     }
 
     pub fn note_off(&mut self, note: u8) {
+        if self.mono { return self.mono_notes.note_off(&mut self.voices, note); }
         if let Some(v) = self.voices.get_mut(&note) { v.release(); }
     }
 
+    /// Synth 1's (synth 2's when `synth2`) envelope: attack, decay,
+    /// sustain level, release.
+    pub fn adsr(&self, synth2: bool) -> [f32; 4] {
+        if synth2 {
+            [self.attack2, self.decay2, self.sustain2, self.release2]
+        } else {
+            [self.attack, self.decay, self.sustain, self.release]
+        }
+    }
+
+    /// Stage `i` (in `adsr()` order) of synth 1's or 2's envelope.
+    pub fn adsr_stage(&mut self, synth2: bool, i: usize) -> &mut f32 {
+        match (synth2, i) {
+            (false, 0) => &mut self.attack,
+            (false, 1) => &mut self.decay,
+            (false, 2) => &mut self.sustain,
+            (false, _) => &mut self.release,
+            (true, 0)  => &mut self.attack2,
+            (true, 1)  => &mut self.decay2,
+            (true, 2)  => &mut self.sustain2,
+            (true, _)  => &mut self.release2,
+        }
+    }
+
+    /// Switch synth 1 (synth 2 when `synth2`) between poly and mono.  The
+    /// held-note stack starts over; sounding voices release on their keys.
+    pub fn set_mono(&mut self, synth2: bool, mono: bool) {
+        if synth2 {
+            self.mono2 = mono;
+            self.mono_notes2 = MonoNotes::default();
+        } else {
+            self.mono = mono;
+            self.mono_notes = MonoNotes::default();
+        }
+    }
+
     pub fn active_notes(&self) -> Vec<u8> {
         self.voices.keys().copied().collect()
     }
 
     // ── Synth 2 note control ──────────────────────────────────────────────
 
-    #[allow(dead_code)]
     pub fn note_on2(&mut self, note: u8) {
+        if self.mono2 { return self.mono_notes2.note_on(&mut self.voices2, note); }
         self.voices2.insert(note, Voice::new(note));
     }
 
     pub fn note_off2(&mut self, note: u8) {
+        if self.mono2 { return self.mono_notes2.note_off(&mut self.voices2, note); }
         if let Some(v) = self.voices2.get_mut(&note) { v.release(); }
     }
 
@@ -252,93 +741,624 @@ impl Synth {
         self.voices2.keys().copied().collect()
     }
 
+    // ── MIDI note routing ─────────────────────────────────────────────────
+
+    /// Send a note to the MIDI output when `routed` and a port is open.
+    /// Returns whether the internal synth should still play it.
+    fn midi_note(&self, routed: bool, note: u8, on: bool) -> bool {
+        match &self.midi_out {
+            Some(out) if routed => {
+                if on { out.note_on(note) } else { out.note_off(note) }
+                self.midi_notes.local
+            }
+            _ => true,
+        }
+    }
+
+    /// Live keyboard note-on: synth 1 and/or the MIDI output, or the
+    /// arpeggiator's held notes when it is on.
+    pub fn key_on(&mut self, note: u8) {
+        if self.arp.enabled { self.arp.press(note); return; }
+        if self.midi_note(self.midi_notes.keys, note, true) { self.note_on(note); }
+    }
+
+    /// Live keyboard note-off.  The internal voice is always released, so
+    /// changing the routing while a note is held can't leave it hanging.
+    /// A note the arpeggiator holds just leaves its pattern.
+    pub fn key_off(&mut self, note: u8) {
+        if self.arp.release(note) { return; }
+        self.midi_note(self.midi_notes.keys, note, false);
+        self.note_off(note);
+    }
+
+    /// Note-on from the MIDI input (`--midi-in`): a live key on synth 1,
+    /// snapped to `key_scale`, at `velocity` (0–1).
+    pub fn midi_in_on(&mut self, note: u8, velocity: f32) {
+        let played = self.key_scale.as_ref().map_or(note, |q| q.quantize(note));
+        if let Some(old) = self.midi_held.insert(note, played) { self.midi_in_release(old); }
+        self.key_on(played);
+        if let Some(v) = self.voices.get_mut(&played).filter(|v| v.stage == EnvelopeStage::Attack) {
+            v.velocity = velocity;
+        }
+    }
+
+    pub fn midi_in_off(&mut self, note: u8) {
+        if let Some(played) = self.midi_held.remove(&note) { self.midi_in_release(played); }
+    }
+
+    /// End `played` unless another held input note still plays it.
+    fn midi_in_release(&mut self, played: u8) {
+        if !self.midi_held.values().any(|&p| p == played) { self.key_off(played); }
+    }
+
+    /// Turn the arpeggiator on or off, ending its note and held keys.
+    pub fn set_arp(&mut self, enabled: bool) {
+        if let Some(n) = self.arp.clear() { self.arp_note_off(n); }
+        self.arp.enabled = enabled;
+    }
+
+    fn arp_note_off(&mut self, note: u8) {
+        self.midi_note(self.midi_notes.keys, note, false);
+        self.note_off(note);
+    }
+
+    /// Sequencer note-on (sequencer 2 when `synth2`), internal and MIDI.
+    /// `velocity` sets the level of a voice that (re)attacks; a mono voice
+    /// retuned legato keeps its own.
+    fn seq_note_on(&mut self, synth2: bool, note: u8, velocity: f32) {
+        let midi = if synth2 { self.midi_notes.seq2 } else { self.midi_notes.seq1 };
+        if !self.midi_note(midi, note, true) || self.frozen[synth2 as usize].is_some() { return; }
+        if synth2 { self.note_on2(note); } else { self.note_on(note); }
+        let voices = if synth2 { &mut self.voices2 } else { &mut self.voices };
+        if let Some(v) = voices.get_mut(&note).filter(|v| v.stage == EnvelopeStage::Attack) {
+            v.velocity = velocity;
+        }
+    }
+
+    /// Sequencer note-off (sequencer 2 when `synth2`), internal and MIDI.
+    pub fn seq_note_off(&mut self, synth2: bool, note: u8) {
+        if synth2 {
+            self.midi_note(self.midi_notes.seq2, note, false);
+            self.note_off2(note);
+        } else {
+            self.midi_note(self.midi_notes.seq1, note, false);
+            self.note_off(note);
+        }
+    }
+
+    /// Play a sequencer step's notes (sequencer 2 when `synth2`).  A slide
+    /// glides the old notes into the new ones pairwise, root to root; notes
+    /// left over on either side just end or start.
+    fn seq_step(&mut self, synth2: bool, ev: StepEvent) {
+        let pairs = if ev.slide { ev.note_off.len().min(ev.note_on.len()) } else { 0 };
+        for (&from, &to) in ev.note_off.iter().zip(&ev.note_on).take(pairs) {
+            self.seq_slide(synth2, from, to);
+        }
+        for &n in &ev.note_off[pairs..] { self.seq_note_off(synth2, n); }
+        for &n in &ev.note_on[pairs..]  { self.seq_note_on(synth2, n, ev.velocity); }
+    }
+
+    /// A sequencer slide (sequencer 2 when `synth2`) from `from` into `to`:
+    /// the sounding voice keeps its envelope and glides over `SLIDE_MS`.  On
+    /// MIDI the new note starts before the old one ends, the overlap a mono
+    /// receiver slides on.  A slide onto the same note just holds it.
+    fn seq_slide(&mut self, synth2: bool, from: u8, to: u8) {
+        if from == to { return; }
+        let midi = if synth2 { self.midi_notes.seq2 } else { self.midi_notes.seq1 };
+        let local = self.midi_note(midi, to, true);
+        self.midi_note(midi, from, false);
+        let live = local && self.frozen[synth2 as usize].is_none();
+
+        let (voices, mono) = if synth2 { (&mut self.voices2, self.mono2) } else { (&mut self.voices, self.mono) };
+        let from_freq = voices.get(&from).map(|v| v.frequency);
+        if !live || from_freq.is_none() {
+            // Nothing to glide: end the old note and start the new one as usual.
+            if synth2 { self.note_off2(from); } else { self.note_off(from); }
+            if live { if synth2 { self.note_on2(to); } else { self.note_on(to); } }
+            return;
+        }
+        if mono {
+            // Taking `to` while `from` is held retunes the one voice legato.
+            if synth2 { self.note_on2(to); self.note_off2(from); } else { self.note_on(to); self.note_off(from); }
+        } else if let Some(v) = voices.remove(&from) {
+            voices.insert(to, v);
+        }
+        let samples = SLIDE_MS * 0.001 * self.sample_rate;
+        let voices = if synth2 { &mut self.voices2 } else { &mut self.voices };
+        if let (Some(f0), Some(v)) = (from_freq, voices.get_mut(&to)) {
+            v.glide(f0, note_to_freq(to), samples);
+        }
+    }
+
+    /// Feed a message from the MIDI clock input.  The displayed BPM follows
+    /// the measured tempo, and Stop releases the sequencers' held notes and
+    /// locks since the frozen playhead won't reach the next step.  Start
+    /// restarts the drums' pass count for trig conditions.
+    pub fn ext_clock_msg(&mut self, msg: ClockMsg, now: std::time::Instant) {
+        let Some(ext) = &mut self.ext_clock else { return };
+        ext.handle(msg, now);
+        if let Some(bpm) = ext.bpm() { self.bpm = bpm.clamp(30.0, 300.0); }
+        if msg == ClockMsg::Start { self.drum_machine.rewind(); }
+        if msg == ClockMsg::Stop {
+            for n in self.sequencer.release()  { self.seq_note_off(false, n); }
+            for n in self.sequencer2.release() { self.seq_note_off(true, n); }
+            if let Some(n) = self.arp.cut() { self.arp_note_off(n); }
+            self.release_lock(false);
+            self.release_lock(true);
+        }
+    }
+
+    /// Move the playhead to `step_pos`, as a section jump does.  Refused
+    /// (false) under an external clock, which owns the position.
+    pub fn locate(&mut self, step_pos: f64) -> bool {
+        if self.ext_clock.is_some() { return false; }
+        self.step_pos = step_pos;
+        self.drum_machine.rewind();
+        true
+    }
+
+    // ── Parameter locks ───────────────────────────────────────────────────
+
+    /// Put back what the last step's locks on sequencer 1 (or 2) replaced,
+    /// then apply `lock`, remembering the values it replaces.
+    pub fn apply_lock(&mut self, synth2: bool, lock: PLock) {
+        fn swap<T: Copy>(field: &mut T, restore: Option<T>, lock: Option<T>) -> Option<T> {
+            if let Some(v) = restore { *field = v; }
+            let saved = lock.map(|_| *field);
+            if let Some(v) = lock { *field = v; }
+            saved
+        }
+        let restore = std::mem::take(&mut self.lock_saved[synth2 as usize]);
+        let (filter, wave, volume) = if synth2 {
+            (&mut self.filter2, &mut self.wave_type2, &mut self.volume2)
+        } else {
+            (&mut self.filter1, &mut self.wave_type, &mut self.volume)
+        };
+        self.lock_saved[synth2 as usize] = PLock {
+            cutoff: swap(&mut filter.cutoff, restore.cutoff, lock.cutoff),
+            wave:   swap(wave, restore.wave, lock.wave),
+            volume: swap(volume, restore.volume, lock.volume),
+        };
+    }
+
+    /// Synth 1 (or 2)'s panel values underneath any locks in force, all `Some`.
+    pub fn unlocked(&self, synth2: bool) -> PLock {
+        let saved = self.lock_saved[synth2 as usize];
+        let (filter, wave, volume) = if synth2 {
+            (&self.filter2, self.wave_type2, self.volume2)
+        } else {
+            (&self.filter1, self.wave_type, self.volume)
+        };
+        PLock {
+            cutoff: saved.cutoff.or(Some(filter.cutoff)),
+            wave:   saved.wave.or(Some(wave)),
+            volume: saved.volume.or(Some(volume)),
+        }
+    }
+
+    /// Undo any locks in force on sequencer 1 (or 2), e.g. when it stops.
+    pub fn release_lock(&mut self, synth2: bool) {
+        self.apply_lock(synth2, PLock::NONE);
+    }
+
+    /// A step's locks for display, e.g. "vol 80%  wave Saw  cut 1200Hz".
+    pub fn lock_summary(&self, lock: PLock) -> String {
+        let mut parts = Vec::new();
+        if let Some(v) = lock.volume { parts.push(format!("vol {:.0}%", v * 100.0)); }
+        if let Some(w) = lock.wave   { parts.push(format!("wave {}", self.wave_name(w))); }
+        if let Some(c) = lock.cutoff { parts.push(format!("cut {:.0}Hz", c)); }
+        if parts.is_empty() { "none".to_string() } else { parts.join("  ") }
+    }
+
+    // ── Freeze ────────────────────────────────────────────────────────────
+
+    /// Play `frozen` back in place of `bus`'s pattern.
+    pub fn freeze(&mut self, bus: Bus, frozen: Frozen) {
+        if bus == Bus::Drums { self.drum_machine.frozen = true; }
+        self.frozen[bus.index()] = Some(frozen);
+    }
+
+    /// Back to live synthesis.  Returns false if `bus` wasn't frozen.
+    pub fn unfreeze(&mut self, bus: Bus) -> bool {
+        if bus == Bus::Drums { self.drum_machine.frozen = false; }
+        self.frozen[bus.index()].take().is_some()
+    }
+
+    /// Bus `i`'s frozen render at the playhead, while its pattern plays.
+    fn frozen_sample(&self, i: usize, playing: bool) -> f32 {
+        match &self.frozen[i] {
+            Some(f) if playing => f.sample_at(self.step_pos),
+            _ => 0.0,
+        }
+    }
+
+    /// Side of the frozen drum render at the playhead, while the drums play.
+    fn frozen_drum_side(&self) -> f32 {
+        match &self.frozen[2] {
+            Some(f) if self.drum_machine.playing => f.side_at(self.step_pos),
+            _ => 0.0,
+        }
+    }
+
+    // ── Automation ────────────────────────────────────────────────────────
+
+    /// Whether either sequencer or the drum machine is playing.
+    pub fn transport_running(&self) -> bool {
+        self.sequencer.playing || self.sequencer2.playing || self.drum_machine.playing
+    }
+
+    /// The field behind a mirrored parameter, in the control's own units.
+    pub fn param_mut(&mut self, param: CcParam) -> &mut f32 {
+        match param {
+            CcParam::Volume           => &mut self.volume,
+            CcParam::Volume2          => &mut self.volume2,
+            CcParam::Crossfade        => &mut self.crossfade,
+            CcParam::DrumVolume       => &mut self.drum_volume,
+            CcParam::MasterGain       => &mut self.master_gain,
+            CcParam::Bpm              => &mut self.bpm,
+            CcParam::ReverbRoom       => &mut self.reverb.room_size,
+            CcParam::ReverbDamp       => &mut self.reverb.damping,
+            CcParam::ReverbMix        => &mut self.reverb.mix,
+            CcParam::ReverbEr         => &mut self.reverb.er_amount,
+            CcParam::ReverbShimmer    => &mut self.reverb.shimmer,
+            CcParam::DelayTime        => &mut self.delay.time_ms,
+            CcParam::DelayFeedback    => &mut self.delay.feedback,
+            CcParam::DelayMix         => &mut self.delay.mix,
+            CcParam::DelayDuck        => &mut self.delay.duck_amount,
+            CcParam::DelayDuckRelease => &mut self.delay.duck_release,
+            CcParam::DistDrive        => &mut self.distortion.drive,
+            CcParam::DistTone         => &mut self.distortion.tone,
+            CcParam::DistLevel        => &mut self.distortion.level,
+            CcParam::DistAsym         => &mut self.distortion.asymmetry,
+            CcParam::SidechainDepth   => &mut self.sidechain.depth,
+            CcParam::SidechainRelease => &mut self.sidechain.release_ms,
+            CcParam::Filter1Cutoff    => &mut self.filter1.cutoff,
+            CcParam::Filter1Q         => &mut self.filter1.q,
+            CcParam::Filter2Cutoff    => &mut self.filter2.cutoff,
+            CcParam::Filter2Q         => &mut self.filter2.q,
+            CcParam::CombFreq         => &mut self.comb.freq,
+            CcParam::CombFeedback     => &mut self.comb.feedback,
+            CcParam::CombMix          => &mut self.comb.mix,
+            CcParam::FormantVowel     => &mut self.formant.vowel,
+            CcParam::FormantMix       => &mut self.formant.mix,
+        }
+    }
+
+    /// Write every automation lane's value at the current loop position.
+    fn apply_automation(&mut self) {
+        let auto = std::mem::take(&mut self.automation);
+        for (param, value) in auto.values(self.step_pos) {
+            *self.param_mut(param) = value;
+        }
+        self.automation = auto;
+    }
+
     // ── Audio render ──────────────────────────────────────────────────────
 
+    /// Set the additive harmonics and rebuild their table.  Voices pick up
+    /// the new shape on their next sample.
+    pub fn set_harmonics(&mut self, amps: [f32; HARMONICS]) {
+        self.harmonics = amps;
+        self.wavetables[ADDITIVE_TABLE] = Wavetable::from_harmonics(&amps);
+    }
+
+    /// Add a table, replacing one loaded from the same file.  Returns its
+    /// index.
+    pub fn add_wavetable(&mut self, table: Wavetable) -> usize {
+        match self.wavetables.iter().position(|t| t.save_key() == table.save_key()) {
+            Some(i) => { self.wavetables[i] = table; i }
+            None    => { self.wavetables.push(table); self.wavetables.len() - 1 }
+        }
+    }
+
+    /// Display name of a wave, including the table name for wavetables.
+    pub fn wave_name(&self, wave: WaveType) -> &str {
+        match wave {
+            WaveType::Wavetable(i) => self.wavetables.get(i).map_or("Table", |t| t.name.as_str()),
+            w => w.name(),
+        }
+    }
+
+    /// Copy the most recent `n` output samples (at most `SCOPE_LEN`), oldest
+    /// first, into `out`.  Lets the UI take just what it draws under the lock.
+    pub fn scope_window(&self, n: usize, out: &mut Vec<f32>) {
+        let n = n.min(SCOPE_LEN);
+        let start = self.scope_pos.wrapping_sub(n) % SCOPE_LEN;
+        out.clear();
+        if start + n <= SCOPE_LEN {
+            out.extend_from_slice(&self.scope_buf[start..start + n]);
+        } else {
+            out.extend_from_slice(&self.scope_buf[start..]);
+            out.extend_from_slice(&self.scope_buf[..start + n - SCOPE_LEN]);
+        }
+    }
+
+    /// Copy the most recent `n` output frames (at most `STEREO_LEN`), oldest
+    /// first, into `out`.
+    pub fn stereo_window(&self, n: usize, out: &mut Vec<(f32, f32)>) {
+        let n = n.min(STEREO_LEN);
+        let start = self.scope_pos.wrapping_sub(n) % STEREO_LEN;
+        out.clear();
+        if start + n <= STEREO_LEN {
+            out.extend_from_slice(&self.stereo_buf[start..start + n]);
+        } else {
+            out.extend_from_slice(&self.stereo_buf[start..]);
+            out.extend_from_slice(&self.stereo_buf[..start + n - STEREO_LEN]);
+        }
+    }
+
+    /// One mono sample: the mid of `generate_frame()`.  For renders that
+    /// only need the mono signal or the bus taps (freeze, the null driver).
     pub fn generate_sample(&mut self) -> f32 {
-        let clock = self.master_clock;
+        let (l, r) = self.generate_frame();
+        (l + r) * 0.5
+    }
+
+    /// One stereo output frame, left and right.  The synths are panned on
+    /// the dry mix and the drum bus per track; the effect returns are mono,
+    /// on both sides.  With `mono_sum` on the frame is folded to its mid.
+    pub fn generate_frame(&mut self) -> (f32, f32) {
         self.master_clock += 1;
+        let prev = self.step_pos;
+        self.step_pos = match &mut self.ext_clock {
+            Some(ext) => ext.advance(prev, self.sample_rate),
+            None      => prev + self.bpm as f64 * 4.0 / (60.0 * self.sample_rate as f64),
+        };
+        let clock = StepClock { prev, pos: self.step_pos };
+        if self.master_clock.is_multiple_of(AUTOMATION_INTERVAL) && self.transport_running() {
+            self.apply_automation();
+        }
+        let mel_groove = if self.groove.melodic { self.groove } else { Groove::STRAIGHT };
 
         // ── Sequencer 1 ───────────────────────────────────────────────────
-        if let Some(ev) = self.sequencer.tick(self.bpm, clock) {
-            if let Some(n) = ev.note_off { if let Some(v) = self.voices.get_mut(&n) { v.release(); } }
-            if let Some(n) = ev.note_on  { self.voices.insert(n, Voice::new(n)); }
+        if let Some(ev) = self.sequencer.tick(clock, mel_groove, self.live_scale.as_ref()) {
+            self.apply_lock(false, ev.lock);
+            if self.acid.enabled && !ev.note_on.is_empty() { self.acid.note(ev.accent, ev.slide); }
+            self.seq_step(false, ev);
         }
 
         // ── Sequencer 2 ───────────────────────────────────────────────────
-        if let Some(ev) = self.sequencer2.tick(self.bpm, clock) {
-            if let Some(n) = ev.note_off { if let Some(v) = self.voices2.get_mut(&n) { v.release(); } }
-            if let Some(n) = ev.note_on  { self.voices2.insert(n, Voice::new(n)); }
+        if let Some(ev) = self.sequencer2.tick(clock, mel_groove, self.live_scale.as_ref()) {
+            self.apply_lock(true, ev.lock);
+            self.seq_step(true, ev);
         }
 
+        // ── Arpeggiator (keyboard notes, routed as keys) ──────────────────
+        let ev = self.arp.tick(clock);
+        if let Some(n) = ev.note_off { self.arp_note_off(n); }
+        if let Some(n) = ev.note_on  {
+            if self.midi_note(self.midi_notes.keys, n, true) { self.note_on(n); }
+        }
+
+        // ── LFOs ──────────────────────────────────────────────────────────
+        // One factor per `LfoTarget`, for this sample only.
+        let mut lfo = [1.0f32; LfoTarget::ALL.len()];
+        for l in &self.lfos { lfo[l.target as usize] *= l.factor_at(clock.prev); }
+
         // ── Melodic bus 1 ─────────────────────────────────────────────────
         let sr   = self.sample_rate;
         let wave = self.wave_type;
         let (a, d, s, r) = (self.attack, self.decay, self.sustain, self.release);
         let mut mel1 = 0.0f32;
-        for v in self.voices.values_mut() { mel1 += v.next_sample(sr, wave, a, d, s, r); }
+        let bl   = self.band_limited;
+        for v in self.voices.values_mut() { mel1 += v.next_sample(sr, wave, &self.wavetables, &self.granular, bl, a, d, s, r); }
         self.voices.retain(|_, v| !v.is_finished());
-        let mel1_scaled   = mel1 * self.volume / (self.voices.len().max(1) as f32).sqrt();
-        let mel1_filtered = self.filter1.process(mel1_scaled);
+        let mel1_raw      = mel1 / (self.voices.len().max(1) as f32).sqrt()
+                          + self.frozen_sample(0, self.sequencer.playing);
+        let (cutoff_mod, acid_gain) = if self.acid.enabled { self.acid.tick(sr) } else { (1.0, 1.0) };
+        self.filter1.cutoff_mod = cutoff_mod * lfo[LfoTarget::Cutoff1 as usize];
+        let mel1_scaled   = mel1_raw * self.volume * acid_gain * lfo[LfoTarget::Volume1 as usize];
+        let mut mel1_filtered = self.filter1.process(mel1_scaled);
+        if !self.formant.on_s2 { mel1_filtered = self.formant.process(mel1_filtered); }
         let mel1_out      = self.fx.process(mel1_filtered);
 
         // ── Melodic bus 2 ─────────────────────────────────────────────────
         let wave2 = self.wave_type2;
         let (a2, d2, s2, r2) = (self.attack2, self.decay2, self.sustain2, self.release2);
         let mut mel2 = 0.0f32;
-        for v in self.voices2.values_mut() { mel2 += v.next_sample(sr, wave2, a2, d2, s2, r2); }
+        for v in self.voices2.values_mut() { mel2 += v.next_sample(sr, wave2, &self.wavetables, &self.granular, bl, a2, d2, s2, r2); }
         self.voices2.retain(|_, v| !v.is_finished());
-        let mel2_scaled   = mel2 * self.volume2 / (self.voices2.len().max(1) as f32).sqrt();
-        let mel2_filtered = self.filter2.process(mel2_scaled);
+        let mel2_raw      = mel2 / (self.voices2.len().max(1) as f32).sqrt()
+                          + self.frozen_sample(1, self.sequencer2.playing);
+        self.filter2.cutoff_mod = lfo[LfoTarget::Cutoff2 as usize];
+        let mel2_scaled   = mel2_raw * self.volume2 * lfo[LfoTarget::Volume2 as usize];
+        let mut mel2_filtered = self.filter2.process(mel2_scaled);
+        if self.formant.on_s2 { mel2_filtered = self.formant.process(mel2_filtered); }
         let mel2_out      = self.fx2.process(mel2_filtered);
 
+        // ── Crossfader ────────────────────────────────────────────────────
+        let pre_xf = [mel1_out, mel2_out];
+        let (xf1, xf2) = crossfade_gains(self.crossfade);
+        let (mel1_out, mel2_out) = (mel1_out * xf1, mel2_out * xf2);
+
         // ── Drum bus ──────────────────────────────────────────────────────
-        let drum_out = self.drum_machine.generate_sample(self.bpm, clock) * self.volume;
+        // Stereo from the track pans; the sends and cue take its mid.
+        let (drum_l, drum_r) = self.drum_machine.generate_sample(clock, self.groove);
+        let drum_raw  = (drum_l + drum_r) * 0.5 + self.frozen_sample(2, self.drum_machine.playing);
+        let drum_side = (drum_l - drum_r) * 0.5 + self.frozen_drum_side();
+        self.bus_raw = [mel1_raw, mel2_raw, drum_raw];
+        self.drum_side_raw = drum_side;
+        let (drum_mid, drum_side) = self.deesser.process_ms(drum_raw, drum_side);
+        let drum_out  = drum_mid * self.drum_volume;
+        let (drum_out_l, drum_out_r) = ((drum_mid + drum_side) * self.drum_volume,
+                                        (drum_mid - drum_side) * self.drum_volume);
+
+        // ── Cue (pre-listen) ──────────────────────────────────────────────
+        // After the bus's volume and inserts, before the crossfader and
+        // sidechain, so a bus faded out of the main mix can still be heard.
+        self.cue_out = self.cue.map(|b| match b {
+            Bus::Synth1 => pre_xf[0],
+            Bus::Synth2 => pre_xf[1],
+            Bus::Drums  => drum_out,
+        }.tanh());
 
         // ── Sidechain ─────────────────────────────────────────────────────
         let kick = self.drum_machine.kick_triggered;
         self.drum_machine.kick_triggered = false;
-        if kick { self.sidechain.envelope = 1.0; }
-        let rel_c = (-1.0_f32 / (self.sidechain.release_ms * 0.001 * self.sample_rate)).exp();
-        self.sidechain.envelope = (self.sidechain.envelope * rel_c).clamp(0.0, 1.0);
-        let sc_gain = 1.0 - self.sidechain.envelope * self.sidechain.depth;
+        let sc_gain = self.sidechain.next_gain(kick, self.sample_rate);
         let mel1_out = if self.sidechain.enabled && self.sidechain.duck_s1 { mel1_out * sc_gain } else { mel1_out };
         let mel2_out = if self.sidechain.enabled && self.sidechain.duck_s2 { mel2_out * sc_gain } else { mel2_out };
 
         // ── Master mix (always dry) ───────────────────────────────────────
-        let dry = (mel1_out + mel2_out + drum_out).tanh();
+        // The synths are panned on the dry mix only; the sends below take
+        // their mono signal.
+        let ((p1l, p1r), (p2l, p2r)) = (pan_gains(self.pan), pan_gains(self.pan2));
+        let dry_l = (mel1_out * p1l + mel2_out * p2l + drum_out_l).tanh();
+        let dry_r = (mel1_out * p1r + mel2_out * p2r + drum_out_r).tanh();
 
         // ── FX sends (wet-only, parallel) ─────────────────────────────────
         // Copy routing values out to avoid split-borrow conflicts.
-        let (s1_rev, s1_dly, s1_dst,
-             s2_rev, s2_dly, s2_dst,
-             dr_rev, dr_dly, dr_dst) = {
+        let (s1_rev, s1_dly, s1_dst, s1_cmb, s1_chr, s1_crs,
+             s2_rev, s2_dly, s2_dst, s2_cmb, s2_chr, s2_crs,
+             dr_rev, dr_dly, dr_dst, dr_cmb, dr_chr, dr_crs) = {
             let rt = &self.fx_routing;
-            (rt.s1_reverb, rt.s1_delay, rt.s1_dist,
-             rt.s2_reverb, rt.s2_delay, rt.s2_dist,
-             rt.dr_reverb, rt.dr_delay, rt.dr_dist)
+            (rt.s1_reverb, rt.s1_delay, rt.s1_dist, rt.s1_comb, rt.s1_chorus, rt.s1_crush,
+             rt.s2_reverb, rt.s2_delay, rt.s2_dist, rt.s2_comb, rt.s2_chorus, rt.s2_crush,
+             rt.dr_reverb, rt.dr_delay, rt.dr_dist, rt.dr_comb, rt.dr_chorus, rt.dr_crush)
         };
 
         let rev_wet = self.reverb.process(
             s1_rev * mel1_out + s2_rev * mel2_out + dr_rev * drum_out);
-        let dly_wet = self.delay.process(
+        self.delay.set_bpm(self.bpm);
+        let (dly_l, dly_r) = self.delay.process_stereo(
             s1_dly * mel1_out + s2_dly * mel2_out + dr_dly * drum_out);
         let dst_wet = self.distortion.process(
             (s1_dst * mel1_out + s2_dst * mel2_out + dr_dst * drum_out).tanh());
+        let cmb_wet = self.comb.process(
+            s1_cmb * mel1_out + s2_cmb * mel2_out + dr_cmb * drum_out);
+        let (chr_l, chr_r) = self.chorus.process_stereo(
+            s1_chr * mel1_out + s2_chr * mel2_out + dr_chr * drum_out);
+        let crs_wet = self.crusher.process(
+            s1_crs * mel1_out + s2_crs * mel2_out + dr_crs * drum_out);
+
+        let fall = (-1.0_f32 / (METER_RELEASE_S * self.sample_rate)).exp();
+        let wets = [rev_wet, dly_l.abs().max(dly_r.abs()), dst_wet, cmb_wet, chr_l.abs().max(chr_r.abs()), crs_wet];
+        for (m, wet) in self.fx_meters.iter_mut().zip(wets) { m.feed(wet, fall); }
+        let drum_peak = drum_out_l.abs().max(drum_out_r.abs());
+        for (m, bus) in self.bus_meters.iter_mut().zip([mel1_scaled, mel2_scaled, drum_peak]) { m.feed(bus, fall); }
 
-        let out = (dry + rev_wet + dly_wet + dst_wet).tanh();
-        self.scope_buf[self.scope_pos % 512] = out;
+        let click = self.metronome.tick(clock, self.transport_running(), self.sample_rate);
+        let (l, r) = self.spectral.process_stereo(dry_l + rev_wet + dly_l + dst_wet + cmb_wet + chr_l + crs_wet,
+                                                  dry_r + rev_wet + dly_r + dst_wet + cmb_wet + chr_r + crs_wet);
+        let (l, r) = self.compressor.process_stereo(l, r);
+        let l = (l + click).tanh() * self.master_gain;
+        let r = (r + click).tanh() * self.master_gain;
+        let (l, r) = if self.mono_sum { let m = (l + r) * 0.5; (m, m) } else { (l, r) };
+        self.master_peak = self.master_peak.max(l.abs()).max(r.abs());
+        self.correlation.feed(l, r, 1.0 - (-1.0 / (CORRELATION_S * self.sample_rate)).exp());
+        self.scope_buf[self.scope_pos % SCOPE_LEN] = (l + r) * 0.5;
+        self.stereo_buf[self.scope_pos % STEREO_LEN] = (l, r);
         self.scope_pos = self.scope_pos.wrapping_add(1);
-        out
+        (l, r)
     }
 }
 
 // ── Helpers ───────────────────────────────────────────────────────────────────
 
+/// Synth 1 and synth 2 gains at crossfader position `x`: each side holds
+/// unity up to the centre and fades linearly to silence at the far end, so
+/// the centre leaves the mix as it was without a fader.
+pub fn crossfade_gains(x: f32) -> (f32, f32) {
+    let x = x.clamp(0.0, 1.0);
+    ((2.0 * (1.0 - x)).min(1.0), (2.0 * x).min(1.0))
+}
+
 pub fn note_to_freq(note: u8) -> f32 {
     440.0 * 2f32.powf((note as f32 - 69.0) / 12.0)
 }
 
+/// Left and right gains at pan position `pan` (-1..1): the far side holds
+/// unity and the near one fades linearly to silence, so the centre leaves
+/// the signal as it was on both sides.
+pub fn pan_gains(pan: f32) -> (f32, f32) {
+    let p = pan.clamp(-1.0, 1.0);
+    ((1.0 - p).min(1.0), (1.0 + p).min(1.0))
+}
+
+/// Pan position for display: `C`, or the side and percent, e.g. `L40`.
+pub fn pan_label(pan: f32) -> String {
+    let pct = (pan.abs() * 100.0).round() as u32;
+    match pct {
+        0                 => "C".to_string(),
+        _ if pan < 0.0    => format!("L{}", pct),
+        _                 => format!("R{}", pct),
+    }
+}
+
+/// BPM for display: whole numbers as is, fractional tempos with one decimal.
+pub fn bpm_label(bpm: f32) -> String {
+    if (bpm - bpm.round()).abs() < 0.05 { format!("{:.0}", bpm) } else { format!("{:.1}", bpm) }
+}
+
+/// Length of one 16th-note step at `bpm`, for display: `125ms`.
+pub fn step_ms_label(bpm: f32) -> String {
+    format!("{:.0}ms", 60_000.0 / (bpm * 4.0))
+}
+
+/// An envelope time in seconds, for display: `10ms`, `1.25s`.
+pub fn env_time_label(secs: f32) -> String {
+    if secs < 1.0 { format!("{:.0}ms", secs * 1000.0) } else { format!("{:.2}s", secs) }
+}
+
 pub fn note_name(note: u8) -> String {
     let names = ["C","C#","D","D#","E","F","F#","G","G#","A","A#","B"];
     format!("{}{}", names[(note % 12) as usize], (note / 12) as i32 - 1)
 }
+
+/// Several notes by name, space-separated: `C4 E4 G4`.
+pub fn notes_label(notes: &[u8]) -> String {
+    notes.iter().map(|&n| note_name(n)).collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sidechain_envelope_settles_to_exact_zero() {
+        for shape in SidechainShape::ALL {
+            let mut sc = Sidechain::new();
+            (sc.shape, sc.release_ms) = (shape, 500.0);
+            sc.next_gain(true, 44_100.0);
+            for _ in 0..30 * 44_100 { sc.next_gain(false, 44_100.0); }
+            assert_eq!(sc.envelope, 0.0, "{:?}", shape);
+        }
+    }
+
+    /// Share of a sawtooth's spectrum (Hann-windowed, 8192 points at
+    /// 44.1 kHz) that lies away from its harmonics: the aliasing.
+    fn saw_inharmonic_share(note: u8, band_limited: bool) -> f32 {
+        const N: usize = 8192;
+        let sr = 44_100.0;
+        let granular = Granular::new();
+        let mut v = Voice::new(note);
+        (v.stage, v.level) = (EnvelopeStage::Sustain, 1.0);
+        let window = crate::fft::hann(N);
+        let mut re: Vec<f32> = window.iter()
+            .map(|w| w * v.next_sample(sr, WaveType::Sawtooth, &[], &granular, band_limited, 0.01, 0.1, 1.0, 0.1))
+            .collect();
+        let mut im = vec![0.0; N];
+        crate::fft::fft(&mut re, &mut im, false);
+
+        let bin_hz = sr / N as f32;
+        let f0 = note_to_freq(note);
+        let (mut harmonic, mut total) = (0.0, 0.0);
+        for (k, (r, i)) in re.iter().zip(&im).enumerate().take(N / 2) {
+            let power = r * r + i * i;
+            let hz = k as f32 * bin_hz;
+            // The Hann main lobe is two bins either side; allow one more.
+            let near = (hz / f0).round() * f0;
+            if near > 0.0 && (hz - near).abs() <= 3.0 * bin_hz { harmonic += power; }
+            total += power;
+        }
+        1.0 - harmonic / total
+    }
+
+    #[test]
+    fn band_limited_saw_aliases_less_than_naive() {
+        // A7, 3520 Hz: only six harmonics fit under Nyquist.
+        let naive = saw_inharmonic_share(105, false);
+        let bl    = saw_inharmonic_share(105, true);
+        assert!(bl < naive / 10.0, "band-limited {:.4} vs naive {:.4}", bl, naive);
+    }
+}
@@ -7,10 +7,15 @@ use ratatui::{
 };
 use std::collections::HashSet;
 
-use crate::app::{App, AppMode, InputMode};
-use crate::drums::DrumKind;
-use crate::effects::FilterMode;
-use crate::synth::note_name;
+use crate::app::{App, AppMode, ClearTarget, CompRow, DrumTrackView, FilterRow, FreezeRow, InputMode, Snapshot};
+use crate::drums::{DrumKind, TrigCondition};
+use crate::effects::{FormantFilter, NoteValue, Reverb, CHORUS_RATE_MAX, CHORUS_RATE_MIN, COMB_MAX_HZ, COMB_MIN_HZ, FILTER_MAX_GAIN_DB,
+                     FREEZE_MAX_FADE_MS};
+use crate::freeze::Bus;
+use crate::scale::{chord_name, pitch_class_name};
+use crate::sequencer::{nudge_label, stack_notes};
+use crate::spectrogram;
+use crate::synth::{bpm_label, env_time_label, note_name, notes_label, pan_label, step_ms_label, SidechainShape, ENV_TIME_MAX, ENV_TIME_MIN};
 
 // ── Top-level routing ─────────────────────────────────────────────────────────
 
@@ -18,51 +23,105 @@ use crate::synth::note_name;
 /// keyboard focus (highlighted border), not what is visible.
 pub fn draw(f: &mut Frame, app: &App, enhanced: bool) {
     let area = f.area();
+    // One lock per frame: every panel draws from the same copy of the synth.
+    // The scope shows two samples per (bordered) column, beside the goniometer.
+    let snap = app.snapshot(area.width.saturating_sub(2 + GONIO_WIDTH) as usize * 2);
+    // Grids grow a pair of lines per extra row of steps (8 lines at most 32).
+    let inner_w = area.width.saturating_sub(2);
+    let seq_rows = |n: usize| (4 + 2 * n.div_ceil(seq_per_row(n, inner_w)) as u16).max(8);
+    // Drum panel: header, then per block of steps a number row and one row
+    // per track, borders.
+    let drum_blocks = snap.drums.num_steps.div_ceil(drum_block_steps(snap.drums.num_steps, inner_w)) as u16;
+    let drum_rows = drum_blocks * (snap.drums.tracks.len() as u16 + 1) + 3;
+    // The spectrogram takes a taller slot (from the help panel) than the scope.
+    let editor = matches!(app.mode, AppMode::Harmonics | AppMode::Sample | AppMode::Acid | AppMode::Envelope
+                                   | AppMode::Modulation | AppMode::Inserts);
+    let show_spectrogram = app.spectrogram_on && !editor;
+    let scope_rows = if show_spectrogram { SPECTROGRAM_LINES + 2 } else { 6 };
+
     let chunks = Layout::default()
         .direction(Direction::Vertical)
         .constraints([
             Constraint::Length(3),  // title bar      chunks[0]
-            Constraint::Length(12), // piano keyboard  chunks[1]
-            Constraint::Length(8),  // synth seq 1     chunks[2]
-            Constraint::Length(8),  // synth seq 2     chunks[3]
-            Constraint::Length(12), // drum machine    chunks[4]
-            Constraint::Length(8),  // effects         chunks[5]
-            Constraint::Length(4),  // status          chunks[6]
-            Constraint::Length(6),  // scope           chunks[7]
-            Constraint::Min(0),     // help            chunks[8]
+            Constraint::Length(4),  // timeline       chunks[1]
+            Constraint::Length(12), // piano keyboard  chunks[2]
+            Constraint::Length(seq_rows(snap.seq.num_steps)),  // synth seq 1  chunks[3]
+            Constraint::Length(seq_rows(snap.seq2.num_steps)), // synth seq 2  chunks[4]
+            Constraint::Length(drum_rows), // drums   chunks[5]
+            Constraint::Length(15), // effects         chunks[6]
+            Constraint::Length(5),  // status          chunks[7]
+            Constraint::Length(scope_rows), // scope  chunks[8]
+            Constraint::Min(0),     // help            chunks[9]
         ])
         .split(area);
 
-    draw_title(f, chunks[0], enhanced, app);
-    draw_piano(f, chunks[1], app);
-    draw_synth_seq(f, chunks[2], app);
-    draw_synth_seq2(f, chunks[3], app);
-    draw_drums(f, chunks[4], app);
-    draw_effects(f, chunks[5], app);
-    draw_status(f, chunks[6], app);
-    draw_oscilloscope(f, chunks[7], app);
-    draw_help(f, chunks[8], app);
+    draw_title(f, chunks[0], enhanced, app, &snap);
+    draw_timeline(f, chunks[1], app, &snap);
+    draw_piano(f, chunks[2], app, &snap);
+    draw_synth_seq(f, chunks[3], app, &snap);
+    draw_synth_seq2(f, chunks[4], app, &snap);
+    draw_drums(f, chunks[5], app, &snap);
+    draw_effects(f, chunks[6], app, &snap);
+    draw_status(f, chunks[7], app, &snap);
+    // The harmonics, sample, acid, envelope, LFO and insert editors take the scope's place while focused.
+    match app.mode {
+        AppMode::Harmonics => draw_harmonics(f, chunks[8], app, &snap),
+        AppMode::Sample    => draw_sample(f, chunks[8], app, &snap),
+        AppMode::Acid      => draw_acid(f, chunks[8], app, &snap),
+        AppMode::Envelope  => draw_envelope(f, chunks[8], app, &snap),
+        AppMode::Modulation => draw_modulation(f, chunks[8], app, &snap),
+        AppMode::Inserts   => draw_inserts(f, chunks[8], app, &snap),
+        _                  => {
+            let cols = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Min(0), Constraint::Length(GONIO_WIDTH)])
+                .split(chunks[8]);
+            if show_spectrogram { draw_spectrogram(f, cols[0], app); } else { draw_oscilloscope(f, cols[0], &snap); }
+            draw_goniometer(f, cols[1], &snap);
+        }
+    }
+    draw_help(f, chunks[9], app, &snap);
 }
 
 // ── Title bar ─────────────────────────────────────────────────────────────────
 
-fn draw_title(f: &mut Frame, area: Rect, enhanced: bool, app: &App) {
+fn draw_title(f: &mut Frame, area: Rect, enhanced: bool, app: &App, snap: &Snapshot) {
     let focus_label = match app.mode {
         AppMode::Play      => "Keyboard",
         AppMode::SynthSeq  => "Synth Seq",
         AppMode::SynthSeq2 => "Synth Seq 2",
         AppMode::Drums     => "Drums",
         AppMode::Effects   => "Effects",
+        AppMode::Harmonics => "Harmonics",
+        AppMode::Sample    => "Sample",
+        AppMode::Acid      => "Acid",
+        AppMode::Envelope  => "Envelope",
+        AppMode::Modulation => "Modulation",
+        AppMode::Inserts   => "Inserts",
     };
     let kb_mode  = if enhanced { "enhanced" } else { "fallback" };
-    let seq_ind  = if app.seq_playing()  { "  ▶SEQ"  } else { "" };
-    let seq2_ind = if app.seq2_playing() { "  ▶SEQ2" } else { "" };
-    let drum_ind = if app.drum_playing() { "  ▶DRUM" } else { "" };
-    let fx_ind   = app.fx_indicators();
+    let audio_ind = if app.audio.is_null() {
+        "  ─  NO AUDIO".to_string()
+    } else {
+        format!("  ─  {} @ {:.1} kHz", app.audio.device_name(), app.audio.sample_rate() as f32 / 1000.0)
+    };
+    let seq_ind  = if snap.seq.playing   { "  ▶SEQ"  } else { "" };
+    let seq2_ind = if snap.seq2.playing  { "  ▶SEQ2" } else { "" };
+    let drum_ind = if snap.drums.playing { "  ▶DRUM" } else { "" };
+    let auto_ind = if app.automation.armed { "  ●AUTO REC" } else { "" };
+    let rec_ind  = if app.seq_rec { "  ●REC SEQ" } else { "" };
+    let click_ind = if snap.metronome { "  ─  CLICK" } else { "" };
+    let mono_ind = if snap.mono_sum { "  ─  MONO" } else { "" };
+    let sync_ind = match snap.ext_sync {
+        Some(true)  => "  ─  EXT SYNC ▶",
+        Some(false) => "  ─  EXT SYNC ■",
+        None        => "",
+    };
+    let fx_ind   = snap.fx_indicators();
 
     let text = format!(
-        "  RustTuiSynth  ─  Focus: {}{}{}{}{}  ─  [{}]  ─  Tab/F2: cycle focus  F1: wave  F3: drums",
-        focus_label, seq_ind, seq2_ind, drum_ind, fx_ind, kb_mode
+        "  RustTuiSynth  ─  Focus: {}{}{}{}{}{}{}  ─  [{}]{}{}{}{}  ─  Tab/F2: cycle focus  F1: wave  F3: drums",
+        focus_label, seq_ind, seq2_ind, drum_ind, auto_ind, rec_ind, fx_ind, kb_mode, audio_ind, mono_ind, click_ind, sync_ind
     );
     let color = if enhanced { Color::Cyan } else { Color::Yellow };
     f.render_widget(
@@ -74,14 +133,87 @@ fn draw_title(f: &mut Frame, area: Rect, enhanced: bool, app: &App) {
     );
 }
 
+// ── Arrangement timeline ──────────────────────────────────────────────────────
+
+/// Columns per bar on the timeline ruler.
+const BAR_COLS: usize = 4;
+
+/// Bar ruler with section names above it.  Shows a page of bars holding
+/// the playhead; each marker's name runs up to the next marker.
+fn draw_timeline(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
+    let markers = &app.arrangement.markers;
+    let section = app.arrangement.section_at(snap.bar);
+    let title = format!(
+        " Arrangement — Bar {}{} ",
+        snap.bar + 1,
+        section.map_or(String::new(), |m| format!("  ·  {}", m.name)),
+    );
+
+    let width = area.width.saturating_sub(2) as usize;
+    let bars  = (width / BAR_COLS).max(1);
+    let first = snap.bar / bars * bars;
+    let running = snap.seq.playing || snap.seq2.playing || snap.drums.playing;
+
+    let mut names = vec![' '; bars * BAR_COLS];
+    for (i, m) in markers.iter().enumerate().filter(|(_, m)| (first..first + bars).contains(&m.bar)) {
+        let from = (m.bar - first) * BAR_COLS;
+        let to   = markers.get(i + 1).map_or(names.len(), |n| ((n.bar - first) * BAR_COLS).min(names.len()));
+        for (slot, c) in names[from..to.saturating_sub(1).max(from + 1)].iter_mut().zip(m.name.chars()) {
+            *slot = c;
+        }
+    }
+    // A section that began on an earlier page carries its name in from the left.
+    if let Some(m) = section.filter(|m| m.bar < first) {
+        let to = markers.iter().find(|n| n.bar >= first).map_or(names.len(), |n| (n.bar - first) * BAR_COLS);
+        for (slot, c) in names[..to.saturating_sub(1)].iter_mut().zip(format!("…{}", m.name).chars()) {
+            *slot = c;
+        }
+    }
+
+    let mut ruler = Vec::with_capacity(bars);
+    for b in first..first + bars {
+        let marked = markers.iter().any(|m| m.bar == b);
+        let label  = format!("{}{:<w$}", if marked { '┃' } else { '│' }, b + 1, w = BAR_COLS - 1);
+        let sty = if b == snap.bar && running {
+            Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD)
+        } else if b == snap.bar {
+            Style::default().fg(Color::Black).bg(Color::DarkGray)
+        } else if marked {
+            Style::default().fg(Color::Yellow)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        ruler.push(Span::styled(label, sty));
+    }
+
+    f.render_widget(
+        Paragraph::new(vec![
+            Line::from(Span::styled(names.into_iter().collect::<String>(), Style::default().fg(Color::Yellow))),
+            Line::from(ruler),
+        ])
+        .block(Block::default().title(title).borders(Borders::ALL)
+            .border_style(Style::default().fg(Color::DarkGray))),
+        area,
+    );
+}
+
 // ── Piano keyboard ────────────────────────────────────────────────────────────
 
-fn draw_piano(f: &mut Frame, area: Rect, app: &App) {
+fn draw_piano(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
     let focused = app.mode == AppMode::Play;
-    let title = if focused {
-        " ► Keyboard — [←→] Octave  [↑↓] Volume  [Z-M / Q-P] Play notes "
-    } else {
-        " Keyboard "
+    let title = match (focused, app.latch) {
+        (true, false) => " ► Keyboard — [←→] Octave  [↑↓] Volume  [Z-M / Q-P] Play notes  [Space] Latch ".to_string(),
+        (true, true)  => format!(" ► Keyboard — LATCH ({} held)  [Space] Latch off  [Del] Release all ", app.latched.len()),
+        (false, false) => " Keyboard ".to_string(),
+        (false, true)  => format!(" Keyboard — LATCH ({} held) ", app.latched.len()),
+    };
+    let title = match app.chord {
+        Some(c) => format!("{}— CHORD: {} ", title, c.name()),
+        None    => title,
+    };
+    let title = match snap.arp {
+        Some((rate, mode)) => format!("{}— ARP {} {} ", title, rate.name(), mode.name()),
+        None               => title,
     };
     let block = Block::default()
         .title(title)
@@ -275,19 +407,92 @@ fn render_piano_widget(f: &mut Frame, area: Rect, base_octave: i32, active: &Has
 
 // ── Melodic step sequencer ────────────────────────────────────────────────────
 
-fn draw_synth_seq(f: &mut Frame, area: Rect, app: &App) {
+/// Width of one melodic step cell.
+const SEQ_CELL: u16 = 5;
+
+/// Steps per row of a melodic grid `width` columns wide: 16 (8 for an
+/// 8-step pattern), in fewer groups of 4 where that doesn't fit.
+fn seq_per_row(num_steps: usize, width: u16) -> usize {
+    let fit = ((width / SEQ_CELL) as usize / 4 * 4).max(4);
+    (if num_steps <= 8 { 8 } else { 16 }).min(fit)
+}
+
+/// Panel-header label of a sequencer's velocity humanize.
+fn vel_humanize_label(amount: f32) -> String {
+    if amount > 0.0 { format!("VelHum:{:.0}%", amount * 100.0) } else { "VelHum:off".to_string() }
+}
+
+/// A step cell marked as sliding into the next note: its right bracket
+/// becomes `~` (a late nudge's `›` still takes precedence).
+fn slid(cell: String, slide: bool) -> String {
+    if !slide { return cell; }
+    let mut chars: Vec<char> = cell.chars().collect();
+    *chars.last_mut().unwrap() = '~';
+    chars.into_iter().collect()
+}
+
+/// A step cell with its nudge marked: an early step's left bracket becomes
+/// `‹`, a late one's right bracket `›`.
+fn nudged(cell: String, nudge: i8) -> String {
+    let mut chars: Vec<char> = cell.chars().collect();
+    match nudge.signum() {
+        -1 => chars[0] = '‹',
+        1  => *chars.last_mut().unwrap() = '›',
+        _  => {}
+    }
+    chars.into_iter().collect()
+}
+
+/// A panel title, with a "FROZEN" badge while the bus plays a frozen
+/// render.  The badge names the render's tempo once the BPM has moved off it.
+fn frozen_title(title: &str, frozen: Option<f32>, bpm: f32) -> Line<'_> {
+    let mut spans = vec![Span::raw(title)];
+    if let Some(at) = frozen {
+        let badge = if (at - bpm).abs() < 0.05 { " FROZEN ".to_string() }
+                    else { format!(" FROZEN @ {} BPM ", bpm_label(at)) };
+        spans.push(Span::styled(badge, Style::default().fg(Color::Black).bg(Color::LightBlue).add_modifier(Modifier::BOLD)));
+        spans.push(Span::raw(" "));
+    }
+    Line::from(spans)
+}
+
+/// `Mono` (highlighted) or `Poly`, for a synth's header line.
+/// The cued bus after the output device, red while the device has no
+/// channels for it.
+fn cue_span(cue: Option<Bus>, audible: bool) -> Span<'static> {
+    match cue {
+        Some(b) => Span::styled(
+            format!("  Cue: {}", b.name()),
+            Style::default().fg(if audible { Color::Yellow } else { Color::Red }),
+        ),
+        None => Span::raw(""),
+    }
+}
+
+fn mono_span(mono: bool) -> Span<'static> {
+    if mono {
+        Span::styled("Mono", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD))
+    } else {
+        Span::styled("Poly", Style::default().fg(Color::DarkGray))
+    }
+}
+
+fn draw_synth_seq(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
     let focused = app.mode == AppMode::SynthSeq;
     let title = if focused {
-        " ► Synth Seq — [←→] Cursor  [↑↓] BPM  [Enter/Space] Play  [Del] Clear  []] Steps  [-=] Vol  [[{] Oct "
+        " ► Synth Seq — [←→] Cursor  [↑↓] BPM  [Enter/Space] Play  [Del] Clear  [\\] Mute  [~] Slide  [a] Audition  []] Steps  [-=] Vol  [()] Cutoff  [<>] Pan  [[{] Oct  [k] Hold step  [Alt←→] Nudge  [Q] Quantize "
     } else {
         " Synth Seq "
     };
 
-    let (bpm, num_steps, current_step, playing, steps, volume) = {
-        let s = app.synth.lock().unwrap();
-        (s.bpm, s.sequencer.num_steps, s.sequencer.current_step,
-         s.sequencer.playing, s.sequencer.steps.clone(), s.volume)
-    };
+    let (bpm, volume) = (snap.bpm, snap.volume);
+    let (num_steps, current_step, playing, steps) =
+        (snap.seq.num_steps, snap.seq.current_step, snap.seq.playing, &snap.seq.steps);
+    let muted = &snap.seq.muted;
+    let slide = &snap.seq.slide;
+    let octave = &snap.seq.octave;
+    let locked = &snap.seq.locked;
+    let nudge = &snap.seq.nudge;
     let cursor = app.seq_cursor;
     let mut lines: Vec<Line> = Vec::new();
 
@@ -295,7 +500,8 @@ fn draw_synth_seq(f: &mut Frame, area: Rect, app: &App) {
         if playing { ("▶ PLAYING", Color::Green) } else { ("■ STOPPED", Color::DarkGray) };
     lines.push(Line::from(vec![
         Span::styled("BPM: ", Style::default().fg(Color::DarkGray)),
-        Span::styled(format!("{:.0}", bpm), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled(bpm_label(bpm), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" ({}/step)", step_ms_label(bpm)), Style::default().fg(Color::DarkGray)),
         Span::raw("  "),
         Span::styled("Steps: ", Style::default().fg(Color::DarkGray)),
         Span::styled(format!("{}", num_steps), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
@@ -305,37 +511,70 @@ fn draw_synth_seq(f: &mut Frame, area: Rect, app: &App) {
         Span::styled("Vol: ", Style::default().fg(Color::DarkGray)),
         Span::styled(format!("{:.0}%", volume * 100.0), Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
         Span::raw("  "),
+        Span::styled("Pan: ", Style::default().fg(Color::DarkGray)),
+        Span::styled(pan_label(snap.pan[0]), Style::default().fg(Color::Magenta)),
+        Span::raw("  "),
+        mono_span(snap.mono[0]),
+        Span::raw("  "),
         Span::styled(format!("Oct:{}", app.base_octave), Style::default().fg(Color::DarkGray)),
+        Span::raw("  "),
+        Span::styled(if app.audition { "Aud:on" } else { "Aud:off" }, Style::default().fg(Color::DarkGray)),
+        Span::raw("  "),
+        Span::styled(if app.paint { "Paint:on" } else { "Paint:off" }, Style::default().fg(Color::DarkGray)),
+        Span::raw("  "),
+        Span::styled(if snap.seq.tie_repeats { "Tie:on" } else { "Tie:off" }, Style::default().fg(Color::DarkGray)),
+        Span::raw("  "),
+        Span::styled(vel_humanize_label(snap.seq.vel_humanize), Style::default().fg(Color::DarkGray)),
+        Span::raw("  "),
+        Span::styled(format!("Dir:{}", snap.seq.direction.name()), Style::default().fg(Color::DarkGray)),
     ]));
 
-    let per_row = if num_steps <= 8 { 8 } else { 16 };
+    let per_row = seq_per_row(num_steps, area.width.saturating_sub(2));
     for chunk_start in (0..num_steps).step_by(per_row) {
         let chunk_end = (chunk_start + per_row).min(num_steps);
 
         let mut nums = Vec::new();
-        for i in chunk_start..chunk_end {
+        for (i, &oct) in octave.iter().enumerate().take(chunk_end).skip(chunk_start) {
             let is_ph = playing && i == current_step;
             let is_cu = i == cursor;
             let sty = if is_ph && is_cu { Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD) }
                       else if is_ph     { Style::default().fg(Color::Black).bg(Color::Green) }
                       else if is_cu     { Style::default().fg(Color::Black).bg(Color::Yellow) }
                       else              { Style::default().fg(Color::DarkGray) };
-            nums.push(Span::styled(format!("{:^5}", i + 1), sty));
+            // Octave-shifted steps carry a badge after the step number,
+            // p-locked ones a dot.
+            let (label, sty) = match oct {
+                0 => (format!("{}", i + 1), sty),
+                o => (format!("{}{:+}", i + 1, o),
+                      if is_ph || is_cu { sty } else { Style::default().fg(Color::Magenta) }),
+            };
+            let (label, sty) = if locked[i] {
+                (format!("{}•", label), if is_ph || is_cu { sty } else { Style::default().fg(Color::Cyan) })
+            } else {
+                (label, sty)
+            };
+            nums.push(Span::styled(format!("{:^5}", label), sty));
         }
         lines.push(Line::from(nums));
 
         let mut cells = Vec::new();
-        for i in chunk_start..chunk_end {
+        for (i, step) in steps.iter().enumerate().take(chunk_end).skip(chunk_start) {
             let is_ph = playing && i == current_step;
             let is_cu = i == cursor;
-            let cell = match steps[i] {
-                Some(n) => format!("[{:<3}]", note_name(n)),
+            let is_mu = muted[i];
+            // Muted steps keep their note but are shown in parentheses, dimmed.
+            let cell = match step {
+                Some(n) if is_mu => format!("({:<3})", note_name(*n)),
+                Some(n) => format!("[{:<3}]", note_name(*n)),
                 None    => "[ · ]".to_string(),
             };
+            let cell = nudged(slid(cell, step.is_some() && slide[i]), nudge[i]);
             let sty = if is_ph && is_cu   { Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD) }
                       else if is_ph       { Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD) }
                       else if is_cu       { Style::default().fg(Color::Black).bg(Color::Yellow) }
-                      else if steps[i].is_some() { Style::default().fg(Color::White) }
+                      else if is_mu       { Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM) }
+                      else if step.is_some() && slide[i] { Style::default().fg(Color::LightYellow) }
+                      else if step.is_some() { Style::default().fg(Color::White) }
                       else               { Style::default().fg(Color::DarkGray) };
             cells.push(Span::styled(cell, sty));
         }
@@ -343,18 +582,35 @@ fn draw_synth_seq(f: &mut Frame, area: Rect, app: &App) {
     }
 
     let note_disp = steps.get(cursor).copied().flatten()
-        .map(|n| note_name(n)).unwrap_or_else(|| "·".to_string());
-    lines.push(Line::from(vec![
+        .map(|n| notes_label(&stack_notes(n, &snap.seq.chord[cursor]))).unwrap_or_else(|| "·".to_string());
+    let mute_disp = match (muted.get(cursor).copied().unwrap_or(false), slide.get(cursor).copied().unwrap_or(false)) {
+        (true, true)  => " (muted, slide)",
+        (true, false) => " (muted)",
+        (false, true) => " (slide)",
+        (false, false) => "",
+    };
+    let mut cursor_line = vec![
         Span::styled("Cursor: ", Style::default().fg(Color::DarkGray)),
         Span::styled(
-            format!("step {}/{}  note: {}", cursor + 1, num_steps, note_disp),
+            format!("step {}/{}  note: {}{}", cursor + 1, num_steps, note_disp, mute_disp),
             Style::default().fg(Color::White),
         ),
-    ]));
+    ];
+    if app.held_step == Some((false, cursor)) {
+        cursor_line.push(Span::styled("  HELD", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+    }
+    if let Some(&n) = nudge.get(cursor).filter(|&&n| n != 0) {
+        cursor_line.push(Span::styled(format!("  nudge: {}", nudge_label(n)), Style::default().fg(Color::DarkGray)));
+    }
+    if let Some(l) = &snap.seq.cursor_locks {
+        cursor_line.push(Span::styled("  locks: ", Style::default().fg(Color::DarkGray)));
+        cursor_line.push(Span::styled(l.as_str(), Style::default().fg(Color::Cyan)));
+    }
+    lines.push(Line::from(cursor_line));
 
     f.render_widget(
         Paragraph::new(lines).block(
-            Block::default().title(title).borders(Borders::ALL)
+            Block::default().title(frozen_title(title, snap.frozen[0], snap.bpm)).borders(Borders::ALL)
                 .border_style(if focused {
                     Style::default().fg(Color::Cyan)
                 } else {
@@ -367,20 +623,22 @@ fn draw_synth_seq(f: &mut Frame, area: Rect, app: &App) {
 
 // ── Melodic step sequencer 2 ──────────────────────────────────────────────────
 
-fn draw_synth_seq2(f: &mut Frame, area: Rect, app: &App) {
+fn draw_synth_seq2(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
     let focused = app.mode == AppMode::SynthSeq2;
     let title = if focused {
-        " ► Synth Seq 2 — [←→] Cursor  [↑↓] BPM  [Enter/Space] Play  [Del] Clear  []] Steps  [F5] Wave  [-=] Vol  [[{] Oct "
+        " ► Synth Seq 2 — [←→] Cursor  [↑↓] BPM  [Enter/Space] Play  [Del] Clear  [\\] Mute  [~] Slide  [a] Audition  []] Steps  [F5] Wave  [-=] Vol  [()] Cutoff  [<>] Pan  [[{] Oct  [k] Hold step  [Alt←→] Nudge  [Q] Quantize "
     } else {
         " Synth Seq 2 "
     };
 
-    let (bpm, num_steps, current_step, playing, steps, wave_name, volume2) = {
-        let s = app.synth.lock().unwrap();
-        (s.bpm, s.sequencer2.num_steps, s.sequencer2.current_step,
-         s.sequencer2.playing, s.sequencer2.steps.clone(),
-         s.wave_type2.name().to_string(), s.volume2)
-    };
+    let (bpm, wave_name, volume2) = (snap.bpm, snap.wave2.as_str(), snap.volume2);
+    let (num_steps, current_step, playing, steps) =
+        (snap.seq2.num_steps, snap.seq2.current_step, snap.seq2.playing, &snap.seq2.steps);
+    let muted = &snap.seq2.muted;
+    let slide = &snap.seq2.slide;
+    let octave = &snap.seq2.octave;
+    let locked = &snap.seq2.locked;
+    let nudge = &snap.seq2.nudge;
     let cursor = app.seq2_cursor;
     let mut lines: Vec<Line> = Vec::new();
 
@@ -388,7 +646,8 @@ fn draw_synth_seq2(f: &mut Frame, area: Rect, app: &App) {
         if playing { ("▶ PLAYING", Color::Green) } else { ("■ STOPPED", Color::DarkGray) };
     lines.push(Line::from(vec![
         Span::styled("BPM: ", Style::default().fg(Color::DarkGray)),
-        Span::styled(format!("{:.0}", bpm), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled(bpm_label(bpm), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" ({}/step)", step_ms_label(bpm)), Style::default().fg(Color::DarkGray)),
         Span::raw("  "),
         Span::styled("Steps: ", Style::default().fg(Color::DarkGray)),
         Span::styled(format!("{}", num_steps), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
@@ -401,37 +660,70 @@ fn draw_synth_seq2(f: &mut Frame, area: Rect, app: &App) {
         Span::styled("Vol: ", Style::default().fg(Color::DarkGray)),
         Span::styled(format!("{:.0}%", volume2 * 100.0), Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD)),
         Span::raw("  "),
+        Span::styled("Pan: ", Style::default().fg(Color::DarkGray)),
+        Span::styled(pan_label(snap.pan[1]), Style::default().fg(Color::Magenta)),
+        Span::raw("  "),
+        mono_span(snap.mono[1]),
+        Span::raw("  "),
         Span::styled(format!("Oct:{}", app.base_octave), Style::default().fg(Color::DarkGray)),
+        Span::raw("  "),
+        Span::styled(if app.audition { "Aud:on" } else { "Aud:off" }, Style::default().fg(Color::DarkGray)),
+        Span::raw("  "),
+        Span::styled(if app.paint { "Paint:on" } else { "Paint:off" }, Style::default().fg(Color::DarkGray)),
+        Span::raw("  "),
+        Span::styled(if snap.seq2.tie_repeats { "Tie:on" } else { "Tie:off" }, Style::default().fg(Color::DarkGray)),
+        Span::raw("  "),
+        Span::styled(vel_humanize_label(snap.seq2.vel_humanize), Style::default().fg(Color::DarkGray)),
+        Span::raw("  "),
+        Span::styled(format!("Dir:{}", snap.seq2.direction.name()), Style::default().fg(Color::DarkGray)),
     ]));
 
-    let per_row = if num_steps <= 8 { 8 } else { 16 };
+    let per_row = seq_per_row(num_steps, area.width.saturating_sub(2));
     for chunk_start in (0..num_steps).step_by(per_row) {
         let chunk_end = (chunk_start + per_row).min(num_steps);
 
         let mut nums = Vec::new();
-        for i in chunk_start..chunk_end {
+        for (i, &oct) in octave.iter().enumerate().take(chunk_end).skip(chunk_start) {
             let is_ph = playing && i == current_step;
             let is_cu = i == cursor;
             let sty = if is_ph && is_cu { Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD) }
                       else if is_ph     { Style::default().fg(Color::Black).bg(Color::Green) }
                       else if is_cu     { Style::default().fg(Color::Black).bg(Color::Yellow) }
                       else              { Style::default().fg(Color::DarkGray) };
-            nums.push(Span::styled(format!("{:^5}", i + 1), sty));
+            // Octave-shifted steps carry a badge after the step number,
+            // p-locked ones a dot.
+            let (label, sty) = match oct {
+                0 => (format!("{}", i + 1), sty),
+                o => (format!("{}{:+}", i + 1, o),
+                      if is_ph || is_cu { sty } else { Style::default().fg(Color::Magenta) }),
+            };
+            let (label, sty) = if locked[i] {
+                (format!("{}•", label), if is_ph || is_cu { sty } else { Style::default().fg(Color::Cyan) })
+            } else {
+                (label, sty)
+            };
+            nums.push(Span::styled(format!("{:^5}", label), sty));
         }
         lines.push(Line::from(nums));
 
         let mut cells = Vec::new();
-        for i in chunk_start..chunk_end {
+        for (i, step) in steps.iter().enumerate().take(chunk_end).skip(chunk_start) {
             let is_ph = playing && i == current_step;
             let is_cu = i == cursor;
-            let cell = match steps[i] {
-                Some(n) => format!("[{:<3}]", note_name(n)),
+            let is_mu = muted[i];
+            // Muted steps keep their note but are shown in parentheses, dimmed.
+            let cell = match step {
+                Some(n) if is_mu => format!("({:<3})", note_name(*n)),
+                Some(n) => format!("[{:<3}]", note_name(*n)),
                 None    => "[ · ]".to_string(),
             };
+            let cell = nudged(slid(cell, step.is_some() && slide[i]), nudge[i]);
             let sty = if is_ph && is_cu   { Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD) }
                       else if is_ph       { Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD) }
                       else if is_cu       { Style::default().fg(Color::Black).bg(Color::Yellow) }
-                      else if steps[i].is_some() { Style::default().fg(Color::White) }
+                      else if is_mu       { Style::default().fg(Color::DarkGray).add_modifier(Modifier::DIM) }
+                      else if step.is_some() && slide[i] { Style::default().fg(Color::LightYellow) }
+                      else if step.is_some() { Style::default().fg(Color::White) }
                       else               { Style::default().fg(Color::DarkGray) };
             cells.push(Span::styled(cell, sty));
         }
@@ -439,18 +731,35 @@ fn draw_synth_seq2(f: &mut Frame, area: Rect, app: &App) {
     }
 
     let note_disp = steps.get(cursor).copied().flatten()
-        .map(|n| note_name(n)).unwrap_or_else(|| "·".to_string());
-    lines.push(Line::from(vec![
+        .map(|n| notes_label(&stack_notes(n, &snap.seq2.chord[cursor]))).unwrap_or_else(|| "·".to_string());
+    let mute_disp = match (muted.get(cursor).copied().unwrap_or(false), slide.get(cursor).copied().unwrap_or(false)) {
+        (true, true)  => " (muted, slide)",
+        (true, false) => " (muted)",
+        (false, true) => " (slide)",
+        (false, false) => "",
+    };
+    let mut cursor_line = vec![
         Span::styled("Cursor: ", Style::default().fg(Color::DarkGray)),
         Span::styled(
-            format!("step {}/{}  note: {}", cursor + 1, num_steps, note_disp),
+            format!("step {}/{}  note: {}{}", cursor + 1, num_steps, note_disp, mute_disp),
             Style::default().fg(Color::White),
         ),
-    ]));
+    ];
+    if app.held_step == Some((true, cursor)) {
+        cursor_line.push(Span::styled("  HELD", Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)));
+    }
+    if let Some(&n) = nudge.get(cursor).filter(|&&n| n != 0) {
+        cursor_line.push(Span::styled(format!("  nudge: {}", nudge_label(n)), Style::default().fg(Color::DarkGray)));
+    }
+    if let Some(l) = &snap.seq2.cursor_locks {
+        cursor_line.push(Span::styled("  locks: ", Style::default().fg(Color::DarkGray)));
+        cursor_line.push(Span::styled(l.as_str(), Style::default().fg(Color::Cyan)));
+    }
+    lines.push(Line::from(cursor_line));
 
     f.render_widget(
         Paragraph::new(lines).block(
-            Block::default().title(title).borders(Borders::ALL)
+            Block::default().title(frozen_title(title, snap.frozen[1], snap.bpm)).borders(Borders::ALL)
                 .border_style(if focused {
                     Style::default().fg(Color::Cyan)
                 } else {
@@ -463,6 +772,17 @@ fn draw_synth_seq2(f: &mut Frame, area: Rect, app: &App) {
 
 // ── Drum machine grid ─────────────────────────────────────────────────────────
 
+/// Columns before a drum row's first step: name, mute, volume, pitch, edge.
+const DRUM_PREFIX: u16 = 18;
+
+/// Steps per block of the drum grid `width` columns wide: the whole
+/// pattern if it fits, else 32, 16 or 8.  A step takes two columns, plus
+/// a separator before each group of 4 after the first.
+fn drum_block_steps(num_steps: usize, width: u16) -> usize {
+    let fits = |n: usize| DRUM_PREFIX as usize + 2 * n + n.div_ceil(4) - 1 <= width as usize;
+    [num_steps, 32, 16, 8].into_iter().filter(|&n| n <= num_steps).find(|&n| fits(n)).unwrap_or(8.min(num_steps))
+}
+
 fn drum_color(kind: DrumKind) -> Color {
     match kind {
         DrumKind::Kick      => Color::Red,
@@ -476,32 +796,30 @@ fn drum_color(kind: DrumKind) -> Color {
     }
 }
 
-fn draw_drums(f: &mut Frame, area: Rect, app: &App) {
+fn draw_drums(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
     let focused = app.mode == AppMode::Drums;
     let title = if focused {
-        " ► Drum Machine — [↑↓] Track  [←→] Step  [Space] Toggle  [\\] Mute  [-=] Vol  []] Steps  [p/[] Prob  [e] Euclid "
+        " ► Drum Machine — [↑↓] Track  [←→] Step  [Alt←→] Nudge  [Space] Toggle  [\\] Mute  [-=] Vol  [{}] Pitch  []] Steps  [p/[] Prob  [e] Euclid  [t] Cond  [f] Fill  [R/N] Retrig "
     } else {
         " Drum Machine "
     };
 
-    let (bpm, num_steps, current_step, playing, swing, tracks) = {
-        let s = app.synth.lock().unwrap();
-        let dm = &s.drum_machine;
-        let tracks: Vec<(DrumKind, Vec<u8>, bool, f32)> =
-            dm.tracks.iter().map(|t| (t.kind, t.steps.clone(), t.muted, t.volume)).collect();
-        (s.bpm, dm.num_steps, dm.current_step, dm.playing, dm.swing, tracks)
-    };
+    let bpm = snap.bpm;
+    let dm  = &snap.drums;
+    let (num_steps, current_step, playing, groove, tracks) =
+        (dm.num_steps, dm.current_step, dm.playing, dm.groove, &dm.tracks);
     let sel_track = app.drum_track;
     let sel_step  = app.drum_step;
 
     let mut lines: Vec<Line> = Vec::new();
 
-    let swing_pct = (swing * 100.0).round() as u32;
+    let swing_pct = (groove.amount * 100.0).round() as u32;
     let (status_str, status_color) =
         if playing { ("▶ PLAYING", Color::Green) } else { ("■ STOPPED", Color::DarkGray) };
     lines.push(Line::from(vec![
         Span::styled("BPM: ", Style::default().fg(Color::DarkGray)),
-        Span::styled(format!("{:.0}", bpm), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled(bpm_label(bpm), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+        Span::styled(format!(" ({}/step)", step_ms_label(bpm)), Style::default().fg(Color::DarkGray)),
         Span::raw("  "),
         Span::styled("Steps: ", Style::default().fg(Color::DarkGray)),
         Span::styled(format!("{}", num_steps), Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
@@ -517,89 +835,141 @@ fn draw_drums(f: &mut Frame, area: Rect, app: &App) {
                 Style::default().fg(Color::DarkGray)
             },
         ),
+        Span::styled(
+            format!(" {}{}", groove.template.name(), if groove.melodic { " (all seqs)" } else { " (drums)" }),
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            if playing { format!("  Pass {}", dm.loop_count + 1) } else { String::new() },
+            Style::default().fg(Color::DarkGray),
+        ),
+        Span::styled(
+            if dm.fill { "  FILL" } else { "" },
+            Style::default().fg(Color::Magenta).add_modifier(Modifier::BOLD),
+        ),
     ]));
-
-    {
-        let mut s = vec![Span::styled("              ", Style::default())];
-        for i in 0..num_steps {
-            let is_ph = playing && i == current_step;
-            let label = if i % 4 == 0 { format!("{:>2}", i + 1) } else { " .".to_string() };
-            let sty = if is_ph { Style::default().fg(Color::Green).add_modifier(Modifier::BOLD) }
-                      else     { Style::default().fg(Color::DarkGray) };
-            s.push(Span::styled(label, sty));
-        }
-        lines.push(Line::from(s));
+    if let Some(t) = tracks.get(sel_track).filter(|t| focused && t.retrig > 0) {
+        lines[0].spans.push(Span::styled(
+            format!("  {} retrig {}% ×{}", t.label, t.retrig, t.retrig_hits),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    if let Some(t) = tracks.get(sel_track).filter(|t| focused && t.sample.is_some()) {
+        lines[0].spans.push(Span::styled(
+            format!("  {} ♪ {}", t.label, t.sample.as_deref().unwrap_or_default()),
+            Style::default().fg(Color::DarkGray),
+        ));
+    }
+    if let Some(t) = tracks.get(sel_track).filter(|t| focused && t.pan != 0.0) {
+        lines[0].spans.push(Span::styled(
+            format!("  {} pan {}", t.label, pan_label(t.pan)),
+            Style::default().fg(Color::DarkGray),
+        ));
     }
 
-    for (ti, (kind, steps, muted, volume)) in tracks.iter().enumerate() {
-        let is_selected = ti == sel_track;
-        let track_color = drum_color(*kind);
-        let vol_pct = (volume * 100.0).round() as u32;
-
-        let mute_char  = if *muted { 'M' } else { '·' };
-        let name_style = if is_selected && !muted {
-            Style::default().fg(track_color).add_modifier(Modifier::BOLD)
-        } else if is_selected {
-            Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD)
-        } else if *muted {
-            Style::default().fg(Color::DarkGray)
-        } else {
-            Style::default().fg(track_color)
-        };
-        let mute_style = Style::default().fg(Color::DarkGray);
-        let vol_style = if is_selected && focused {
-            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
-        } else {
-            Style::default().fg(Color::DarkGray)
-        };
-
-        let mut row: Vec<Span> = vec![
-            Span::styled(format!(" {:5}", kind.name()), name_style),
-            Span::styled("[", Style::default().fg(Color::DarkGray)),
-            Span::styled(mute_char.to_string(), mute_style),
-            Span::styled("]", Style::default().fg(Color::DarkGray)),
-            Span::styled(format!("{:3}%", vol_pct), vol_style),
-            Span::styled("│", Style::default().fg(Color::DarkGray)),
-        ];
+    // Patterns too long for one row of the panel wrap into blocks, each
+    // with its own step numbers.
+    let block = drum_block_steps(num_steps, area.width.saturating_sub(2));
+    for start in (0..num_steps).step_by(block) {
+        let end = (start + block).min(num_steps);
+        {
+            let mut s = vec![Span::styled(" ".repeat(DRUM_PREFIX as usize), Style::default())];
+            for i in start..end {
+                let is_ph = playing && i == current_step;
+                let label = if i % 4 == 0 { format!("{:>2}", i + 1) } else { " .".to_string() };
+                let sty = if is_ph { Style::default().fg(Color::Green).add_modifier(Modifier::BOLD) }
+                          else     { Style::default().fg(Color::DarkGray) };
+                s.push(Span::styled(label, sty));
+            }
+            lines.push(Line::from(s));
+        }
 
-        for i in 0..num_steps {
-            let prob    = steps.get(i).copied().unwrap_or(0);
-            let active  = prob > 0;
-            let is_ph   = playing && i == current_step;
-            let is_cu   = is_selected && i == sel_step;
-
-            let cell_char = match prob {
-                0       => "·",
-                1..=33  => "░",
-                34..=66 => "▒",
-                67..=99 => "▓",
-                _       => "█",
-            };
+        for (ti, DrumTrackView { kind, steps, conds, nudge, muted, volume, pitch, label, retrig, .. }) in tracks.iter().enumerate() {
+            let is_selected = ti == sel_track;
+            let track_color = drum_color(*kind);
+            let vol_pct = (volume * 100.0).round() as u32;
 
-            let sty = if is_ph && is_cu {
-                Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
-            } else if is_ph {
-                Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD)
-            } else if is_cu {
-                Style::default().fg(Color::Black).bg(Color::Yellow)
-            } else if active && !muted {
+            let mute_char  = if *muted { 'M' } else { '·' };
+            let name_style = if is_selected && !muted {
                 Style::default().fg(track_color).add_modifier(Modifier::BOLD)
+            } else if is_selected {
+                Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD)
+            } else if *muted {
+                Style::default().fg(Color::DarkGray)
+            } else {
+                Style::default().fg(track_color)
+            };
+            let mute_style = Style::default().fg(Color::DarkGray);
+            let vol_style = if is_selected && focused {
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
             } else {
                 Style::default().fg(Color::DarkGray)
             };
 
-            if i > 0 && i % 4 == 0 {
-                row.push(Span::styled("┆", Style::default().fg(Color::DarkGray)));
+            let mut row: Vec<Span> = vec![
+                Span::styled(format!(" {:5}", label.chars().take(5).collect::<String>()), name_style),
+                Span::styled("[", Style::default().fg(Color::DarkGray)),
+                Span::styled(mute_char.to_string(), mute_style),
+                Span::styled("]", Style::default().fg(Color::DarkGray)),
+                Span::styled(format!("{:3}%", vol_pct), vol_style),
+                // Tuning, left blank on an untuned track.
+                Span::styled(match pitch.round() as i32 {
+                    0 => "    ".to_string(),
+                    p => format!(" {:+3}", p),
+                }, vol_style),
+                // A track that sometimes retriggers marks its grid edge.
+                Span::styled(if *retrig > 0 { "↻" } else { "│" }, Style::default().fg(Color::DarkGray)),
+            ];
+
+            for i in start..end {
+                let prob    = steps.get(i).copied().unwrap_or(0);
+                let active  = prob > 0;
+                let is_ph   = playing && i == current_step;
+                let is_cu   = is_selected && i == sel_step;
+
+                let cell_char = match prob {
+                    0       => "·",
+                    1..=33  => "░",
+                    34..=66 => "▒",
+                    67..=99 => "▓",
+                    _       => "█",
+                };
+                // The trig condition's glyph fills the gap after an active step,
+                // or else a nudge mark (`‹` early, `›` late).
+                let cond_char = conds.get(i).copied().filter(|_| active)
+                    .and_then(TrigCondition::glyph)
+                    .or_else(|| match nudge.get(i).copied().filter(|_| active).unwrap_or(0).signum() {
+                        -1 => Some('‹'),
+                        1  => Some('›'),
+                        _  => None,
+                    })
+                    .unwrap_or(' ');
+
+                let sty = if is_ph && is_cu {
+                    Style::default().fg(Color::Black).bg(Color::Cyan).add_modifier(Modifier::BOLD)
+                } else if is_ph {
+                    Style::default().fg(Color::Black).bg(Color::Green).add_modifier(Modifier::BOLD)
+                } else if is_cu {
+                    Style::default().fg(Color::Black).bg(Color::Yellow)
+                } else if active && !muted {
+                    Style::default().fg(track_color).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                };
+
+                if i > start && i % 4 == 0 {
+                    row.push(Span::styled("┆", Style::default().fg(Color::DarkGray)));
+                }
+                row.push(Span::styled(format!("{}{}", cell_char, cond_char), sty));
             }
-            row.push(Span::styled(format!("{} ", cell_char), sty));
-        }
 
-        lines.push(Line::from(row));
+            lines.push(Line::from(row));
+        }
     }
 
     f.render_widget(
         Paragraph::new(lines).block(
-            Block::default().title(title).borders(Borders::ALL)
+            Block::default().title(frozen_title(title, snap.frozen[2], snap.bpm)).borders(Borders::ALL)
                 .border_style(if focused {
                     Style::default().fg(Color::Cyan)
                 } else {
@@ -625,36 +995,27 @@ fn pbar4(v: f32) -> String {
     format!("{}{}", "█".repeat(filled), "░".repeat(4 - filled))
 }
 
-fn draw_effects(f: &mut Frame, area: Rect, app: &App) {
+/// Meter level (linear peak) on a -48..0 dB scale, as 0.0–1.0.
+fn meter_norm(level: f32) -> f32 {
+    if level <= 0.0 { return 0.0; }
+    ((20.0 * level.log10() + 48.0) / 48.0).clamp(0.0, 1.0)
+}
+
+/// Linear peak as dBFS for display, e.g. `-3.2 dBFS`; `-inf` for silence.
+fn dbfs_label(level: f32) -> String {
+    if level <= 0.0 { return "-inf dBFS".to_string(); }
+    format!("{:.1} dBFS", 20.0 * level.log10())
+}
+
+fn draw_effects(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
     let focused = app.mode == AppMode::Effects;
     let title = if focused {
-        " ► Effects — [↑↓] Select  [←→] Param  [-=] Adjust  [Enter] On/Off  [Space] Route 0↔100% "
+        " ► Effects — [↑↓] Select  [←→] Param  [-=] Adjust  [Enter] On/Off  [Space] Route 0↔100%  [[]] Reverb ER  [m] Dist mode  [,.] Dist asym "
     } else {
         " Effects "
     };
 
-    // Snapshot all effect params + routing in one lock acquisition
-    let (rev_en, rev_room, rev_damp, rev_mix,
-         dly_en, dly_time, dly_feed, dly_mix,
-         dst_en, dst_drv, dst_tone, dst_lvl,
-         s1_rev, s2_rev, dr_rev,
-         s1_dly, s2_dly, dr_dly,
-         s1_dst, s2_dst, dr_dst,
-         sc_en, sc_depth, sc_rel, sc_s1, sc_s2,
-         f1_en, f1_mode, f1_cut, f1_q,
-         f2_en, f2_mode, f2_cut, f2_q) = {
-        let s = app.synth.lock().unwrap();
-        (s.reverb.enabled, s.reverb.room_size, s.reverb.damping, s.reverb.mix,
-         s.delay.enabled,  s.delay.time_ms,    s.delay.feedback,  s.delay.mix,
-         s.distortion.enabled, s.distortion.drive, s.distortion.tone, s.distortion.level,
-         s.fx_routing.s1_reverb, s.fx_routing.s2_reverb, s.fx_routing.dr_reverb,
-         s.fx_routing.s1_delay,  s.fx_routing.s2_delay,  s.fx_routing.dr_delay,
-         s.fx_routing.s1_dist,   s.fx_routing.s2_dist,   s.fx_routing.dr_dist,
-         s.sidechain.enabled, s.sidechain.depth, s.sidechain.release_ms,
-         s.sidechain.duck_s1, s.sidechain.duck_s2,
-         s.filter1.enabled, s.filter1.mode, s.filter1.cutoff, s.filter1.q,
-         s.filter2.enabled, s.filter2.mode, s.filter2.cutoff, s.filter2.q)
-    };
+    let fx = &snap.fx;
 
     let sel = app.effects_sel;
     let par = app.effects_param;
@@ -662,7 +1023,7 @@ fn draw_effects(f: &mut Frame, area: Rect, app: &App) {
     // Build one effect row (params 0-2 + routing sends 3-5)
     let make_row = |fi: usize, enabled: bool, color: Color, name: &str,
                     labels: &[&str; 3], vals: &[f32; 3], maxes: &[f32; 3], disps: &[String; 3],
-                    sends: &[f32; 3]| -> Line {
+                    sends: &[f32; 3], wet: Option<f32>| -> Line {
         let is_sel = fi == sel;
         let on_str   = if enabled { "[ON ] " } else { "[OFF] " };
         let on_style = if enabled { Style::default().fg(Color::Green) }
@@ -718,13 +1079,24 @@ fn draw_effects(f: &mut Frame, area: Rect, app: &App) {
             ));
         }
 
+        // Wet output meter
+        if let Some(level) = wet {
+            let sty = if level > 0.0 && enabled { Style::default().fg(Color::Green) }
+                      else                      { Style::default().fg(Color::DarkGray) };
+            spans.push(Span::styled(format!("Wet:[{}]  ", pbar4(meter_norm(level))), sty));
+        }
+
         Line::from(spans)
     };
 
+    let [rev_room, rev_damp, rev_mix] = fx.reverb.params;
+    let [dly_time, dly_feed, dly_mix] = fx.delay.params;
+    let [dst_drv, dst_tone, dst_lvl]  = fx.dist.params;
+    let [sc_depth, sc_rel, sc_shape]  = fx.sidechain.params;
     let rev_d = [format!("{:.0}%",  rev_room * 100.0),
                  format!("{:.0}%",  rev_damp * 100.0),
                  format!("{:.0}%",  rev_mix  * 100.0)];
-    let dly_d = [format!("{:.0}ms", dly_time),
+    let dly_d = [fx.delay_sync.map_or_else(|| format!("{:.0}ms", dly_time), |d| d.name()),
                  format!("{:.0}%",  dly_feed * 100.0),
                  format!("{:.0}%",  dly_mix  * 100.0)];
     let dst_d = [format!("{:.1}x",  dst_drv),
@@ -732,11 +1104,11 @@ fn draw_effects(f: &mut Frame, area: Rect, app: &App) {
                  format!("{:.0}%",  dst_lvl  * 100.0)];
     let sc_d  = [format!("{:.0}%",  sc_depth * 100.0),
                  format!("{:.0}ms", sc_rel),
-                 "---".to_string()];
+                 SidechainShape::ALL.get(sc_shape as usize).map_or("---", |s| s.name()).to_string()];
 
     // Render one filter row (no routing sends)
-    let make_filter_row = |fi: usize, enabled: bool, color: Color, name: &str,
-                           mode: FilterMode, cutoff: f32, q: f32| -> Line {
+    let make_filter_row = |fi: usize, color: Color, name: &str, row: FilterRow| -> Line {
+        let FilterRow { enabled, mode, cutoff, q, gain_db } = row;
         let is_sel = fi == sel;
         let on_str   = if enabled { "[ON ] " } else { "[OFF] " };
         let on_style = if enabled { Style::default().fg(Color::Green) }
@@ -774,25 +1146,181 @@ fn draw_effects(f: &mut Frame, area: Rect, app: &App) {
             Span::styled(format!("Type: [{:^8}]  ", mode.name()), psty(0)),
             Span::styled(format!("Cutoff: [{}] {:>7}  ", pbar(cut_norm, 1.0), cut_disp), psty(1)),
             Span::styled(format!("Q:    [{}] {:>4.1}  ", pbar(q_norm, 1.0), q), psty(2)),
-            Span::styled("---              ---              ---  ", Style::default().fg(Color::DarkGray)),
+            // Gain only means something to the EQ modes.
+            if mode.has_gain() {
+                Span::styled(format!("Gain: [{}] {:>+6.1}dB  ", pbar(gain_db + FILTER_MAX_GAIN_DB, 2.0 * FILTER_MAX_GAIN_DB), gain_db),
+                             psty(3))
+            } else {
+                Span::styled("Gain: ---  ", if is_sel && par == 3 && focused { psty(3) }
+                                           else { Style::default().fg(Color::DarkGray) })
+            },
+            Span::styled("---              ---  ", Style::default().fg(Color::DarkGray)),
         ])
     };
 
+    // Room size as a decay time, after the sends.
+    let mut rev_line = make_row(0, fx.reverb.enabled, Color::Blue, "REVERB ", &["Room","Damp","Mix "],
+                                &fx.reverb.params, &[1.0, 1.0, 1.0], &rev_d, &fx.reverb.sends, fx.reverb.wet);
+    let rt_sty = if fx.reverb.enabled { Style::default().fg(Color::Gray) }
+                 else                 { Style::default().fg(Color::DarkGray) };
+    rev_line.spans.push(Span::styled(format!("RT60 ≈{:.1}s  ", Reverb::rt60(rev_room)), rt_sty));
+    rev_line.spans.push(Span::styled(format!("ER:[{}]{:>3.0}%", pbar4(fx.reverb_er), fx.reverb_er * 100.0), rt_sty));
+    if fx.reverb_shimmer > 0.0 {
+        rev_line.spans.push(Span::styled(format!("  Shimmer:{:.0}%", fx.reverb_shimmer * 100.0), rt_sty));
+    }
+    // Delay time as its nearest note value at the current tempo, unless synced.
+    let mut dly_line = make_row(1, fx.delay.enabled, Color::Green, "DELAY  ", &["Time","Feed","Mix "],
+                                &fx.delay.params, &[1000.0, 0.95, 1.0], &dly_d, &fx.delay.sends, fx.delay.wet);
+    let note_sty = if fx.delay.enabled { Style::default().fg(Color::Gray) }
+                   else                { Style::default().fg(Color::DarkGray) };
+    if fx.delay_sync.is_some() {
+        dly_line.spans.push(Span::styled(format!("SYNC {:.0}ms", dly_time), note_sty.add_modifier(Modifier::BOLD)));
+    } else {
+        dly_line.spans.push(Span::styled(format!("≈{}", NoteValue::nearest(dly_time, snap.bpm).name()), note_sty));
+    }
+    if fx.delay_reverse {
+        dly_line.spans.push(Span::styled("  REV", note_sty.add_modifier(Modifier::BOLD)));
+    }
+    if fx.delay_taps > 0 {
+        dly_line.spans.push(Span::styled(format!("  +{} taps", fx.delay_taps), note_sty));
+    }
+    let (duck, duck_rel) = fx.delay_duck;
+    if duck > 0.0 {
+        dly_line.spans.push(Span::styled(format!("  Duck:{:.0}% {:.0}ms", duck * 100.0, duck_rel), note_sty));
+    }
+
+    let mut dst_line = make_row(2, fx.dist.enabled, Color::Red, "DISTORT", &["Drv ","Tone","Lvl "],
+                                &fx.dist.params, &[10.0, 1.0, 1.0], &dst_d, &fx.dist.sends, fx.dist.wet);
+    let mode_sty = if fx.dist.enabled { Style::default().fg(Color::Gray) }
+                   else               { Style::default().fg(Color::DarkGray) };
+    dst_line.spans.push(Span::styled(format!("Mode:{}  ", fx.dist_mode.name()), mode_sty));
+    dst_line.spans.push(Span::styled(format!("Asym:{:+.0}%", fx.dist_asym * 100.0), mode_sty));
+
+    // Tuning on a log bar, as the filter cutoff, and as its nearest note.
+    let [cmb_freq, cmb_feed, cmb_mix] = fx.comb.params;
+    let cmb_norm = (cmb_freq / COMB_MIN_HZ).ln() / (COMB_MAX_HZ / COMB_MIN_HZ).ln();
+    let cmb_d = [format!("{:.0}Hz", cmb_freq),
+                 format!("{:.0}%",  cmb_feed * 100.0),
+                 format!("{:.0}%",  cmb_mix  * 100.0)];
+    let mut cmb_line = make_row(6, fx.comb.enabled, Color::Yellow, "COMB   ", &["Freq","Feed","Mix "],
+                                &[cmb_norm, cmb_feed, cmb_mix], &[1.0, 0.98, 1.0], &cmb_d, &fx.comb.sends, fx.comb.wet);
+    let cmb_note = (69.0 + 12.0 * (cmb_freq / 440.0).log2()).round() as u8;
+    let cmb_sty = if fx.comb.enabled { Style::default().fg(Color::Gray) }
+                  else               { Style::default().fg(Color::DarkGray) };
+    cmb_line.spans.push(Span::styled(format!("≈{}", note_name(cmb_note)), cmb_sty));
+
+    let [fmt_vowel, fmt_q, fmt_mix] = fx.formant.params;
+    let fmt_d = [FormantFilter::vowel_label(fmt_vowel),
+                 format!("{:.0}",   fmt_q),
+                 format!("{:.0}%",  fmt_mix * 100.0)];
+
+    // Threshold on a bar from -40 dB, and the cut it is making now.
+    let [des_freq, des_thr, des_amt] = fx.deesser.params;
+    let des_d = [format!("{:.1}k", des_freq / 1000.0),
+                 format!("{:.0}dB", des_thr),
+                 format!("{:.0}%",  des_amt * 100.0)];
+    let mut des_line = make_row(8, fx.deesser.enabled, Color::LightCyan, "DE-ESS ", &["Freq","Thr ","Amt "],
+                                &[des_freq, des_thr + 40.0, des_amt], &[12000.0, 40.0, 1.0], &des_d,
+                                &fx.deesser.sends, fx.deesser.wet);
+    let gr_sty = if fx.deesser.enabled && fx.deesser_gr > 0.05 { Style::default().fg(Color::Yellow) }
+                 else                                           { Style::default().fg(Color::DarkGray) };
+    des_line.spans.push(Span::styled(format!("Cut:{:>5.1}dB", 0.0 - fx.deesser_gr), gr_sty));
+
+    // The freeze row: held or not, and its two knobs (no sends).
+    let FreezeRow { held, mix: frz_mix, fade_ms } = fx.spectral;
+    let frz_sel = sel == 9;
+    let frz_psty = |pi: usize| -> Style {
+        if frz_sel && pi == par && focused {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        } else if held {
+            Style::default().fg(Color::Gray)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        }
+    };
+    let frz_name = match (frz_sel, held) {
+        (true, _)      => Style::default().fg(Color::LightBlue).add_modifier(Modifier::BOLD),
+        (false, true)  => Style::default().fg(Color::LightBlue),
+        (false, false) => Style::default().fg(Color::DarkGray),
+    };
+    let frz_line = Line::from(vec![
+        if held { Span::styled("[HLD] ", Style::default().fg(Color::Green)) }
+        else    { Span::styled("[---] ", Style::default().fg(Color::DarkGray)) },
+        Span::styled("FREEZE ", frz_name),
+        Span::raw("  "),
+        Span::styled(format!("Mix : [{}] {:>5}  ", pbar(frz_mix, 1.0), format!("{:.0}%", frz_mix * 100.0)), frz_psty(0)),
+        Span::styled(format!("Fade: [{}] {:>5}  ", pbar(fade_ms, FREEZE_MAX_FADE_MS), format!("{:.0}ms", fade_ms)), frz_psty(1)),
+        Span::styled("[Enter/Shift+F4] capture/release", Style::default().fg(Color::DarkGray)),
+    ]);
+
+    // Rate on a log bar, as the comb's tuning.
+    let [cho_rate, cho_depth, cho_mix] = fx.chorus.params;
+    let cho_norm = (cho_rate / CHORUS_RATE_MIN).ln() / (CHORUS_RATE_MAX / CHORUS_RATE_MIN).ln();
+    let cho_d = [format!("{:.2}Hz", cho_rate),
+                 format!("{:.0}%",  cho_depth * 100.0),
+                 format!("{:.0}%",  cho_mix   * 100.0)];
+    let cho_line = make_row(10, fx.chorus.enabled, Color::LightGreen, "CHORUS ", &["Rate","Dpth","Mix "],
+                            &[cho_norm, cho_depth, cho_mix], &[1.0, 1.0, 1.0], &cho_d, &fx.chorus.sends, fx.chorus.wet);
+
+    let [crs_bits, crs_down, crs_mix] = fx.crusher.params;
+    let crs_d = [format!("{:.0}bit", crs_bits),
+                 format!("×{:.0}",   crs_down),
+                 format!("{:.0}%",   crs_mix * 100.0)];
+    let crs_line = make_row(11, fx.crusher.enabled, Color::LightRed, "CRUSH  ", &["Bits","Down","Mix "],
+                            &fx.crusher.params, &[16.0, 64.0, 1.0], &crs_d, &fx.crusher.sends, fx.crusher.wet);
+
+    // The compressor row: five knobs (no sends) and the gain reduction now.
+    let CompRow { enabled: cmp_on, params: cmp_p, reduction_db: cmp_gr } = fx.compressor;
+    let [cmp_thr, cmp_ratio, cmp_atk, cmp_rel, cmp_mk] = cmp_p;
+    let cmp_knobs = [
+        ("Thr ", cmp_thr + 60.0, 60.0, format!("{:.0}dB", cmp_thr)),
+        ("Rat ", cmp_ratio,      20.0, format!("{:.1}:1", cmp_ratio)),
+        ("Atk ", cmp_atk,       100.0, format!("{:.1}ms", cmp_atk)),
+        ("Rel ", cmp_rel,      2000.0, format!("{:.0}ms", cmp_rel)),
+        ("Gain", cmp_mk,         24.0, format!("+{:.1}dB", cmp_mk)),
+    ];
+    let cmp_sel = sel == 12;
+    let mut cmp_line = Line::from(vec![
+        if cmp_on { Span::styled("[ON ] ", Style::default().fg(Color::Green)) }
+        else      { Span::styled("[OFF] ", Style::default().fg(Color::DarkGray)) },
+        Span::styled("COMP   ", match (cmp_sel, cmp_on) {
+            (true, true)   => Style::default().fg(Color::LightYellow).add_modifier(Modifier::BOLD),
+            (true, false)  => Style::default().fg(Color::DarkGray).add_modifier(Modifier::BOLD),
+            (false, true)  => Style::default().fg(Color::LightYellow),
+            (false, false) => Style::default().fg(Color::DarkGray),
+        }),
+        Span::raw("  "),
+    ]);
+    for (pi, (label, v, max, disp)) in cmp_knobs.iter().enumerate() {
+        let sty = if cmp_sel && pi == par && focused {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        } else if cmp_on {
+            Style::default().fg(Color::Gray)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        cmp_line.spans.push(Span::styled(format!("{}: [{}] {:>6}  ", label, pbar(*v, *max), disp), sty));
+    }
+    let cmp_gr_sty = if cmp_on && cmp_gr > 0.05 { Style::default().fg(Color::Yellow) }
+                     else                       { Style::default().fg(Color::DarkGray) };
+    cmp_line.spans.push(Span::styled(format!("GR:{:>5.1}dB", 0.0 - cmp_gr), cmp_gr_sty));
+
     let lines = vec![
-        make_row(0, rev_en, Color::Blue,    "REVERB ", &["Room","Damp","Mix "],
-                 &[rev_room, rev_damp, rev_mix], &[1.0, 1.0, 1.0], &rev_d,
-                 &[s1_rev, s2_rev, dr_rev]),
-        make_row(1, dly_en, Color::Green,   "DELAY  ", &["Time","Feed","Mix "],
-                 &[dly_time, dly_feed, dly_mix], &[1000.0, 0.95, 1.0], &dly_d,
-                 &[s1_dly, s2_dly, dr_dly]),
-        make_row(2, dst_en, Color::Red,     "DISTORT", &["Drv ","Tone","Lvl "],
-                 &[dst_drv,  dst_tone, dst_lvl],  &[10.0,  1.0,  1.0], &dst_d,
-                 &[s1_dst, s2_dst, dr_dst]),
-        make_row(3, sc_en,  Color::Magenta, "SIDECHN", &["Dpth","Rel ","--- "],
-                 &[sc_depth, sc_rel, 0.0], &[1.0, 500.0, 1.0], &sc_d,
-                 &[sc_s1 as u8 as f32, sc_s2 as u8 as f32, 0.0]),
-        make_filter_row(4, f1_en, Color::Cyan,  "FILT-S1", f1_mode, f1_cut, f1_q),
-        make_filter_row(5, f2_en, Color::Green, "FILT-S2", f2_mode, f2_cut, f2_q),
+        rev_line,
+        dly_line,
+        dst_line,
+        make_row(3, fx.sidechain.enabled, Color::Magenta, "SIDECHN", &["Dpth","Rel ","Shp "],
+                 &fx.sidechain.params, &[1.0, 500.0, 2.0], &sc_d, &fx.sidechain.sends, fx.sidechain.wet),
+        make_filter_row(4, Color::Cyan,  "FILT-S1", fx.filter1),
+        make_filter_row(5, Color::Green, "FILT-S2", fx.filter2),
+        cmb_line,
+        make_row(7, fx.formant.enabled, Color::LightMagenta, "FORMANT", &["Vowl","Q   ","Mix "],
+                 &fx.formant.params, &[4.0, 20.0, 1.0], &fmt_d, &fx.formant.sends, fx.formant.wet),
+        des_line,
+        frz_line,
+        cho_line,
+        crs_line,
+        cmp_line,
     ];
 
     f.render_widget(
@@ -810,17 +1338,21 @@ fn draw_effects(f: &mut Frame, area: Rect, app: &App) {
 
 // ── Status bar ────────────────────────────────────────────────────────────────
 
-fn draw_status(f: &mut Frame, area: Rect, app: &App) {
-    let wave    = app.wave_name();
-    let vol     = app.volume();
-    let bpm     = { app.synth.lock().unwrap().bpm };
+fn draw_status(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
+    let wave    = snap.wave.as_str();
+    let vol     = snap.volume;
+    let bpm     = snap.bpm;
     let notes   = app.active_note_names();
+    let xruns   = app.audio.xruns();
     let notes_s = if notes.is_empty() { "—".to_string() } else { notes.join(" ") };
+    let chord   = chord_name(&app.active_notes).unwrap_or_else(|| "—".to_string());
     let extra   = if app.status_msg.is_empty() { String::new() } else { format!("  │  {}", app.status_msg) };
+    let count_in = snap.count_in.map_or(String::new(), |n| format!("  │  Count-in: {}", n));
 
     let scale_active = app.scale_q.active();
     let scale_str = if scale_active {
-        format!("{} {}", app.scale_q.root_name(), app.scale_q.scale.name())
+        let live = if app.live_quantize { " (live)" } else { "" };
+        format!("{} {}{}", app.scale_q.root_name(), app.scale_q.scale.name(), live)
     } else {
         "Off".to_string()
     };
@@ -830,13 +1362,88 @@ fn draw_status(f: &mut Frame, area: Rect, app: &App) {
         Style::default().fg(Color::DarkGray)
     };
 
+    let (arp_str, arp_style) = match snap.arp {
+        Some((rate, mode)) => (format!("{} {}", rate.name(), mode.name()),
+                               Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        None               => ("Off".to_string(), Style::default().fg(Color::DarkGray)),
+    };
+    let (chord_mode, chord_mode_style) = match app.chord {
+        Some(c) => (c.name(), Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+        None    => ("Off",    Style::default().fg(Color::DarkGray)),
+    };
+
+    // Bus meters: green, yellow above -6 dB, red at full scale (into the
+    // master tanh).
+    let mut buses = vec![Span::styled("Buses: ", Style::default().fg(Color::DarkGray))];
+    let bus_vols = [Some(snap.volume), Some(snap.volume2), Some(snap.drum_volume)];
+    for (i, ((name, level), vol)) in ["S1", "S2", "DR"].iter().zip(snap.bus_levels).zip(bus_vols).enumerate() {
+        let norm = meter_norm(level);
+        let color = if level >= 1.0 { Color::Red } else if norm > 0.875 { Color::Yellow } else { Color::Green };
+        if i > 0 { buses.push(Span::raw("  │  ")); }
+        buses.push(Span::styled(format!("{} ", name), Style::default().fg(Color::DarkGray)));
+        buses.push(Span::styled(format!("[{}]", pbar(norm, 1.0)), Style::default().fg(color)));
+        if let Some(v) = vol {
+            buses.push(Span::styled(format!(" {:.0}%", v * 100.0), Style::default().fg(Color::Magenta)));
+        }
+    }
+    // Crossfader: a marker on a short S1–S2 track, lit off centre.
+    let xf_pos = (snap.crossfade * 8.0).round() as usize;
+    let xf: String = (0..=8).map(|i| if i == xf_pos { '●' } else { '─' }).collect();
+    let xf_sty = if (snap.crossfade - 0.5).abs() < 1e-3 { Style::default().fg(Color::DarkGray) }
+                 else                                   { Style::default().fg(Color::Yellow) };
+    buses.push(Span::raw("  │  "));
+    buses.push(Span::styled("XF S1 ", Style::default().fg(Color::DarkGray)));
+    buses.push(Span::styled(xf, xf_sty));
+    buses.push(Span::styled(" S2", Style::default().fg(Color::DarkGray)));
+    buses.push(Span::raw("  │  "));
+    buses.push(Span::styled("Master ", Style::default().fg(Color::DarkGray)));
+    buses.push(Span::styled(format!("{:.0}%", snap.master_gain * 100.0), Style::default().fg(Color::Magenta)));
+    // Peak hold in dBFS, coloured like the bus meters.
+    let peak = app.peak_hold;
+    let peak_color = if peak >= 1.0 { Color::Red } else if peak >= 0.5 { Color::Yellow } else { Color::Green };
+    buses.push(Span::styled("  Peak ", Style::default().fg(Color::DarkGray)));
+    buses.push(Span::styled(dbfs_label(peak), Style::default().fg(peak_color).add_modifier(Modifier::BOLD)));
+
+    // MIDI output: target, then the note sources routed to it (lit when
+    // on), and whether those sources still sound locally.
+    let mut midi = vec![
+        Span::raw("  │  "),
+        Span::styled("MIDI: ", Style::default().fg(Color::DarkGray)),
+    ];
+    match &snap.midi_out {
+        Some(t) => {
+            midi.push(Span::styled(t.as_str(), Style::default().fg(Color::Cyan)));
+            let r = snap.midi_notes;
+            for (name, on) in [("Keys", r.keys), ("S1", r.seq1), ("S2", r.seq2)] {
+                let style = if on { Style::default().fg(Color::Green) } else { Style::default().fg(Color::DarkGray) };
+                midi.push(Span::styled(format!(" {}", name), style));
+            }
+            if !r.local { midi.push(Span::styled(" (MIDI only)", Style::default().fg(Color::Yellow))); }
+        }
+        None => midi.push(Span::styled("off", Style::default().fg(Color::DarkGray))),
+    }
+
+    // Automation lanes, lit while they play back.
+    if !app.automation.lanes.is_empty() {
+        buses.push(Span::raw("  │  "));
+        buses.push(Span::styled(format!("Auto ({} steps):", app.automation.loop_steps),
+                                Style::default().fg(Color::DarkGray)));
+        for lane in &app.automation.lanes {
+            let style = if lane.bypass { Style::default().fg(Color::DarkGray) } else { Style::default().fg(Color::Green) };
+            buses.push(Span::styled(format!(" {}", lane.param.name()), style));
+        }
+    }
+
     let text = vec![
         Line::from(vec![
             Span::styled("Wave: ",   Style::default().fg(Color::DarkGray)),
-            Span::styled(&wave,      Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled(wave,       Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)),
+            Span::styled(if snap.band_limited { " (BL)" } else { " (naive)" },
+                         Style::default().fg(Color::DarkGray)),
             Span::raw("  │  "),
             Span::styled("BPM: ",    Style::default().fg(Color::DarkGray)),
-            Span::styled(format!("{:.0}", bpm), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            Span::styled(bpm_label(bpm), Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)),
+            Span::styled(format!(" ({}/step)", step_ms_label(bpm)), Style::default().fg(Color::DarkGray)),
             Span::raw("  │  "),
             Span::styled("Vol: ",    Style::default().fg(Color::DarkGray)),
             Span::styled(format!("{:.0}%", vol * 100.0),
@@ -844,12 +1451,43 @@ fn draw_status(f: &mut Frame, area: Rect, app: &App) {
             Span::raw("  │  "),
             Span::styled("Scale: ",  Style::default().fg(Color::DarkGray)),
             Span::styled(scale_str,  scale_style),
+            Span::raw("  │  "),
+            Span::styled("Arp: ",    Style::default().fg(Color::DarkGray)),
+            Span::styled(arp_str,    arp_style),
+            Span::raw("  │  "),
+            Span::styled("Chord mode: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(chord_mode, chord_mode_style),
+            Span::styled(count_in,   Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)),
             Span::styled(&extra,     Style::default().fg(Color::Yellow)),
         ]),
-        Line::from(vec![
+        Line::from([vec![
             Span::styled("Playing: ", Style::default().fg(Color::DarkGray)),
             Span::styled(notes_s,     Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
-        ]),
+            Span::raw("  │  "),
+            Span::styled("Chord: ",   Style::default().fg(Color::DarkGray)),
+            Span::styled(chord,       Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD)),
+            Span::raw("  │  "),
+            Span::styled("Out: ",     Style::default().fg(Color::DarkGray)),
+            Span::styled(app.audio.device_name(), Style::default().fg(Color::Cyan)),
+            cue_span(snap.cue, app.audio.has_cue_output()),
+            Span::raw("  │  "),
+            Span::styled("Latency: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                app.audio.latency_ms().map_or("—".to_string(), |ms| format!("{:.1} ms", ms)),
+                Style::default().fg(Color::Cyan),
+            ),
+            Span::raw("  │  "),
+            Span::styled("Xruns: ", Style::default().fg(Color::DarkGray)),
+            Span::styled(
+                format!("{}", xruns),
+                if xruns > 0 {
+                    Style::default().fg(Color::Red).add_modifier(Modifier::BOLD)
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                },
+            ),
+        ], midi].concat()),
+        Line::from(buses),
     ];
 
     f.render_widget(
@@ -860,6 +1498,282 @@ fn draw_status(f: &mut Frame, area: Rect, app: &App) {
     );
 }
 
+// ── Additive harmonics editor ─────────────────────────────────────────────────
+
+/// Columns per harmonic bar.
+const HARM_COLS: usize = 7;
+
+/// One vertical bar per harmonic, drawn in eighth blocks, with its number
+/// and level underneath.
+fn draw_harmonics(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
+    const EIGHTHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let users: Vec<&str> = [("S1", &snap.wave), ("S2", &snap.wave2)].iter()
+        .filter(|(_, w)| w.as_str() == "Additive").map(|&(n, _)| n).collect();
+    let title = format!(
+        " ► Harmonics{} — [←→] Select  [↑↓/-=] Level  [x] Clear  [r] Sine  [1/2] Use on synth 1/2 ",
+        if users.is_empty() { String::new() } else { format!(" (on {})", users.join("+")) },
+    );
+    let block = Block::default().title(title).borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let rows = block.inner(area).height.saturating_sub(1).max(1) as usize;
+
+    let style = |k: usize| if k == app.harm_cursor {
+        Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(Color::Cyan)
+    };
+    let mut lines: Vec<Line> = (0..rows).map(|r| {
+        Line::from(snap.harmonics.iter().enumerate().map(|(k, &amp)| {
+            let eighths = (amp * (rows * 8) as f32).round() as usize;
+            let fill = eighths.saturating_sub((rows - 1 - r) * 8).min(8);
+            let bar: String = std::iter::repeat_n(EIGHTHS[fill], HARM_COLS - 2).collect();
+            Span::styled(format!(" {} ", bar), style(k))
+        }).collect::<Vec<_>>())
+    }).collect();
+    lines.push(Line::from(snap.harmonics.iter().enumerate().map(|(k, &amp)| {
+        Span::styled(format!("{}:{:>3.0}% ", k + 1, amp * 100.0), style(k))
+    }).collect::<Vec<_>>()));
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+// ── Acid step editor ──────────────────────────────────────────────────────────
+
+/// Columns per step in the acid editor.
+const ACID_COLS: usize = 4;
+
+/// Sequencer 1 as a 303 sees it: a note row with accent and slide rows
+/// under it, one page of steps around the cursor, and the acid settings.
+fn draw_acid(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
+    let acid = &snap.acid;
+    let title = format!(" ► Acid (synth 1 / seq 1) {} ", if acid.enabled { "ON" } else { "OFF" });
+    let block = Block::default().title(title).borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    let seq = &snap.seq;
+    let cursor = app.seq_cursor;
+
+    let per_page = (inner.width as usize / ACID_COLS).clamp(1, seq.num_steps.max(1));
+    let first = cursor / per_page * per_page;
+    let page = first..(first + per_page).min(seq.num_steps);
+    let style = |i: usize| {
+        if seq.playing && i == seq.current_step { Style::default().fg(Color::Black).bg(Color::Green) }
+        else if i == cursor { Style::default().fg(Color::Black).bg(Color::Yellow) }
+        else { Style::default().fg(Color::White) }
+    };
+    let row = |mark: &dyn Fn(usize) -> String| Line::from(page.clone().map(|i| {
+        Span::styled(format!("{:<w$}", mark(i), w = ACID_COLS), style(i))
+    }).collect::<Vec<_>>());
+    let flag = |on: &[bool], i: usize, c: &str| {
+        let set = seq.steps[i].is_some() && on.get(i).copied().unwrap_or(false);
+        if set { c.to_string() } else { "·".to_string() }
+    };
+
+    let setting = |i: usize, label: String| {
+        let sty = if i == app.acid_sel {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        [Span::styled(format!(" {} ", label), sty), Span::raw(" ")]
+    };
+    let f1 = &snap.fx.filter1;
+    let mut settings: Vec<Span> = [
+        format!("Cutoff {:.0}Hz", f1.cutoff),
+        format!("Reso {:.2}", f1.q),
+        format!("Env +{:.2}oct", acid.env_mod),
+        format!("Decay {:.0}ms", acid.decay_ms),
+        format!("Accent {:.0}%", acid.accent * 100.0),
+    ].into_iter().enumerate().flat_map(|(i, l)| setting(i, l)).collect();
+    let env_cells = (acid.env().min(1.0) * 8.0).round() as usize;
+    settings.push(Span::styled(format!(" Env {}{}", "▮".repeat(env_cells), "▯".repeat(8 - env_cells)),
+                               Style::default().fg(Color::Yellow)));
+    if !f1.enabled {
+        settings.push(Span::styled("  S1 filter off", Style::default().fg(Color::Red)));
+    }
+
+    let lines = vec![
+        row(&|i| seq.steps[i].map_or_else(|| "·".to_string(), note_name)),
+        row(&|i| flag(&seq.accent, i, "A")),
+        row(&|i| flag(&seq.slide, i, "~")),
+        Line::from(settings),
+    ];
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+// ── Amp envelopes ─────────────────────────────────────────────────────────────
+
+/// One row per synth of its attack, decay, sustain and release, as bars
+/// like the effects panel's (times on a log scale).
+fn draw_envelope(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
+    let block = Block::default().title(" ► Envelope (amp ADSR) ").borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let span = (ENV_TIME_MAX / ENV_TIME_MIN).ln();
+    let lines: Vec<Line> = snap.adsr.iter().enumerate().map(|(i, adsr)| {
+        let row_sel = (i == 1) == app.env_synth2;
+        let name_sty = if row_sel {
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let mut spans = vec![Span::styled(format!("{} Synth {}  ", if row_sel { "►" } else { " " }, i + 1), name_sty)];
+        for (pi, (&v, label)) in adsr.iter().zip(["Attack", "Decay", "Sustain", "Release"]).enumerate() {
+            let (bar, disp) = if pi == 2 {
+                (pbar(v, 1.0), format!("{:.0}%", v * 100.0))
+            } else {
+                (pbar((v / ENV_TIME_MIN).ln(), span), env_time_label(v))
+            };
+            let sty = if row_sel && pi == app.env_sel {
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+            } else if row_sel {
+                Style::default().fg(Color::Gray)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            spans.push(Span::styled(format!("{}: [{}] {:>6}  ", label, bar, disp), sty));
+        }
+        Line::from(spans)
+    }).collect();
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+// ── LFOs ──────────────────────────────────────────────────────────────────────
+
+/// One row per LFO: shape, rate (note value), depth and target.
+fn draw_modulation(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
+    let block = Block::default().title(" ► Modulation (LFOs) ").borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let lines: Vec<Line> = snap.lfos.iter().enumerate().map(|(i, l)| {
+        let row_sel = i == app.lfo_sel;
+        let name_sty = if row_sel {
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else if l.depth > 0.0 {
+            Style::default().fg(Color::Gray)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let mut spans = vec![Span::styled(format!("{} LFO {}  ", if row_sel { "►" } else { " " }, i + 1), name_sty)];
+        let params = [
+            format!("Shape: {:<8}", l.shape.name()),
+            format!("Rate: {:<6}", l.rate.name()),
+            format!("Depth: [{}] {:>3.0}%", pbar(l.depth, 1.0), l.depth * 100.0),
+            format!("Target: {}", l.target.name()),
+        ];
+        for (pi, text) in params.into_iter().enumerate() {
+            let sty = if row_sel && pi == app.lfo_param {
+                Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+            } else if row_sel {
+                Style::default().fg(Color::Gray)
+            } else {
+                Style::default().fg(Color::DarkGray)
+            };
+            spans.push(Span::styled(format!("{}  ", text), sty));
+        }
+        Line::from(spans)
+    }).collect();
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+// ── Synth-bus inserts ─────────────────────────────────────────────────────────
+
+/// One row per synth bus: its insert chain in processing order, then the
+/// effect the add key puts in.
+fn draw_inserts(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
+    let block = Block::default().title(" ► Inserts (after each synth's filter) ").borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let mut lines: Vec<Line> = snap.inserts.iter().enumerate().map(|(i, chain)| {
+        let row_sel = i == app.insert_synth2 as usize;
+        let name_sty = if row_sel {
+            Style::default().fg(Color::Cyan).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        let mut spans = vec![Span::styled(format!("{} Synth {}: ", if row_sel { "►" } else { " " }, i + 1), name_sty)];
+        if chain.is_empty() {
+            spans.push(Span::styled("(empty)", Style::default().fg(Color::DarkGray)));
+        }
+        for (ei, name) in chain.iter().enumerate() {
+            if ei > 0 { spans.push(Span::styled(" → ", Style::default().fg(Color::DarkGray))); }
+            let sty = if row_sel && ei == app.insert_sel {
+                Style::default().fg(Color::Black).bg(Color::Yellow)
+            } else if row_sel {
+                Style::default().fg(Color::White)
+            } else {
+                Style::default().fg(Color::Gray)
+            };
+            spans.push(Span::styled(format!("{} {}", ei + 1, name), sty));
+        }
+        Line::from(spans)
+    }).collect();
+    lines.push(Line::from(vec![
+        Span::styled("  Add: ", Style::default().fg(Color::DarkGray)),
+        Span::styled(app.insert_kind.name(), Style::default().fg(Color::White).add_modifier(Modifier::BOLD)),
+    ]));
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
+// ── Granular sample ───────────────────────────────────────────────────────────
+
+/// The loaded sample's peak outline, with the span grains read from
+/// `position` highlighted, and the grain settings underneath.
+fn draw_sample(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
+    const EIGHTHS: [char; 9] = [' ', '▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    let g = &snap.granular;
+    let users: Vec<String> = [("S1", &snap.wave), ("S2", &snap.wave2)].iter()
+        .filter(|(_, w)| matches!(w.as_str(), "Granular" | "Sampler"))
+        .map(|(n, w)| format!("{} {}", n, w)).collect();
+    let title = match &g.sample {
+        Some(smp) => format!(" ► Sample: {} ({:.2} s){} ", smp.name, smp.seconds(),
+                             if users.is_empty() { String::new() } else { format!(" — {}", users.join(", ")) }),
+        None      => " ► Sample: none — [o] open a WAV ".to_string(),
+    };
+    let block = Block::default().title(title).borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::Cyan));
+    let inner = block.inner(area);
+    let rows  = inner.height.saturating_sub(1).max(1) as usize;
+    let width = inner.width as usize;
+
+    let mut lines: Vec<Line> = Vec::new();
+    if let Some(smp) = g.sample.as_deref().filter(|_| width > 0) {
+        // Columns the grains cover: from `position` for one grain's length.
+        let span = (g.size_ms * 0.001 / smp.seconds()).min(1.0);
+        let first = (g.position * width as f32) as usize;
+        let last  = ((g.position + span) * width as f32).ceil() as usize;
+        let peaks: Vec<f32> = (0..width).map(|c| {
+            let lo = c * smp.peaks.len() / width;
+            let hi = ((c + 1) * smp.peaks.len() / width).max(lo + 1);
+            smp.peaks[lo..hi].iter().fold(0.0f32, |m, &p| m.max(p))
+        }).collect();
+        for r in 0..rows {
+            lines.push(Line::from(peaks.iter().enumerate().map(|(c, &p)| {
+                let eighths = (p.min(1.0) * (rows * 8) as f32).round() as usize;
+                let fill = eighths.saturating_sub((rows - 1 - r) * 8).min(8);
+                let color = if (first..last.max(first + 1)).contains(&c) { Color::Yellow } else { Color::Cyan };
+                Span::styled(EIGHTHS[fill].to_string(), Style::default().fg(color))
+            }).collect::<Vec<_>>()));
+        }
+    } else {
+        lines.extend((0..rows).map(|_| Line::raw("")));
+    }
+
+    let labels = [
+        format!("Size {:.0} ms", g.size_ms),
+        format!("Density {:.0}/s", g.density),
+        format!("Position {:.0}%", g.position * 100.0),
+        format!("Root {}", note_name(g.root)),
+    ];
+    lines.push(Line::from(labels.into_iter().enumerate().flat_map(|(i, l)| {
+        let style = if i == app.grain_sel {
+            Style::default().fg(Color::White).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        [Span::styled(format!(" {} ", l), style), Span::raw("  ")]
+    }).collect::<Vec<_>>()));
+
+    f.render_widget(Paragraph::new(lines).block(block), area);
+}
+
 // ── Oscilloscope ──────────────────────────────────────────────────────────────
 
 fn braille_bit(col: usize, row: usize) -> u8 {
@@ -870,23 +1784,17 @@ fn braille_bit(col: usize, row: usize) -> u8 {
     }
 }
 
-fn draw_oscilloscope(f: &mut Frame, area: Rect, app: &App) {
+fn draw_oscilloscope(f: &mut Frame, area: Rect, snap: &Snapshot) {
     let block = Block::default().title(" Scope ").borders(Borders::ALL)
         .border_style(Style::default().fg(Color::DarkGray));
     let inner = block.inner(area);
     f.render_widget(block, area);
 
-    let (buf, pos) = {
-        let s = app.synth.lock().unwrap();
-        (s.scope_buf.clone(), s.scope_pos)
-    };
     let w = inner.width as usize;
     let h = inner.height as usize;
     if w == 0 || h == 0 { return; }
 
-    let n = (w * 2).min(buf.len());
-    let start = pos.wrapping_sub(n) % buf.len();
-    let samples: Vec<f32> = (0..n).map(|i| buf[(start + i) % buf.len()]).collect();
+    let samples = &snap.scope;
 
     let mut lines = Vec::with_capacity(h);
     for row in 0..h {
@@ -910,15 +1818,184 @@ fn draw_oscilloscope(f: &mut Frame, area: Rect, app: &App) {
     f.render_widget(Paragraph::new(lines), inner);
 }
 
+/// Lines of the spectrogram, two rows of `Spectrogram::rows` each.
+const SPECTROGRAM_LINES: u16 = (spectrogram::ROWS / 2) as u16;
+
+/// Colour of a spectrogram level (0–1): nothing at the floor, then blue
+/// through magenta, red and yellow to white at full scale.
+fn heat(level: f32) -> Option<Color> {
+    const SCALE: [Color; 5] = [Color::Blue, Color::Magenta, Color::Red, Color::Yellow, Color::White];
+    if level <= 0.0 { return None; }
+    Some(SCALE[((level * SCALE.len() as f32) as usize).min(SCALE.len() - 1)])
+}
+
+/// The output's spectrum over the last second, newest at the bottom and
+/// scrolling up: log frequency across, two rows per line in half blocks.
+fn draw_spectrogram(f: &mut Frame, area: Rect, app: &App) {
+    let block = Block::default().title(" Spectrogram 40 Hz – 16 kHz ").borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let (w, h) = (inner.width as usize, inner.height as usize);
+    if w == 0 || h == 0 { return; }
+    let rows = &app.spectrogram.rows;
+    // Row `i` of the display counted back from the newest, if there is one.
+    let row = |i: usize| rows.len().checked_sub(2 * h - i).and_then(|r| rows.get(r));
+
+    let lines: Vec<Line> = (0..h).map(|line| {
+        let (top, bottom) = (row(2 * line), row(2 * line + 1));
+        Line::from((0..w).map(|col| {
+            let band = col * spectrogram::BANDS / w;
+            let level = |r: Option<&[f32; spectrogram::BANDS]>| r.and_then(|r| heat(r[band]));
+            match (level(top), level(bottom)) {
+                (None, None)       => Span::raw(" "),
+                (Some(t), None)    => Span::styled("▀", Style::default().fg(t)),
+                (None, Some(b))    => Span::styled("▄", Style::default().fg(b)),
+                (Some(t), Some(b)) => Span::styled("▀", Style::default().fg(t).bg(b)),
+            }
+        }).collect::<Vec<_>>())
+    }).collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+/// Width of the goniometer panel, borders included: a square-looking plot
+/// and the correlation readout beside it.
+const GONIO_WIDTH: u16 = 24;
+
+/// Columns of the goniometer's plot; its rows fill the panel.
+const GONIO_PLOT_COLS: usize = 8;
+
+/// L against R in braille, turned 45° so a mono signal draws a vertical
+/// line, mid up and side across; beside it the phase correlation, as a
+/// number and a marker on a -1..+1 track.
+fn draw_goniometer(f: &mut Frame, area: Rect, snap: &Snapshot) {
+    let block = Block::default().title(" Stereo ").borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::DarkGray));
+    let inner = block.inner(area);
+    f.render_widget(block, area);
+
+    let (w, h) = (GONIO_PLOT_COLS.min(inner.width as usize), inner.height as usize);
+    if w == 0 || h == 0 { return; }
+
+    let mut cells = vec![0u8; w * h];
+    for &(l, r) in &snap.stereo {
+        let side = ((l - r) * 0.5).clamp(-1.0, 1.0);
+        let mid  = ((l + r) * 0.5).clamp(-1.0, 1.0);
+        let x = (((side + 1.0) * 0.5 * (w * 2) as f32) as usize).min(w * 2 - 1);
+        let y = (((1.0 - mid) * 0.5 * (h * 4) as f32) as usize).min(h * 4 - 1);
+        cells[(y / 4) * w + x / 2] |= braille_bit(x % 2, y % 4);
+    }
+
+    let (corr_text, corr_color, marker) = match snap.correlation {
+        Some(c) => {
+            let color = if c < 0.0 { Color::Red } else if c < 0.5 { Color::Yellow } else { Color::Green };
+            (format!("{:+.2}", c), color, Some(((c + 1.0) * 0.5 * 10.0).round() as usize))
+        }
+        None => ("—".to_string(), Color::DarkGray, None),
+    };
+    let track: String = (0..=10).map(|i| if Some(i) == marker { '●' } else if i == 5 { '┼' } else { '─' }).collect();
+    let side_text = [
+        vec![Span::styled(" Corr ", Style::default().fg(Color::DarkGray)),
+             Span::styled(corr_text, Style::default().fg(corr_color).add_modifier(Modifier::BOLD))],
+        vec![Span::styled(format!(" {}", track), Style::default().fg(corr_color))],
+        vec![Span::styled(" -1   0   +1", Style::default().fg(Color::DarkGray))],
+    ];
+
+    let lines: Vec<Line> = (0..h).map(|row| {
+        let mut spans: Vec<Span> = cells[row * w..(row + 1) * w].iter().map(|&bits| {
+            let ch = char::from_u32(0x2800 + bits as u32).unwrap_or(' ');
+            let color = if bits != 0 { Color::Cyan } else { Color::DarkGray };
+            Span::styled(ch.to_string(), Style::default().fg(color))
+        }).collect();
+        if let Some(text) = side_text.get(row) { spans.extend(text.iter().cloned()); }
+        Line::from(spans)
+    }).collect();
+    f.render_widget(Paragraph::new(lines), inner);
+}
+
+// ── Custom scale editor ───────────────────────────────────────────────────────
+
+/// One cell per semitone above the root: in the scale (green) or not, the
+/// cursor reversed.  Replaces the help panel while open.
+fn draw_scale_editor(f: &mut Frame, area: Rect, app: &App) {
+    let w = Style::default().fg(Color::White);
+    let mut cells = vec![Span::styled("Notes: ", Style::default().fg(Color::DarkGray))];
+    for (i, &on) in app.scale_draft.iter().enumerate() {
+        let name = pitch_class_name(app.scale_q.root + i as u8);
+        let mut sty = if on || i == 0 {
+            Style::default().fg(Color::Green).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(Color::DarkGray)
+        };
+        if i == app.scale_cursor { sty = sty.add_modifier(Modifier::REVERSED); }
+        cells.push(Span::styled(format!(" {:<2} ", name), sty));
+    }
+    let count = app.scale_draft.iter().skip(1).filter(|&&on| on).count() + 1;
+    cells.push(Span::styled(format!("  ({} notes)", count), Style::default().fg(Color::DarkGray)));
+    let hint = Line::from(vec![
+        Span::styled("[←→] ",    w), Span::raw("Select  │  "),
+        Span::styled("[Space] ", w), Span::raw("Toggle note  │  "),
+        Span::styled("[Enter] ", w), Span::raw("Use custom scale  │  "),
+        Span::styled("[Esc] ",   w), Span::raw("Cancel  │  root is always in; F7 moves it"),
+    ]);
+    f.render_widget(
+        Paragraph::new(vec![Line::from(cells), hint])
+            .block(Block::default().title(" Custom Scale ").borders(Borders::ALL))
+            .style(Style::default().fg(Color::DarkGray)),
+        area,
+    );
+}
+
 // ── Unified help panel ────────────────────────────────────────────────────────
 
-fn draw_help(f: &mut Frame, area: Rect, app: &App) {
-    // File path prompt overlay — replaces help when save/load is active.
+// ── Clear confirmation ────────────────────────────────────────────────────────
+
+/// Replaces the help panel while a clear waits for yes or no.
+fn draw_confirm_clear(f: &mut Frame, area: Rect, target: ClearTarget) {
+    let w = Style::default().fg(Color::White);
+    let question = Line::from(Span::styled(
+        format!("Clear {}?", target.name()),
+        Style::default().fg(Color::Yellow).add_modifier(Modifier::BOLD),
+    ));
+    let hint = Line::from(vec![
+        Span::styled("[Enter/y] ", w), Span::raw("Clear  │  "),
+        Span::styled("[Esc/n] ",   w), Span::raw("Keep  │  "),
+        Span::styled("[^Z] ",      w), Span::raw("restores the last clear"),
+    ]);
+    f.render_widget(
+        Paragraph::new(vec![question, hint])
+            .block(Block::default().title(" Confirm ").borders(Borders::ALL))
+            .style(Style::default().fg(Color::DarkGray)),
+        area,
+    );
+}
+
+fn draw_help(f: &mut Frame, area: Rect, app: &App, snap: &Snapshot) {
+    if app.input_mode == InputMode::ScaleEdit { return draw_scale_editor(f, area, app); }
+    if let InputMode::ConfirmClear(target) = app.input_mode { return draw_confirm_clear(f, area, target); }
+    // Prompt overlay (file path or track name) — replaces help while open.
     if app.input_mode != InputMode::None {
         let action = match app.input_mode {
             InputMode::Save => "Save to file",
             InputMode::Load => "Load from file",
-            InputMode::None => "",
+            InputMode::LoadWavetable => "Load wavetable WAV",
+            InputMode::LoadSample => "Load granular sample WAV",
+            InputMode::Export => "Export WAV (path [bars] [16bit|24bit|float] [dither|nodither])",
+            InputMode::ExportMidi => "Export sequencers as MIDI file",
+            InputMode::RenameTrack => "Rename track",
+            InputMode::LoadDrumSample(_) => "Load drum sample WAV (empty = synthesised)",
+            InputMode::DelayNote => "Delay note value (1/8, 1/8., 1/8T)",
+            InputMode::DelayTaps => "Delay taps (ms level pan, …)",
+            InputMode::Marker => "Section marker at the playhead's bar",
+            InputMode::ScaleEdit | InputMode::ConfirmClear(_) | InputMode::None => "",
+        };
+        let title = match app.input_mode {
+            InputMode::RenameTrack => " Track Name ",
+            InputMode::DelayNote   => " Note Value ",
+            InputMode::DelayTaps   => " Delay Taps ",
+            InputMode::Marker      => " Marker Name ",
+            _                      => " File Path ",
         };
         let w = Style::default().fg(Color::White);
         let prompt = Line::from(vec![
@@ -939,7 +2016,7 @@ fn draw_help(f: &mut Frame, area: Rect, app: &App) {
         ]);
         f.render_widget(
             Paragraph::new(vec![prompt, hint])
-                .block(Block::default().title(" File Path ").borders(Borders::ALL))
+                .block(Block::default().title(title).borders(Borders::ALL))
                 .style(Style::default().fg(Color::DarkGray)),
             area,
         );
@@ -952,27 +2029,80 @@ fn draw_help(f: &mut Frame, area: Rect, app: &App) {
     let global = Line::from(vec![
         Span::styled("[Tab/F2] ", w), Span::raw("Cycle focus  │  "),
         Span::styled("[F1] ",     w), Span::raw("Waveform  │  "),
-        Span::styled("[F3] ",     w), Span::raw("Drum play/stop  │  "),
+        Span::styled("[F3] ",     w), Span::raw("Drum play/stop  "),
+        Span::styled("[Shift+F3] ", w), Span::raw("Spectrogram  │  "),
+        Span::styled("[F4] ",     w), Span::raw("Band-limit  "),
+        Span::styled("[Shift+F4] ", w), Span::raw("Spectral freeze  │  "),
         Span::styled("[PgUp/Dn] ",w), Span::raw("BPM  │  "),
-        Span::styled("[F6] ",     w), Span::raw("Scale  │  "),
-        Span::styled("[F7] ",     w), Span::raw("Root  │  "),
+        Span::styled("[Shift+PgUp/Dn] ",w), Span::raw("BPM ±0.1  │  "),
+        Span::styled("[^←/^→] ",  w), Span::raw("Crossfade  │  "),
+        Span::styled("[^↑/^↓] ",  w), Span::raw("Master  │  "),
+        Span::styled("[F6] ",     w), Span::raw("Scale  "),
+        Span::styled("[Shift+F6] ", w), Span::raw("custom  │  "),
+        Span::styled("[F7] ",     w), Span::raw("Root  "),
+        Span::styled("[Shift+F7] ", w), Span::raw("live  │  "),
+        Span::styled("[F8] ",     w), Span::raw("Audio out  "),
+        Span::styled("[Shift+F8] ", w), Span::raw("Mono sum  │  "),
+        Span::styled("[F9] ",     w), Span::raw("Reset xruns  "),
+        Span::styled("[^F9] ",    w), Span::raw("Reset peak  "),
+        Span::styled("[Shift+F9] ", w), Span::raw("Cue  │  "),
+        Span::styled("[F10] ",    w), Span::raw("MIDI local on/off  "),
+        Span::styled("[Shift+F10] ", w), Span::raw("Metronome  │  "),
+        Span::styled("[^R] ",     w), Span::raw("Auto rec  │  "),
+        Span::styled("[^B] ",     w), Span::raw("Bypass lane  │  "),
+        Span::styled("[F11/⇧F11] ", w), Span::raw("Add/remove marker  │  "),
+        Span::styled("[F12/⇧F12] ", w), Span::raw("Next/prev marker  │  "),
         Span::styled("[^S] ",     w), Span::raw("Save  │  "),
         Span::styled("[^L] ",     w), Span::raw("Load  │  "),
+        Span::styled("[^W] ",     w), Span::raw("Wavetable  │  "),
+        Span::styled("[Alt+E] ",  w), Span::raw("Export WAV  "),
+        Span::styled("[Alt+M] ",  w), Span::raw("MIDI  │  "),
+        Span::styled("[^Del] ",   w), Span::raw("Clear pattern  "),
+        Span::styled("[^⇧Del] ",  w), Span::raw("all  "),
+        Span::styled("[^Z] ",     w), Span::raw("undo  │  "),
         Span::styled("[Esc] ",    w), Span::raw("Quit"),
     ]);
 
     let focus_line = match app.mode {
         AppMode::Play => Line::from(vec![
             Span::styled("Keys: ", d),
-            Span::raw("Z X C V B N M  (white)  S D G H J  (black)  │  upper row: Q-P / 2-0"),
+            Span::raw("Z X C V B N M  (white)  S D G H J  (black)  │  upper row: Q-P / 2-0  │  "),
+            Span::styled("[Space] ", w), Span::raw("Latch  │  "),
+            Span::styled("[Del] ",   w), Span::raw("Release latched  │  "),
+            Span::styled("[^K] ",    w), Span::raw("Chord mode  │  "),
+            Span::styled("[^A] ",    w), Span::raw("Arp  "),
+            Span::styled("[^E] ",    w), Span::raw("rate  "),
+            Span::styled("[^D] ",    w), Span::raw("mode  │  "),
+            Span::styled("[^N] ",    w), Span::raw("Mono  │  "),
+            Span::styled("[^T] ",    w), Span::raw("Record to Seq 1  "),
+            Span::styled("[^P] ",    w), Span::raw("count-in  │  "),
+            Span::styled("[^O] ",    w), Span::raw("Keys to MIDI"),
         ]),
         AppMode::SynthSeq => Line::from(vec![
             Span::styled("Piano keys: ", d),
             Span::raw("set note at cursor (advances)  │  "),
             Span::styled("[Enter/Space] ", w), Span::raw("Play/Pause  │  "),
             Span::styled("[Del] ",   w), Span::raw("Clear  │  "),
+            Span::styled("[\\ ] ",   w), Span::raw("Mute step  │  "),
+            Span::styled("[~] ",    w), Span::raw("Slide  │  "),
+            Span::styled("[^G] ",    w), Span::raw("Tie repeats  │  "),
+            Span::styled("[Alt+V] ", w), Span::raw("Velocity humanize  │  "),
+            Span::styled("[Alt+D] ", w), Span::raw("Direction  │  "),
+            Span::styled("[a] ",     w), Span::raw("Audition  │  "),
+            Span::styled("[^P] ",    w), Span::raw("Hold-to-paint  │  "),
+            Span::styled("[^O] ",    w), Span::raw("To MIDI  │  "),
+            Span::styled("[^F] ",    w), Span::raw("Freeze/unfreeze  │  "),
+            Span::styled("[^N] ",    w), Span::raw("Mono  │  "),
+            Span::styled("[Alt↑↓] ", w), Span::raw("Transpose degree  │  "),
+            Span::styled("[Shift↑↓] ", w), Span::raw("Step octave  │  "),
             Span::styled("[]] ",     w), Span::raw("Cycle steps  │  "),
             Span::styled("[-=] ",    w), Span::raw("Vol  │  "),
+            Span::styled("[()] ",    w), Span::raw("Cutoff  │  "),
+            Span::styled("[<>] ",    w), Span::raw("Pan  │  "),
+            Span::styled("[k/K] ",   w), Span::raw("Hold step (p-lock) / clear locks  │  "),
+            Span::styled("[Alt←→] ", w), Span::raw("Nudge step  │  "),
+            Span::styled("[Q] ",     w), Span::raw("Quantize  "),
+            Span::styled("[Alt+q] ", w), Span::raw("strength  │  "),
             Span::styled("[[{] ",    w), Span::raw("Oct down/up"),
         ]),
         AppMode::SynthSeq2 => Line::from(vec![
@@ -980,42 +2110,132 @@ fn draw_help(f: &mut Frame, area: Rect, app: &App) {
             Span::raw("set note at cursor (advances)  │  "),
             Span::styled("[Enter/Space] ", w), Span::raw("Play/Pause  │  "),
             Span::styled("[Del] ",   w), Span::raw("Clear  │  "),
+            Span::styled("[\\ ] ",   w), Span::raw("Mute step  │  "),
+            Span::styled("[~] ",    w), Span::raw("Slide  │  "),
+            Span::styled("[^G] ",    w), Span::raw("Tie repeats  │  "),
+            Span::styled("[Alt+V] ", w), Span::raw("Velocity humanize  │  "),
+            Span::styled("[Alt+D] ", w), Span::raw("Direction  │  "),
+            Span::styled("[a] ",     w), Span::raw("Audition  │  "),
+            Span::styled("[^P] ",    w), Span::raw("Hold-to-paint  │  "),
+            Span::styled("[^O] ",    w), Span::raw("To MIDI  │  "),
+            Span::styled("[^F] ",    w), Span::raw("Freeze/unfreeze  │  "),
+            Span::styled("[^N] ",    w), Span::raw("Mono  │  "),
+            Span::styled("[Alt↑↓] ", w), Span::raw("Transpose degree  │  "),
+            Span::styled("[Shift↑↓] ", w), Span::raw("Step octave  │  "),
             Span::styled("[]] ",     w), Span::raw("Cycle steps  │  "),
             Span::styled("[F5] ",    w), Span::raw("Wave  │  "),
             Span::styled("[-=] ",    w), Span::raw("Vol  │  "),
+            Span::styled("[()] ",    w), Span::raw("Cutoff  │  "),
+            Span::styled("[<>] ",    w), Span::raw("Pan  │  "),
+            Span::styled("[k/K] ",   w), Span::raw("Hold step (p-lock) / clear locks  │  "),
+            Span::styled("[Alt←→] ", w), Span::raw("Nudge step  │  "),
+            Span::styled("[Q] ",     w), Span::raw("Quantize  "),
+            Span::styled("[Alt+q] ", w), Span::raw("strength  │  "),
             Span::styled("[[{] ",    w), Span::raw("Oct down/up"),
         ]),
-        AppMode::Drums => Line::from(vec![
-            Span::styled("Preview: ", d),
-            Span::styled("Z",  Style::default().fg(Color::Red)),     Span::raw(" Kick  "),
-            Span::styled("X",  Style::default().fg(Color::Yellow)),  Span::raw(" Snare  "),
-            Span::styled("C",  Style::default().fg(Color::Cyan)),    Span::raw(" C-Hat  "),
-            Span::styled("V",  Style::default().fg(Color::Blue)),    Span::raw(" O-Hat  "),
-            Span::styled("B",  Style::default().fg(Color::Magenta)), Span::raw(" Clap  "),
-            Span::styled("N",  Style::default().fg(Color::Green)),   Span::raw(" L.Tom  "),
-            Span::styled("M",  Style::default().fg(Color::LightGreen)), Span::raw(" M.Tom  "),
-            Span::styled(",",  Style::default().fg(Color::LightCyan)),  Span::raw(" H.Tom  │  "),
-            Span::styled("[Enter] ", w), Span::raw("Play  │  "),
-            Span::styled("[\\ ] ", w),  Span::raw("Mute  │  "),
-            Span::styled("[Del] ",  w), Span::raw("Clear  │  "),
-            Span::styled("[p/[] ", w),  Span::raw("Prob +/-25%  │  "),
-            Span::styled("[e] ",    w), Span::raw("Euclidean fill  │  "),
-            Span::styled("[</>] ",  w), Span::raw("Swing ±5%"),
+        AppMode::Drums => Line::from({
+            // Preview legend follows the kit: n-th drum key → n-th track.
+            let mut spans = vec![Span::styled("Preview: ", d)];
+            for (i, t) in snap.drums.tracks.iter().enumerate() {
+                let Some(key) = app.keymap.drum_key(i) else { break };
+                spans.push(Span::styled(key.to_uppercase().to_string(), Style::default().fg(drum_color(t.kind))));
+                spans.push(Span::raw(format!(" {}  ", t.label)));
+            }
+            spans.push(Span::raw("│  "));
+            spans.extend([
+                Span::styled("[Enter] ", w), Span::raw("Play  │  "),
+                Span::styled("[\\ ] ", w),  Span::raw("Mute  │  "),
+                Span::styled("[Del] ",  w), Span::raw("Clear  │  "),
+                Span::styled("[p/[] ", w),  Span::raw("Prob +/-25%  │  "),
+                Span::styled("[e] ",    w), Span::raw("Euclidean fill  │  "),
+                Span::styled("[Alt←→] ", w), Span::raw("Nudge step  │  "),
+                Span::styled("[t] ",    w), Span::raw("Trig condition  │  "),
+                Span::styled("[f] ",    w), Span::raw("Fill on/off  │  "),
+                Span::styled("[R/N] ",  w), Span::raw("Retrig chance / hits  │  "),
+                Span::styled("[(/)] ",  w), Span::raw("Pan L/R  │  "),
+                Span::styled("[{/}] ",  w), Span::raw("Pitch -/+1 st  │  "),
+                Span::styled("[</>] ",  w), Span::raw("Swing ±5%  │  "),
+                Span::styled("[g] ",    w), Span::raw("Groove template  │  "),
+                Span::styled("[G] ",    w), Span::raw("Groove drums/all  │  "),
+                Span::styled("[Ins/A] ", w), Span::raw("Add track  │  "),
+                Span::styled("[D] ",    w), Span::raw("Remove track  │  "),
+                Span::styled("[Shift↑↓] ", w), Span::raw("Move track  │  "),
+                Span::styled("[k] ",    w), Span::raw("Track sound  │  "),
+                Span::styled("[r] ",    w), Span::raw("Rename  │  "),
+                Span::styled("[o] ",    w), Span::raw("Load sample  │  "),
+                Span::styled("[^F] ",   w), Span::raw("Freeze/unfreeze"),
+            ]);
+            spans
+        }),
+        AppMode::Acid => Line::from(vec![
+            Span::styled("[←→] ", w), Span::raw("Step  │  "),
+            Span::styled("[a] ", w), Span::raw("Accent  │  "),
+            Span::styled("[~] ", w), Span::raw("Slide  │  "),
+            Span::styled("[<>] ", w), Span::raw("Setting  │  "),
+            Span::styled("[↑↓/-=] ", w), Span::raw("Adjust  │  "),
+            Span::styled("[Ctrl+A] ", w), Span::raw("303 preset  │  "),
+            Span::styled("[Ctrl+X] ", w), Span::raw("Env/accent on/off  │  "),
+            Span::styled("Also: ", d), Span::raw("piano keys enter notes, Space plays seq 1"),
+        ]),
+        AppMode::Envelope => Line::from(vec![
+            Span::styled("[↑↓] ", w), Span::raw("Synth 1 / 2  │  "),
+            Span::styled("[←→] ", w), Span::raw("Attack / Decay / Sustain / Release  │  "),
+            Span::styled("[-=] ", w), Span::raw("Adjust  │  "),
+            Span::styled("Times ", d), Span::raw("1 ms – 4 s, step 10%"),
+        ]),
+        AppMode::Modulation => Line::from(vec![
+            Span::styled("[↑↓] ", w), Span::raw("LFO 1 / 2  │  "),
+            Span::styled("[←→] ", w), Span::raw("Shape / Rate / Depth / Target  │  "),
+            Span::styled("[-=] ", w), Span::raw("Adjust  │  "),
+            Span::styled("Depth ", d), Span::raw("0% = off; cutoff ±2 oct, volume dips"),
+        ]),
+        AppMode::Inserts => Line::from(vec![
+            Span::styled("[↑↓] ", w), Span::raw("Synth 1 / 2  │  "),
+            Span::styled("[←→] ", w), Span::raw("Select  │  "),
+            Span::styled("[k] ", w), Span::raw("Effect to add  │  "),
+            Span::styled("[Ins/A] ", w), Span::raw("Add  │  "),
+            Span::styled("[D/Del] ", w), Span::raw("Remove  │  "),
+            Span::styled("[Shift←→] ", w), Span::raw("Move  │  "),
+            Span::styled("Also: ", d), Span::raw("each insert runs at its effect's defaults"),
+        ]),
+        AppMode::Sample => Line::from(vec![
+            Span::styled("[o] ", w), Span::raw("Open WAV  │  "),
+            Span::styled("[←→] ", w), Span::raw("Size / Density / Position / Root  │  "),
+            Span::styled("[↑↓/-=] ", w), Span::raw("Adjust  │  "),
+            Span::styled("[1/2] ", w), Span::raw("Granular on synth 1 / 2  │  "),
+            Span::styled("[3/4] ", w), Span::raw("Sampler on synth 1 / 2  │  "),
+            Span::styled("Also: ", d), Span::raw("notes transpose from the root"),
+        ]),
+        AppMode::Harmonics => Line::from(vec![
+            Span::styled("[←→] ", w), Span::raw("Harmonic  │  "),
+            Span::styled("[↑↓/-=] ", w), Span::raw("Level ±5%  │  "),
+            Span::styled("[x] ", w), Span::raw("Clear  │  "),
+            Span::styled("[r] ", w), Span::raw("Reset to sine  │  "),
+            Span::styled("[1/2] ", w), Span::raw("Play on synth 1 / 2  │  "),
+            Span::styled("Also: ", d), Span::raw("F1/F5 cycle to the \"Additive\" wave"),
         ]),
         AppMode::Effects => Line::from(vec![
-            Span::styled("[↑↓] ", w), Span::raw("Select (1-2=Rev/Dly  3=Dist  4=SC  5-6=Filt S1/S2)  │  "),
+            Span::styled("[↑↓] ", w), Span::raw("Select (1-2=Rev/Dly  3=Dist  4=SC  5-6=Filt S1/S2  7=Comb  8=Formant  9=De-ess  10=Freeze  11=Chorus  12=Crush  13=Comp)  │  "),
             Span::styled("[←→] ", w), Span::raw("Param  │  "),
             Span::styled("[-=] ", w), Span::raw("Adjust  │  "),
             Span::styled("[Enter] ", w), Span::raw("On/Off  │  "),
             Span::styled("[Space] ", w), Span::raw("Route 0↔100%  │  "),
-            Span::styled("Filt params: ", d), Span::raw("Type / Cutoff / Q"),
+            Span::styled("[n] ", w), Span::raw("Delay note value  │  "),
+            Span::styled("[t/T] ", w), Span::raw("Delay tap preset / edit taps  │  "),
+            Span::styled("[r] ", w), Span::raw("Reverse delay  │  "),
+            Span::styled("[s] ", w), Span::raw("Delay tempo sync / division  │  "),
+            Span::styled("[{}/<>] ", w), Span::raw("Delay duck amount / release  │  "),
+            Span::styled("[[]] ", w), Span::raw("Reverb early reflections  │  "),
+            Span::styled("[()] ", w), Span::raw("Reverb shimmer  │  "),
+            Span::styled("Filt params: ", d), Span::raw("Type / Cutoff / Q / Gain (PK/LS/HS)"),
         ]),
     };
 
     f.render_widget(
         Paragraph::new(vec![global, focus_line])
             .block(Block::default().title(" Help ").borders(Borders::ALL))
-            .style(Style::default().fg(Color::DarkGray)),
+            .style(Style::default().fg(Color::DarkGray))
+            .wrap(Wrap { trim: false }),
         area,
     );
 }
@@ -0,0 +1,169 @@
+use anyhow::{Context, Result};
+
+/// Sample format of a written WAV file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WavFormat { Int16, Int24, Float32 }
+
+impl WavFormat {
+    /// The format an export prompt word names: `16bit`, `24bit` or `float`.
+    pub fn parse(word: &str) -> Option<Self> {
+        match word {
+            "16bit" => Some(Self::Int16),
+            "24bit" => Some(Self::Int24),
+            "float" => Some(Self::Float32),
+            _       => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self { Self::Int16 => "16-bit", Self::Int24 => "24-bit", Self::Float32 => "32-bit float" }
+    }
+
+    fn bits(self) -> u16 {
+        match self { Self::Int16 => 16, Self::Int24 => 24, Self::Float32 => 32 }
+    }
+
+    /// Dither by default only where it is audible: at 16 bits.  24-bit
+    /// quantisation noise sits below any playback chain, and float has none.
+    pub fn default_dither(self) -> bool {
+        self == Self::Int16
+    }
+}
+
+/// How samples are written: format, rate and whether to dither.
+#[derive(Clone, Copy, Debug)]
+pub struct WavSpec {
+    pub format:      WavFormat,
+    pub sample_rate: u32,
+    /// TPDF dither with first-order noise shaping on integer formats.
+    /// Ignored for float.
+    pub dither:      bool,
+}
+
+impl WavSpec {
+    pub fn new(format: WavFormat, sample_rate: u32) -> Self {
+        Self { format, sample_rate, dither: format.default_dither() }
+    }
+}
+
+/// Float-to-integer conversion for one channel.  With dither on, each
+/// sample gets triangular (TPDF) noise of ±1 LSB before rounding, which
+/// turns quantisation distortion on quiet signals into a steady hiss; the
+/// rounding error is fed back into the next sample so that hiss is pushed
+/// up towards the top of the spectrum, where the ear is least sensitive.
+struct Quantizer {
+    /// Full scale in LSBs (32768 for 16 bits).
+    scale:  f32,
+    dither: bool,
+    /// Rounding error of the last sample, in LSBs.
+    error:  f32,
+    seed:   u32,
+}
+
+impl Quantizer {
+    fn new(bits: u16, dither: bool) -> Self {
+        Self { scale: (1u32 << (bits - 1)) as f32, dither, error: 0.0, seed: 0x1234_5678 }
+    }
+
+    /// A uniform value in -0.5..0.5 from an XOR-shift generator.
+    fn uniform(&mut self) -> f32 {
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 17;
+        self.seed ^= self.seed << 5;
+        self.seed as f32 / u32::MAX as f32 - 0.5
+    }
+
+    fn quantize(&mut self, x: f32) -> i32 {
+        let (lo, hi) = (-self.scale, self.scale - 1.0);
+        let v = x.clamp(-1.0, 1.0) * self.scale;
+        if !self.dither { return v.round().clamp(lo, hi) as i32; }
+        let shaped = v - self.error;
+        let q = (shaped + self.uniform() + self.uniform()).round().clamp(lo, hi);
+        self.error = q - shaped;
+        q as i32
+    }
+}
+
+/// Mono RIFF/WAVE bytes for `samples` (-1..1) in `spec`'s format.
+pub fn encode_wav(samples: &[f32], spec: WavSpec) -> Vec<u8> {
+    let bits  = spec.format.bits();
+    let width = bits as u32 / 8;
+    let data_len = samples.len() as u32 * width;
+    let tag: u16 = if spec.format == WavFormat::Float32 { 3 } else { 1 };
+
+    let mut b = Vec::with_capacity(44 + data_len as usize);
+    b.extend_from_slice(b"RIFF");
+    b.extend_from_slice(&(36 + data_len).to_le_bytes());
+    b.extend_from_slice(b"WAVEfmt ");
+    b.extend_from_slice(&16u32.to_le_bytes());
+    b.extend_from_slice(&tag.to_le_bytes());
+    b.extend_from_slice(&1u16.to_le_bytes());                          // channels
+    b.extend_from_slice(&spec.sample_rate.to_le_bytes());
+    b.extend_from_slice(&(spec.sample_rate * width).to_le_bytes());   // byte rate
+    b.extend_from_slice(&(width as u16).to_le_bytes());               // block align
+    b.extend_from_slice(&bits.to_le_bytes());
+    b.extend_from_slice(b"data");
+    b.extend_from_slice(&data_len.to_le_bytes());
+
+    let mut q = Quantizer::new(bits, spec.dither);
+    for &x in samples {
+        match spec.format {
+            WavFormat::Int16   => b.extend_from_slice(&(q.quantize(x) as i16).to_le_bytes()),
+            WavFormat::Int24   => b.extend_from_slice(&q.quantize(x).to_le_bytes()[..3]),
+            WavFormat::Float32 => b.extend_from_slice(&x.to_le_bytes()),
+        }
+    }
+    b
+}
+
+/// Write `samples` to a mono WAV file at `path`.
+pub fn write_wav(path: &str, samples: &[f32], spec: WavSpec) -> Result<()> {
+    std::fs::write(path, encode_wav(samples, spec)).with_context(|| format!("Cannot write {}", path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u16_at(b: &[u8], i: usize) -> u16 { u16::from_le_bytes([b[i], b[i + 1]]) }
+    fn u32_at(b: &[u8], i: usize) -> u32 { u32::from_le_bytes(b[i..i + 4].try_into().unwrap()) }
+
+    #[test]
+    fn header_fields_match_the_format() {
+        let samples = [0.0, 0.5, -0.5];
+        for (format, tag, bits) in [(WavFormat::Int16, 1, 16), (WavFormat::Int24, 1, 24), (WavFormat::Float32, 3, 32)] {
+            let b = encode_wav(&samples, WavSpec::new(format, 48_000));
+            let width = bits / 8;
+            let data_len = samples.len() as u32 * width as u32;
+            assert_eq!(&b[0..4], b"RIFF");
+            assert_eq!(u32_at(&b, 4), 36 + data_len);
+            assert_eq!(&b[8..16], b"WAVEfmt ");
+            assert_eq!(u32_at(&b, 16), 16);
+            assert_eq!(u16_at(&b, 20), tag);
+            assert_eq!(u16_at(&b, 22), 1);
+            assert_eq!(u32_at(&b, 24), 48_000);
+            assert_eq!(u32_at(&b, 28), 48_000 * width as u32);
+            assert_eq!(u16_at(&b, 32), width);
+            assert_eq!(u16_at(&b, 34), bits);
+            assert_eq!(&b[36..40], b"data");
+            assert_eq!(u32_at(&b, 40), data_len);
+            assert_eq!(b.len(), 44 + data_len as usize, "{:?}", format);
+        }
+    }
+
+    #[test]
+    fn int24_packs_three_little_endian_bytes() {
+        let mut spec = WavSpec::new(WavFormat::Int24, 44_100);
+        spec.dither = false;
+        // Full scale both ways (clamped), and one LSB either side of zero.
+        let lsb = 1.0 / 8_388_608.0;
+        let b = encode_wav(&[1.0, -1.0, lsb, -lsb, 0.0], spec);
+        assert_eq!(&b[44..], &[
+            0xff, 0xff, 0x7f,
+            0x00, 0x00, 0x80,
+            0x01, 0x00, 0x00,
+            0xff, 0xff, 0xff,
+            0x00, 0x00, 0x00,
+        ]);
+    }
+}
@@ -0,0 +1,162 @@
+use std::f32::consts::PI;
+use std::path::Path;
+
+use anyhow::{bail, Context, Result};
+
+/// Samples per stored cycle.  Loaded WAVs are resampled to this length.
+pub const TABLE_LEN: usize = 2048;
+
+/// Harmonics in the additive editor.
+pub const HARMONICS: usize = 8;
+
+/// Index of the "Additive" table in `builtins()`, rebuilt from
+/// `Synth::harmonics`.
+pub const ADDITIVE_TABLE: usize = 4;
+
+/// The additive editor's starting point: a plain sine.
+pub const DEFAULT_HARMONICS: [f32; HARMONICS] = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+
+/// One single-cycle waveform, read by phase (0..1) with linear interpolation.
+#[derive(Clone)]
+pub struct Wavetable {
+    pub name: String,
+    /// File the table was loaded from (`None` for built-ins).  Saved in place
+    /// of the name so the table can be re-read when a project is loaded.
+    pub source: Option<String>,
+    samples: Vec<f32>,
+}
+
+impl Wavetable {
+    /// A table summed from harmonics: `(harmonic number, amplitude)` pairs.
+    fn additive(name: &str, partials: &[(u32, f32)]) -> Self {
+        let mut samples: Vec<f32> = (0..TABLE_LEN)
+            .map(|i| {
+                let ph = i as f32 / TABLE_LEN as f32 * 2.0 * PI;
+                partials.iter().map(|&(h, a)| a * (ph * h as f32).sin()).sum()
+            })
+            .collect();
+        normalize(&mut samples);
+        Self { name: name.to_string(), source: None, samples }
+    }
+
+    /// The additive editor's table: harmonics 1–8 at `amps`, on sine phases.
+    pub fn from_harmonics(amps: &[f32; HARMONICS]) -> Self {
+        let partials: Vec<(u32, f32)> = amps.iter().enumerate().map(|(h, &a)| (h as u32 + 1, a)).collect();
+        Self::additive("Additive", &partials)
+    }
+
+    /// Tables always available.  Harmonics are limited so they stay clean
+    /// up to the top of the keyboard.
+    pub fn builtins() -> Vec<Self> {
+        let organ = [(1, 1.0), (2, 0.7), (3, 0.5), (4, 0.35), (6, 0.25), (8, 0.2)];
+        let soft_saw: Vec<(u32, f32)> = (1..=16).map(|h| (h, 1.0 / h as f32)).collect();
+        let hollow: Vec<(u32, f32)> = (1..=15).step_by(2).map(|h| (h, 1.0 / (h * h) as f32)).collect();
+        // Spectrum of a 25% pulse (sin(π·h/4)/h) on sine phases: a nasal reed tone.
+        let reed: Vec<(u32, f32)> = (1..=24)
+            .map(|h| (h, (PI * h as f32 * 0.25).sin() / h as f32))
+            .collect();
+        vec![
+            Self::additive("Organ",    &organ),
+            Self::additive("Soft Saw", &soft_saw),
+            Self::additive("Hollow",   &hollow),
+            Self::additive("Reed",     &reed),
+            Self::from_harmonics(&DEFAULT_HARMONICS),
+        ]
+    }
+
+    /// Load a single-cycle WAV (PCM 8/16/24/32-bit or 32-bit float; the first
+    /// channel is used).  The cycle is resampled to `TABLE_LEN`, DC is removed,
+    /// and the peak is normalised to 1.
+    pub fn load_wav(path: &str) -> Result<Self> {
+        let bytes = std::fs::read(path).with_context(|| format!("Cannot read {}", path))?;
+        let (raw, _) = decode_wav(&bytes).with_context(|| format!("{} is not a usable WAV", path))?;
+        if raw.len() < 2 { bail!("{} has fewer than 2 samples", path); }
+
+        let mut samples: Vec<f32> = (0..TABLE_LEN)
+            .map(|i| {
+                let pos = i as f32 * raw.len() as f32 / TABLE_LEN as f32;
+                let i0 = pos as usize;
+                let i1 = (i0 + 1) % raw.len();
+                let frac = pos - i0 as f32;
+                raw[i0] + (raw[i1] - raw[i0]) * frac
+            })
+            .collect();
+        let dc = samples.iter().sum::<f32>() / TABLE_LEN as f32;
+        for s in &mut samples { *s -= dc; }
+        normalize(&mut samples);
+
+        let name = Path::new(path)
+            .file_stem()
+            .map_or_else(|| path.to_string(), |s| s.to_string_lossy().into_owned());
+        Ok(Self { name, source: Some(path.to_string()), samples })
+    }
+
+    /// Name used in save files: the source path for loaded tables, else the name.
+    pub fn save_key(&self) -> &str {
+        self.source.as_deref().unwrap_or(&self.name)
+    }
+
+    #[inline]
+    pub fn sample(&self, phase: f32) -> f32 {
+        let pos = phase * TABLE_LEN as f32;
+        let i0 = (pos as usize) % TABLE_LEN;
+        let i1 = (i0 + 1) % TABLE_LEN;
+        let frac = pos - pos.floor();
+        self.samples[i0] + (self.samples[i1] - self.samples[i0]) * frac
+    }
+}
+
+fn normalize(samples: &mut [f32]) {
+    let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+    if peak > 0.0 {
+        for s in samples { *s /= peak; }
+    }
+}
+
+/// First-channel samples of a RIFF/WAVE file as f32 in -1..1, and the
+/// file's sample rate.
+pub fn decode_wav(b: &[u8]) -> Result<(Vec<f32>, u32)> {
+    if b.len() < 12 || &b[0..4] != b"RIFF" || &b[8..12] != b"WAVE" {
+        bail!("missing RIFF/WAVE header");
+    }
+    let u16_at = |i: usize| u16::from_le_bytes([b[i], b[i + 1]]);
+    let u32_at = |i: usize| u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]]);
+
+    let mut fmt: Option<(u16, u16, u32, u16)> = None; // (format, channels, rate, bits)
+    let mut pos = 12;
+    while pos + 8 <= b.len() {
+        let id   = &b[pos..pos + 4];
+        let size = u32_at(pos + 4) as usize;
+        let body = pos + 8;
+        let end  = (body + size).min(b.len());
+        match id {
+            b"fmt " if end - body >= 16 => {
+                let mut format = u16_at(body);
+                if format == 0xFFFE && end - body >= 26 { format = u16_at(body + 24); } // WAVE_FORMAT_EXTENSIBLE
+                fmt = Some((format, u16_at(body + 2), u32_at(body + 4), u16_at(body + 14)));
+            }
+            b"data" => {
+                let (format, channels, rate, bits) = fmt.context("data chunk before fmt chunk")?;
+                let width = (bits as usize).div_ceil(8);
+                let frame = width * channels.max(1) as usize;
+                if width == 0 { bail!("zero bit depth"); }
+                let data = &b[body..end];
+                let samples = data.chunks_exact(frame).map(|f| {
+                    let s = &f[..width];
+                    Ok(match (format, bits) {
+                        (1, 8)  => (s[0] as f32 - 128.0) / 128.0,
+                        (1, 16) => i16::from_le_bytes([s[0], s[1]]) as f32 / 32768.0,
+                        (1, 24) => (i32::from_le_bytes([0, s[0], s[1], s[2]]) >> 8) as f32 / 8_388_608.0,
+                        (1, 32) => i32::from_le_bytes([s[0], s[1], s[2], s[3]]) as f32 / 2_147_483_648.0,
+                        (3, 32) => f32::from_le_bytes([s[0], s[1], s[2], s[3]]),
+                        _ => bail!("unsupported format {} / {} bits", format, bits),
+                    })
+                }).collect::<Result<_>>()?;
+                return Ok((samples, rate));
+            }
+            _ => {}
+        }
+        pos = body + size + (size & 1); // chunks are word-aligned
+    }
+    bail!("no data chunk")
+}